@@ -17,8 +17,10 @@
 #pragma once
 
 #include <cassert>
+#include <cmath>
 #include <cstdint>
 #include <memory>
+#include <optional>
 #include <utility>
 #include <vector>
 
@@ -157,4 +159,81 @@ class Builder {
   }
 };
 
+/// Optional value stored behind an indirection, used for optional fields
+/// of recursive struct declarations. The interface mirrors std::optional,
+/// and the stored value is copied with the container.
+template <typename T>
+class indirect {
+ public:
+  indirect() = default;
+  indirect(std::nullopt_t) {}
+  indirect(T value) : value_(std::make_unique<T>(std::move(value))) {}
+  indirect(indirect const& other)
+      : value_(other.value_ ? std::make_unique<T>(*other.value_) : nullptr) {}
+  indirect(indirect&&) = default;
+
+  indirect& operator=(indirect const& other) {
+    value_ = other.value_ ? std::make_unique<T>(*other.value_) : nullptr;
+    return *this;
+  }
+  indirect& operator=(indirect&&) = default;
+
+  /// Construct a default value in place and return a reference to it.
+  T& emplace() {
+    value_ = std::make_unique<T>();
+    return *value_;
+  }
+
+  bool has_value() const { return value_ != nullptr; }
+  explicit operator bool() const { return has_value(); }
+
+  T const& operator*() const { return *value_; }
+  T& operator*() { return *value_; }
+  T const* operator->() const { return value_.get(); }
+  T* operator->() { return value_.get(); }
+
+ private:
+  std::unique_ptr<T> value_;
+};
+
+/// Maximum nesting depth of recursive declarations accepted by the parsers
+/// running on the current thread.
+inline thread_local size_t max_parse_depth = 64;
+
+/// Guard held by the parsers of recursive declarations for the duration
+/// of the parsing. The nesting depth is decremented when the guard
+/// is destroyed.
+class parse_depth_guard {
+ public:
+  parse_depth_guard() { depth()++; }
+  ~parse_depth_guard() { depth()--; }
+  parse_depth_guard(parse_depth_guard const&) = delete;
+  parse_depth_guard& operator=(parse_depth_guard const&) = delete;
+
+  /// Return false if the nesting depth exceeds the configured limit.
+  bool ok() const { return depth() <= max_parse_depth; }
+
+ private:
+  static size_t& depth() {
+    static thread_local size_t depth = 0;
+    return depth;
+  }
+};
+
+/// Convert the scaled value of a scalar field to its raw value.
+/// The raw value is rounded to the nearest integer and saturated to
+/// the range of the field.
+template <typename T, size_t width>
+T from_scaled(double value, double scale) {
+  constexpr T max = static_cast<T>(~T(0)) >> (8 * sizeof(T) - width);
+  double raw = std::round(value / scale);
+  if (!(raw > 0)) {
+    return 0;
+  }
+  if (raw >= static_cast<double>(max)) {
+    return max;
+  }
+  return static_cast<T>(raw);
+}
+
 }  // namespace pdl::packet
@@ -54,6 +54,8 @@ class Tag(Node):
     value: Optional[int] = field(default=None)
     range: Optional[Tuple[int, int]] = field(default=None)
     tags: Optional[List['Tag']] = field(default=None)
+    display_name: Optional[str] = field(default=None)
+    aliases: List[str] = field(default_factory=list)
 
 
 @node('constraint')
@@ -63,10 +65,48 @@ class Constraint(Node):
     tag_id: Optional[str]
 
 
+@node('integer')
+class IntegerExpr(Node):
+    value: int
+
+
+@node('field')
+class FieldExpr(Node):
+    id: str
+
+
+@node('length')
+class LengthExpr(Node):
+    id: str
+
+
+@node('binary')
+class BinaryExpr(Node):
+    op: str
+    lhs: Node
+    rhs: Node
+
+
+@node('invariant')
+class Invariant(Node):
+    text: str
+    expr: Node
+
+
+@node('test_case')
+class TestCase(Node):
+    input: str
+    fields: List[Constraint] = field(default_factory=list)
+    error: Optional[str] = field(default=None)
+
+
 @dataclass
 class Field(Node):
     parent: Node = field(init=False)
     cond: Optional[Constraint] = field(kw_only=True, default=None)
+    endianness: Optional[str] = field(kw_only=True, default=None)
+    # Index of the extension block declaring this field.
+    extension: Optional[int] = field(kw_only=True, default=None)
     # Backlink to the (optional) optional field referencing
     # this field as condition.
     cond_for: Optional['Field'] = field(init=False, default=None)
@@ -85,6 +125,8 @@ class PaddingField(Field):
 class SizeField(Field):
     field_id: str
     width: int
+    # 'bits', 'octets', or {'words': width}.
+    unit: object = 'octets'
 
 
 @node('elementsize_field')
@@ -107,6 +149,7 @@ class BodyField(Field):
 @node('payload_field')
 class PayloadField(Field):
     size_modifier: Optional[str]
+    max_size: Optional[int] = None
     id: str = field(init=False, default='_payload_')
 
 
@@ -134,6 +177,7 @@ class ArrayField(Field):
     type_id: Optional[str]
     size_modifier: Optional[str]
     size: Optional[int]
+    max_count: Optional[int] = None
     padded_size: Optional[int] = field(init=False, default=None)
 
     @property
@@ -145,12 +189,16 @@ class ArrayField(Field):
 class ScalarField(Field):
     id: str
     width: int
+    default: Optional[int] = None
+    scale: Optional[str] = None
+    unit: Optional[str] = None
 
 
 @node('typedef_field')
 class TypedefField(Field):
     id: str
     type_id: str
+    default: Optional[str] = None
 
     @property
     def type(self) -> 'Declaration':
@@ -166,6 +214,9 @@ class GroupField(Field):
 @dataclass
 class Declaration(Node):
     file: 'File' = field(init=False)
+    endianness: Optional[str] = field(kw_only=True, default=None)
+    bit_order: Optional[str] = field(kw_only=True, default=None)
+    size_assertion: Optional[dict] = field(kw_only=True, default=None)
 
     def __post_init__(self):
         if hasattr(self, 'fields'):
@@ -197,6 +248,14 @@ class EnumDeclaration(Declaration):
     id: str
     tags: List[Tag]
     width: int
+    parent_id: Optional[str] = None
+
+
+@node('flags_declaration')
+class FlagsDeclaration(Declaration):
+    id: str
+    flags: List[Tag]
+    width: int
 
 
 @node('packet_declaration')
@@ -205,6 +264,7 @@ class PacketDeclaration(Declaration):
     parent_id: Optional[str]
     constraints: List[Constraint]
     fields: List[Field]
+    invariants: List[Invariant] = field(default_factory=list)
 
     @property
     def parent(self) -> Optional['PacketDeclaration']:
@@ -217,6 +277,7 @@ class StructDeclaration(Declaration):
     parent_id: Optional[str]
     constraints: List[Constraint]
     fields: List[Field]
+    invariants: List[Invariant] = field(default_factory=list)
 
     @property
     def parent(self) -> Optional['StructDeclaration']:
@@ -229,6 +290,26 @@ class GroupDeclaration(Declaration):
     fields: List[Field]
 
 
+@node('test_declaration')
+class TestDeclaration(Declaration):
+    type_id: str
+    test_cases: List[TestCase]
+
+
+@node('bind_declaration')
+class BindDeclaration(Declaration):
+    packet_id: str
+    field_id: str
+    type_id: str
+    constraints: List[Constraint]
+
+
+@node('response_declaration')
+class ResponseDeclaration(Declaration):
+    request_id: str
+    response_id: str
+
+
 @dataclass
 class File:
     endianness: EndiannessDeclaration
@@ -249,6 +330,9 @@ class File:
                 self.packet_scope[d.id] = d
             elif isinstance(d, GroupDeclaration):
                 self.group_scope[d.id] = d
+            elif isinstance(d, (TestDeclaration, BindDeclaration, ResponseDeclaration)):
+                # Unnamed declarations.
+                pass
             else:
                 self.typedef_scope[d.id] = d
 
@@ -278,9 +362,13 @@ def convert_(obj: object) -> object:
     if isinstance(obj, object):
         if 'start' in obj.keys() and 'end' in obj.keys():
             return (obj['start'], obj['end'])
+        if 'kind' not in obj.keys():
+            # Plain objects, e.g. size assertions.
+            return {name: convert_(value) for name, value in obj.items()}
         kind = obj['kind']
-        loc = obj['loc']
-        loc = SourceRange(loc['file'], SourceLocation(**loc['start']), SourceLocation(**loc['end']))
+        # Invariant expressions have no source location.
+        loc = obj.get('loc')
+        loc = SourceRange(loc['file'], SourceLocation(**loc['start']), SourceLocation(**loc['end'])) if loc else None
         constructor = constructors_.get(kind)
         if not constructor:
             raise Exception(f'Unknown kind {kind}')
@@ -12,11 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use codespan_reporting::diagnostic::Diagnostic;
-use codespan_reporting::files;
+use codespan_reporting::diagnostic::{Diagnostic, LabelStyle, Severity};
+use codespan_reporting::files::{self, Files};
 use codespan_reporting::term;
 use codespan_reporting::term::termcolor;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use crate::ast::*;
@@ -134,11 +135,11 @@ pub enum ErrorCode {
     InvalidTagValue = 14,
     UndeclaredConstraintIdentifier = 15,
     InvalidConstraintIdentifier = 16,
-    E17 = 17,
+    InvalidConstraintScalarValue = 17,
     ConstraintValueOutOfRange = 18,
-    E19 = 19,
-    E20 = 20,
-    E21 = 21,
+    InvalidConstraintEnumValue = 19,
+    UndeclaredConstraintTag = 20,
+    InvalidConstraintType = 21,
     DuplicateConstraintIdentifier = 22,
     DuplicateSizeField = 23,
     UndeclaredSizeIdentifier = 24,
@@ -150,26 +151,214 @@ pub enum ErrorCode {
     UndeclaredElementSizeIdentifier = 30,
     InvalidElementSizeIdentifier = 31,
     FixedValueOutOfRange = 32,
-    E33 = 33,
-    E34 = 34,
-    E35 = 35,
+    UndeclaredFixedEnumIdentifier = 33,
+    UndeclaredFixedTagIdentifier = 34,
+    InvalidFixedEnumIdentifier = 35,
     DuplicatePayloadField = 36,
     MissingPayloadField = 37,
     RedundantArraySize = 38,
     InvalidPaddingField = 39,
     InvalidTagRange = 40,
     DuplicateTagRange = 41,
-    E42 = 42,
-    E43 = 43,
+    InvalidConstraintTagRange = 42,
+    TagValueInReservedRange = 43,
     DuplicateDefaultTag = 44,
     InvalidOptionalField = 45,
     UndeclaredConditionIdentifier = 46,
     InvalidConditionIdentifier = 47,
     InvalidConditionValue = 48,
-    E49 = 49,
+    OptionalConditionIdentifier = 49,
     InvalidFieldOffset = 51,
     InvalidFieldSize = 52,
     InvalidPacketSize = 53,
+    DefaultValueOutOfRange = 54,
+    UndeclaredDefaultTag = 55,
+    InvalidDefaultValue = 56,
+    InvalidFieldEndianness = 57,
+    InvalidSizeUnit = 58,
+    InvalidTrailingSizeField = 59,
+    InvalidTestInput = 60,
+    InvalidEnumWidth = 61,
+    InvalidScalarScale = 62,
+    InvalidFieldWidth = 63,
+    InvalidExtensionField = 64,
+    InvalidExtensionPosition = 65,
+    InvalidSizeBound = 66,
+    UndeclaredBindIdentifier = 67,
+    InvalidBindIdentifier = 68,
+    InvalidBindField = 69,
+    UndeclaredResponseIdentifier = 70,
+    InvalidResponseIdentifier = 71,
+    DuplicateResponseDeclaration = 72,
+    InvalidSizeAssertion = 73,
+    UndeclaredInvariantIdentifier = 74,
+    InvalidInvariantIdentifier = 75,
+    InvalidInvariantExpression = 76,
+    DuplicateTagDisplayName = 77,
+}
+
+impl ErrorCode {
+    /// List of all error codes.
+    pub const ALL: [ErrorCode; 76] = [
+        ErrorCode::DuplicateDeclIdentifier,
+        ErrorCode::RecursiveDecl,
+        ErrorCode::UndeclaredGroupIdentifier,
+        ErrorCode::InvalidGroupIdentifier,
+        ErrorCode::UndeclaredTypeIdentifier,
+        ErrorCode::InvalidTypeIdentifier,
+        ErrorCode::UndeclaredParentIdentifier,
+        ErrorCode::InvalidParentIdentifier,
+        ErrorCode::UndeclaredTestIdentifier,
+        ErrorCode::InvalidTestIdentifier,
+        ErrorCode::DuplicateFieldIdentifier,
+        ErrorCode::DuplicateTagIdentifier,
+        ErrorCode::DuplicateTagValue,
+        ErrorCode::InvalidTagValue,
+        ErrorCode::UndeclaredConstraintIdentifier,
+        ErrorCode::InvalidConstraintIdentifier,
+        ErrorCode::InvalidConstraintScalarValue,
+        ErrorCode::ConstraintValueOutOfRange,
+        ErrorCode::InvalidConstraintEnumValue,
+        ErrorCode::UndeclaredConstraintTag,
+        ErrorCode::InvalidConstraintType,
+        ErrorCode::DuplicateConstraintIdentifier,
+        ErrorCode::DuplicateSizeField,
+        ErrorCode::UndeclaredSizeIdentifier,
+        ErrorCode::InvalidSizeIdentifier,
+        ErrorCode::DuplicateCountField,
+        ErrorCode::UndeclaredCountIdentifier,
+        ErrorCode::InvalidCountIdentifier,
+        ErrorCode::DuplicateElementSizeField,
+        ErrorCode::UndeclaredElementSizeIdentifier,
+        ErrorCode::InvalidElementSizeIdentifier,
+        ErrorCode::FixedValueOutOfRange,
+        ErrorCode::UndeclaredFixedEnumIdentifier,
+        ErrorCode::UndeclaredFixedTagIdentifier,
+        ErrorCode::InvalidFixedEnumIdentifier,
+        ErrorCode::DuplicatePayloadField,
+        ErrorCode::MissingPayloadField,
+        ErrorCode::RedundantArraySize,
+        ErrorCode::InvalidPaddingField,
+        ErrorCode::InvalidTagRange,
+        ErrorCode::DuplicateTagRange,
+        ErrorCode::InvalidConstraintTagRange,
+        ErrorCode::TagValueInReservedRange,
+        ErrorCode::DuplicateDefaultTag,
+        ErrorCode::InvalidOptionalField,
+        ErrorCode::UndeclaredConditionIdentifier,
+        ErrorCode::InvalidConditionIdentifier,
+        ErrorCode::InvalidConditionValue,
+        ErrorCode::OptionalConditionIdentifier,
+        ErrorCode::InvalidFieldOffset,
+        ErrorCode::InvalidFieldSize,
+        ErrorCode::InvalidPacketSize,
+        ErrorCode::DefaultValueOutOfRange,
+        ErrorCode::UndeclaredDefaultTag,
+        ErrorCode::InvalidDefaultValue,
+        ErrorCode::InvalidFieldEndianness,
+        ErrorCode::InvalidSizeUnit,
+        ErrorCode::InvalidTrailingSizeField,
+        ErrorCode::InvalidTestInput,
+        ErrorCode::InvalidEnumWidth,
+        ErrorCode::InvalidScalarScale,
+        ErrorCode::InvalidFieldWidth,
+        ErrorCode::InvalidExtensionField,
+        ErrorCode::InvalidExtensionPosition,
+        ErrorCode::InvalidSizeBound,
+        ErrorCode::UndeclaredBindIdentifier,
+        ErrorCode::InvalidBindIdentifier,
+        ErrorCode::InvalidBindField,
+        ErrorCode::UndeclaredResponseIdentifier,
+        ErrorCode::InvalidResponseIdentifier,
+        ErrorCode::DuplicateResponseDeclaration,
+        ErrorCode::InvalidSizeAssertion,
+        ErrorCode::UndeclaredInvariantIdentifier,
+        ErrorCode::InvalidInvariantIdentifier,
+        ErrorCode::InvalidInvariantExpression,
+        ErrorCode::DuplicateTagDisplayName,
+    ];
+
+    /// Return the long-form explanation of the error, with examples.
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            ErrorCode::DuplicateDeclIdentifier => include_str!("error_codes/E1.md"),
+            ErrorCode::RecursiveDecl => include_str!("error_codes/E2.md"),
+            ErrorCode::UndeclaredGroupIdentifier => include_str!("error_codes/E3.md"),
+            ErrorCode::InvalidGroupIdentifier => include_str!("error_codes/E4.md"),
+            ErrorCode::UndeclaredTypeIdentifier => include_str!("error_codes/E5.md"),
+            ErrorCode::InvalidTypeIdentifier => include_str!("error_codes/E6.md"),
+            ErrorCode::UndeclaredParentIdentifier => include_str!("error_codes/E7.md"),
+            ErrorCode::InvalidParentIdentifier => include_str!("error_codes/E8.md"),
+            ErrorCode::UndeclaredTestIdentifier => include_str!("error_codes/E9.md"),
+            ErrorCode::InvalidTestIdentifier => include_str!("error_codes/E10.md"),
+            ErrorCode::DuplicateFieldIdentifier => include_str!("error_codes/E11.md"),
+            ErrorCode::DuplicateTagIdentifier => include_str!("error_codes/E12.md"),
+            ErrorCode::DuplicateTagValue => include_str!("error_codes/E13.md"),
+            ErrorCode::InvalidTagValue => include_str!("error_codes/E14.md"),
+            ErrorCode::UndeclaredConstraintIdentifier => include_str!("error_codes/E15.md"),
+            ErrorCode::InvalidConstraintIdentifier => include_str!("error_codes/E16.md"),
+            ErrorCode::InvalidConstraintScalarValue => include_str!("error_codes/E17.md"),
+            ErrorCode::ConstraintValueOutOfRange => include_str!("error_codes/E18.md"),
+            ErrorCode::InvalidConstraintEnumValue => include_str!("error_codes/E19.md"),
+            ErrorCode::UndeclaredConstraintTag => include_str!("error_codes/E20.md"),
+            ErrorCode::InvalidConstraintType => include_str!("error_codes/E21.md"),
+            ErrorCode::DuplicateConstraintIdentifier => include_str!("error_codes/E22.md"),
+            ErrorCode::DuplicateSizeField => include_str!("error_codes/E23.md"),
+            ErrorCode::UndeclaredSizeIdentifier => include_str!("error_codes/E24.md"),
+            ErrorCode::InvalidSizeIdentifier => include_str!("error_codes/E25.md"),
+            ErrorCode::DuplicateCountField => include_str!("error_codes/E26.md"),
+            ErrorCode::UndeclaredCountIdentifier => include_str!("error_codes/E27.md"),
+            ErrorCode::InvalidCountIdentifier => include_str!("error_codes/E28.md"),
+            ErrorCode::DuplicateElementSizeField => include_str!("error_codes/E29.md"),
+            ErrorCode::UndeclaredElementSizeIdentifier => include_str!("error_codes/E30.md"),
+            ErrorCode::InvalidElementSizeIdentifier => include_str!("error_codes/E31.md"),
+            ErrorCode::FixedValueOutOfRange => include_str!("error_codes/E32.md"),
+            ErrorCode::UndeclaredFixedEnumIdentifier => include_str!("error_codes/E33.md"),
+            ErrorCode::UndeclaredFixedTagIdentifier => include_str!("error_codes/E34.md"),
+            ErrorCode::InvalidFixedEnumIdentifier => include_str!("error_codes/E35.md"),
+            ErrorCode::DuplicatePayloadField => include_str!("error_codes/E36.md"),
+            ErrorCode::MissingPayloadField => include_str!("error_codes/E37.md"),
+            ErrorCode::RedundantArraySize => include_str!("error_codes/E38.md"),
+            ErrorCode::InvalidPaddingField => include_str!("error_codes/E39.md"),
+            ErrorCode::InvalidTagRange => include_str!("error_codes/E40.md"),
+            ErrorCode::DuplicateTagRange => include_str!("error_codes/E41.md"),
+            ErrorCode::InvalidConstraintTagRange => include_str!("error_codes/E42.md"),
+            ErrorCode::TagValueInReservedRange => include_str!("error_codes/E43.md"),
+            ErrorCode::DuplicateDefaultTag => include_str!("error_codes/E44.md"),
+            ErrorCode::InvalidOptionalField => include_str!("error_codes/E45.md"),
+            ErrorCode::UndeclaredConditionIdentifier => include_str!("error_codes/E46.md"),
+            ErrorCode::InvalidConditionIdentifier => include_str!("error_codes/E47.md"),
+            ErrorCode::InvalidConditionValue => include_str!("error_codes/E48.md"),
+            ErrorCode::OptionalConditionIdentifier => include_str!("error_codes/E49.md"),
+            ErrorCode::InvalidFieldOffset => include_str!("error_codes/E51.md"),
+            ErrorCode::InvalidFieldSize => include_str!("error_codes/E52.md"),
+            ErrorCode::InvalidPacketSize => include_str!("error_codes/E53.md"),
+            ErrorCode::DefaultValueOutOfRange => include_str!("error_codes/E54.md"),
+            ErrorCode::UndeclaredDefaultTag => include_str!("error_codes/E55.md"),
+            ErrorCode::InvalidDefaultValue => include_str!("error_codes/E56.md"),
+            ErrorCode::InvalidFieldEndianness => include_str!("error_codes/E57.md"),
+            ErrorCode::InvalidSizeUnit => include_str!("error_codes/E58.md"),
+            ErrorCode::InvalidTrailingSizeField => include_str!("error_codes/E59.md"),
+            ErrorCode::InvalidTestInput => include_str!("error_codes/E60.md"),
+            ErrorCode::InvalidEnumWidth => include_str!("error_codes/E61.md"),
+            ErrorCode::InvalidScalarScale => include_str!("error_codes/E62.md"),
+            ErrorCode::InvalidFieldWidth => include_str!("error_codes/E63.md"),
+            ErrorCode::InvalidExtensionField => include_str!("error_codes/E64.md"),
+            ErrorCode::InvalidExtensionPosition => include_str!("error_codes/E65.md"),
+            ErrorCode::InvalidSizeBound => include_str!("error_codes/E66.md"),
+            ErrorCode::UndeclaredBindIdentifier => include_str!("error_codes/E67.md"),
+            ErrorCode::InvalidBindIdentifier => include_str!("error_codes/E68.md"),
+            ErrorCode::InvalidBindField => include_str!("error_codes/E69.md"),
+            ErrorCode::UndeclaredResponseIdentifier => include_str!("error_codes/E70.md"),
+            ErrorCode::InvalidResponseIdentifier => include_str!("error_codes/E71.md"),
+            ErrorCode::DuplicateResponseDeclaration => include_str!("error_codes/E72.md"),
+            ErrorCode::InvalidSizeAssertion => include_str!("error_codes/E73.md"),
+            ErrorCode::UndeclaredInvariantIdentifier => include_str!("error_codes/E74.md"),
+            ErrorCode::InvalidInvariantIdentifier => include_str!("error_codes/E75.md"),
+            ErrorCode::InvalidInvariantExpression => include_str!("error_codes/E76.md"),
+            ErrorCode::DuplicateTagDisplayName => include_str!("error_codes/E77.md"),
+        }
+    }
 }
 
 impl fmt::Display for ErrorCode {
@@ -184,12 +373,144 @@ impl From<ErrorCode> for String {
     }
 }
 
+impl std::str::FromStr for ErrorCode {
+    type Err = String;
+
+    /// Parse an error code (e.g. `E33`).
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .into_iter()
+            .find(|code| code.to_string() == input)
+            .ok_or_else(|| format!("unknown error code {input:?}"))
+    }
+}
+
+/// List of unique warnings reported as analyzer diagnostics.
+/// Warnings flag legal but suspicious declarations and never cause
+/// the analysis to fail.
+#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WarningCode {
+    UnusedDecl = 1,
+    UnmatchedEnumTag = 2,
+    ShadowedChildDecl = 3,
+    OversizedReservedField = 4,
+    UndeclaredSizeField = 5,
+    OverlappingConstraints = 6,
+}
+
+impl WarningCode {
+    /// List of all warning codes.
+    pub const ALL: [WarningCode; 6] = [
+        WarningCode::UnusedDecl,
+        WarningCode::UnmatchedEnumTag,
+        WarningCode::ShadowedChildDecl,
+        WarningCode::OversizedReservedField,
+        WarningCode::UndeclaredSizeField,
+        WarningCode::OverlappingConstraints,
+    ];
+
+    /// Return the name of the warning, used to select the warning
+    /// in `pdlc` options.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningCode::UnusedDecl => "unused_decl",
+            WarningCode::UnmatchedEnumTag => "unmatched_enum_tag",
+            WarningCode::ShadowedChildDecl => "shadowed_child_decl",
+            WarningCode::OversizedReservedField => "oversized_reserved_field",
+            WarningCode::UndeclaredSizeField => "undeclared_size_field",
+            WarningCode::OverlappingConstraints => "overlapping_constraints",
+        }
+    }
+
+    /// Return the long-form explanation of the warning, with examples.
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            WarningCode::UnusedDecl => include_str!("error_codes/W1.md"),
+            WarningCode::UnmatchedEnumTag => include_str!("error_codes/W2.md"),
+            WarningCode::ShadowedChildDecl => include_str!("error_codes/W3.md"),
+            WarningCode::OversizedReservedField => include_str!("error_codes/W4.md"),
+            WarningCode::UndeclaredSizeField => include_str!("error_codes/W5.md"),
+            WarningCode::OverlappingConstraints => include_str!("error_codes/W6.md"),
+        }
+    }
+}
+
+impl fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "W{}", *self as u16)
+    }
+}
+
+impl From<WarningCode> for String {
+    fn from(code: WarningCode) -> Self {
+        format!("{}", code)
+    }
+}
+
+impl std::str::FromStr for WarningCode {
+    type Err = String;
+
+    /// Parse a warning code from either its code (e.g. `W1`)
+    /// or its name (e.g. `unused_decl`).
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        WarningCode::ALL
+            .into_iter()
+            .find(|code| code.to_string() == input || code.name() == input)
+            .ok_or_else(|| format!("unknown warning {input:?}"))
+    }
+}
+
 /// Aggregate analyzer diagnostics.
 #[derive(Debug, Default)]
 pub struct Diagnostics {
     pub diagnostics: Vec<Diagnostic<FileId>>,
 }
 
+/// Severity of a structured diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Bug,
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// Line and column of a source position, both counted from one.
+/// Columns are counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DiagnosticPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Source range annotated by a structured diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticLabel {
+    /// True for the label pointing at the cause of the diagnostic,
+    /// false for labels providing additional context.
+    pub primary: bool,
+    pub message: String,
+    /// Name of the source file.
+    pub file: String,
+    pub start: DiagnosticPosition,
+    /// Position following the end of the range.
+    pub end: DiagnosticPosition,
+}
+
+/// Structured representation of a diagnostic, for machine-readable output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticData {
+    /// Error or warning code, e.g. `E33`.
+    pub code: Option<String>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub labels: Vec<DiagnosticLabel>,
+    pub notes: Vec<String>,
+}
+
 /// Gather information about the full AST.
 #[derive(Debug)]
 pub struct Scope<'d> {
@@ -234,6 +555,48 @@ impl Diagnostics {
         }
         Ok(())
     }
+
+    /// Return the diagnostics as structured data, with the source
+    /// positions of the labels resolved to lines and columns.
+    pub fn structured(
+        &self,
+        sources: &SourceDatabase,
+    ) -> Result<Vec<DiagnosticData>, files::Error> {
+        let position = |file_id, offset| -> Result<DiagnosticPosition, files::Error> {
+            let location = sources.location(file_id, offset)?;
+            Ok(DiagnosticPosition { line: location.line_number, column: location.column_number })
+        };
+        self.diagnostics
+            .iter()
+            .map(|d| {
+                Ok(DiagnosticData {
+                    code: d.code.clone(),
+                    severity: match d.severity {
+                        Severity::Bug => DiagnosticSeverity::Bug,
+                        Severity::Error => DiagnosticSeverity::Error,
+                        Severity::Warning => DiagnosticSeverity::Warning,
+                        Severity::Note => DiagnosticSeverity::Note,
+                        Severity::Help => DiagnosticSeverity::Help,
+                    },
+                    message: d.message.clone(),
+                    labels: d
+                        .labels
+                        .iter()
+                        .map(|label| {
+                            Ok(DiagnosticLabel {
+                                primary: label.style == LabelStyle::Primary,
+                                message: label.message.clone(),
+                                file: sources.name(label.file_id)?,
+                                start: position(label.file_id, label.range.start)?,
+                                end: position(label.file_id, label.range.end)?,
+                            })
+                        })
+                        .collect::<Result<_, files::Error>>()?,
+                    notes: d.notes.clone(),
+                })
+            })
+            .collect()
+    }
 }
 
 impl<'d> Scope<'d> {
@@ -349,11 +712,43 @@ impl<'d> Scope<'d> {
             | FieldDesc::Scalar { .. } => true,
             FieldDesc::Typedef { type_id, .. } => {
                 let field = self.typedef.get(type_id.as_str());
-                matches!(field, Some(Decl { desc: DeclDesc::Enum { .. }, .. }))
+                matches!(
+                    field,
+                    Some(Decl { desc: DeclDesc::Enum { .. } | DeclDesc::Flags { .. }, .. })
+                )
             }
             _ => false,
         }
     }
+
+    /// Test if the selected declaration is recursive, i.e. if the declaration
+    /// is reachable from its own fields. Recursion is only allowed through
+    /// dynamically sized arrays and optional fields.
+    pub fn is_recursive(&self, decl: &'d Decl) -> bool {
+        let Some(id) = decl.id() else { return false };
+        let mut visited = HashSet::new();
+        let mut queue = vec![decl];
+        while let Some(decl) = queue.pop() {
+            for type_decl in self.iter_fields(decl).filter_map(|f| self.get_type_declaration(f)) {
+                let type_id = type_decl.id().unwrap();
+                if type_id == id {
+                    return true;
+                }
+                if visited.insert(type_id) {
+                    queue.push(type_decl);
+                }
+            }
+        }
+        false
+    }
+
+    /// Test if the selected field is an optional field of recursive type.
+    /// The field value must be stored behind an indirection.
+    pub fn is_indirect(&self, field: &Field) -> bool {
+        field.is_optional()
+            && matches!(&field.desc, FieldDesc::Typedef { .. })
+            && self.get_type_declaration(field).is_some_and(|decl| self.is_recursive(decl))
+    }
 }
 
 impl Schema {
@@ -403,12 +798,15 @@ impl Schema {
                     (decl_size, payload_size)
                 }
                 DeclDesc::Enum { width, .. }
+                | DeclDesc::Flags { width, .. }
                 | DeclDesc::Checksum { width, .. }
                 | DeclDesc::CustomField { width: Some(width), .. } => {
                     (Size::Static(*width), Size::Static(0))
                 }
                 DeclDesc::CustomField { width: None, .. } => (Size::Dynamic, Size::Static(0)),
-                DeclDesc::Test { .. } => (Size::Static(0), Size::Static(0)),
+                DeclDesc::Test { .. } | DeclDesc::Bind { .. } | DeclDesc::Response { .. } => {
+                    (Size::Static(0), Size::Static(0))
+                }
             };
 
             schema.parent_size.insert(decl.key, parent_size);
@@ -423,7 +821,7 @@ impl Schema {
             field: &Field,
         ) -> Size {
             let size = match &field.desc {
-                _ if field.cond.is_some() => Size::Dynamic,
+                _ if field.is_optional() => Size::Dynamic,
                 FieldDesc::Checksum { .. } | FieldDesc::Padding { .. } => Size::Static(0),
                 FieldDesc::Size { width, .. }
                 | FieldDesc::Count { width, .. }
@@ -433,19 +831,23 @@ impl Schema {
                 | FieldDesc::Scalar { width, .. } => Size::Static(*width),
                 FieldDesc::Flag { .. } => Size::Static(1),
                 FieldDesc::Body | FieldDesc::Payload { .. } => {
-                    let has_payload_size = decl.fields().any(|field| match &field.desc {
-                        FieldDesc::Size { field_id, .. } => {
-                            field_id == "_body_" || field_id == "_payload_"
-                        }
-                        _ => false,
-                    });
+                    // The size of the payload is unknown when the size field
+                    // is placed after it, and must be deduced from the
+                    // enclosing span.
+                    let has_payload_size =
+                        decl.payload_size().is_some_and(|size| !decl.is_trailing_size(size));
                     if has_payload_size { Size::Dynamic } else { Size::Unknown }
                 }
+                FieldDesc::Typedef { id, .. } if decl.array_size(id).is_some() => {
+                    // The size of the struct is indicated by a size field,
+                    // and may differ from the size of the struct declaration.
+                    Size::Dynamic
+                }
                 FieldDesc::Typedef { type_id, .. }
                 | FieldDesc::FixedEnum { enum_id: type_id, .. }
                 | FieldDesc::Group { group_id: type_id, .. } => {
                     let type_key = scope.get(type_id).unwrap();
-                    schema.total_size(*type_key)
+                    schema.recursive_total_size(*type_key)
                 }
                 FieldDesc::Array { width: Some(width), size: Some(size), .. } => {
                     Size::Static(*size * *width)
@@ -454,18 +856,14 @@ impl Schema {
                     width: None, size: Some(size), type_id: Some(type_id), ..
                 } => {
                     let type_key = scope.get(type_id).unwrap();
-                    schema.total_size(*type_key) * *size
+                    schema.recursive_total_size(*type_key) * *size
                 }
                 FieldDesc::Array { id, size: None, .. } => {
                     // The element does not matter when the size of the array is
                     // not static. The array size depends on there being a count
-                    // or size field or not.
-                    let has_array_size = decl.fields().any(|field| match &field.desc {
-                        FieldDesc::Size { field_id, .. } | FieldDesc::Count { field_id, .. } => {
-                            field_id == id
-                        }
-                        _ => false,
-                    });
+                    // or size field placed before the array or not.
+                    let has_array_size =
+                        decl.array_size(id).is_some_and(|size| !decl.is_trailing_size(size));
                     if has_array_size { Size::Dynamic } else { Size::Unknown }
                 }
                 FieldDesc::Array { .. } => unreachable!(),
@@ -520,6 +918,13 @@ impl Schema {
     pub fn total_size(&self, key: DeclKey) -> Size {
         self.decl_size(key) + self.parent_size(key) + self.payload_size(key)
     }
+
+    /// Return the total size of a declaration during annotation.
+    /// Declarations that are not yet annotated are part of a recursive
+    /// cycle through an optional field, and have a dynamic size.
+    fn recursive_total_size(&self, key: DeclKey) -> Size {
+        if self.decl_size.contains_key(&key) { self.total_size(key) } else { Size::Dynamic }
+    }
 }
 
 /// Compute the element size of an array field.
@@ -545,6 +950,7 @@ pub fn array_size(decl: &Decl, field: &Field) -> ArraySize {
     match &field.desc {
         FieldDesc::Array { size: Some(count), .. } => ArraySize::StaticCount(*count),
         FieldDesc::Array { id, .. } => match decl.array_size(id) {
+            Some(size) if decl.is_trailing_size(size) => ArraySize::Unknown,
             Some(Field { desc: FieldDesc::Count { .. }, .. }) => ArraySize::DynamicCount,
             Some(Field { desc: FieldDesc::Size { .. }, .. }) => ArraySize::DynamicSize,
             _ => ArraySize::Unknown,
@@ -554,8 +960,10 @@ pub fn array_size(decl: &Decl, field: &Field) -> ArraySize {
 }
 
 /// Return the bit-width of a scalar value.
-fn bit_width(value: usize) -> usize {
-    usize::BITS as usize - value.leading_zeros() as usize
+/// Values are compared as `u128` to cover the scalar fields wider
+/// than 64 bits.
+fn bit_width(value: u128) -> usize {
+    u128::BITS as usize - value.leading_zeros() as usize
 }
 
 /// Return the maximum value for a scalar value.
@@ -579,13 +987,16 @@ fn scalar_max(width: usize) -> usize {
 /// reordered by topological sort to remove forward references.
 fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostics> {
     enum Mark {
-        Temporary,
+        /// The declaration is being visited; records the number of
+        /// optional fields traversed before entering the declaration.
+        Temporary(usize),
         Permanent,
     }
     #[derive(Default)]
     struct Context<'d> {
         history: Vec<Decl>,
         visited: HashMap<&'d str, Mark>,
+        optional_depth: usize,
     }
 
     fn bfs<'d>(
@@ -597,7 +1008,9 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
         let decl_id = decl.id().unwrap();
         match context.visited.get(decl_id) {
             Some(Mark::Permanent) => return,
-            Some(Mark::Temporary) => {
+            // The cycle goes through an optional field, the recursion is finite.
+            Some(Mark::Temporary(depth)) if *depth < context.optional_depth => return,
+            Some(Mark::Temporary(_)) => {
                 diagnostics.push(
                     Diagnostic::error()
                         .with_code(ErrorCode::RecursiveDecl)
@@ -614,7 +1027,7 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
         }
 
         // Start visiting current declaration.
-        context.visited.insert(decl_id, Mark::Temporary);
+        context.visited.insert(decl_id, Mark::Temporary(context.optional_depth));
 
         // Iterate over Struct and Group fields.
         for field in decl.fields() {
@@ -669,7 +1082,12 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
                         Some(typedef_decl) =>
                             // Not recursing on array type since it is allowed to
                             // have recursive structures, e.g. nested TLV types.
-                            if matches!(&field.desc, FieldDesc::Typedef { .. }) ||
+                            // Recursion through optional fields is allowed as well.
+                            if matches!(&field.desc, FieldDesc::Typedef { .. }) && field.cond.is_some() {
+                                context.optional_depth += 1;
+                                bfs(typedef_decl, context, scope, diagnostics);
+                                context.optional_depth -= 1;
+                            } else if matches!(&field.desc, FieldDesc::Typedef { .. }) ||
                                matches!(&field.desc, FieldDesc::Array { size: Some(_), .. }) {
                                 bfs(typedef_decl, context, scope, diagnostics)
                             }
@@ -680,6 +1098,29 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
             }
         }
 
+        // Iterate over the base enum declaration.
+        if let DeclDesc::Enum { parent_id: Some(parent_id), .. } = &decl.desc {
+            match scope.typedef.get(parent_id) {
+                None => diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::UndeclaredParentIdentifier)
+                        .with_message(format!("undeclared parent identifier `{parent_id}`"))
+                        .with_labels(vec![decl.loc.primary()])
+                        .with_notes(vec!["hint: expected enum identifier".to_owned()]),
+                ),
+                Some(parent_decl @ Decl { desc: DeclDesc::Enum { .. }, .. }) => {
+                    bfs(parent_decl, context, scope, diagnostics)
+                }
+                Some(_) => diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidParentIdentifier)
+                        .with_message(format!("invalid parent identifier `{parent_id}`"))
+                        .with_labels(vec![decl.loc.primary()])
+                        .with_notes(vec!["hint: expected enum identifier".to_owned()]),
+                ),
+            }
+        }
+
         // Iterate over parent declaration.
         if let Some(parent_id) = decl.parent_id() {
             let parent_decl = scope.typedef.get(parent_id);
@@ -718,12 +1159,14 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
 
     // Start bfs.
     let mut diagnostics = Default::default();
-    let mut context = Default::default();
+    let mut context: Context = Default::default();
+    let mut tests = vec![];
     for decl in &file.declarations {
         match &decl.desc {
             DeclDesc::Checksum { .. }
             | DeclDesc::CustomField { .. }
             | DeclDesc::Enum { .. }
+            | DeclDesc::Flags { .. }
             | DeclDesc::Packet { .. }
             | DeclDesc::Struct { .. }
             | DeclDesc::Group { .. } => bfs(decl, &mut context, scope, &mut diagnostics),
@@ -735,7 +1178,7 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
                         .with_labels(vec![decl.loc.primary()])
                         .with_notes(vec!["hint: expected packet identifier".to_owned()]),
                 ),
-                Some(Decl { desc: DeclDesc::Packet { .. }, .. }) => (),
+                Some(Decl { desc: DeclDesc::Packet { .. }, .. }) => tests.push(decl.clone()),
                 Some(_) => diagnostics.push(
                     Diagnostic::error()
                         .with_code(ErrorCode::InvalidTestIdentifier)
@@ -744,14 +1187,77 @@ fn check_decl_identifiers(file: &File, scope: &Scope) -> Result<File, Diagnostic
                         .with_notes(vec!["hint: expected packet identifier".to_owned()]),
                 ),
             },
+            DeclDesc::Bind { packet_id, type_id, .. } => {
+                let mut valid = true;
+                for id in [packet_id, type_id] {
+                    match scope.typedef.get(id) {
+                        None => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::UndeclaredBindIdentifier)
+                                .with_message(format!("undeclared bind identifier `{id}`"))
+                                .with_labels(vec![decl.loc.primary()])
+                                .with_notes(vec![
+                                    "hint: expected packet or struct identifier".to_owned(),
+                                ]),
+                        ),
+                        Some(Decl {
+                            desc: DeclDesc::Packet { .. } | DeclDesc::Struct { .. },
+                            ..
+                        }) => continue,
+                        Some(_) => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidBindIdentifier)
+                                .with_message(format!("invalid bind identifier `{id}`"))
+                                .with_labels(vec![decl.loc.primary()])
+                                .with_notes(vec![
+                                    "hint: expected packet or struct identifier".to_owned(),
+                                ]),
+                        ),
+                    }
+                    valid = false;
+                }
+                if valid {
+                    tests.push(decl.clone())
+                }
+            }
+            DeclDesc::Response { request_id, response_id } => {
+                let mut valid = true;
+                for id in [request_id, response_id] {
+                    match scope.typedef.get(id) {
+                        None => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::UndeclaredResponseIdentifier)
+                                .with_message(format!("undeclared response identifier `{id}`"))
+                                .with_labels(vec![decl.loc.primary()])
+                                .with_notes(vec!["hint: expected packet identifier".to_owned()]),
+                        ),
+                        Some(Decl { desc: DeclDesc::Packet { .. }, .. }) => continue,
+                        Some(_) => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidResponseIdentifier)
+                                .with_message(format!("invalid response identifier `{id}`"))
+                                .with_labels(vec![decl.loc.primary()])
+                                .with_notes(vec!["hint: expected packet identifier".to_owned()]),
+                        ),
+                    }
+                    valid = false;
+                }
+                if valid {
+                    tests.push(decl.clone())
+                }
+            }
         }
     }
 
+    // Test, bind, and response declarations are kept after the
+    // declarations they refer to.
+    context.history.extend(tests);
     diagnostics.err_or(File {
         version: file.version.clone(),
         file: file.file,
         comments: file.comments.clone(),
         endianness: file.endianness,
+        bit_order: file.bit_order,
         declarations: context.history,
         max_key: file.max_key,
     })
@@ -790,10 +1296,116 @@ fn check_field_identifiers(file: &File) -> Result<(), Diagnostics> {
     diagnostics.err_or(())
 }
 
-/// Check enum declarations.
+/// Inherit the tags and width of base enums in enum extensions.
+/// Raises error diagnostics for the following cases:
+///      - enum extension width different from the base enum width
+///
+/// Returns a copy of the file where the tags of the base enum are
+/// prepended to the tags of each enum extension. Collisions between
+/// inherited and extension tags are reported by `check_enum_declarations`.
+fn inherit_enum_tags(file: &File) -> Result<File, Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    let mut enums: HashMap<String, (Vec<Tag>, usize)> = HashMap::new();
+    let mut file = file.clone();
+
+    // Declarations are sorted in topological order:
+    // base enums are visited before their extensions.
+    for decl in &mut file.declarations {
+        let DeclDesc::Enum { id, tags, width, parent_id } = &mut decl.desc else {
+            continue;
+        };
+        if let Some((parent_tags, parent_width)) =
+            parent_id.as_ref().and_then(|parent_id| enums.get(parent_id))
+        {
+            if *width == 0 {
+                *width = *parent_width;
+            } else if *width != *parent_width {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidEnumWidth)
+                        .with_message(format!(
+                            "enum width `{}` does not match the width `{}` of the base enum `{}`",
+                            width,
+                            parent_width,
+                            parent_id.as_ref().unwrap()
+                        ))
+                        .with_labels(vec![decl.loc.primary()]),
+                )
+            }
+            *tags = parent_tags.iter().cloned().chain(tags.drain(..)).collect();
+        }
+        enums.insert(id.clone(), (tags.clone(), *width));
+    }
+
+    diagnostics.err_or(file)
+}
+
+/// Maximum width of scalar, array element, and custom field values.
+const MAX_SCALAR_WIDTH: usize = 128;
+
+/// Maximum width of enum, flags, checksum, fixed, size, and count values.
+const MAX_VALUE_WIDTH: usize = 64;
+
+/// Check field and declaration widths.
+/// Raises error diagnostics for the following cases:
+///      - scalar or custom field larger than 128 bits
+///      - enum, flags, checksum, fixed, size, or count field
+///        larger than 64 bits
+fn check_field_widths(file: &File) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    let mut check_width = |width: usize, max_width: usize, kind: &str, loc: &SourceRange| {
+        if width > max_width {
+            diagnostics.push(
+                Diagnostic::error()
+                    .with_code(ErrorCode::InvalidFieldWidth)
+                    .with_message(format!("{kind} width `{width}` exceeds {max_width} bits"))
+                    .with_labels(vec![loc.primary()]),
+            )
+        }
+    };
+
+    for decl in &file.declarations {
+        match &decl.desc {
+            DeclDesc::CustomField { width: Some(width), .. } => {
+                check_width(*width, MAX_SCALAR_WIDTH, "custom field", &decl.loc)
+            }
+            DeclDesc::Checksum { width, .. } => {
+                check_width(*width, MAX_VALUE_WIDTH, "checksum", &decl.loc)
+            }
+            DeclDesc::Enum { width, .. } => check_width(*width, MAX_VALUE_WIDTH, "enum", &decl.loc),
+            DeclDesc::Flags { width, .. } => {
+                check_width(*width, MAX_VALUE_WIDTH, "flags", &decl.loc)
+            }
+            _ => (),
+        }
+
+        for field in decl.fields() {
+            match &field.desc {
+                FieldDesc::Scalar { width, .. } | FieldDesc::Array { width: Some(width), .. } => {
+                    check_width(*width, MAX_SCALAR_WIDTH, "scalar", &field.loc)
+                }
+                FieldDesc::Size { width, .. }
+                | FieldDesc::Count { width, .. }
+                | FieldDesc::ElementSize { width, .. } => {
+                    check_width(*width, MAX_VALUE_WIDTH, "size", &field.loc)
+                }
+                FieldDesc::FixedScalar { width, .. } => {
+                    check_width(*width, MAX_VALUE_WIDTH, "fixed", &field.loc)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check enum and flags declarations.
 /// Raises error diagnostics for the following cases:
-///      - duplicate tag identifier
+///      - duplicate tag identifier or alias
 ///      - duplicate tag value
+///      - duplicate tag display name
+///      - flag value zero
 fn check_enum_declarations(file: &File) -> Result<(), Diagnostics> {
     // Return the inclusive range with bounds correctly ordered.
     // The analyzer will raise an error if the bounds are incorrectly ordered, but this
@@ -810,17 +1422,19 @@ fn check_enum_declarations(file: &File) -> Result<(), Diagnostics> {
         tags_by_value: &mut HashMap<usize, SourceRange>,
         diagnostics: &mut Diagnostics,
     ) {
-        if let Some(prev) = tags_by_id.insert(&tag.id, tag.loc) {
-            diagnostics.push(
-                Diagnostic::error()
-                    .with_code(ErrorCode::DuplicateTagIdentifier)
-                    .with_message(format!("duplicate tag identifier `{}`", tag.id))
-                    .with_labels(vec![
-                        tag.loc.primary(),
-                        prev.secondary()
-                            .with_message(format!("`{}` is first declared here", tag.id)),
-                    ]),
-            )
+        // Aliases share the namespace of the tag identifiers.
+        for id in std::iter::once(&tag.id).chain(tag.aliases.iter()) {
+            if let Some(prev) = tags_by_id.insert(id, tag.loc) {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::DuplicateTagIdentifier)
+                        .with_message(format!("duplicate tag identifier `{id}`"))
+                        .with_labels(vec![
+                            tag.loc.primary(),
+                            prev.secondary().with_message(format!("`{id}` is first declared here")),
+                        ]),
+                )
+            }
         }
         if let Some(prev) = tags_by_value.insert(tag.value, tag.loc) {
             diagnostics.push(
@@ -851,7 +1465,7 @@ fn check_enum_declarations(file: &File) -> Result<(), Diagnostics> {
             if ordered_range(&reserved_range.range).contains(&tag.value) {
                 diagnostics.push(
                     Diagnostic::error()
-                        .with_code(ErrorCode::E43)
+                        .with_code(ErrorCode::TagValueInReservedRange)
                         .with_message(format!(
                             "tag value `{}` is declared inside the reserved range `{} = {}..{}`",
                             tag.value,
@@ -987,6 +1601,33 @@ fn check_enum_declarations(file: &File) -> Result<(), Diagnostics> {
                 }
             }
 
+            // Display names are used to convert values from strings,
+            // and must identify a single tag.
+            let mut tags_by_display_name = HashMap::new();
+            let value_tags = tags.iter().flat_map(|tag| match tag {
+                Tag::Value(tag) => std::slice::from_ref(tag),
+                Tag::Range(tag) => tag.tags.as_slice(),
+                Tag::Other(_) => &[],
+            });
+            for tag in value_tags {
+                let Some(display_name) = &tag.display_name else {
+                    continue;
+                };
+                if let Some(prev) = tags_by_display_name.insert(display_name, tag.loc) {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::DuplicateTagDisplayName)
+                            .with_message(format!("duplicate tag display name \"{display_name}\""))
+                            .with_labels(vec![
+                                tag.loc.primary(),
+                                prev.secondary().with_message(format!(
+                                    "\"{display_name}\" is first declared here"
+                                )),
+                            ]),
+                    )
+                }
+            }
+
             // Order tag ranges by increasing bounds in order to check for intersecting ranges.
             tags_by_range.sort_by(|lhs, rhs| {
                 ordered_range(&lhs.range).into_inner().cmp(&ordered_range(&rhs.range).into_inner())
@@ -1021,6 +1662,20 @@ fn check_enum_declarations(file: &File) -> Result<(), Diagnostics> {
                 }
             }
         }
+        if let DeclDesc::Flags { flags, width, .. } = &decl.desc {
+            let mut flags_by_id = HashMap::new();
+            let mut flags_by_value = HashMap::new();
+            for flag in flags {
+                check_tag_value(
+                    flag,
+                    1..=scalar_max(*width),
+                    [].iter(),
+                    &mut flags_by_id,
+                    &mut flags_by_value,
+                    &mut diagnostics,
+                )
+            }
+        }
     }
 
     diagnostics.err_or(())
@@ -1054,11 +1709,37 @@ fn check_constraint(
                 ])
                 .with_notes(vec!["hint: expected scalar or typedef identifier".to_owned()]),
         ),
+        Some(field @ Field { desc: FieldDesc::Flag { .. }, .. }) => diagnostics.push(
+            Diagnostic::error()
+                .with_code(ErrorCode::InvalidConstraintIdentifier)
+                .with_message(format!("invalid constraint identifier `{}`", constraint.id))
+                .with_labels(vec![
+                    constraint.loc.primary(),
+                    field.loc.secondary().with_message(format!(
+                        "`{}` is declared here as optional field condition",
+                        constraint.id
+                    )),
+                ])
+                .with_notes(vec!["hint: expected scalar or typedef identifier".to_owned()]),
+        ),
+        Some(field @ Field { extension: Some(_), .. }) => diagnostics.push(
+            Diagnostic::error()
+                .with_code(ErrorCode::InvalidConstraintIdentifier)
+                .with_message(format!("invalid constraint identifier `{}`", constraint.id))
+                .with_labels(vec![
+                    constraint.loc.primary(),
+                    field.loc.secondary().with_message(format!(
+                        "`{}` is declared here as extension field",
+                        constraint.id
+                    )),
+                ])
+                .with_notes(vec!["hint: expected scalar or typedef identifier".to_owned()]),
+        ),
         Some(field @ Field { desc: FieldDesc::Scalar { width, .. }, .. }) => {
             match constraint.value {
                 None => diagnostics.push(
                     Diagnostic::error()
-                        .with_code(ErrorCode::E17)
+                        .with_code(ErrorCode::InvalidConstraintScalarValue)
                         .with_message(format!(
                             "invalid constraint value `{}`",
                             constraint.tag_id.as_ref().unwrap()
@@ -1089,7 +1770,7 @@ fn check_constraint(
                 Some(Decl { desc: DeclDesc::Enum { tags, .. }, .. }) => match &constraint.tag_id {
                     None => diagnostics.push(
                         Diagnostic::error()
-                            .with_code(ErrorCode::E19)
+                            .with_code(ErrorCode::InvalidConstraintEnumValue)
                             .with_message(format!(
                                 "invalid constraint value `{}`",
                                 constraint.value.unwrap()
@@ -1106,7 +1787,7 @@ fn check_constraint(
                     Some(tag_id) => match tags.iter().find(|tag| tag.id() == tag_id) {
                         None => diagnostics.push(
                             Diagnostic::error()
-                                .with_code(ErrorCode::E20)
+                                .with_code(ErrorCode::UndeclaredConstraintTag)
                                 .with_message(format!("undeclared enum tag `{tag_id}`"))
                                 .with_labels(vec![
                                     constraint.loc.primary(),
@@ -1118,7 +1799,7 @@ fn check_constraint(
                         ),
                         Some(Tag::Range { .. }) => diagnostics.push(
                             Diagnostic::error()
-                                .with_code(ErrorCode::E42)
+                                .with_code(ErrorCode::InvalidConstraintTagRange)
                                 .with_message(format!("enum tag `{tag_id}` defines a range"))
                                 .with_labels(vec![
                                     constraint.loc.primary(),
@@ -1134,7 +1815,7 @@ fn check_constraint(
                 },
                 Some(decl) => diagnostics.push(
                     Diagnostic::error()
-                        .with_code(ErrorCode::E21)
+                        .with_code(ErrorCode::InvalidConstraintType)
                         .with_message(format!(
                             "invalid constraint identifier `{}`",
                             constraint.value.unwrap()
@@ -1221,6 +1902,126 @@ fn check_decl_constraints(file: &File, scope: &Scope) -> Result<(), Diagnostics>
     diagnostics.err_or(())
 }
 
+/// Set of field values matched by a constraint.
+enum ConstraintValues {
+    /// Range of scalar values, or of enum values for constraints
+    /// on enum tags with values or ranges.
+    Range(std::ops::RangeInclusive<u128>),
+    /// Enum values not covered by any declared tag, for constraints
+    /// on the default enum tag.
+    Default,
+}
+
+impl ConstraintValues {
+    fn new(constraint: &Constraint, parent_decl: &Decl, scope: &Scope) -> ConstraintValues {
+        let tag_id = match constraint {
+            Constraint { value: Some(value), .. } => {
+                return ConstraintValues::Range(*value..=*value);
+            }
+            Constraint { tag_id: Some(tag_id), .. } => tag_id,
+            _ => unreachable!(),
+        };
+        let field = scope
+            .iter_parents_and_self(parent_decl)
+            .flat_map(Decl::fields)
+            .find(|field| field.id() == Some(&constraint.id))
+            .unwrap();
+        let Some(Decl { desc: DeclDesc::Enum { tags, .. }, .. }) =
+            scope.get_type_declaration(field)
+        else {
+            unreachable!()
+        };
+        match tags.iter().find(|tag| tag.id() == tag_id).unwrap() {
+            Tag::Value(TagValue { value, .. }) => {
+                ConstraintValues::Range(*value as u128..=*value as u128)
+            }
+            Tag::Range(TagRange { range, .. }) => {
+                ConstraintValues::Range(*range.start() as u128..=*range.end() as u128)
+            }
+            Tag::Other(_) => ConstraintValues::Default,
+        }
+    }
+
+    /// Test if the two sets of values intersect. Tag values and ranges
+    /// are disjoint from the values of the default tag.
+    fn overlaps(&self, other: &ConstraintValues) -> bool {
+        match (self, other) {
+            (ConstraintValues::Range(lhs), ConstraintValues::Range(rhs)) => {
+                lhs.start() <= rhs.end() && rhs.start() <= lhs.end()
+            }
+            (ConstraintValues::Default, ConstraintValues::Default) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Gather the sets of constraints on the fields of `parent_decl` matched
+/// by the declaration `decl` or any of its descendants, with the size of
+/// the parent payload for the matched declaration. Sets without
+/// constraints on the fields of `parent_decl` are left out, as the
+/// declaration cannot be specialized from the parent fields alone.
+fn gather_constraint_sets<'d>(
+    parent_decl: &Decl,
+    decl: &'d Decl,
+    scope: &Scope<'d>,
+    schema: &Schema,
+    constraints: &[&'d Constraint],
+    size: Size,
+    constraint_sets: &mut Vec<(Vec<&'d Constraint>, Size)>,
+) {
+    let mut constraints = constraints.to_vec();
+    constraints.extend(decl.constraints().filter(|constraint| {
+        scope.iter_fields(parent_decl).any(|field| field.id() == Some(&constraint.id))
+    }));
+    let size = size + schema.decl_size(decl.key);
+    for child in scope.iter_children(decl) {
+        gather_constraint_sets(
+            parent_decl,
+            child,
+            scope,
+            schema,
+            &constraints,
+            size,
+            constraint_sets,
+        );
+    }
+    if !constraints.is_empty() {
+        constraint_sets.push((constraints, size + schema.payload_size(decl.key)));
+    }
+}
+
+/// Test if the constraints of two child declarations of `parent_decl`
+/// can be matched by the same field values. Fields constrained by only
+/// one of the declarations can take any value. Declarations with
+/// different static sizes are disambiguated by the payload size.
+fn constraints_overlap(
+    parent_decl: &Decl,
+    lhs: &Decl,
+    rhs: &Decl,
+    scope: &Scope,
+    schema: &Schema,
+) -> bool {
+    let mut lhs_sets = vec![];
+    let mut rhs_sets = vec![];
+    gather_constraint_sets(parent_decl, lhs, scope, schema, &[], Size::Static(0), &mut lhs_sets);
+    gather_constraint_sets(parent_decl, rhs, scope, schema, &[], Size::Static(0), &mut rhs_sets);
+    lhs_sets.iter().any(|(lhs, lhs_size)| {
+        rhs_sets.iter().any(|(rhs, rhs_size)| {
+            let different_sizes = matches!(
+                (lhs_size, rhs_size),
+                (Size::Static(lhs_size), Size::Static(rhs_size)) if lhs_size != rhs_size
+            );
+            !different_sizes
+                && lhs.iter().all(|lhs| {
+                    rhs.iter().filter(|rhs| rhs.id == lhs.id).all(|rhs| {
+                        ConstraintValues::new(lhs, parent_decl, scope)
+                            .overlaps(&ConstraintValues::new(rhs, parent_decl, scope))
+                    })
+                })
+        })
+    })
+}
+
 /// Check constraints.
 /// Raises error diagnostics for the following cases:
 ///      - undeclared constraint identifier
@@ -1251,29 +2052,271 @@ fn check_group_constraints(file: &File, scope: &Scope) -> Result<(), Diagnostics
     diagnostics.err_or(())
 }
 
-/// Check size fields.
+/// Check test cases.
 /// Raises error diagnostics for the following cases:
-///      - undeclared size identifier
-///      - invalid size identifier
-///      - duplicate size field
-///      - undeclared count identifier
-///      - invalid count identifier
-///      - duplicate count field
-///      - undeclared elementsize identifier
-///      - invalid elementsize identifier
-///      - duplicate elementsize field
-fn check_size_fields(file: &File) -> Result<(), Diagnostics> {
+///      - invalid escape sequence in test input
+///      - undeclared test field identifier
+///      - invalid test field identifier
+///      - invalid test field scalar value (bad type)
+///      - invalid test field scalar value (overflow)
+///      - invalid test field enum value (bad type)
+///      - invalid test field enum value (undeclared tag)
+///      - duplicate test field
+fn check_test_declarations(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
     let mut diagnostics: Diagnostics = Default::default();
     for decl in &file.declarations {
-        let mut size_for_id = HashMap::new();
-        let mut element_size_for_id = HashMap::new();
-        for field in decl.fields() {
-            // Check for duplicate size, count, or element size fields.
-            if let Some((reverse_map, field_id, err)) = match &field.desc {
-                FieldDesc::Size { field_id, .. } => {
-                    Some((&mut size_for_id, field_id, ErrorCode::DuplicateSizeField))
-                }
-                FieldDesc::Count { field_id, .. } => {
+        let DeclDesc::Test { type_id, test_cases } = &decl.desc else {
+            continue;
+        };
+        let Some(packet_decl) = scope.typedef.get(type_id) else {
+            continue;
+        };
+        for test_case in test_cases {
+            if let Err(err) = test_case.bytes() {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidTestInput)
+                        .with_message(format!("invalid test input: {err}"))
+                        .with_labels(vec![test_case.loc.primary()])
+                        .with_notes(vec![
+                            "hint: use `\\xHH` to encode arbitrary octets".to_owned(),
+                        ]),
+                )
+            }
+            // Expected field values follow the same rules as
+            // constraints on the tested packet.
+            check_constraints_list(
+                &test_case.fields,
+                packet_decl,
+                scope,
+                HashMap::new(),
+                &mut diagnostics,
+            )
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check invariant clauses.
+/// Raises error diagnostics for the following cases:
+///      - undeclared field identifier
+///      - invalid field identifier, invariants reference non optional
+///        scalar fields of at most 64 bits, and the length of
+///        non optional array fields
+///      - invariant expression is not a condition, or mixes
+///        integer and condition operands
+fn check_invariants(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum ExprType {
+        Integer,
+        Condition,
+    }
+
+    fn check_expr(
+        expr: &Expr,
+        decl: &Decl,
+        invariant: &Invariant,
+        scope: &Scope,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<ExprType> {
+        match expr {
+            Expr::Integer { .. } => Some(ExprType::Integer),
+            Expr::Field { id } | Expr::Length { id } => {
+                let is_length = matches!(expr, Expr::Length { .. });
+                let hint = if is_length {
+                    "hint: expected array field identifier"
+                } else {
+                    "hint: expected scalar field identifier"
+                };
+                match scope.iter_fields(decl).find(|field| field.id() == Some(id)) {
+                    None => diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::UndeclaredInvariantIdentifier)
+                            .with_message(format!("undeclared invariant identifier `{id}`"))
+                            .with_labels(vec![invariant.loc.primary()])
+                            .with_notes(vec![hint.to_owned()]),
+                    ),
+                    Some(field) => match &field.desc {
+                        FieldDesc::Scalar { width, .. }
+                            if !is_length && *width <= 64 && !field.is_optional() =>
+                        {
+                            return Some(ExprType::Integer);
+                        }
+                        FieldDesc::Array { .. } if is_length && !field.is_optional() => {
+                            return Some(ExprType::Integer);
+                        }
+                        _ => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidInvariantIdentifier)
+                                .with_message(format!("invalid invariant identifier `{id}`"))
+                                .with_labels(vec![
+                                    invariant.loc.primary(),
+                                    field
+                                        .loc
+                                        .secondary()
+                                        .with_message(format!("`{id}` is declared here")),
+                                ])
+                                .with_notes(vec![hint.to_owned()]),
+                        ),
+                    },
+                }
+                None
+            }
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = check_expr(lhs, decl, invariant, scope, diagnostics);
+                let rhs = check_expr(rhs, decl, invariant, scope, diagnostics);
+                let (operand_type, result_type) = match op {
+                    BinaryOp::Mul | BinaryOp::Add | BinaryOp::Sub => {
+                        (ExprType::Integer, ExprType::Integer)
+                    }
+                    BinaryOp::And | BinaryOp::Or => (ExprType::Condition, ExprType::Condition),
+                    _ => (ExprType::Integer, ExprType::Condition),
+                };
+                match (lhs?, rhs?) {
+                    (lhs, rhs) if lhs == operand_type && rhs == operand_type => Some(result_type),
+                    _ => {
+                        diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidInvariantExpression)
+                                .with_message(format!(
+                                    "invalid invariant expression `{}`",
+                                    invariant.text
+                                ))
+                                .with_labels(vec![invariant.loc.primary()])
+                                .with_notes(vec![format!(
+                                    "hint: the operator `{op}` expects {} operands",
+                                    if operand_type == ExprType::Integer {
+                                        "integer"
+                                    } else {
+                                        "condition"
+                                    }
+                                )]),
+                        );
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for invariant in decl.invariants() {
+            if check_expr(&invariant.expr, decl, invariant, scope, &mut diagnostics)
+                == Some(ExprType::Integer)
+            {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidInvariantExpression)
+                        .with_message(format!("invalid invariant expression `{}`", invariant.text))
+                        .with_labels(vec![invariant.loc.primary()])
+                        .with_notes(vec!["hint: expected a condition".to_owned()]),
+                )
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check bind declarations.
+/// Raises error diagnostics for the following cases:
+///      - invalid bind field identifier
+///      - bound packet without payload or body field
+///      - invalid bind constraints, following the rules
+///        of packet constraints
+fn check_bind_declarations(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let DeclDesc::Bind { packet_id, field_id, constraints, .. } = &decl.desc else {
+            continue;
+        };
+        let Some(packet_decl) = scope.typedef.get(packet_id) else {
+            continue;
+        };
+        match (field_id.as_str(), packet_decl.payload().map(|field| &field.desc)) {
+            ("payload" | "_payload_", Some(FieldDesc::Payload { .. }))
+            | ("payload" | "_body_", Some(FieldDesc::Body)) => (),
+            (_, None) => diagnostics.push(
+                Diagnostic::error()
+                    .with_code(ErrorCode::InvalidBindField)
+                    .with_message(format!("`{packet_id}` has no payload or body field"))
+                    .with_labels(vec![
+                        decl.loc.primary(),
+                        packet_decl
+                            .loc
+                            .secondary()
+                            .with_message(format!("`{packet_id}` is declared here")),
+                    ]),
+            ),
+            (_, Some(_)) => diagnostics.push(
+                Diagnostic::error()
+                    .with_code(ErrorCode::InvalidBindField)
+                    .with_message(format!("invalid bind field identifier `{field_id}`"))
+                    .with_labels(vec![decl.loc.primary()])
+                    .with_notes(vec![
+                        "hint: expected `payload`, `_payload_` or `_body_`".to_owned(),
+                    ]),
+            ),
+        }
+        check_constraints_list(constraints, packet_decl, scope, HashMap::new(), &mut diagnostics)
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check response declarations.
+/// Raises error diagnostics for the following cases:
+///      - multiple response declarations for the same packet
+fn check_response_declarations(file: &File) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    let mut responses: HashMap<&str, &Decl> = HashMap::new();
+    for decl in &file.declarations {
+        let DeclDesc::Response { request_id, .. } = &decl.desc else {
+            continue;
+        };
+        if let Some(prev) = responses.insert(request_id, decl) {
+            diagnostics.push(
+                Diagnostic::error()
+                    .with_code(ErrorCode::DuplicateResponseDeclaration)
+                    .with_message(format!("redeclaration of the response to `{request_id}`"))
+                    .with_labels(vec![
+                        decl.loc.primary(),
+                        prev.loc.secondary().with_message(format!(
+                            "`{request_id}` response is first declared here"
+                        )),
+                    ]),
+            )
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check size fields.
+/// Raises error diagnostics for the following cases:
+///      - undeclared size identifier
+///      - invalid size identifier
+///      - duplicate size field
+///      - undeclared count identifier
+///      - invalid count identifier
+///      - duplicate count field
+///      - undeclared elementsize identifier
+///      - invalid elementsize identifier
+///      - duplicate elementsize field
+fn check_size_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let mut size_for_id = HashMap::new();
+        let mut element_size_for_id = HashMap::new();
+        for field in decl.fields() {
+            // Check for duplicate size, count, or element size fields.
+            if let Some((reverse_map, field_id, err)) = match &field.desc {
+                FieldDesc::Size { field_id, .. } => {
+                    Some((&mut size_for_id, field_id, ErrorCode::DuplicateSizeField))
+                }
+                FieldDesc::Count { field_id, .. } => {
                     Some((&mut size_for_id, field_id, ErrorCode::DuplicateCountField))
                 }
                 FieldDesc::ElementSize { field_id, .. } => {
@@ -1315,12 +2358,21 @@ fn check_size_fields(file: &File) -> Result<(), Diagnostics> {
                                 ))
                                 .with_labels(vec![field.loc.primary()])
                                 .with_notes(vec![
-                                    "hint: expected payload, body, or array identifier".to_owned(),
+                                    "hint: expected payload, body, array, or struct identifier"
+                                        .to_owned(),
                                 ]),
                         ),
                         Some(Field { desc: FieldDesc::Body, .. })
                         | Some(Field { desc: FieldDesc::Payload { .. }, .. })
                         | Some(Field { desc: FieldDesc::Array { .. }, .. }) => (),
+                        Some(Field {
+                            desc: FieldDesc::Typedef { type_id, .. },
+                            cond: None,
+                            ..
+                        }) if matches!(
+                            scope.typedef.get(type_id),
+                            Some(Decl { desc: DeclDesc::Struct { .. }, .. })
+                        ) => {}
                         Some(Field { loc, .. }) => diagnostics.push(
                             Diagnostic::error()
                                 .with_code(ErrorCode::InvalidSizeIdentifier)
@@ -1331,7 +2383,8 @@ fn check_size_fields(file: &File) -> Result<(), Diagnostics> {
                                 ))
                                 .with_labels(vec![field.loc.primary(), loc.secondary()])
                                 .with_notes(vec![
-                                    "hint: expected payload, body, or array identifier".to_owned(),
+                                    "hint: expected payload, body, array, or struct identifier"
+                                        .to_owned(),
                                 ]),
                         ),
                     }
@@ -1384,6 +2437,62 @@ fn check_size_fields(file: &File) -> Result<(), Diagnostics> {
     diagnostics.err_or(())
 }
 
+/// Check size field units.
+/// Raises error diagnostics for the following cases:
+///      - word unit width is not a non-zero multiple of 8
+///      - payload size modifier is not a multiple of the size unit
+fn check_size_units(file: &File) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for field in decl.fields() {
+            let FieldDesc::Size { field_id, unit, .. } = &field.desc else {
+                continue;
+            };
+            let unit_width = unit.width();
+            if unit_width == 0 || (*unit != SizeUnit::Bits && unit_width % 8 != 0) {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidSizeUnit)
+                        .with_message(format!(
+                            "invalid size unit, word width {unit_width} is not a multiple of 8"
+                        ))
+                        .with_labels(vec![field.loc.primary()]),
+                );
+                continue;
+            }
+            let size_modifier = decl.fields().find_map(|field| match &field.desc {
+                FieldDesc::Payload { size_modifier: Some(size_modifier), .. }
+                    if field_id == "_payload_" =>
+                {
+                    Some((field, size_modifier))
+                }
+                _ => None,
+            });
+            if let Some((payload, size_modifier)) = size_modifier {
+                let size_modifier = size_modifier.parse::<usize>().unwrap();
+                if (size_modifier * 8) % unit_width != 0 {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidSizeUnit)
+                            .with_message(format!(
+                                "size modifier is not a multiple of the size unit ({} octets)",
+                                unit_width / 8
+                            ))
+                            .with_labels(vec![
+                                field.loc.primary(),
+                                payload.loc.secondary().with_message(format!(
+                                    "`_payload_` has size modifier +{size_modifier}"
+                                )),
+                            ]),
+                    )
+                }
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
 /// Check fixed fields.
 /// Raises error diagnostics for the following cases:
 ///      - invalid scalar value
@@ -1408,7 +2517,7 @@ fn check_fixed_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
                 FieldDesc::FixedEnum { tag_id, enum_id } => match scope.typedef.get(enum_id) {
                     None => diagnostics.push(
                         Diagnostic::error()
-                            .with_code(ErrorCode::E33)
+                            .with_code(ErrorCode::UndeclaredFixedEnumIdentifier)
                             .with_message(format!("undeclared type identifier `{enum_id}`"))
                             .with_labels(vec![field.loc.primary()])
                             .with_notes(vec!["hint: expected enum identifier".to_owned()]),
@@ -1417,7 +2526,7 @@ fn check_fixed_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
                         if !tags.iter().any(|tag| tag.id() == tag_id) {
                             diagnostics.push(
                                 Diagnostic::error()
-                                    .with_code(ErrorCode::E34)
+                                    .with_code(ErrorCode::UndeclaredFixedTagIdentifier)
                                     .with_message(format!("undeclared tag identifier `{tag_id}`"))
                                     .with_labels(vec![
                                         field.loc.primary(),
@@ -1428,7 +2537,7 @@ fn check_fixed_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
                     }
                     Some(decl) => diagnostics.push(
                         Diagnostic::error()
-                            .with_code(ErrorCode::E35)
+                            .with_code(ErrorCode::InvalidFixedEnumIdentifier)
                             .with_message(format!("invalid type identifier `{enum_id}`"))
                             .with_labels(vec![
                                 field.loc.primary(),
@@ -1447,6 +2556,119 @@ fn check_fixed_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
     diagnostics.err_or(())
 }
 
+/// Check default field values.
+/// Raises error diagnostics for the following cases:
+///      - scalar default value overflow
+///      - undeclared default tag identifier
+///      - default tag identifier defines a range or default tag
+///      - default value on non-enum typedef field
+///      - default value on optional field
+fn check_default_values(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for field in decl.fields() {
+            match &field.desc {
+                FieldDesc::Scalar { default: Some(_), .. }
+                | FieldDesc::Typedef { default: Some(_), .. }
+                    if field.cond.is_some() =>
+                {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidDefaultValue)
+                            .with_message("invalid default value for optional field".to_owned())
+                            .with_labels(vec![field.loc.primary()])
+                            .with_notes(vec!["note: optional fields default to absent".to_owned()]),
+                    )
+                }
+                FieldDesc::Scalar { default: Some(value), width, .. }
+                    if bit_width(*value) > *width =>
+                {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::DefaultValueOutOfRange)
+                            .with_message(format!(
+                                "default value `{value}` is larger than maximum value"
+                            ))
+                            .with_labels(vec![field.loc.primary()]),
+                    )
+                }
+                FieldDesc::Typedef { default: Some(tag_id), type_id, .. } => {
+                    match scope.typedef.get(type_id) {
+                        None => (),
+                        Some(enum_decl @ Decl { desc: DeclDesc::Enum { tags, .. }, .. }) => {
+                            match tags.iter().find(|tag| tag.id() == tag_id) {
+                                None => diagnostics.push(
+                                    Diagnostic::error()
+                                        .with_code(ErrorCode::UndeclaredDefaultTag)
+                                        .with_message(format!("undeclared enum tag `{tag_id}`"))
+                                        .with_labels(vec![
+                                            field.loc.primary(),
+                                            enum_decl.loc.secondary().with_message(format!(
+                                                "`{type_id}` is declared here"
+                                            )),
+                                        ]),
+                                ),
+                                Some(tag) if tag.value().is_none() => diagnostics.push(
+                                    Diagnostic::error()
+                                        .with_code(ErrorCode::InvalidDefaultValue)
+                                        .with_message(format!(
+                                            "enum tag `{tag_id}` does not define a value"
+                                        ))
+                                        .with_labels(vec![field.loc.primary()])
+                                        .with_notes(vec![
+                                            "hint: expected enum tag with value".to_owned(),
+                                        ]),
+                                ),
+                                Some(_) => (),
+                            }
+                        }
+                        Some(decl) => diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidDefaultValue)
+                                .with_message(format!("invalid default value `{tag_id}`"))
+                                .with_labels(vec![
+                                    field.loc.primary(),
+                                    decl.loc.secondary().with_message(format!(
+                                        "`{type_id}` is declared here as {} declaration",
+                                        decl.kind()
+                                    )),
+                                ])
+                                .with_notes(vec!["hint: expected enum typedef field".to_owned()]),
+                        ),
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check scalar field scales.
+/// Raises error diagnostics for the following cases:
+///      - zero scale factor
+fn check_scalar_scales(file: &File) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for field in decl.fields() {
+            if let FieldDesc::Scalar { scale: Some(scale), .. } = &field.desc {
+                if scale.parse::<f64>().map_or(true, |scale| scale == 0.0) {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidScalarScale)
+                            .with_message(format!("invalid scale `{scale}`"))
+                            .with_labels(vec![field.loc.primary()])
+                            .with_notes(vec!["hint: expected non-zero scale".to_owned()]),
+                    )
+                }
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
 /// Check payload fields.
 /// Raises error diagnostics for the following cases:
 ///      - duplicate payload field
@@ -1605,7 +2827,7 @@ fn check_optional_fields(file: &File) -> Result<(), Diagnostics> {
                     ),
                     Some(Field { cond: Some(_), loc, .. }) => diagnostics.push(
                         Diagnostic::error()
-                            .with_code(ErrorCode::E49)
+                            .with_code(ErrorCode::OptionalConditionIdentifier)
                             .with_message("invalid condition identifier".to_owned())
                             .with_labels(vec![
                                 field.loc.primary(),
@@ -1672,61 +2894,402 @@ fn check_optional_fields(file: &File) -> Result<(), Diagnostics> {
     diagnostics.err_or(())
 }
 
-/// Check field offsets.
+/// Check extension fields.
 /// Raises error diagnostics for the following cases:
-///      - non bit-field field not aligned to a octet boundary
-fn check_field_offsets(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+///      - extension field is not a scalar or typedef field
+///      - extension field is conditional
+///      - extension field size is not a multiple of 8 bits
+///      - non extension field declared after an extension block
+fn check_extension_fields(file: &File, scope: &Scope) -> Result<(), Diagnostics> {
     let mut diagnostics: Diagnostics = Default::default();
     for decl in &file.declarations {
-        let mut offset = 0;
-
+        let mut extension_field: Option<&Field> = None;
         for field in decl.fields() {
-            match &field.desc {
-                FieldDesc::Typedef { type_id, .. }
-                    if matches!(
-                        scope.typedef.get(type_id),
-                        Some(Decl { desc: DeclDesc::Enum { .. }, .. })
-                    ) => {}
-                FieldDesc::Payload { .. }
-                | FieldDesc::Body
-                | FieldDesc::Typedef { .. }
-                | FieldDesc::Array { .. }
-                | FieldDesc::Padding { .. }
-                | FieldDesc::Checksum { .. } => {
-                    if offset % 8 != 0 {
-                        diagnostics.push(
-                            Diagnostic::error()
-                                .with_code(ErrorCode::InvalidFieldOffset)
-                                .with_message(format!(
-                                    "{} field is not aligned to an octet boundary",
-                                    field.kind()
-                                ))
-                                .with_labels(vec![field.loc.primary()]),
-                        )
-                    }
+            if field.extension.is_none() {
+                if let Some(extension_field) = extension_field {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidExtensionPosition)
+                            .with_message(format!(
+                                "{} field is declared after an extension block",
+                                field.kind()
+                            ))
+                            .with_labels(vec![
+                                field.loc.primary(),
+                                extension_field
+                                    .loc
+                                    .secondary()
+                                    .with_message("extension field is declared here"),
+                            ])
+                            .with_notes(vec![
+                                "note: extension blocks must be declared last".to_owned(),
+                            ]),
+                    );
                 }
-                FieldDesc::Size { .. }
-                | FieldDesc::Count { .. }
-                | FieldDesc::ElementSize { .. }
-                | FieldDesc::FixedEnum { .. }
-                | FieldDesc::FixedScalar { .. }
-                | FieldDesc::Group { .. }
-                | FieldDesc::Flag { .. }
-                | FieldDesc::Reserved { .. }
-                | FieldDesc::Scalar { .. } => (),
+                continue;
             }
-            offset = match schema.field_size[&field.key] {
-                Size::Static(size) => offset + size,
-                Size::Dynamic | Size::Unknown => 0,
+            extension_field = Some(field);
+
+            let width = match &field.desc {
+                FieldDesc::Scalar { width, .. } => Some(*width),
+                FieldDesc::Typedef { type_id, .. } => match scope.typedef.get(type_id) {
+                    Some(Decl { desc: DeclDesc::Enum { width, .. }, .. })
+                    | Some(Decl { desc: DeclDesc::Flags { width, .. }, .. }) => Some(*width),
+                    Some(Decl { desc: DeclDesc::Struct { .. }, .. }) => Some(0),
+                    _ => None,
+                },
+                _ => None,
             };
+            if width.is_none_or(|width| !width.is_multiple_of(8)) || field.cond.is_some() {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidExtensionField)
+                        .with_message("invalid extension field".to_owned())
+                        .with_labels(vec![field.loc.primary()])
+                        .with_notes(vec![
+                            "note: expected octet sized scalar, enum, flags, or struct field"
+                                .to_owned(),
+                        ]),
+                );
+            }
         }
     }
     diagnostics.err_or(())
 }
 
-/// Check field sizes.
-/// Raises error diagnostics for the following cases:
-///      - struct size is not an integral number of octets
+/// Test if the declaration ends with an extension block, directly or
+/// through its last field. The presence of the extension fields of
+/// such declarations is detected from the bytes remaining in the
+/// enclosing declaration.
+fn has_trailing_extension(decl: &Decl, scope: &Scope) -> bool {
+    let fields = decl.fields().collect::<Vec<_>>();
+    if fields.iter().any(|field| field.extension.is_some()) {
+        return true;
+    }
+    let has_size_field = |id: &str| {
+        fields
+            .iter()
+            .any(|field| matches!(&field.desc, FieldDesc::Size { field_id, .. } if field_id == id))
+    };
+    match fields.last().map(|field| &field.desc) {
+        Some(FieldDesc::Typedef { id, .. }) if has_size_field(id) => false,
+        Some(FieldDesc::Typedef { type_id, .. }) => match scope.typedef.get(type_id) {
+            Some(decl @ Decl { desc: DeclDesc::Struct { .. }, .. }) => {
+                has_trailing_extension(decl, scope)
+            }
+            _ => false,
+        },
+        Some(FieldDesc::Payload { .. }) | Some(FieldDesc::Body) => {
+            !has_size_field("_payload_")
+                && !has_size_field("_body_")
+                && scope.iter_children(decl).any(|child| has_trailing_extension(child, scope))
+        }
+        _ => false,
+    }
+}
+
+/// Check that the size of the fields declared before an extension block
+/// can be computed, which is required to detect the presence of the
+/// extension fields.
+/// Raises error diagnostics for the following cases:
+///      - extension block declared after a field of unknown size
+///      - typedef field referencing a struct declaration with a trailing
+///        extension block, not declared last and without size field
+///      - array field of struct declarations with a trailing extension
+///        block, without element size field
+fn check_extension_sizes(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let fields = decl.fields().collect::<Vec<_>>();
+        let has_size_field = |id: &str| {
+            fields.iter().any(
+                |field| matches!(&field.desc, FieldDesc::Size { field_id, .. } if field_id == id),
+            )
+        };
+        let has_element_size_field = |id: &str| {
+            fields.iter().any(|field| {
+                matches!(&field.desc, FieldDesc::ElementSize { field_id, .. } if field_id == id)
+            })
+        };
+        for (index, field) in fields.iter().enumerate() {
+            let (type_id, delimited, hint) = match &field.desc {
+                FieldDesc::Typedef { id, type_id, .. } => (
+                    type_id,
+                    index + 1 == fields.len() || has_size_field(id),
+                    "hint: declare the field last, or declare a size field for the field",
+                ),
+                FieldDesc::Array { id, type_id: Some(type_id), .. } => (
+                    type_id,
+                    has_element_size_field(id),
+                    "hint: declare an element size field for the array",
+                ),
+                _ => continue,
+            };
+            match scope.typedef.get(type_id) {
+                Some(struct_decl @ Decl { desc: DeclDesc::Struct { .. }, .. })
+                    if !delimited && has_trailing_extension(struct_decl, scope) =>
+                {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidExtensionPosition)
+                            .with_message(format!(
+                                "{} field of struct `{}` with an extension block is not \
+                                 delimited",
+                                field.kind(),
+                                type_id
+                            ))
+                            .with_labels(vec![
+                                field.loc.primary(),
+                                struct_decl
+                                    .loc
+                                    .secondary()
+                                    .with_message(format!("`{type_id}` is declared here")),
+                            ])
+                            .with_notes(vec![
+                                "note: the extension fields are present if bytes remain \
+                                 in the enclosing declaration"
+                                    .to_owned(),
+                                hint.to_owned(),
+                            ]),
+                    );
+                }
+                _ => (),
+            }
+        }
+
+        let Some(extension_field) = decl.fields().find(|field| field.extension.is_some()) else {
+            continue;
+        };
+        for field in decl.fields().take_while(|field| field.extension.is_none()) {
+            if schema.field_size(field.key) == Size::Unknown {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidExtensionPosition)
+                        .with_message(format!(
+                            "extension block is declared after {} field of unknown size",
+                            field.kind()
+                        ))
+                        .with_labels(vec![
+                            extension_field.loc.primary(),
+                            field
+                                .loc
+                                .secondary()
+                                .with_message(format!("{} field is declared here", field.kind())),
+                        ]),
+                );
+            }
+        }
+    }
+    diagnostics.err_or(())
+}
+
+/// Check field offsets.
+/// Raises error diagnostics for the following cases:
+///      - non bit-field field not aligned to a octet boundary
+fn check_field_offsets(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let mut offset = 0;
+
+        for field in decl.fields() {
+            match &field.desc {
+                _ if field.extension.is_some() && offset % 8 != 0 => diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidFieldOffset)
+                        .with_message(format!(
+                            "extension {} field is not aligned to an octet boundary",
+                            field.kind()
+                        ))
+                        .with_labels(vec![field.loc.primary()]),
+                ),
+                FieldDesc::Typedef { type_id, .. }
+                    if matches!(
+                        scope.typedef.get(type_id),
+                        Some(Decl { desc: DeclDesc::Enum { .. } | DeclDesc::Flags { .. }, .. })
+                    ) => {}
+                FieldDesc::Payload { .. }
+                | FieldDesc::Body
+                | FieldDesc::Typedef { .. }
+                | FieldDesc::Array { .. }
+                | FieldDesc::Padding { .. }
+                | FieldDesc::Checksum { .. } => {
+                    if offset % 8 != 0 {
+                        diagnostics.push(
+                            Diagnostic::error()
+                                .with_code(ErrorCode::InvalidFieldOffset)
+                                .with_message(format!(
+                                    "{} field is not aligned to an octet boundary",
+                                    field.kind()
+                                ))
+                                .with_labels(vec![field.loc.primary()]),
+                        )
+                    }
+                }
+                FieldDesc::Size { .. }
+                | FieldDesc::Count { .. }
+                | FieldDesc::ElementSize { .. }
+                | FieldDesc::FixedEnum { .. }
+                | FieldDesc::FixedScalar { .. }
+                | FieldDesc::Group { .. }
+                | FieldDesc::Flag { .. }
+                | FieldDesc::Reserved { .. }
+                | FieldDesc::Scalar { .. } => (),
+            }
+            offset = match schema.field_size[&field.key] {
+                Size::Static(size) => offset + size,
+                Size::Dynamic | Size::Unknown => 0,
+            };
+        }
+    }
+    diagnostics.err_or(())
+}
+
+/// Check size and count fields declared after the field they designate.
+/// Raises error diagnostics for the following cases:
+///      - the designated field is a padded array
+///      - the designated field is a struct typedef field
+///      - the designated field is followed by a field with non static size
+fn check_trailing_size_fields(file: &File, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for size in decl.fields() {
+            if !decl.is_trailing_size(size) {
+                continue;
+            }
+            let mut fields = decl.fields().skip_while(|field| match &size.desc {
+                FieldDesc::Size { field_id, .. } | FieldDesc::Count { field_id, .. } => {
+                    match &field.desc {
+                        FieldDesc::Payload { .. } => field_id != "_payload_",
+                        FieldDesc::Body => field_id != "_body_",
+                        _ => field.id() != Some(field_id),
+                    }
+                }
+                _ => unreachable!(),
+            });
+            let sized_field = fields.next().unwrap();
+            if schema.padded_size(sized_field.key).is_some() {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidTrailingSizeField)
+                        .with_message(format!(
+                            "{} field is declared after the padded array it designates",
+                            size.kind()
+                        ))
+                        .with_labels(vec![
+                            size.loc.primary(),
+                            sized_field.loc.secondary().with_message("array is declared here"),
+                        ]),
+                );
+                continue;
+            }
+            if matches!(&sized_field.desc, FieldDesc::Typedef { .. }) {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidTrailingSizeField)
+                        .with_message(format!(
+                            "{} field is declared after the struct field it designates",
+                            size.kind()
+                        ))
+                        .with_labels(vec![
+                            size.loc.primary(),
+                            sized_field.loc.secondary().with_message("struct is declared here"),
+                        ])
+                        .with_notes(vec![
+                            "hint: the size of a struct field must be declared before the field"
+                                .to_owned(),
+                        ]),
+                );
+                continue;
+            }
+            if let Some(field) = fields.find(|field| {
+                schema.padded_size(field.key).is_none()
+                    && schema.field_size(field.key).static_().is_none()
+            }) {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidTrailingSizeField)
+                        .with_message(format!(
+                            "{} field is declared after the field it designates",
+                            size.kind()
+                        ))
+                        .with_labels(vec![
+                            size.loc.primary(),
+                            field.loc.secondary().with_message(format!(
+                                "{} field has non static size",
+                                field.kind()
+                            )),
+                        ])
+                        .with_notes(vec![
+                            "hint: the fields following the designated field must have a static size"
+                                .to_owned(),
+                        ]),
+                );
+            }
+        }
+    }
+    diagnostics.err_or(())
+}
+
+/// Check field endianness overrides.
+/// Raises error diagnostics for the following cases:
+///      - endianness override on a typedef field with non enum type
+///      - endianness override on a field not aligned to an octet boundary
+///      - endianness override on a field whose size is not an integral
+///        number of octets
+fn check_field_endianness(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let mut offset = Some(0);
+
+        for field in decl.fields() {
+            let size = schema.field_size[&field.key].static_();
+            match &field.desc {
+                _ if field.endianness.is_none() => (),
+                FieldDesc::Typedef { type_id, .. }
+                    if !matches!(
+                        scope.typedef.get(type_id),
+                        Some(Decl { desc: DeclDesc::Enum { .. } | DeclDesc::Flags { .. }, .. })
+                    ) =>
+                {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidFieldEndianness)
+                            .with_message(format!(
+                                "endianness override on typedef field with non enum type `{type_id}`"
+                            ))
+                            .with_labels(vec![field.loc.primary()]),
+                    )
+                }
+                _ if offset.is_none_or(|offset| offset % 8 != 0)
+                    || size.is_none_or(|size| size % 8 != 0) =>
+                {
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_code(ErrorCode::InvalidFieldEndianness)
+                            .with_message(format!(
+                                "endianness override on {} field not aligned to octet boundaries",
+                                field.kind()
+                            ))
+                            .with_labels(vec![field.loc.primary()])
+                            .with_notes(vec![
+                                "hint: endianness overrides apply to whole octets".to_owned(),
+                            ]),
+                    )
+                }
+                _ => (),
+            }
+            offset = match size {
+                Some(size) => offset.map(|offset| offset + size),
+                None => Some(0),
+            };
+        }
+    }
+    diagnostics.err_or(())
+}
+
+/// Check field sizes.
+/// Raises error diagnostics for the following cases:
+///      - struct size is not an integral number of octets
 ///      - packet size is not an integral number of octets
 ///      - scalar array element size is not an integral number of octets
 fn check_decl_sizes(file: &File, schema: &Schema) -> Result<(), Diagnostics> {
@@ -1764,6 +3327,48 @@ fn check_decl_sizes(file: &File, schema: &Schema) -> Result<(), Diagnostics> {
     diagnostics.err_or(())
 }
 
+/// Check size assertions.
+/// Raises error diagnostics for the following cases:
+///      - static declaration size does not satisfy the size assertion
+///        of the declaration or of one of its parents
+fn check_size_assertions(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        let Some(size) = schema.total_size(decl.key).static_() else {
+            continue;
+        };
+        for assertion_decl in scope.iter_parents_and_self(decl) {
+            let Some(assertion) = assertion_decl.size_assertion else {
+                continue;
+            };
+            if assertion.check(size / 8) {
+                continue;
+            }
+            let mut labels = vec![decl.loc.primary()];
+            if assertion_decl.key != decl.key {
+                labels.push(
+                    assertion_decl.loc.secondary().with_message(format!(
+                        "`{}` is declared here",
+                        assertion_decl.id().unwrap()
+                    )),
+                );
+            }
+            diagnostics.push(
+                Diagnostic::error()
+                    .with_code(ErrorCode::InvalidSizeAssertion)
+                    .with_message(format!(
+                        "{} size ({} octets) does not satisfy the assertion `{}`",
+                        decl.kind(),
+                        size / 8,
+                        assertion
+                    ))
+                    .with_labels(labels),
+            )
+        }
+    }
+    diagnostics.err_or(())
+}
+
 /// Inline group fields and remove group declarations.
 fn inline_groups(file: &File) -> Result<File, Diagnostics> {
     fn inline_fields<'a>(
@@ -1782,7 +3387,7 @@ fn inline_groups(file: &File) -> Result<File, Diagnostics> {
                     );
                     inline_fields(groups.get(group_id).unwrap().fields(), groups, &constraints)
                 }
-                FieldDesc::Scalar { id, width } if constraints.contains_key(id) => {
+                FieldDesc::Scalar { id, width, .. } if constraints.contains_key(id) => {
                     vec![Field {
                         desc: FieldDesc::FixedScalar {
                             width: *width,
@@ -1791,6 +3396,8 @@ fn inline_groups(file: &File) -> Result<File, Diagnostics> {
                         loc: field.loc,
                         key: field.key,
                         cond: field.cond.clone(),
+                        endianness: field.endianness,
+                        extension: field.extension,
                     }]
                 }
                 FieldDesc::Typedef { id, type_id, .. } if constraints.contains_key(id) => {
@@ -1805,6 +3412,8 @@ fn inline_groups(file: &File) -> Result<File, Diagnostics> {
                         loc: field.loc,
                         key: field.key,
                         cond: field.cond.clone(),
+                        endianness: field.endianness,
+                        extension: field.extension,
                     }]
                 }
                 _ => vec![field.clone()],
@@ -1823,25 +3432,33 @@ fn inline_groups(file: &File) -> Result<File, Diagnostics> {
         .declarations
         .iter()
         .filter_map(|decl| match &decl.desc {
-            DeclDesc::Packet { fields, id, parent_id, constraints } => Some(Decl {
+            DeclDesc::Packet { fields, id, parent_id, constraints, invariants } => Some(Decl {
                 desc: DeclDesc::Packet {
                     fields: inline_fields(fields.iter(), &groups, &HashMap::new()),
                     id: id.clone(),
                     parent_id: parent_id.clone(),
                     constraints: constraints.clone(),
+                    invariants: invariants.clone(),
                 },
                 loc: decl.loc,
                 key: decl.key,
+                endianness: decl.endianness,
+                bit_order: decl.bit_order,
+                size_assertion: decl.size_assertion,
             }),
-            DeclDesc::Struct { fields, id, parent_id, constraints } => Some(Decl {
+            DeclDesc::Struct { fields, id, parent_id, constraints, invariants } => Some(Decl {
                 desc: DeclDesc::Struct {
                     fields: inline_fields(fields.iter(), &groups, &HashMap::new()),
                     id: id.clone(),
                     parent_id: parent_id.clone(),
                     constraints: constraints.clone(),
+                    invariants: invariants.clone(),
                 },
                 loc: decl.loc,
                 key: decl.key,
+                endianness: decl.endianness,
+                bit_order: decl.bit_order,
+                size_assertion: decl.size_assertion,
             }),
             DeclDesc::Group { .. } => None,
             _ => Some(decl.clone()),
@@ -1854,6 +3471,7 @@ fn inline_groups(file: &File) -> Result<File, Diagnostics> {
         file: file.file,
         comments: file.comments.clone(),
         endianness: file.endianness,
+        bit_order: file.bit_order,
         // Keys are reused for inlined fields.
         max_key: file.max_key,
     })
@@ -1874,7 +3492,7 @@ fn desugar_flags(file: &mut File) {
                         condition_ids
                             .entry(cond.id.to_owned())
                             .or_default()
-                            .push((field.id().unwrap().to_owned(), cond.value.unwrap()));
+                            .push((field.id().unwrap().to_owned(), cond.value.unwrap() as usize));
                     }
                 }
                 // Replace condition flags in the fields.
@@ -1894,53 +3512,469 @@ fn desugar_flags(file: &mut File) {
     }
 }
 
-/// Analyzer entry point, produces a new AST with annotations resulting
-/// from the analysis.
-pub fn analyze(file: &File) -> Result<File, Diagnostics> {
-    let scope = Scope::new(file)?;
-    let file = check_decl_identifiers(file, &scope)?;
-    let scope = Scope::new(&file).unwrap();
-    check_field_identifiers(&file)?;
-    check_enum_declarations(&file)?;
-    check_size_fields(&file)?;
-    check_fixed_fields(&file, &scope)?;
-    check_payload_fields(&file)?;
-    check_array_fields(&file)?;
-    check_padding_fields(&file)?;
-    check_checksum_fields(&file, &scope)?;
-    check_optional_fields(&file)?;
-    check_group_constraints(&file, &scope)?;
-    let mut file = inline_groups(&file)?;
-    desugar_flags(&mut file);
-    let scope = Scope::new(&file)?;
-    check_decl_constraints(&file, &scope)?;
-    let schema = Schema::new(&file);
-    check_field_offsets(&file, &scope, &schema)?;
-    check_decl_sizes(&file, &schema)?;
-    Ok(file)
-}
+/// Check array and payload size bounds.
+/// Raises error diagnostics for the following cases:
+///      - null size bound
+///      - size bound exceeding the maximum value of the size or count field
+fn check_size_bounds(file: &File, scope: &Scope, schema: &Schema) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Default::default();
+    for decl in &file.declarations {
+        for field in decl.fields() {
+            let (bound, element_width, size_field) = match &field.desc {
+                FieldDesc::Array { id, max_count: Some(bound), width, type_id, .. } => (
+                    *bound,
+                    width.or_else(|| {
+                        type_id.as_ref().and_then(|type_id| {
+                            schema.total_size(scope.typedef[type_id].key).static_()
+                        })
+                    }),
+                    decl.array_size(id),
+                ),
+                FieldDesc::Payload { max_size: Some(bound), .. } => {
+                    (*bound, Some(8), decl.payload_size())
+                }
+                _ => continue,
+            };
 
-#[cfg(test)]
-mod test {
-    use crate::analyzer;
-    use crate::ast;
-    use crate::parser::parse_inline;
-    use codespan_reporting::term::termcolor;
+            if bound == 0 {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidSizeBound)
+                        .with_message("size bound must be greater than zero".to_owned())
+                        .with_labels(vec![field.loc.primary()]),
+                );
+                continue;
+            }
 
-    use googletest::prelude::{assert_that, eq};
+            let (maximum, unit) = match size_field.map(|field| &field.desc) {
+                Some(FieldDesc::Count { width, .. }) => (scalar_max(*width), "elements"),
+                Some(FieldDesc::Size { width, unit, .. }) => {
+                    let Some(element_width) = element_width.filter(|width| *width > 0) else {
+                        continue;
+                    };
+                    let unit_width = match unit {
+                        SizeUnit::Bits => 1,
+                        SizeUnit::Octets => 8,
+                        SizeUnit::Words(width) => *width,
+                    };
+                    (
+                        scalar_max(*width).saturating_mul(unit_width) / element_width,
+                        if field.id().is_some() { "elements" } else { "octets" },
+                    )
+                }
+                _ => continue,
+            };
 
-    macro_rules! raises {
-        ($code:ident, $text:literal) => {{
-            let mut db = ast::SourceDatabase::new();
-            let file = parse_inline(&mut db, "stdin", $text.to_owned()).expect("parsing failure");
-            let result = analyzer::analyze(&file);
-            assert!(result.is_err());
-            let diagnostics = result.err().unwrap();
-            let mut buffer = termcolor::Buffer::no_color();
-            let _ = diagnostics.emit(&db, &mut buffer);
-            println!("{}", std::str::from_utf8(buffer.as_slice()).unwrap());
-            assert_eq!(diagnostics.diagnostics.len(), 1);
-            assert_eq!(diagnostics.diagnostics[0].code, Some(analyzer::ErrorCode::$code.into()));
+            if bound > maximum {
+                let size_field = size_field.unwrap();
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_code(ErrorCode::InvalidSizeBound)
+                        .with_message(format!(
+                            "size bound {bound} exceeds the maximum of {maximum} {unit}"
+                        ))
+                        .with_labels(vec![
+                            field.loc.primary(),
+                            size_field.loc.secondary().with_message(format!(
+                                "the {} field can encode at most {maximum} {unit}",
+                                size_field.kind()
+                            )),
+                        ]),
+                );
+            }
+        }
+    }
+
+    diagnostics.err_or(())
+}
+
+/// Check for unused declarations.
+/// The check is run before group inlining, on the original file.
+/// Raises warning diagnostics for the following cases:
+///      - enum, flags, group, checksum, or custom field declaration
+///        not referenced by any other declaration
+fn check_unused_declarations(file: &File, diagnostics: &mut Diagnostics) {
+    let mut used = HashSet::new();
+    for decl in &file.declarations {
+        match &decl.desc {
+            DeclDesc::Test { type_id, .. } => {
+                used.insert(type_id.as_str());
+            }
+            DeclDesc::Bind { packet_id, type_id, .. } => {
+                used.insert(packet_id.as_str());
+                used.insert(type_id.as_str());
+            }
+            DeclDesc::Response { request_id, response_id } => {
+                used.insert(request_id.as_str());
+                used.insert(response_id.as_str());
+            }
+            _ => (),
+        }
+        if let Some(parent_id) = decl.parent_id() {
+            used.insert(parent_id);
+        }
+        for field in decl.fields() {
+            match &field.desc {
+                FieldDesc::Typedef { type_id, .. }
+                | FieldDesc::Array { type_id: Some(type_id), .. }
+                | FieldDesc::FixedEnum { enum_id: type_id, .. }
+                | FieldDesc::Group { group_id: type_id, .. } => {
+                    used.insert(type_id.as_str());
+                }
+                _ => (),
+            }
+        }
+    }
+
+    for decl in &file.declarations {
+        match &decl.desc {
+            DeclDesc::Enum { id, .. }
+            | DeclDesc::Flags { id, .. }
+            | DeclDesc::Group { id, .. }
+            | DeclDesc::Checksum { id, .. }
+            | DeclDesc::CustomField { id, .. }
+                if !used.contains(id.as_str()) =>
+            {
+                diagnostics.push(
+                    Diagnostic::warning()
+                        .with_code(WarningCode::UnusedDecl)
+                        .with_message(format!("{} `{}` is never used", decl.kind(), id))
+                        .with_labels(vec![decl.loc.primary()]),
+                )
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Check enum fields used to specialize child declarations.
+/// Raises warning diagnostics for the following cases:
+///      - enum tag not matched by any child declaration constraint
+fn check_unmatched_enum_tags(file: &File, scope: &Scope, diagnostics: &mut Diagnostics) {
+    for decl in &file.declarations {
+        let children = scope.iter_children(decl).collect::<Vec<_>>();
+        for field in decl.fields() {
+            let (Some(id), Some(Decl { desc: DeclDesc::Enum { id: enum_id, tags, .. }, .. })) =
+                (field.id(), scope.get_type_declaration(field))
+            else {
+                continue;
+            };
+
+            // Children not constraining the field match all tag values;
+            // the field is ignored unless all children constrain it.
+            let mut matched = HashSet::new();
+            for child in &children {
+                let Some(constraint) = child.constraints().find(|c| c.id == id) else {
+                    matched.clear();
+                    break;
+                };
+                matched.extend(constraint.tag_id.as_deref());
+            }
+            if matched.is_empty() {
+                continue;
+            }
+
+            let unmatched = tags
+                .iter()
+                .filter_map(|tag| match tag {
+                    Tag::Value(tag) => Some(tag),
+                    Tag::Range(_) | Tag::Other(_) => None,
+                })
+                .filter(|tag| !matched.contains(tag.id.as_str()))
+                .map(|tag| format!("`{}`", tag.id))
+                .collect::<Vec<_>>();
+            if !unmatched.is_empty() {
+                diagnostics.push(
+                    Diagnostic::warning()
+                        .with_code(WarningCode::UnmatchedEnumTag)
+                        .with_message(format!(
+                            "enum `{}` has {} not matched by any child of `{}`",
+                            enum_id,
+                            if unmatched.len() == 1 { "a tag" } else { "tags" },
+                            decl.id().unwrap()
+                        ))
+                        .with_labels(vec![field.loc.primary()])
+                        .with_notes(vec![format!("unmatched: {}", unmatched.join(", "))]),
+                )
+            }
+        }
+    }
+}
+
+/// Check the constraints of sibling declarations, when not
+/// disambiguated by the declaration sizes.
+/// Raises warning diagnostics for the following cases:
+///      - child declaration whose constraints are a superset of the
+///        constraints of a sibling declared before it
+///      - overlapping constraints of sibling declarations
+fn check_sibling_constraints(
+    file: &File,
+    scope: &Scope,
+    schema: &Schema,
+    diagnostics: &mut Diagnostics,
+) {
+    for decl in &file.declarations {
+        let children = scope.iter_children(decl).collect::<Vec<_>>();
+        for (index, child) in children.iter().enumerate() {
+            let Some(sibling) = children[..index]
+                .iter()
+                .find(|sibling| constraints_overlap(decl, sibling, child, scope, schema))
+            else {
+                continue;
+            };
+            let constraints = child
+                .constraints()
+                .map(|c| (c.id.as_str(), c.value, c.tag_id.as_deref()))
+                .collect::<HashSet<_>>();
+            if sibling.constraints().next().is_some()
+                && sibling
+                    .constraints()
+                    .all(|c| constraints.contains(&(c.id.as_str(), c.value, c.tag_id.as_deref())))
+            {
+                diagnostics.push(
+                    Diagnostic::warning()
+                        .with_code(WarningCode::ShadowedChildDecl)
+                        .with_message(format!(
+                            "{} `{}` is shadowed by `{}`",
+                            child.kind(),
+                            child.id().unwrap(),
+                            sibling.id().unwrap()
+                        ))
+                        .with_labels(vec![
+                            child.loc.primary(),
+                            sibling.loc.secondary().with_message(format!(
+                                "`{}` matches all values matched by `{}`",
+                                sibling.id().unwrap(),
+                                child.id().unwrap()
+                            )),
+                        ]),
+                )
+            } else {
+                diagnostics.push(
+                    Diagnostic::warning()
+                        .with_code(WarningCode::OverlappingConstraints)
+                        .with_message(format!(
+                            "constraints of `{}` overlap with constraints of `{}`",
+                            child.id().unwrap(),
+                            sibling.id().unwrap()
+                        ))
+                        .with_labels(vec![
+                            child.loc.primary(),
+                            sibling.loc.secondary().with_message(format!(
+                                "`{}` is declared here",
+                                sibling.id().unwrap()
+                            )),
+                        ]),
+                )
+            }
+        }
+    }
+}
+
+/// Check the width of reserved fields.
+/// Raises warning diagnostics for the following cases:
+///      - reserved field spanning full octets of a bit-field wider
+///        than 64 bits, the full octets can be declared as a separate
+///        octet aligned reserved field
+fn check_reserved_fields(
+    file: &File,
+    scope: &Scope,
+    schema: &Schema,
+    diagnostics: &mut Diagnostics,
+) {
+    for decl in &file.declarations {
+        let mut offset: usize = 0;
+        let mut bitfield_width = 0;
+        let mut reserved_fields = vec![];
+        for field in decl.fields() {
+            let Size::Static(size) = schema.field_size(field.key) else {
+                // Dynamically sized fields are aligned to an octet boundary.
+                offset = 0;
+                continue;
+            };
+            if scope.is_bitfield(field) {
+                let start = offset % 8;
+                if matches!(field.desc, FieldDesc::Reserved { .. })
+                    && start.next_multiple_of(8) + 8 <= start + size
+                {
+                    reserved_fields.push(field);
+                }
+                bitfield_width += size;
+            }
+            offset += size;
+            if offset.is_multiple_of(8) {
+                for field in &reserved_fields {
+                    if bitfield_width > 64
+                        && schema.field_size(field.key) != Size::Static(bitfield_width)
+                    {
+                        diagnostics.push(
+                            Diagnostic::warning()
+                                .with_code(WarningCode::OversizedReservedField)
+                                .with_message(format!(
+                                    "reserved field widens its bit-field to {bitfield_width} bits"
+                                ))
+                                .with_labels(vec![field.loc.primary()])
+                                .with_notes(vec![
+                                    "hint: declare the full octets as a separate reserved field"
+                                        .to_owned(),
+                                ]),
+                        )
+                    }
+                }
+                bitfield_width = 0;
+                reserved_fields.clear();
+            }
+        }
+    }
+}
+
+/// Check scalar fields named like size or count fields.
+/// Raises warning diagnostics for the following cases:
+///      - scalar field `<id>_size`, `<id>_len`, `<id>_length`,
+///        `<id>_count`, or `num_<id>` where `<id>` names an array field
+///        without size or count field
+///      - scalar field `size`, `len`, `length`, `payload_size`,
+///        `payload_length`, `body_size`, or `body_length` in a
+///        declaration with a payload or body field without size field
+fn check_size_like_fields(file: &File, diagnostics: &mut Diagnostics) {
+    for decl in &file.declarations {
+        let unsized_payload = decl
+            .fields()
+            .find_map(|field| match &field.desc {
+                FieldDesc::Payload { .. } => Some("_payload_"),
+                FieldDesc::Body => Some("_body_"),
+                _ => None,
+            })
+            .filter(|_| decl.payload_size().is_none());
+        for field in decl.fields() {
+            let FieldDesc::Scalar { id, .. } = &field.desc else {
+                continue;
+            };
+
+            let suggestion = if let Some(array_id) = id.strip_prefix("num_") {
+                Some(("count", array_id))
+            } else if let Some(array_id) = id.strip_suffix("_count") {
+                Some(("count", array_id))
+            } else {
+                ["_size", "_length", "_len"]
+                    .into_iter()
+                    .find_map(|suffix| id.strip_suffix(suffix))
+                    .map(|array_id| ("size", array_id))
+            };
+
+            let suggestion = match suggestion {
+                Some((kind, array_id))
+                    if decl.fields().any(|field| {
+                        matches!(&field.desc, FieldDesc::Array { id, size: None, .. } if id == array_id)
+                    }) && decl.array_size(array_id).is_none() =>
+                {
+                    format!("_{kind}_({array_id})")
+                }
+                _ if unsized_payload.is_some()
+                    && matches!(
+                        id.as_str(),
+                        "size"
+                            | "len"
+                            | "length"
+                            | "payload_size"
+                            | "payload_length"
+                            | "body_size"
+                            | "body_length"
+                    ) =>
+                {
+                    format!("_size_({})", unsized_payload.unwrap())
+                }
+                _ => continue,
+            };
+
+            diagnostics.push(
+                Diagnostic::warning()
+                    .with_code(WarningCode::UndeclaredSizeField)
+                    .with_message(format!(
+                        "scalar field `{id}` is named like a size or count field"
+                    ))
+                    .with_labels(vec![field.loc.primary()])
+                    .with_notes(vec![format!("hint: declare the field as `{suggestion}`")]),
+            )
+        }
+    }
+}
+
+/// Analyzer entry point, produces a new AST with annotations resulting
+/// from the analysis. Warning diagnostics are discarded.
+pub fn analyze(file: &File) -> Result<File, Diagnostics> {
+    analyze_with_warnings(file).map(|(file, _)| file)
+}
+
+/// Same as [`analyze`], additionally returns the warning diagnostics
+/// raised for the file.
+pub fn analyze_with_warnings(file: &File) -> Result<(File, Diagnostics), Diagnostics> {
+    let original_file = file;
+    let mut warnings: Diagnostics = Default::default();
+    let scope = Scope::new(file)?;
+    let file = check_decl_identifiers(file, &scope)?;
+    let file = inherit_enum_tags(&file)?;
+    let scope = Scope::new(&file).unwrap();
+    check_field_identifiers(&file)?;
+    check_field_widths(&file)?;
+    check_enum_declarations(&file)?;
+    check_size_fields(&file, &scope)?;
+    check_size_units(&file)?;
+    check_fixed_fields(&file, &scope)?;
+    check_default_values(&file, &scope)?;
+    check_scalar_scales(&file)?;
+    check_payload_fields(&file)?;
+    check_array_fields(&file)?;
+    check_padding_fields(&file)?;
+    check_checksum_fields(&file, &scope)?;
+    check_optional_fields(&file)?;
+    check_extension_fields(&file, &scope)?;
+    check_group_constraints(&file, &scope)?;
+    let mut file = inline_groups(&file)?;
+    desugar_flags(&mut file);
+    let scope = Scope::new(&file)?;
+    let schema = Schema::new(&file);
+    check_decl_constraints(&file, &scope)?;
+    check_test_declarations(&file, &scope)?;
+    check_bind_declarations(&file, &scope)?;
+    check_invariants(&file, &scope)?;
+    check_response_declarations(&file)?;
+    check_field_offsets(&file, &scope, &schema)?;
+    check_field_endianness(&file, &scope, &schema)?;
+    check_trailing_size_fields(&file, &schema)?;
+    check_extension_sizes(&file, &scope, &schema)?;
+    check_decl_sizes(&file, &schema)?;
+    check_size_assertions(&file, &scope, &schema)?;
+    check_size_bounds(&file, &scope, &schema)?;
+    check_unused_declarations(original_file, &mut warnings);
+    check_unmatched_enum_tags(&file, &scope, &mut warnings);
+    check_sibling_constraints(&file, &scope, &schema, &mut warnings);
+    check_reserved_fields(&file, &scope, &schema, &mut warnings);
+    check_size_like_fields(&file, &mut warnings);
+    Ok((file, warnings))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analyzer;
+    use crate::ast;
+    use crate::parser::parse_inline;
+    use codespan_reporting::term::termcolor;
+
+    use googletest::prelude::{assert_that, eq};
+
+    macro_rules! raises {
+        ($code:ident, $text:literal) => {{
+            let mut db = ast::SourceDatabase::new();
+            let file = parse_inline(&mut db, "stdin", $text.to_owned()).expect("parsing failure");
+            let result = analyzer::analyze(&file);
+            assert!(result.is_err());
+            let diagnostics = result.err().unwrap();
+            let mut buffer = termcolor::Buffer::no_color();
+            let _ = diagnostics.emit(&db, &mut buffer);
+            println!("{}", std::str::from_utf8(buffer.as_slice()).unwrap());
+            assert_eq!(diagnostics.diagnostics.len(), 1);
+            assert_eq!(diagnostics.diagnostics[0].code, Some(analyzer::ErrorCode::$code.into()));
         }};
     }
 
@@ -1952,6 +3986,28 @@ mod test {
         }};
     }
 
+    macro_rules! warns {
+        ($code:ident, $text:literal) => {{
+            let mut db = ast::SourceDatabase::new();
+            let file = parse_inline(&mut db, "stdin", $text.to_owned()).expect("parsing failure");
+            let (_, warnings) = analyzer::analyze_with_warnings(&file).expect("analysis failure");
+            let mut buffer = termcolor::Buffer::no_color();
+            let _ = warnings.emit(&db, &mut buffer);
+            println!("{}", std::str::from_utf8(buffer.as_slice()).unwrap());
+            assert_eq!(warnings.diagnostics.len(), 1);
+            assert_eq!(warnings.diagnostics[0].code, Some(analyzer::WarningCode::$code.into()));
+        }};
+    }
+
+    macro_rules! no_warnings {
+        ($text:literal) => {{
+            let mut db = ast::SourceDatabase::new();
+            let file = parse_inline(&mut db, "stdin", $text.to_owned()).expect("parsing failure");
+            let (_, warnings) = analyzer::analyze_with_warnings(&file).expect("analysis failure");
+            assert!(warnings.diagnostics.is_empty());
+        }};
+    }
+
     #[test]
     fn test_e1() {
         raises!(
@@ -2015,6 +4071,47 @@ mod test {
             group C { C { x = 1 } }
             "#
         );
+
+        raises!(
+            RecursiveDecl,
+            r#"
+            little_endian_packets
+            enum A : B { X = 1 }
+            enum B : A { Y = 2 }
+            "#
+        );
+
+        raises!(
+            RecursiveDecl,
+            r#"
+            little_endian_packets
+            struct A { c : 1, _reserved_ : 7, x : B if c = 1 }
+            struct B { x : C }
+            struct C { x : B }
+            "#
+        );
+
+        valid!(
+            r#"
+            little_endian_packets
+            struct A { _count_(x) : 8, x : A[] }
+            "#
+        );
+
+        valid!(
+            r#"
+            little_endian_packets
+            struct A { c : 1, _reserved_ : 7, x : A if c = 1 }
+            "#
+        );
+
+        valid!(
+            r#"
+            little_endian_packets
+            struct A { c : 1, _reserved_ : 7, x : B if c = 1 }
+            struct B { x : A }
+            "#
+        );
     }
 
     #[test]
@@ -2097,6 +4194,14 @@ mod test {
         struct A : B { }
         "#
         );
+
+        raises!(
+            UndeclaredParentIdentifier,
+            r#"
+        little_endian_packets
+        enum A : B { X = 1 }
+        "#
+        );
     }
 
     #[test]
@@ -2127,9 +4232,17 @@ mod test {
         struct B : A { }
         "#
         );
+
+        raises!(
+            InvalidParentIdentifier,
+            r#"
+        little_endian_packets
+        struct A { }
+        enum B : A { X = 1 }
+        "#
+        );
     }
 
-    #[ignore]
     #[test]
     fn test_e9() {
         raises!(
@@ -2141,7 +4254,6 @@ mod test {
         );
     }
 
-    #[ignore]
     #[test]
     fn test_e10() {
         raises!(
@@ -2203,6 +4315,17 @@ mod test {
         "#
         );
 
+        raises!(
+            DuplicateTagIdentifier,
+            r#"
+        little_endian_packets
+        enum A : 8 {
+            X = 0,
+            Y | X = 1,
+        }
+        "#
+        );
+
         raises!(
             DuplicateTagIdentifier,
             r#"
@@ -2237,12 +4360,23 @@ mod test {
         }
         "#
         );
-    }
 
-    #[test]
-    fn test_e13() {
         raises!(
-            DuplicateTagValue,
+            DuplicateTagIdentifier,
+            r#"
+        little_endian_packets
+        flags A : 8 {
+            X = 1,
+            X = 2,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e13() {
+        raises!(
+            DuplicateTagValue,
             r#"
         little_endian_packets
         enum A : 8 {
@@ -2264,6 +4398,26 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            DuplicateTagValue,
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 1 }
+        enum B : A { Y = 1 }
+        "#
+        );
+
+        raises!(
+            DuplicateTagValue,
+            r#"
+        little_endian_packets
+        flags A : 8 {
+            X = 1,
+            Y = 1,
+        }
+        "#
+        );
     }
 
     #[test]
@@ -2290,6 +4444,41 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            InvalidTagValue,
+            r#"
+        little_endian_packets
+        flags A : 8 {
+            X = 0x100,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidTagValue,
+            r#"
+        little_endian_packets
+        flags A : 8 {
+            X = 0,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        flags A : 12 {
+            X = 0x1,
+            Y = 0x800,
+            Z = 0x801,
+        }
+        packet B {
+            a : A,
+            _reserved_ : 4,
+        }
+        "#
+        );
     }
 
     #[test]
@@ -2314,6 +4503,15 @@ mod test {
         "#
         );
 
+        raises!(
+            UndeclaredConstraintIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        test A { "\x00" => { y: 0 } }
+        "#
+        );
+
         valid!(
             r#"
         little_endian_packets
@@ -2345,12 +4543,34 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            InvalidConstraintIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8[] }
+        test A { "\x01" => { x: 1 } }
+        "#
+        );
+
+        raises!(
+            InvalidConstraintIdentifier,
+            r#"
+        little_endian_packets
+        packet A {
+            c : 1,
+            _reserved_ : 7,
+            x : 8 if c = 1,
+        }
+        test A { "\x01\x02" => { c: 1 } }
+        "#
+        );
     }
 
     #[test]
     fn test_e17() {
         raises!(
-            E17,
+            InvalidConstraintScalarValue,
             r#"
         little_endian_packets
         packet A { x : 8 }
@@ -2359,7 +4579,7 @@ mod test {
         );
 
         raises!(
-            E17,
+            InvalidConstraintScalarValue,
             r#"
         little_endian_packets
         group A { x : 8 }
@@ -2391,12 +4611,29 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            ConstraintValueOutOfRange,
+            r#"
+        little_endian_packets
+        packet A { x : 96 }
+        packet B : A (x = 0x1000000000000000000000000) { }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A { x : 96, _payload_ }
+        packet B : A (x = 0xffffffffffffffffffffffff) { }
+        "#
+        );
     }
 
     #[test]
     fn test_e19() {
         raises!(
-            E19,
+            InvalidConstraintEnumValue,
             r#"
         little_endian_packets
         enum C : 8 { X = 0 }
@@ -2406,7 +4643,7 @@ mod test {
         );
 
         raises!(
-            E19,
+            InvalidConstraintEnumValue,
             r#"
         little_endian_packets
         enum C : 8 { X = 0 }
@@ -2421,7 +4658,7 @@ mod test {
     #[test]
     fn test_e20() {
         raises!(
-            E20,
+            UndeclaredConstraintTag,
             r#"
         little_endian_packets
         enum C : 8 { X = 0 }
@@ -2431,7 +4668,7 @@ mod test {
         );
 
         raises!(
-            E20,
+            UndeclaredConstraintTag,
             r#"
         little_endian_packets
         enum C : 8 { X = 0 }
@@ -2441,12 +4678,22 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            UndeclaredConstraintTag,
+            r#"
+        little_endian_packets
+        enum C : 8 { X = 0 }
+        packet A { x : C }
+        test A { "\x00" => { x: Y } }
+        "#
+        );
     }
 
     #[test]
     fn test_e21() {
         raises!(
-            E21,
+            InvalidConstraintType,
             r#"
         little_endian_packets
         struct C { }
@@ -2456,7 +4703,7 @@ mod test {
         );
 
         raises!(
-            E21,
+            InvalidConstraintType,
             r#"
         little_endian_packets
         struct C { }
@@ -2466,6 +4713,16 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            InvalidConstraintType,
+            r#"
+        little_endian_packets
+        flags C : 8 { X = 1 }
+        packet A { x : C, _payload_ }
+        packet B : A (x = 1) { }
+        "#
+        );
     }
 
     #[test]
@@ -2564,6 +4821,32 @@ mod test {
         }
         "#
         );
+
+        raises!(
+            InvalidSizeIdentifier,
+            r#"
+        little_endian_packets
+        struct B { x : 8 }
+        struct A {
+            c : 1,
+            _reserved_ : 7,
+            _size_ (x) : 8,
+            x : B if c = 1,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        struct B { x : 8, _payload_ }
+        packet A {
+            _size_ (x) : 8,
+            x : B,
+            y : 8,
+        }
+        "#
+        );
     }
 
     #[test]
@@ -2668,7 +4951,7 @@ mod test {
     #[test]
     fn test_e33() {
         raises!(
-            E33,
+            UndeclaredFixedEnumIdentifier,
             r#"
         little_endian_packets
         struct A {
@@ -2681,7 +4964,7 @@ mod test {
     #[test]
     fn test_e34() {
         raises!(
-            E34,
+            UndeclaredFixedTagIdentifier,
             r#"
         little_endian_packets
         enum B : 8 { X = 0 }
@@ -2695,7 +4978,7 @@ mod test {
     #[test]
     fn test_e35() {
         raises!(
-            E35,
+            InvalidFixedEnumIdentifier,
             r#"
         little_endian_packets
         struct B { }
@@ -2886,7 +5169,7 @@ mod test {
     #[test]
     fn test_e42() {
         raises!(
-            E42,
+            InvalidConstraintTagRange,
             r#"
         little_endian_packets
         enum C : 8 { X = 0..15 }
@@ -2896,7 +5179,7 @@ mod test {
         );
 
         raises!(
-            E42,
+            InvalidConstraintTagRange,
             r#"
         little_endian_packets
         enum C : 8 { X = 0..15 }
@@ -2911,7 +5194,7 @@ mod test {
     #[test]
     fn test_e43() {
         raises!(
-            E43,
+            TagValueInReservedRange,
             r#"
         little_endian_packets
         enum A : 8 {
@@ -3121,7 +5404,7 @@ mod test {
     #[test]
     fn test_e49() {
         raises!(
-            E49,
+            OptionalConditionIdentifier,
             r#"
         little_endian_packets
         packet B {
@@ -3272,6 +5555,1276 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_e54() {
+        raises!(
+            DefaultValueOutOfRange,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 8 = 256,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            a : 8 = 255,
+            b : 7 = 64,
+            _reserved_ : 1,
+        }
+        "#
+        );
+
+        raises!(
+            DefaultValueOutOfRange,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 72 = 0x1000000000000000000,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            a : 128 = 0xffffffffffffffffffffffffffffffff,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e55() {
+        raises!(
+            UndeclaredDefaultTag,
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0, Y = 1 }
+        packet A {
+            e : E = Z,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0, Y = 1 }
+        packet A {
+            e : E = Y,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e56() {
+        raises!(
+            InvalidDefaultValue,
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0, Y = 1..10 }
+        packet A {
+            e : E = Y,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidDefaultValue,
+            r#"
+        little_endian_packets
+        struct S { a: 8 }
+        packet A {
+            s : S = X,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidDefaultValue,
+            r#"
+        little_endian_packets
+        packet A {
+            c : 1,
+            _reserved_ : 7,
+            a : 8 = 1 if c = 1,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e57() {
+        raises!(
+            InvalidFieldEndianness,
+            r#"
+        little_endian_packets
+        struct S { a: 8 }
+        packet A {
+            s : S big_endian,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidFieldEndianness,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 4,
+            b : 12 big_endian,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidFieldEndianness,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 12 big_endian,
+            b : 4,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum E : 16 { X = 0 }
+        packet A {
+            a : 4,
+            _reserved_ : 4,
+            b : 16 big_endian,
+            c : E big_endian,
+            d : 24 little_endian,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e58() {
+        raises!(
+            InvalidSizeUnit,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(a, words12): 8,
+            a: 8[],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidSizeUnit,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(_payload_, words32): 8,
+            _payload_ : [+2],
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(a, bits): 16,
+            _size_(b, words32): 8,
+            _size_(_payload_, words16): 8,
+            a: 8[],
+            b: 8[],
+            _payload_ : [+2],
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e59() {
+        raises!(
+            InvalidTrailingSizeField,
+            r#"
+        little_endian_packets
+        struct B { x : 8 }
+        packet A {
+            a: B,
+            _size_(a): 8,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidTrailingSizeField,
+            r#"
+        little_endian_packets
+        packet A {
+            a: 8[],
+            b: 8[],
+            _size_(a): 8,
+            _size_(b): 8,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidTrailingSizeField,
+            r#"
+        little_endian_packets
+        packet A {
+            a: 8[],
+            _padding_ [16],
+            _count_(a): 8,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidTrailingSizeField,
+            r#"
+        little_endian_packets
+        packet A {
+            _payload_,
+            _size_(_payload_): 8,
+            b: 8[],
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        struct S { a: 8, b: 8 }
+        packet A {
+            a: 8[],
+            _size_(a): 8,
+            d: S,
+        }
+        packet B {
+            b: S[],
+            _count_(b): 4,
+            c: 4,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            _payload_ : [+1],
+            _size_(_payload_): 16,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e60() {
+        raises!(
+            InvalidTestInput,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        test A { "\x0" }
+        "#
+        );
+
+        raises!(
+            InvalidTestInput,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        test A { fail "\n" => length }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum C : 8 { X = 0, Y = 1 }
+        packet A { x : 8, c : C, _payload_ }
+        packet B : A (x = 1) { y : 8 }
+        test B {
+            "\x01\x01\x02" => { x: 1, c: Y, y: 2 },
+            "\x01\x00\\",
+            fail "\x01\x00" => length,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e61() {
+        raises!(
+            InvalidEnumWidth,
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 1 }
+        enum B : A : 16 { Y = 2 }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 1 }
+        enum B : A : 8 { Y = 2 }
+        enum C : B { Z = 3 }
+        packet P { a : A, c : C }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e62() {
+        raises!(
+            InvalidScalarScale,
+            r#"
+        little_endian_packets
+        packet A { a : 8 scale 0 }
+        "#
+        );
+
+        raises!(
+            InvalidScalarScale,
+            r#"
+        little_endian_packets
+        struct A { a : 16 scale 0.00 unit "ms" }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            a : 16 scale 1.25 unit "ms",
+            b : 8 scale 2,
+            c : 8 scale 0.5 = 4,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e63() {
+        raises!(
+            InvalidFieldWidth,
+            r#"
+        little_endian_packets
+        packet A { a : 136 }
+        "#
+        );
+
+        raises!(
+            InvalidFieldWidth,
+            r#"
+        little_endian_packets
+        custom_field A : 256 "a"
+        "#
+        );
+
+        raises!(
+            InvalidFieldWidth,
+            r#"
+        little_endian_packets
+        enum A : 72 { X = 0 }
+        "#
+        );
+
+        raises!(
+            InvalidFieldWidth,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(a) : 72,
+            a : 8[],
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        custom_field A : 128 "a"
+        packet B {
+            a : 128,
+            b : 72,
+            c : A,
+            d : 96[],
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e64() {
+        raises!(
+            InvalidExtensionField,
+            r#"
+        little_endian_packets
+        packet A {
+            _extension_ { a : 8[] }
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionField,
+            r#"
+        little_endian_packets
+        packet A {
+            _extension_ { a : 12 }
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionField,
+            r#"
+        little_endian_packets
+        packet A {
+            c : 1,
+            _reserved_ : 7,
+            _extension_ { a : 8 if c = 1 }
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0 }
+        struct S { x : 8 }
+        packet A {
+            _extension_ { a : 8, b : E },
+            _extension_ { c : S },
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e65() {
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        packet A {
+            _extension_ { a : 8 },
+            b : 8,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        packet A {
+            _payload_,
+            _extension_ { a : 8 },
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 8[],
+            _extension_ { b : 8 },
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        struct S {
+            a : 8,
+            _extension_ { b : 8 },
+        }
+        packet A {
+            s : S,
+            c : 8,
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        struct S {
+            a : 8,
+            _extension_ { b : 8 },
+        }
+        struct T {
+            s : S,
+        }
+        packet A {
+            _count_(t) : 8,
+            t : T[],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidExtensionPosition,
+            r#"
+        little_endian_packets
+        struct S {
+            a : 8,
+            _payload_,
+        }
+        struct T : S (a = 1) {
+            _extension_ { b : 8 },
+        }
+        packet A {
+            s : S,
+            c : 8,
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(_payload_) : 8,
+            _payload_,
+            _count_(a) : 8,
+            a : 8[],
+            _extension_ { b : 8 },
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        struct S {
+            a : 8,
+            _extension_ { b : 8 },
+        }
+        packet A {
+            c : 8,
+            s : S,
+        }
+        packet B {
+            _elementsize_(s) : 8,
+            s : S[],
+            c : 8,
+        }
+        packet C {
+            _size_(s) : 8,
+            s : S,
+            c : 8,
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e66() {
+        raises!(
+            InvalidSizeBound,
+            r#"
+        little_endian_packets
+        packet A {
+            a : 8[..0],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidSizeBound,
+            r#"
+        little_endian_packets
+        packet A {
+            _count_(a) : 4,
+            _reserved_ : 4,
+            a : 8[..16],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidSizeBound,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(a) : 8,
+            a : 16[..128],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidSizeBound,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(a, bits) : 8,
+            a : 8[..32],
+        }
+        "#
+        );
+
+        raises!(
+            InvalidSizeBound,
+            r#"
+        little_endian_packets
+        packet A {
+            _size_(_payload_) : 8,
+            _payload_ : [..256],
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        struct S {
+            a : 16,
+        }
+        packet A {
+            _count_(a) : 4,
+            _reserved_ : 4,
+            a : 8[..15],
+            _size_(b) : 8,
+            b : S[..127],
+            _size_(_payload_) : 8,
+            _payload_ : [..255],
+        }
+        packet B {
+            _size_(a, words16) : 4,
+            _reserved_ : 4,
+            a : 16[..15],
+            c : 8[..16],
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e67() {
+        raises!(
+            UndeclaredBindIdentifier,
+            r#"
+        little_endian_packets
+        packet A { _payload_ }
+        bind A.payload as B
+        "#
+        );
+
+        raises!(
+            UndeclaredBindIdentifier,
+            r#"
+        little_endian_packets
+        packet B { x : 8 }
+        bind A.payload as B
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e68() {
+        raises!(
+            InvalidBindIdentifier,
+            r#"
+        little_endian_packets
+        packet A { _payload_ }
+        enum B : 8 { X = 0 }
+        bind A.payload as B
+        "#
+        );
+
+        raises!(
+            InvalidBindIdentifier,
+            r#"
+        little_endian_packets
+        group A { x : 8 }
+        packet B { x : 8 }
+        bind A.payload as B
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e69() {
+        raises!(
+            InvalidBindField,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        packet B { x : 8 }
+        bind A.payload as B
+        "#
+        );
+
+        raises!(
+            InvalidBindField,
+            r#"
+        little_endian_packets
+        packet A { x : 8, _payload_ }
+        packet B { x : 8 }
+        bind A.x as B
+        "#
+        );
+
+        raises!(
+            InvalidBindField,
+            r#"
+        little_endian_packets
+        packet A { x : 8, _payload_ }
+        packet B { x : 8 }
+        bind A._body_ as B
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0 }
+        packet A { x : 8, e : E, _payload_ }
+        packet C : A (x = 1) { _body_ }
+        struct B { x : 8 }
+        bind A.payload as B if x = 1, e = X
+        bind A._payload_ as B
+        bind C._body_ as B if x = 1
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e70() {
+        raises!(
+            UndeclaredResponseIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        response A -> B
+        "#
+        );
+
+        raises!(
+            UndeclaredResponseIdentifier,
+            r#"
+        little_endian_packets
+        packet B { x : 8 }
+        response A -> B
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e71() {
+        raises!(
+            InvalidResponseIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        struct B { x : 8 }
+        response A -> B
+        "#
+        );
+
+        raises!(
+            InvalidResponseIdentifier,
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0 }
+        packet B { x : 8 }
+        response A -> B
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e72() {
+        raises!(
+            DuplicateResponseDeclaration,
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        packet B { x : 8 }
+        packet C { x : 8 }
+        response A -> B
+        response A -> C
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A { x : 8 }
+        packet B { x : 8 }
+        packet C { x : 8 }
+        response A -> C
+        response B -> C
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e73() {
+        raises!(
+            InvalidSizeAssertion,
+            r#"
+        little_endian_packets
+        struct A : size == 2 { x : 8 }
+        "#
+        );
+
+        raises!(
+            InvalidSizeAssertion,
+            r#"
+        little_endian_packets
+        packet A : size <= 1 { x : 16 }
+        "#
+        );
+
+        raises!(
+            InvalidSizeAssertion,
+            r#"
+        little_endian_packets
+        packet A : size >= 4 { x : 8, _payload_ }
+        packet B : A (x = 1) { y : 8 }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        struct A : size == 2 { x : 16 }
+        packet B big_endian : size <= 255 { x : 8, _payload_ }
+        packet C : B (x = 1) : size == 3 { y : 16 }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e74() {
+        raises!(
+            UndeclaredInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8, assert x <= y }
+        "#
+        );
+
+        raises!(
+            UndeclaredInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8, assert len(y) == x }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e75() {
+        raises!(
+            InvalidInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8, y : 8[], assert x <= y }
+        "#
+        );
+
+        raises!(
+            InvalidInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8, y : 8[], assert len(x) == 1 }
+        "#
+        );
+
+        raises!(
+            InvalidInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 72, assert x == 1 }
+        "#
+        );
+
+        raises!(
+            InvalidInvariantIdentifier,
+            r#"
+        little_endian_packets
+        packet A { c : 1, _reserved_ : 7, x : 8 if c = 1, assert x == 1 }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e76() {
+        raises!(
+            InvalidInvariantExpression,
+            r#"
+        little_endian_packets
+        packet A { x : 8, assert x + 1 }
+        "#
+        );
+
+        raises!(
+            InvalidInvariantExpression,
+            r#"
+        little_endian_packets
+        packet A { x : 8, assert x && x == 1 }
+        "#
+        );
+
+        raises!(
+            InvalidInvariantExpression,
+            r#"
+        little_endian_packets
+        packet A { x : 8, assert (x == 1) + 1 == 2 }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        packet A { x : 8, y : 8, _payload_, assert x <= y }
+        packet B : A (x = 1) {
+            _count_(z) : 8,
+            z : 16[],
+            assert x + 2 * y == len(z) || (y == 0 && x != 1),
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_e77() {
+        raises!(
+            DuplicateTagDisplayName,
+            r#"
+        little_endian_packets
+        enum A : 8 {
+            X = 0 "Value",
+            Y = 1 "Value",
+        }
+        "#
+        );
+
+        raises!(
+            DuplicateTagDisplayName,
+            r#"
+        little_endian_packets
+        enum A : 8 {
+            X = 0 "Value",
+            R = 1..10 {
+                Y = 1 "Value",
+            },
+        }
+        "#
+        );
+
+        valid!(
+            r#"
+        little_endian_packets
+        enum A : 8 {
+            X | OLD_X = 0 "Value X",
+            R = 1..10 {
+                Y = 1 "Value Y",
+            },
+        }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_structured_diagnostics() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "stdin",
+            "little_endian_packets\nstruct A {}\npacket A {}\n".to_owned(),
+        )
+        .expect("parsing failure");
+        let diagnostics = analyzer::analyze(&file).expect_err("analysis success");
+        let position = |line, column| analyzer::DiagnosticPosition { line, column };
+        assert_eq!(
+            diagnostics.structured(&db).unwrap(),
+            vec![analyzer::DiagnosticData {
+                code: Some("E1".to_owned()),
+                severity: analyzer::DiagnosticSeverity::Error,
+                message: "redeclaration of packet identifier `A`".to_owned(),
+                labels: vec![
+                    analyzer::DiagnosticLabel {
+                        primary: true,
+                        message: String::new(),
+                        file: "stdin".to_owned(),
+                        start: position(3, 1),
+                        end: position(3, 12),
+                    },
+                    analyzer::DiagnosticLabel {
+                        primary: false,
+                        message: "`A` is first declared here".to_owned(),
+                        file: "stdin".to_owned(),
+                        start: position(2, 1),
+                        end: position(2, 12),
+                    },
+                ],
+                notes: vec![],
+            }]
+        );
+    }
+
+    /// Extract the PDL examples of an error code explanation.
+    fn explanation_examples(explanation: &str) -> Vec<String> {
+        explanation
+            .split("```pdl\n")
+            .skip(1)
+            .map(|block| block.split("```").next().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_error_code_explanations() {
+        for code in analyzer::ErrorCode::ALL {
+            let examples = explanation_examples(code.explanation());
+            assert_eq!(examples.len(), 2, "{code}");
+
+            let mut db = ast::SourceDatabase::new();
+            let file =
+                parse_inline(&mut db, "stdin", examples[0].clone()).expect("parsing failure");
+            let diagnostics = analyzer::analyze(&file).expect_err("analysis success");
+            assert!(
+                diagnostics.diagnostics.iter().any(|d| d.code == Some(code.into())),
+                "{code} is not raised by the erroneous example"
+            );
+
+            let file =
+                parse_inline(&mut db, "stdin", examples[1].clone()).expect("parsing failure");
+            assert!(analyzer::analyze(&file).is_ok(), "{code} is raised by the valid example");
+        }
+    }
+
+    #[test]
+    fn test_warning_code_explanations() {
+        for code in analyzer::WarningCode::ALL {
+            let examples = explanation_examples(code.explanation());
+            assert_eq!(examples.len(), 2, "{code}");
+
+            let mut db = ast::SourceDatabase::new();
+            let file =
+                parse_inline(&mut db, "stdin", examples[0].clone()).expect("parsing failure");
+            let (_, warnings) = analyzer::analyze_with_warnings(&file).expect("analysis failure");
+            assert!(
+                warnings.diagnostics.iter().any(|d| d.code == Some(code.into())),
+                "{code} is not raised by the suspicious example"
+            );
+
+            let file =
+                parse_inline(&mut db, "stdin", examples[1].clone()).expect("parsing failure");
+            let (_, warnings) = analyzer::analyze_with_warnings(&file).expect("analysis failure");
+            assert!(warnings.diagnostics.is_empty(), "{code} is raised by the valid example");
+        }
+    }
+
+    #[test]
+    fn test_w1() {
+        warns!(
+            UnusedDecl,
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0 }
+        packet B { x: 8 }
+        "#
+        );
+
+        warns!(
+            UnusedDecl,
+            r#"
+        little_endian_packets
+        group A { x: 8 }
+        packet B { x: 8 }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0 }
+        group G { a: A }
+        packet B { G }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_w2() {
+        warns!(
+            UnmatchedEnumTag,
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0, Y = 1 }
+        packet B { a: A, _payload_ }
+        packet C : B (a = X) { }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0, Y = 1 }
+        packet B { a: A, _payload_ }
+        packet C : B (a = X) { }
+        packet D : B (a = Y) { }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        enum A : 8 { X = 0, Y = 1 }
+        packet B { a: A, b: 8, _payload_ }
+        packet C : B (a = X, b = 0) { }
+        packet D : B (b = 1) { }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_w3() {
+        warns!(
+            ShadowedChildDecl,
+            r#"
+        little_endian_packets
+        packet A { a: 8, b: 8, _payload_ }
+        packet B : A (a = 1) { }
+        packet C : A (a = 1, b = 2) { }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        packet A { a: 8, b: 8, _payload_ }
+        packet B : A (a = 1, b = 2) { }
+        packet C : A (a = 2) { }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_w4() {
+        warns!(
+            OversizedReservedField,
+            r#"
+        little_endian_packets
+        packet A { a: 4, _reserved_: 68, b: 8 }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        packet A { a: 6, _reserved_: 10, _reserved_: 72 }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_w5() {
+        warns!(
+            UndeclaredSizeField,
+            r#"
+        little_endian_packets
+        packet A { num_items: 8, items: 8[] }
+        "#
+        );
+
+        warns!(
+            UndeclaredSizeField,
+            r#"
+        little_endian_packets
+        packet A { length: 8, _payload_ }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        packet A { items_size: 8, items: 8[4], length: 8, _size_(_payload_): 8, _payload_ }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_w6() {
+        warns!(
+            OverlappingConstraints,
+            r#"
+        little_endian_packets
+        packet A { a: 8, b: 8, _payload_ }
+        packet B : A (a = 1) { }
+        packet C : A (b = 2) { }
+        "#
+        );
+
+        warns!(
+            OverlappingConstraints,
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0, Y = 1, Other = .. }
+        packet A { e: E, _payload_ }
+        packet B : A (e = Other) { }
+        packet C : A { _payload_ }
+        packet D : C (e = Other) { }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        enum E : 8 { X = 0, Y = 1, Other = .. }
+        packet A { e: E, b: 8, _payload_ }
+        packet B : A (e = X) { }
+        packet C : A (e = Other, b = 1) { }
+        packet D : A { _payload_ }
+        packet F : D (e = Y) { }
+        "#
+        );
+
+        no_warnings!(
+            r#"
+        little_endian_packets
+        packet A { a: 8, _payload_ }
+        packet B : A (a = 1) { x: 8 }
+        packet C : A (a = 1) { x: 16 }
+        "#
+        );
+    }
+
+    #[test]
+    fn test_bind_constraints() {
+        raises!(
+            UndeclaredConstraintIdentifier,
+            r#"
+        little_endian_packets
+        packet A { x : 8, _payload_ }
+        packet B { x : 8 }
+        bind A.payload as B if y = 1
+        "#
+        );
+    }
+
     #[test]
     fn test_decl_ordering() {
         valid!(
@@ -64,12 +64,55 @@ pub struct Endianness {
     pub value: EndiannessValue,
 }
 
+/// Order in which bit-fields are packed into their containing
+/// byte-aligned chunk. The first declared field occupies the least
+/// significant bits of the chunk with `LsbFirst` (the default),
+/// and the most significant bits with `MsbFirst`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitOrder {
+    #[default]
+    LsbFirst,
+    MsbFirst,
+}
+
+/// Unit of the value of size fields.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeUnit {
+    Bits,
+    #[default]
+    Octets,
+    /// Words of the selected width in bits.
+    Words(usize),
+}
+
+/// Comparison operator of size assertions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeComparison {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+/// Assertion on the encoded size of a declaration, in octets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct SizeAssertion {
+    pub comparison: SizeComparison,
+    pub size: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "kind", rename = "tag")]
 pub struct TagValue {
     pub id: String,
     pub loc: SourceRange,
     pub value: usize,
+    /// Human readable name of the tag, used when displaying the value.
+    pub display_name: Option<String>,
+    /// Alternative identifiers for the tag value.
+    pub aliases: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -101,10 +144,59 @@ pub enum Tag {
 pub struct Constraint {
     pub id: String,
     pub loc: SourceRange,
-    pub value: Option<usize>,
+    pub value: Option<u128>,
     pub tag_id: Option<String>,
 }
 
+/// Binary operator of invariant expressions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinaryOp {
+    Mul,
+    Add,
+    Sub,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// Invariant expression over the field values of a declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Expr {
+    Integer {
+        value: usize,
+    },
+    /// Value of the scalar field `id`.
+    Field {
+        id: String,
+    },
+    /// Number of elements of the array field `id`.
+    Length {
+        id: String,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename = "invariant")]
+pub struct Invariant {
+    pub loc: SourceRange,
+    /// Source text of the invariant expression, used to name
+    /// the invariant in generated errors.
+    pub text: String,
+    pub expr: Expr,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FieldKey(pub usize);
 
@@ -116,21 +208,25 @@ pub enum FieldDesc {
     #[serde(rename = "padding_field")]
     Padding { size: usize },
     #[serde(rename = "size_field")]
-    Size { field_id: String, width: usize },
+    Size { field_id: String, width: usize, unit: SizeUnit },
     #[serde(rename = "count_field")]
     Count { field_id: String, width: usize },
     #[serde(rename = "elementsize_field")]
     ElementSize { field_id: String, width: usize },
     #[serde(rename = "body_field")]
     Body,
+    /// Payload field. The optional `max_size` is the upper bound
+    /// on the payload size in octets.
     #[serde(rename = "payload_field")]
-    Payload { size_modifier: Option<String> },
+    Payload { size_modifier: Option<String>, max_size: Option<usize> },
     #[serde(rename = "fixed_field")]
-    FixedScalar { width: usize, value: usize },
+    FixedScalar { width: usize, value: u128 },
     #[serde(rename = "fixed_field")]
     FixedEnum { enum_id: String, tag_id: String },
     #[serde(rename = "reserved_field")]
     Reserved { width: usize },
+    /// Array field. The optional `max_count` is the upper bound
+    /// on the number of elements of dynamic arrays.
     #[serde(rename = "array_field")]
     Array {
         id: String,
@@ -138,15 +234,24 @@ pub enum FieldDesc {
         type_id: Option<String>,
         size_modifier: Option<String>,
         size: Option<usize>,
+        max_count: Option<usize>,
     },
+    /// Scalar field. The optional `scale` is the decimal factor
+    /// applied to the raw value to obtain the value in `unit`.
     #[serde(rename = "scalar_field")]
-    Scalar { id: String, width: usize },
+    Scalar {
+        id: String,
+        width: usize,
+        default: Option<u128>,
+        scale: Option<String>,
+        unit: Option<String>,
+    },
     /// Special case of Scalar for fields used as condition for
     /// optional fields. The width is always 1.
     #[serde(rename = "flag_field")]
     Flag { id: String, optional_field_ids: Vec<(String, usize)> },
     #[serde(rename = "typedef_field")]
-    Typedef { id: String, type_id: String },
+    Typedef { id: String, type_id: String, default: Option<String> },
     #[serde(rename = "group_field")]
     Group { group_id: String, constraints: Vec<Constraint> },
 }
@@ -161,6 +266,31 @@ pub struct Field {
     #[serde(flatten)]
     pub desc: FieldDesc,
     pub cond: Option<Constraint>,
+    /// Endianness override for this field.
+    pub endianness: Option<EndiannessValue>,
+    /// Index of the `_extension_` block declaring this field.
+    /// Extension fields are decoded only if bytes remain at the
+    /// start of the block.
+    pub extension: Option<usize>,
+}
+
+/// Decoding error expected from a failing test case.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestError {
+    ArraySize,
+    Bound,
+    ConstraintValue,
+    DepthLimit,
+    EnumValue,
+    FixedValue,
+    Invariant,
+    Length,
+    SizeAssertion,
+    SizeBound,
+    SizeUnit,
+    TrailingBytes,
+    TrailingSize,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -168,6 +298,10 @@ pub struct Field {
 pub struct TestCase {
     pub loc: SourceRange,
     pub input: String,
+    /// Expected values of the decoded fields.
+    pub fields: Vec<Constraint>,
+    /// Set for inputs that must be rejected by the parser.
+    pub error: Option<TestError>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -180,14 +314,21 @@ pub enum DeclDesc {
     Checksum { id: String, function: String, width: usize },
     #[serde(rename = "custom_field_declaration")]
     CustomField { id: String, width: Option<usize>, function: String },
+    /// Enum declaration. Enum extensions name the base enum in
+    /// `parent_id`; the tags and width of the base enum are inherited
+    /// by the analyzer. The width of an enum extension is set to zero
+    /// by the parser when omitted.
     #[serde(rename = "enum_declaration")]
-    Enum { id: String, tags: Vec<Tag>, width: usize },
+    Enum { id: String, tags: Vec<Tag>, width: usize, parent_id: Option<String> },
+    #[serde(rename = "flags_declaration")]
+    Flags { id: String, flags: Vec<TagValue>, width: usize },
     #[serde(rename = "packet_declaration")]
     Packet {
         id: String,
         constraints: Vec<Constraint>,
         fields: Vec<Field>,
         parent_id: Option<String>,
+        invariants: Vec<Invariant>,
     },
     #[serde(rename = "struct_declaration")]
     Struct {
@@ -195,11 +336,21 @@ pub enum DeclDesc {
         constraints: Vec<Constraint>,
         fields: Vec<Field>,
         parent_id: Option<String>,
+        invariants: Vec<Invariant>,
     },
     #[serde(rename = "group_declaration")]
     Group { id: String, fields: Vec<Field> },
     #[serde(rename = "test_declaration")]
     Test { type_id: String, test_cases: Vec<TestCase> },
+    /// Binding of the payload field `field_id` of the declaration
+    /// `packet_id` to the declaration `type_id`. The binding applies
+    /// when the packet field values match `constraints`.
+    #[serde(rename = "bind_declaration")]
+    Bind { packet_id: String, field_id: String, type_id: String, constraints: Vec<Constraint> },
+    /// Pairing of the command packet `request_id` with the packet
+    /// `response_id` sent in response.
+    #[serde(rename = "response_declaration")]
+    Response { request_id: String, response_id: String },
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -211,6 +362,12 @@ pub struct Decl {
     pub key: DeclKey,
     #[serde(flatten)]
     pub desc: DeclDesc,
+    /// Endianness override for the fields of this declaration.
+    pub endianness: Option<EndiannessValue>,
+    /// Bit order override for the fields of this declaration.
+    pub bit_order: Option<BitOrder>,
+    /// Assertion on the encoded size of this declaration.
+    pub size_assertion: Option<SizeAssertion>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -219,6 +376,7 @@ pub struct File {
     pub file: FileId,
     pub comments: Vec<Comment>,
     pub endianness: Endianness,
+    pub bit_order: BitOrder,
     pub declarations: Vec<Decl>,
     #[serde(skip_serializing)]
     pub max_key: usize,
@@ -341,6 +499,87 @@ impl Tag {
     }
 }
 
+impl SizeUnit {
+    /// Return the width of the unit in bits.
+    pub fn width(&self) -> usize {
+        match self {
+            SizeUnit::Bits => 1,
+            SizeUnit::Octets => 8,
+            SizeUnit::Words(width) => *width,
+        }
+    }
+}
+
+impl BitOrder {
+    /// Return the shift to apply to a bit-field of width `width` located
+    /// at bit offset `offset` inside a chunk of width `chunk_width`.
+    pub fn shift(&self, chunk_width: usize, offset: usize, width: usize) -> usize {
+        match self {
+            BitOrder::LsbFirst => offset,
+            BitOrder::MsbFirst => chunk_width - offset - width,
+        }
+    }
+}
+
+impl TestError {
+    /// Return the name of the matching decoding error variant
+    /// in the generated code.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TestError::ArraySize => "ArraySizeError",
+            TestError::Bound => "BoundError",
+            TestError::ConstraintValue => "ConstraintValueError",
+            TestError::DepthLimit => "DepthLimitError",
+            TestError::EnumValue => "EnumValueError",
+            TestError::FixedValue => "FixedValueError",
+            TestError::Invariant => "InvariantViolation",
+            TestError::Length => "LengthError",
+            TestError::SizeAssertion => "SizeAssertionError",
+            TestError::SizeBound => "SizeBoundError",
+            TestError::SizeUnit => "SizeUnitError",
+            TestError::TrailingBytes => "TrailingBytesError",
+            TestError::TrailingSize => "TrailingSizeError",
+        }
+    }
+}
+
+impl TestCase {
+    /// Return the octets of the test input.
+    /// The input can contain `\xHH` escapes for arbitrary octets and
+    /// `\\` for a literal backslash; other characters stand for their
+    /// UTF-8 encoding.
+    pub fn bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = vec![];
+        let mut chars = self.input.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('\\') => bytes.push(b'\\'),
+                    Some('x') => {
+                        let digits: String = chars.by_ref().take(2).collect();
+                        match u8::from_str_radix(&digits, 16) {
+                            Ok(byte) if digits.len() == 2 => bytes.push(byte),
+                            _ => return Err(format!("invalid escape sequence `\\x{digits}`")),
+                        }
+                    }
+                    Some(c) => return Err(format!("invalid escape sequence `\\{c}`")),
+                    None => return Err("unterminated escape sequence".to_owned()),
+                },
+                c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+impl Eq for Invariant {}
+impl PartialEq for Invariant {
+    fn eq(&self, other: &Self) -> bool {
+        // Implement structural equality, leave out loc.
+        self.expr == other.expr
+    }
+}
+
 impl Eq for Constraint {}
 impl PartialEq for Constraint {
     fn eq(&self, other: &Self) -> bool {
@@ -353,7 +592,7 @@ impl Eq for TestCase {}
 impl PartialEq for TestCase {
     fn eq(&self, other: &Self) -> bool {
         // Implement structural equality, leave out loc.
-        self.input == other.input
+        self.input == other.input && self.fields == other.fields && self.error == other.error
     }
 }
 
@@ -377,17 +616,50 @@ impl File {
                 loc: SourceRange::default(),
                 value: EndiannessValue::LittleEndian,
             },
+            bit_order: BitOrder::LsbFirst,
             declarations: vec![],
             file,
             max_key: 0,
         }
     }
 
+    /// Return the bit order applied to the fields of the selected
+    /// declaration: the declaration override if present, the file
+    /// bit order otherwise.
+    pub fn bit_order(&self, decl: &Decl) -> BitOrder {
+        decl.bit_order.unwrap_or(self.bit_order)
+    }
+
+    /// Return the endianness applied to the fields of the selected
+    /// declaration: the declaration override if present, the file
+    /// endianness otherwise.
+    pub fn decl_endianness(&self, decl: &Decl) -> EndiannessValue {
+        decl.endianness.unwrap_or(self.endianness.value)
+    }
+
+    /// Return the endianness applied to the selected field of the
+    /// declaration `decl`: the field override if present, the
+    /// declaration endianness otherwise.
+    pub fn field_endianness(&self, decl: &Decl, field: &Field) -> EndiannessValue {
+        field.endianness.unwrap_or_else(|| self.decl_endianness(decl))
+    }
+
+    /// Iterate over the bind declarations targeting the payload
+    /// of the selected declaration.
+    pub fn iter_bindings<'d>(&'d self, decl: &'d Decl) -> impl Iterator<Item = &'d Decl> {
+        self.declarations.iter().filter(|other_decl| {
+            matches!(&other_decl.desc, DeclDesc::Bind { packet_id, .. }
+                if Some(packet_id.as_str()) == decl.id())
+        })
+    }
+
     /// Iterate over the children of the selected declaration.
     /// /!\ This method is unsafe to use if the file contains cyclic
     /// declarations, use with caution.
     pub fn iter_children<'d>(&'d self, decl: &'d Decl) -> impl Iterator<Item = &'d Decl> {
-        self.declarations.iter().filter(|other_decl| other_decl.parent_id() == decl.id())
+        self.declarations
+            .iter()
+            .filter(|other_decl| decl.id().is_some() && other_decl.parent_id() == decl.id())
     }
 }
 
@@ -399,13 +671,55 @@ impl PartialEq for Decl {
     }
 }
 
+impl SizeAssertion {
+    /// Check whether the encoded size `size`, in octets, satisfies
+    /// the assertion.
+    pub fn check(&self, size: usize) -> bool {
+        match self.comparison {
+            SizeComparison::Equal => size == self.size,
+            SizeComparison::LessOrEqual => size <= self.size,
+            SizeComparison::GreaterOrEqual => size >= self.size,
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOp::Mul => "*",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        })
+    }
+}
+
+impl fmt::Display for SizeAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let comparison = match self.comparison {
+            SizeComparison::Equal => "==",
+            SizeComparison::LessOrEqual => "<=",
+            SizeComparison::GreaterOrEqual => ">=",
+        };
+        write!(f, "size {comparison} {}", self.size)
+    }
+}
+
 impl Decl {
     pub fn id(&self) -> Option<&str> {
         match &self.desc {
-            DeclDesc::Test { .. } => None,
+            DeclDesc::Test { .. } | DeclDesc::Bind { .. } | DeclDesc::Response { .. } => None,
             DeclDesc::Checksum { id, .. }
             | DeclDesc::CustomField { id, .. }
             | DeclDesc::Enum { id, .. }
+            | DeclDesc::Flags { id, .. }
             | DeclDesc::Packet { id, .. }
             | DeclDesc::Struct { id, .. }
             | DeclDesc::Group { id, .. } => Some(id),
@@ -430,6 +744,15 @@ impl Decl {
         }
     }
 
+    pub fn invariants(&self) -> std::slice::Iter<'_, Invariant> {
+        match &self.desc {
+            DeclDesc::Packet { invariants, .. } | DeclDesc::Struct { invariants, .. } => {
+                invariants.iter()
+            }
+            _ => [].iter(),
+        }
+    }
+
     pub fn fields(&self) -> std::slice::Iter<'_, Field> {
         match &self.desc {
             DeclDesc::Packet { fields, .. }
@@ -464,6 +787,20 @@ impl Decl {
         })
     }
 
+    /// Return true if the size or count field `size` is declared after
+    /// the field it designates.
+    pub fn is_trailing_size(&self, size: &Field) -> bool {
+        let field_id = match &size.desc {
+            FieldDesc::Size { field_id, .. } | FieldDesc::Count { field_id, .. } => field_id,
+            _ => return false,
+        };
+        self.fields().take_while(|field| field.key != size.key).any(|field| match &field.desc {
+            FieldDesc::Payload { .. } => field_id == "_payload_",
+            FieldDesc::Body => field_id == "_body_",
+            _ => field.id() == Some(field_id),
+        })
+    }
+
     /// Return the reference to the array element size in a declaration, if present.
     pub fn element_size(&self, id: &str) -> Option<&Field> {
         self.fields().find(|field| match &field.desc {
@@ -477,10 +814,13 @@ impl Decl {
             DeclDesc::Checksum { .. } => "checksum",
             DeclDesc::CustomField { .. } => "custom field",
             DeclDesc::Enum { .. } => "enum",
+            DeclDesc::Flags { .. } => "flags",
             DeclDesc::Packet { .. } => "packet",
             DeclDesc::Struct { .. } => "struct",
             DeclDesc::Group { .. } => "group",
             DeclDesc::Test { .. } => "test",
+            DeclDesc::Bind { .. } => "bind",
+            DeclDesc::Response { .. } => "response",
         }
     }
 }
@@ -532,6 +872,13 @@ impl Field {
             FieldDesc::Typedef { .. } => "typedef",
         }
     }
+
+    /// Return true if the field may be absent from the encoded
+    /// declaration, i.e. the field is conditional or is declared in an
+    /// extension block.
+    pub fn is_optional(&self) -> bool {
+        self.cond.is_some() || self.extension.is_some()
+    }
 }
 
 #[cfg(test)]
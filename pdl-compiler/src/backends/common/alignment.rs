@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::ast::BitOrder;
 use std::{
     fmt::Debug,
     mem,
@@ -68,13 +69,28 @@ pub struct ByteAligner<S: Symbol> {
     staged_width: usize,
     /// Committed chunks.
     chunks: Vec<Chunk<S>>,
+    /// Order in which fields are packed into a chunk.
+    bit_order: BitOrder,
 }
 
 impl<S: Symbol> ByteAligner<S> {
     pub const MAX_CHUNK_WIDTH: usize = 64;
+    pub const MAX_BYTES_WIDTH: usize = 128;
 
     pub fn new() -> Self {
-        Self { staged_fields: vec![], staged_width: 0, chunks: vec![] }
+        Self {
+            staged_fields: vec![],
+            staged_width: 0,
+            chunks: vec![],
+            bit_order: BitOrder::LsbFirst,
+        }
+    }
+
+    /// Create an aligner packing fields in the selected bit order.
+    /// Field offsets in committed chunks are always relative to the
+    /// least significant bit of the chunk.
+    pub fn with_bit_order(bit_order: BitOrder) -> Self {
+        Self { bit_order, ..Self::new() }
     }
 
     /// Get the generated chunks.
@@ -103,7 +119,7 @@ impl<S: Symbol> ByteAligner<S> {
     }
 
     /// Add a [`Symbol`] to the alignment.
-    /// This symbol's width must satisfy `width % 8 == 0`.
+    /// This symbol's width must satisfy `width % 8 == 0` and be <= [`Self::MAX_BYTES_WIDTH`].
     pub fn add_bytes(&mut self, symbol: S, width: usize) {
         if !self.is_aligned() {
             panic!("sized bytes must start at a byte boundary")
@@ -111,8 +127,8 @@ impl<S: Symbol> ByteAligner<S> {
         if !width.is_multiple_of(8) {
             panic!("width must be byte-divisible")
         }
-        if width > Self::MAX_CHUNK_WIDTH {
-            panic!("width can't be larger than max chunk width of {}", Self::MAX_CHUNK_WIDTH)
+        if width > Self::MAX_BYTES_WIDTH {
+            panic!("width can't be larger than max bytes width of {}", Self::MAX_BYTES_WIDTH)
         }
 
         self.chunks.push(Chunk::Bytes { symbol, width });
@@ -133,9 +149,15 @@ impl<S: Symbol> ByteAligner<S> {
 
     fn try_commit_staged_chunk(&mut self) {
         if self.staged_width != 0 && self.staged_width.is_multiple_of(8) {
+            let width = mem::replace(&mut self.staged_width, 0);
+            let bit_order = self.bit_order;
             self.chunks.push(Chunk::Bitpack {
-                fields: self.staged_fields.drain(..).collect(),
-                width: mem::replace(&mut self.staged_width, 0),
+                fields: self
+                    .staged_fields
+                    .drain(..)
+                    .map(|f| Field { offset: bit_order.shift(width, f.offset, f.width), ..f })
+                    .collect(),
+                width,
             });
         }
     }
@@ -202,6 +224,24 @@ mod tests {
         )
     }
 
+    #[test]
+    fn pack_multiple_fields_msb_first() {
+        let mut a = ByteAligner::<&'static str>::with_bit_order(BitOrder::MsbFirst);
+        a.add_bitfield("a", 4);
+        a.add_bitfield("b", 12);
+
+        assert_eq!(
+            a.align().unwrap(),
+            Alignment(vec![Chunk::Bitpack {
+                fields: vec![
+                    Field { symbol: "a", width: 4, offset: 12 },
+                    Field { symbol: "b", width: 12, offset: 0 }
+                ],
+                width: 16
+            },])
+        )
+    }
+
     #[test]
     fn pack_multiple_fields_into_64_chunk() {
         let mut a = ByteAligner::<&'static str>::new();
@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::{analyzer, ast};
+
 #[derive(Debug, Deserialize)]
 pub struct Packet {
     #[serde(rename = "packet")]
@@ -15,4 +17,81 @@ pub struct TestVector {
     pub unpacked: Option<Value>,
     pub packet: Option<String>,
     pub expected_error: Option<String>,
+    /// Set when `unpacked` lists only a subset of the packet fields.
+    /// The serializer is then tested by re-encoding the decoded packet
+    /// instead of building the packet from `unpacked`.
+    #[serde(default)]
+    pub round_trip: bool,
+}
+
+/// Build test vectors from the test declarations of an analyzed file.
+/// Expected enum values are resolved to the tag value, as in the
+/// canonical test vectors.
+pub fn test_vectors(file: &ast::File) -> Vec<Packet> {
+    let scope = analyzer::Scope::new(file).unwrap();
+    let mut packets: Vec<Packet> = vec![];
+
+    for decl in &file.declarations {
+        let ast::DeclDesc::Test { type_id, test_cases } = &decl.desc else {
+            continue;
+        };
+        let packet_decl = scope.typedef[type_id];
+        let tests = test_cases.iter().map(|test_case| {
+            let packed = test_case
+                .bytes()
+                .expect("invalid test input")
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect();
+            match test_case.error {
+                Some(error) => TestVector {
+                    packed,
+                    unpacked: None,
+                    packet: None,
+                    expected_error: Some(error.name().to_owned()),
+                    round_trip: false,
+                },
+                None => TestVector {
+                    packed,
+                    unpacked: Some(Value::Object(
+                        test_case
+                            .fields
+                            .iter()
+                            .map(|field| {
+                                (field.id.clone(), field_value(&scope, packet_decl, field))
+                            })
+                            .collect(),
+                    )),
+                    packet: None,
+                    expected_error: None,
+                    round_trip: true,
+                },
+            }
+        });
+
+        match packets.iter_mut().find(|packet| &packet.name == type_id) {
+            Some(packet) => packet.tests.extend(tests),
+            None => packets.push(Packet { name: type_id.clone(), tests: tests.collect() }),
+        }
+    }
+
+    packets
+}
+
+/// Return the JSON value of an expected field value.
+/// Values wider than 64 bits do not fit JSON numbers and are
+/// returned as decimal strings.
+fn field_value(scope: &analyzer::Scope, decl: &ast::Decl, constraint: &ast::Constraint) -> Value {
+    if let Some(value) = constraint.value {
+        return u64::try_from(value).map_or_else(|_| value.to_string().into(), Value::from);
+    }
+    let tag_id = constraint.tag_id.as_ref().unwrap();
+    let field = scope.iter_fields(decl).find(|field| field.id() == Some(&constraint.id)).unwrap();
+    let ast::FieldDesc::Typedef { type_id, .. } = &field.desc else {
+        unreachable!("tag value for non enum field `{}`", constraint.id)
+    };
+    let ast::DeclDesc::Enum { tags, .. } = &scope.typedef[type_id].desc else {
+        unreachable!("tag value for non enum field `{}`", constraint.id)
+    };
+    tags.iter().find(|tag| tag.id() == tag_id).and_then(ast::Tag::value).unwrap().into()
 }
@@ -14,6 +14,7 @@
 
 //! C++ compiler backend.
 
+use crate::backends::common::test::test_vectors;
 use crate::{analyzer, ast};
 use heck::ToUpperCamelCase;
 use std::collections::HashSet;
@@ -27,7 +28,12 @@ fn indent(s: &str, level: usize) -> String {
 }
 
 fn mask(width: usize) -> String {
-    format!("{:#x}", (1u128 << width) - 1)
+    if width > 64 {
+        // C++ does not provide 128-bit integer literals.
+        format!("(~static_cast<unsigned __int128>(0) >> {})", 128 - width)
+    } else {
+        format!("{:#x}", (1u128 << width) - 1)
+    }
 }
 
 fn deref(var: Option<&str>, id: &str) -> String {
@@ -43,7 +49,59 @@ fn get_cxx_scalar_type(width: usize) -> String {
             return format!("uint{}_t", n);
         }
     }
-    panic!("PDL type does not fit on non-extended scalar types: width = {}", width);
+    if width <= 128 {
+        return "unsigned __int128".to_string();
+    }
+    panic!("PDL type does not fit on extended scalar types: width = {}", width);
+}
+
+/// Return the type of the member holding the size in octets of a field
+/// sized by a size field of the selected width and unit.
+fn get_cxx_size_type(width: usize, unit: ast::SizeUnit) -> String {
+    match unit {
+        ast::SizeUnit::Words(_) => "size_t".to_string(),
+        ast::SizeUnit::Bits | ast::SizeUnit::Octets => get_cxx_scalar_type(width),
+    }
+}
+
+/// Return the statements converting the size `var` from the size unit
+/// to octets.
+fn size_to_octets(var: &str, unit: ast::SizeUnit) -> Vec<String> {
+    match unit {
+        ast::SizeUnit::Octets => vec![],
+        ast::SizeUnit::Bits => {
+            vec![format!("if ({var} % 8 != 0) return false;"), format!("{var} /= 8;")]
+        }
+        ast::SizeUnit::Words(width) => vec![format!("{var} *= {};", width / 8)],
+    }
+}
+
+/// Return the expression converting the size `expr` from octets
+/// to the size unit.
+fn size_from_octets(expr: &str, unit: ast::SizeUnit) -> String {
+    match unit {
+        ast::SizeUnit::Octets => expr.to_string(),
+        ast::SizeUnit::Bits => format!("({expr} * 8)"),
+        ast::SizeUnit::Words(width) => format!("({expr} / {})", width / 8),
+    }
+}
+
+/// Return the assertions checking that the size `var`, in octets,
+/// converts to the size unit without remainder and fits in the
+/// size field of width `width`.
+fn size_checks(var: &str, width: usize, unit: ast::SizeUnit) -> Vec<String> {
+    let mut checks = vec![];
+    if let ast::SizeUnit::Words(word_width) = unit {
+        checks.push(format!("_ASSERT_VALID({var} % {} == 0);", word_width / 8));
+    }
+    if width < 64 {
+        checks.push(format!(
+            "_ASSERT_VALID({} <= {:#x});",
+            size_from_octets(var, unit),
+            (1u64 << width) - 1
+        ));
+    }
+    checks
 }
 
 pub fn generate(
@@ -121,6 +179,15 @@ pub fn generate(
                 code.push_str(&format!("class {}View;\n", id));
             }
         }
+        // Structs bound to packet payloads are used before
+        // their declaration.
+        if let ast::DeclDesc::Bind { type_id, .. } = &decl.desc {
+            if matches!(scope.typedef[type_id].desc, ast::DeclDesc::Struct { .. })
+                && !exclude_declarations.contains(type_id)
+            {
+                code.push_str(&format!("class {type_id};\n"));
+            }
+        }
     }
 
     for decl in &file.declarations {
@@ -131,10 +198,18 @@ pub fn generate(
         }
 
         match &decl.desc {
-            ast::DeclDesc::Enum { id, tags, width } => {
+            ast::DeclDesc::Enum { id, tags, width, parent_id } => {
                 code.push_str(&generate_enum_declaration(id, tags, *width));
                 code.push_str(&generate_enum_to_text(id, tags));
                 code.push_str(&generate_enum_is_valid(id, tags, *width));
+                if let Some(parent_id) = parent_id {
+                    code.push_str(&generate_enum_extension_conversions(
+                        &scope, id, parent_id, *width,
+                    ));
+                }
+            }
+            ast::DeclDesc::Flags { id, flags, width } => {
+                code.push_str(&generate_flags_declaration(id, flags, *width));
             }
             ast::DeclDesc::Packet { .. } => {
                 code.push_str(&generate_packet_view(&scope, &schema, decl));
@@ -147,6 +222,125 @@ pub fn generate(
         }
     }
 
+    for decl in &file.declarations {
+        if matches!(decl.desc, ast::DeclDesc::Packet { .. })
+            && !exclude_declarations.contains(&decl.id().unwrap().to_string())
+        {
+            code.push_str(&generate_payload_binding_definitions(&scope, decl));
+        }
+    }
+
+    if let Some(ns) = namespace {
+        code.push_str(&format!("}}  // {}\n", ns));
+    }
+
+    code
+}
+
+/// Generate unit tests for the test declarations of an analyzed file.
+/// Packets are only decoded into views, which cannot be re-encoded:
+/// the tests check the decoded field values but not the serializer.
+pub fn generate_tests(
+    sources: &ast::SourceDatabase,
+    file: &ast::File,
+    namespace: Option<&str>,
+    include_headers: &[String],
+    using_namespaces: &[String],
+) -> String {
+    let mut code = String::new();
+    let source = sources.get(file.file).expect("could not read source");
+    let scope = analyzer::Scope::new(file).unwrap();
+
+    code.push_str(&format!(
+        r#"// File generated from {source_name}, with the command
+//   pdlc --output-format cxx --test-declarations {source_name}
+// /!\ Do not edit by hand
+
+#include <cstdint>
+#include <memory>
+#include <vector>
+
+#include <gtest/gtest.h>
+#include <packet_runtime.h>
+"#,
+        source_name = source.name()
+    ));
+
+    for header in include_headers {
+        code.push_str(&format!("#include <{}>\n", header));
+    }
+    if !include_headers.is_empty() {
+        code.push('\n');
+    }
+
+    for ns in using_namespaces {
+        code.push_str(&format!("using namespace {};\n", ns));
+    }
+    if !using_namespaces.is_empty() {
+        code.push('\n');
+    }
+
+    if let Some(ns) = namespace {
+        code.push_str(&format!("namespace {} {{\n", ns));
+    }
+
+    for packet in test_vectors(file) {
+        let decl = scope.typedef[&packet.name];
+        // Views of derived packets are created from the parent view.
+        let parents = scope.iter_parents_and_self(decl).collect::<Vec<_>>();
+        let view = parents.iter().rev().fold("input".to_owned(), |view, decl| {
+            format!("{}View::Create({view})", decl.id().unwrap())
+        });
+
+        code.push_str(&format!("\nclass {}Test : public testing::Test {{}};\n", packet.name));
+
+        for (i, test_vector) in packet.tests.iter().enumerate() {
+            let input = test_vector
+                .packed
+                .as_bytes()
+                .chunks(2)
+                .map(|byte| format!("0x{},", std::str::from_utf8(byte).unwrap()))
+                .collect::<Vec<_>>();
+            let mut checks = Vec::new();
+            if test_vector.expected_error.is_some() {
+                checks.push("ASSERT_FALSE(packet.IsValid());".to_owned());
+            } else {
+                checks.push("ASSERT_TRUE(packet.IsValid());".to_owned());
+                for (field_id, value) in
+                    test_vector.unpacked.iter().flat_map(|v| v.as_object()).flatten()
+                {
+                    let field =
+                        scope.iter_fields(decl).find(|field| field.id() == Some(field_id)).unwrap();
+                    let value = match &field.desc {
+                        ast::FieldDesc::Typedef { type_id, .. } => format!("{type_id}({value})"),
+                        _ => value.to_string(),
+                    };
+                    checks.push(format!(
+                        "ASSERT_EQ(packet.Get{}(), {value});",
+                        field_id.to_upper_camel_case()
+                    ));
+                }
+            }
+
+            code.push_str(&format!(
+                r#"
+TEST_F({packet_name}Test, {test_name}{i}) {{
+    pdl::packet::slice input(std::shared_ptr<std::vector<uint8_t>>(new std::vector<uint8_t> {{
+        {input}
+    }}));
+    {packet_name}View packet = {view};
+{checks}
+}}
+"#,
+                packet_name = packet.name,
+                test_name =
+                    if test_vector.expected_error.is_some() { "DecodeInvalid" } else { "Decode" },
+                input = input.join(" "),
+                checks = indent(&checks.join("\n"), 1),
+            ));
+        }
+    }
+
     if let Some(ns) = namespace {
         code.push_str(&format!("}}  // {}\n", ns));
     }
@@ -160,6 +354,7 @@ fn generate_enum_declaration(id: &str, tags: &[ast::Tag], width: usize) -> Strin
     for tag in tags {
         if let ast::Tag::Value(t) = tag {
             tag_decls.push(format!("{} = {:#x},", t.id, t.value));
+            tag_decls.extend(t.aliases.iter().map(|alias| format!("{} = {},", alias, t.id)));
         }
     }
 
@@ -177,7 +372,8 @@ fn generate_enum_to_text(id: &str, tags: &[ast::Tag]) -> String {
     let mut tag_cases = Vec::new();
     for tag in tags {
         if let ast::Tag::Value(t) = tag {
-            tag_cases.push(format!("case {}::{}: return \"{}\";", id, t.id, t.id));
+            let text = t.display_name.as_ref().unwrap_or(&t.id);
+            tag_cases.push(format!("case {}::{}: return \"{}\";", id, t.id, text));
         }
     }
 
@@ -196,6 +392,125 @@ inline std::string {id}Text({id} tag) {{
     )
 }
 
+/// Generate the conversions between an enum extension and its base enums.
+/// Values of the extension are converted back to a base enum only
+/// if declared by the base enum.
+fn generate_enum_extension_conversions(
+    scope: &analyzer::Scope<'_>,
+    id: &str,
+    parent_id: &str,
+    width: usize,
+) -> String {
+    let backing_type = get_cxx_scalar_type(width);
+    let mut code = String::new();
+    let mut base_decl = scope.typedef[parent_id];
+    loop {
+        let ast::DeclDesc::Enum { id: base_id, tags, parent_id, .. } = &base_decl.desc else {
+            unreachable!()
+        };
+        let is_open = tags.iter().any(|t| matches!(t, ast::Tag::Other(_)));
+        // All values are valid for open enums.
+        let check_value = if is_open {
+            String::new()
+        } else {
+            format!(
+                r#"
+    if (!IsValid{base_id}(static_cast<{backing_type}>(value))) {{
+        return std::nullopt;
+    }}"#
+            )
+        };
+        code.push_str(&format!(
+            r#"
+inline {id} To{id}({base_id} value) {{
+    return static_cast<{id}>(value);
+}}
+
+inline std::optional<{base_id}> To{base_id}({id} value) {{{check_value}
+    return static_cast<{base_id}>(value);
+}}
+"#
+        ));
+        match parent_id {
+            Some(parent_id) => base_decl = scope.typedef[parent_id],
+            None => break,
+        }
+    }
+    code
+}
+
+/// Generate the declaration for flags values.
+/// The flags are declared as an enum class with bitwise operators;
+/// bits that do not match any declared flag are preserved.
+fn generate_flags_declaration(id: &str, flags: &[ast::TagValue], width: usize) -> String {
+    let flags_type = get_cxx_scalar_type(width);
+    let flag_decls =
+        flags.iter().map(|flag| format!("{} = {:#x},", flag.id, flag.value)).collect::<Vec<_>>();
+    let flag_names = flags
+        .iter()
+        .map(|flag| format!("{{{}::{}, \"{}\"}},", id, flag.id, flag.id))
+        .collect::<Vec<_>>();
+    let mask = if width >= 64 { u64::MAX } else { (1 << width) - 1 };
+
+    format!(
+        r#"
+enum class {id} : {flags_type} {{
+{flag_declarations}
+}};
+
+inline constexpr {id} operator|({id} lhs, {id} rhs) {{
+    return static_cast<{id}>(static_cast<{flags_type}>(lhs) | static_cast<{flags_type}>(rhs));
+}}
+
+inline constexpr {id} operator&({id} lhs, {id} rhs) {{
+    return static_cast<{id}>(static_cast<{flags_type}>(lhs) & static_cast<{flags_type}>(rhs));
+}}
+
+inline constexpr {id} operator^({id} lhs, {id} rhs) {{
+    return static_cast<{id}>(static_cast<{flags_type}>(lhs) ^ static_cast<{flags_type}>(rhs));
+}}
+
+inline constexpr {id} operator~({id} value) {{
+    return static_cast<{id}>(~static_cast<{flags_type}>(value) & {mask:#x});
+}}
+
+inline {id}& operator|=({id}& lhs, {id} rhs) {{ return lhs = lhs | rhs; }}
+inline {id}& operator&=({id}& lhs, {id} rhs) {{ return lhs = lhs & rhs; }}
+inline {id}& operator^=({id}& lhs, {id} rhs) {{ return lhs = lhs ^ rhs; }}
+
+/// Return true if all flags of `flags` are set in `value`.
+inline constexpr bool Contains({id} value, {id} flags) {{
+    return (value & flags) == flags;
+}}
+
+inline std::string {id}Text({id} value) {{
+    static const std::pair<{id}, char const*> flags[] = {{
+{flag_names}
+    }};
+    std::string text;
+    {flags_type} unknown_bits = static_cast<{flags_type}>(value);
+    for (auto const& [flag, name] : flags) {{
+        if (Contains(value, flag)) {{
+            text += text.empty() ? name : std::string(" | ") + name;
+            unknown_bits &= ~static_cast<{flags_type}>(flag);
+        }}
+    }}
+    if (unknown_bits != 0 || text.empty()) {{
+        std::string bits;
+        do {{
+            bits.insert(bits.begin(), "0123456789abcdef"[unknown_bits & 0xf]);
+            unknown_bits >>= 4;
+        }} while (unknown_bits != 0);
+        text += (text.empty() ? "0x" : " | 0x") + bits;
+    }}
+    return text;
+}}
+"#,
+        flag_declarations = indent(&flag_decls.join("\n"), 1),
+        flag_names = indent(&flag_names.join("\n"), 2),
+    )
+}
+
 /// Generate the validation function for enum values.
 fn generate_enum_is_valid(id: &str, tags: &[ast::Tag], width: usize) -> String {
     let is_open = tags.iter().any(|t| matches!(t, ast::Tag::Other(_)));
@@ -304,6 +619,38 @@ fn get_all_parent_fields<'a>(
     fields
 }
 
+/// Return the declaration defining the selected field,
+/// which is either `decl` or one of its parents.
+fn field_decl<'d>(
+    scope: &analyzer::Scope<'d>,
+    decl: &'d ast::Decl,
+    field: &ast::Field,
+) -> &'d ast::Decl {
+    scope
+        .iter_parents_and_self(decl)
+        .find(|d| d.fields().any(|f| f.key == field.key))
+        .unwrap_or(decl)
+}
+
+/// Return the bit order of the declaration defining the selected field.
+fn field_bit_order(
+    scope: &analyzer::Scope<'_>,
+    decl: &ast::Decl,
+    field: &ast::Field,
+) -> ast::BitOrder {
+    scope.file.bit_order(field_decl(scope, decl, field))
+}
+
+/// Return the endianness of the selected field, inherited from the
+/// declaration defining the field if not overridden.
+fn field_endianness(
+    scope: &analyzer::Scope<'_>,
+    decl: &ast::Decl,
+    field: &ast::Field,
+) -> ast::EndiannessValue {
+    scope.file.field_endianness(field_decl(scope, decl, field), field)
+}
+
 struct FieldParser<'a> {
     scope: &'a analyzer::Scope<'a>,
     schema: &'a analyzer::Schema,
@@ -411,7 +758,9 @@ impl<'a> FieldParser<'a> {
         };
 
         let chunk = std::mem::take(&mut self.chunk);
+        let bit_order = self.scope.file.bit_order(self.decl);
         for (shift, width, field) in chunk.iter() {
+            let shift = &bit_order.shift(self.shift, *shift, *width);
             let v = if chunk.len() == 1 && *shift == 0 {
                 value.clone()
             } else {
@@ -447,6 +796,12 @@ impl<'a> FieldParser<'a> {
                 ast::FieldDesc::Typedef { id, type_id, .. } => {
                     let type_decl = self.scope.typedef.get(type_id).unwrap();
                     match &type_decl.desc {
+                        ast::DeclDesc::Flags { .. } => {
+                            self.unchecked_append(format!(
+                                "{}{}_ = {}({});",
+                                self.target_prefix, id, type_id, v
+                            ));
+                        }
                         ast::DeclDesc::Enum { tags, .. }
                             if tags.iter().any(|t| matches!(t, ast::Tag::Other(_))) =>
                         {
@@ -473,7 +828,7 @@ impl<'a> FieldParser<'a> {
                         }
                     }
                 }
-                ast::FieldDesc::Size { field_id, .. } => {
+                ast::FieldDesc::Size { field_id, unit, .. } => {
                     let field_name = if field_id == "_payload_" || field_id == "_body_" {
                         "payload"
                     } else {
@@ -483,12 +838,24 @@ impl<'a> FieldParser<'a> {
                         "{}{}_size_ = {};",
                         self.target_prefix, field_name, v
                     ));
+                    for line in size_to_octets(
+                        &format!("{}{}_size_", self.target_prefix, field_name),
+                        *unit,
+                    ) {
+                        self.unchecked_append(line);
+                    }
+                    if let Some(check) = self.trailing_size_check(field) {
+                        self.unchecked_append(check);
+                    }
                 }
                 ast::FieldDesc::Count { field_id, .. } => {
                     self.unchecked_append(format!(
                         "{}{}_count_ = {};",
                         self.target_prefix, field_id, v
                     ));
+                    if let Some(check) = self.trailing_size_check(field) {
+                        self.unchecked_append(check);
+                    }
                 }
                 ast::FieldDesc::ElementSize { field_id, .. } => {
                     let field_name = if field_id == "_payload_" || field_id == "_body_" {
@@ -520,6 +887,22 @@ impl<'a> FieldParser<'a> {
 
         self.check_code();
 
+        if let Some(ast::Field { desc: ast::FieldDesc::Size { .. }, .. }) = self.decl.array_size(id)
+        {
+            // The struct size is known by size field. The struct is parsed
+            // from the designated span, and must consume it entirely.
+            let size = format!("{}{id}_size_", self.target_prefix);
+            self.check_size(&size);
+            self.append(format!("pdl::packet::slice {id}_span = span.subrange(0, {size});"));
+            self.append(format!(
+                "if (!{type_id}::Parse({id}_span, &{}{id}_)) return false;",
+                self.target_prefix
+            ));
+            self.append(format!("if ({id}_span.size() != 0) return false;"));
+            self.append(format!("span.skip({size});"));
+            return;
+        }
+
         let field_size = self.schema.field_size(field.key);
         if let analyzer::Size::Unknown = field_size {
             let trailing_size = self.get_trailing_size(field);
@@ -548,18 +931,30 @@ impl<'a> FieldParser<'a> {
 
     fn parse_optional_field(&mut self, field: &'a ast::Field) {
         self.check_code();
-        let cond = field.cond.as_ref().unwrap();
+        let condition = match (&field.cond, field.extension) {
+            (Some(cond), _) => format!("{} == {}", cond.id, cond.value.unwrap()),
+            // The fields of an extension block are present if bytes remain
+            // at the start of the block.
+            (None, Some(extension)) => {
+                match self.decl.fields().find(|f| f.extension == Some(extension)) {
+                    Some(first) if first.key != field.key => {
+                        format!("{}{}_.has_value()", self.target_prefix, first.id().unwrap())
+                    }
+                    _ => "span.size() > 0".to_owned(),
+                }
+            }
+            (None, None) => unreachable!(),
+        };
         let byteorder = match self.endianness {
             ast::EndiannessValue::LittleEndian => "le",
             ast::EndiannessValue::BigEndian => "be",
         };
 
         match &field.desc {
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let backing_type = get_cxx_scalar_type(*width);
                 let size = width / 8;
-                let cond_value = cond.value.unwrap();
-                self.append(format!("if ({} == {}) {{", cond.id, cond_value));
+                self.append(format!("if ({condition}) {{"));
                 self.append(format!("    if (span.size() < {}) {{", size));
                 self.append("        return false;".to_string());
                 self.append("    }".to_string());
@@ -571,12 +966,17 @@ impl<'a> FieldParser<'a> {
             }
             ast::FieldDesc::Typedef { id, type_id, .. } => {
                 let type_decl = self.scope.typedef.get(type_id).unwrap();
-                let cond_value = cond.value.unwrap();
-                if let ast::DeclDesc::Enum { width, tags, .. } = &type_decl.desc {
-                    let backing_type = get_cxx_scalar_type(*width);
-                    let is_open = tags.iter().any(|t| matches!(t, ast::Tag::Other(_)));
+                let enum_type = match &type_decl.desc {
+                    ast::DeclDesc::Enum { width, tags, .. } => {
+                        Some((*width, tags.iter().any(|t| matches!(t, ast::Tag::Other(_)))))
+                    }
+                    ast::DeclDesc::Flags { width, .. } => Some((*width, true)),
+                    _ => None,
+                };
+                if let Some((width, is_open)) = enum_type {
+                    let backing_type = get_cxx_scalar_type(width);
                     let size = width / 8;
-                    self.append(format!("if ({} == {}) {{", cond.id, cond_value));
+                    self.append(format!("if ({condition}) {{"));
                     self.append(format!("    if (span.size() < {}) {{", size));
                     self.append("        return false;".to_string());
                     self.append("    }".to_string());
@@ -601,7 +1001,7 @@ impl<'a> FieldParser<'a> {
                         self.append("}".to_string());
                     }
                 } else {
-                    self.append(format!("if ({} == {}) {{", cond.id, cond_value));
+                    self.append(format!("if ({condition}) {{"));
                     self.append(format!(
                         "    auto& opt_output = {}{}_.emplace();",
                         self.target_prefix, id
@@ -759,16 +1159,85 @@ impl<'a> FieldParser<'a> {
         trailing_size
     }
 
+    /// Generate the check of a size or count field placed after the
+    /// field it designates.
+    fn trailing_size_check(&self, field: &ast::Field) -> Option<String> {
+        if !self.decl.is_trailing_size(field) {
+            return None;
+        }
+        match &field.desc {
+            ast::FieldDesc::Size { field_id, .. } => {
+                let field_name = if field_id == "_payload_" || field_id == "_body_" {
+                    "payload"
+                } else {
+                    field_id
+                };
+                Some(format!(
+                    "if ({}{field_name}_size_ != {field_name}_trailing_size) return false;",
+                    self.target_prefix
+                ))
+            }
+            ast::FieldDesc::Count { field_id, .. } => Some(format!(
+                "if ({}{field_id}_count_ != {field_id}_trailing_count) return false;",
+                self.target_prefix
+            )),
+            _ => None,
+        }
+    }
+
+    /// Count the elements of an array field with a trailing count field.
+    /// Count the elements of the parsed array `id`
+    /// into the local variable `count`.
+    fn count_array_elements(
+        &mut self,
+        field: &'a ast::Field,
+        id: &str,
+        type_id: Option<&str>,
+        count: &str,
+    ) {
+        if self.extract_arrays {
+            self.append(format!("size_t {count} = {}{id}_.size();", self.target_prefix));
+            return;
+        }
+        match analyzer::element_size(self.scope, self.schema, self.decl, field) {
+            analyzer::ElementSize::Static(element_size) => {
+                self.append(format!("size_t {count} = {id}_.size() / {element_size};"));
+            }
+            analyzer::ElementSize::Dynamic => {
+                self.append(format!("size_t {count} = {id}_.size() / {id}_element_size_;"));
+            }
+            analyzer::ElementSize::Unknown => {
+                let tid = type_id.unwrap();
+                self.append(format!("size_t {count} = 0;"));
+                self.append(format!("pdl::packet::slice {id}_elements = {id}_;"));
+                self.append(format!("while ({id}_elements.size() > 0) {{"));
+                self.append(format!("    {tid} out;"));
+                self.append(format!("    if (!{tid}::Parse({id}_elements, &out)) {{"));
+                self.append("        return false;".to_string());
+                self.append("    }".to_string());
+                self.append(format!("    {count}++;"));
+                self.append("}".to_string());
+            }
+        }
+    }
+
     fn parse_payload_field_lite(&mut self, _field: &'a ast::Field, _is_body: bool) {
         if self.shift != 0 {
             panic!("Payload field does not start on an octet boundary");
         }
         self.check_code();
+        // Struct declarations own a copy of the payload bytes.
+        let bytes = if self.extract_arrays { ".bytes()" } else { "" };
         let mut size_expr = "".to_string();
+        let mut trailing_size = false;
         for f in self.decl.fields() {
             if let ast::FieldDesc::Size { field_id, .. } = &f.desc {
                 if field_id == "_payload_" || field_id == "_body_" {
-                    size_expr = format!("{}{}_size_", self.target_prefix, "payload");
+                    if self.decl.is_trailing_size(f) {
+                        trailing_size = true;
+                    } else {
+                        size_expr = format!("{}{}_size_", self.target_prefix, "payload");
+                    }
                 }
             }
         }
@@ -793,7 +1262,7 @@ impl<'a> FieldParser<'a> {
             };
             self.append(format!("if (span.size() < {}) return false;", actual_size));
             self.append(format!(
-                "{}payload_ = span.subrange(0, {});",
+                "{}payload_ = span.subrange(0, {}){bytes};",
                 self.target_prefix, actual_size
             ));
             self.append(format!("span.skip({});", actual_size));
@@ -803,15 +1272,37 @@ impl<'a> FieldParser<'a> {
                 self.append(format!("if (span.size() < {}) return false;", trailing_size / 8));
                 let size = format!("span.size() - {}", trailing_size / 8);
                 self.append(format!(
-                    "{}payload_ = span.subrange(0, {});",
+                    "{}payload_ = span.subrange(0, {}){bytes};",
                     self.target_prefix, size
                 ));
                 self.append(format!("span.skip({});", size));
             } else {
-                self.append(format!("{}payload_ = span;", self.target_prefix));
+                self.append(format!("{}payload_ = span{bytes};", self.target_prefix));
                 self.append("span.clear();".to_string());
             }
         }
+
+        if let ast::FieldDesc::Payload { max_size: Some(max_size), .. } = &_field.desc {
+            self.append(format!(
+                "if ({}payload_.size() > {max_size}) return false;",
+                self.target_prefix
+            ));
+        }
+
+        if trailing_size {
+            // Save the expected value of the size field placed after
+            // the payload.
+            let size_modifier = match &_field.desc {
+                ast::FieldDesc::Payload { size_modifier: Some(m), .. } => {
+                    format!(" + {}", m.trim_start_matches('+'))
+                }
+                _ => String::new(),
+            };
+            self.append(format!(
+                "size_t payload_trailing_size = {}payload_.size(){size_modifier};",
+                self.target_prefix
+            ));
+        }
     }
 
     fn parse_array_field_full(
@@ -1005,6 +1496,13 @@ impl<'a> FieldParser<'a> {
                             format!("{type_id}(raw_value)")
                         }
                     }
+                    ast::DeclDesc::Flags { width, .. } => {
+                        let element_size = *width / 8;
+                        let backing_type = get_cxx_scalar_type(*width);
+                        format!(
+                            "{type_id}({span}.read_{byteorder}<{backing_type}, {element_size}>())"
+                        )
+                    }
                     _ => {
                         self.append(format!("    {type_id} out;"));
                         self.append(format!("    if (!{type_id}::Parse({span}, &out)) {{"));
@@ -1019,7 +1517,8 @@ impl<'a> FieldParser<'a> {
     }
 
     fn parse(&mut self, field: &'a ast::Field) {
-        if field.cond.is_some() {
+        self.endianness = self.scope.file.field_endianness(self.decl, field);
+        if field.is_optional() {
             self.parse_optional_field(field);
         } else if self.scope.is_bitfield(field) {
             self.parse_bit_field(field);
@@ -1027,11 +1526,29 @@ impl<'a> FieldParser<'a> {
             self.check_code();
             match &field.desc {
                 ast::FieldDesc::Padding { .. } => {}
-                ast::FieldDesc::Array { id, type_id, size_modifier, .. } => {
+                ast::FieldDesc::Array { id, type_id, size_modifier, max_count, .. } => {
                     let padded_size = self.schema.padded_size(field.key);
                     if padded_size.is_some() {
                         self.append(format!("size_t {id}_start_size = span.size();"));
                     }
+                    let trailing_size =
+                        self.decl.array_size(id).filter(|f| self.decl.is_trailing_size(f));
+                    if let Some(size_field) = trailing_size {
+                        // The size or count field is placed after the array:
+                        // the array spans the octets not reserved for
+                        // the following fields.
+                        let trailing_size = self.get_trailing_size(field) / 8;
+                        self.append(format!("if (span.size() < {trailing_size}) return false;"));
+                        self.append(format!(
+                            "pdl::packet::slice {id}_trailer = span.subrange(span.size() - {trailing_size}, {trailing_size});"
+                        ));
+                        self.append(format!(
+                            "span = span.subrange(0, span.size() - {trailing_size});"
+                        ));
+                        if let ast::FieldDesc::Size { .. } = &size_field.desc {
+                            self.append(format!("size_t {id}_trailing_size = span.size();"));
+                        }
+                    }
                     if !self.extract_arrays {
                         self.parse_array_field_lite(
                             field,
@@ -1047,6 +1564,18 @@ impl<'a> FieldParser<'a> {
                             size_modifier.as_deref(),
                         );
                     }
+                    if let Some(max_count) = max_count {
+                        let count = format!("{id}_element_count");
+                        self.count_array_elements(field, id, type_id.as_deref(), &count);
+                        self.append(format!("if ({count} > {max_count}) return false;"));
+                    }
+                    if let Some(size_field) = trailing_size {
+                        self.append(format!("span = {id}_trailer;"));
+                        if let ast::FieldDesc::Count { .. } = &size_field.desc {
+                            let count = format!("{id}_trailing_count");
+                            self.count_array_elements(field, id, type_id.as_deref(), &count);
+                        }
+                    }
                     if let Some(padded_size) = padded_size {
                         let padding_bytes = padded_size / 8;
                         self.append(format!(
@@ -1065,7 +1594,7 @@ impl<'a> FieldParser<'a> {
                         matches!(field.desc, ast::FieldDesc::Body),
                     );
                 }
-                ast::FieldDesc::Scalar { id, width } => {
+                ast::FieldDesc::Scalar { id, width, .. } => {
                     let ty = get_cxx_scalar_type(*width);
                     let byteorder = match self.endianness {
                         ast::EndiannessValue::LittleEndian => "le",
@@ -1078,7 +1607,7 @@ impl<'a> FieldParser<'a> {
                         width / 8
                     ));
                 }
-                ast::FieldDesc::Size { field_id, width, .. } => {
+                ast::FieldDesc::Size { field_id, width, unit } => {
                     let ty = get_cxx_scalar_type(*width);
                     let field_name = if field_id == "_payload_" || field_id == "_body_" {
                         "payload"
@@ -1095,6 +1624,14 @@ impl<'a> FieldParser<'a> {
                         self.target_prefix,
                         width / 8
                     ));
+                    for line in
+                        size_to_octets(&format!("{}{field_name}_size_", self.target_prefix), *unit)
+                    {
+                        self.append(line);
+                    }
+                    if let Some(check) = self.trailing_size_check(field) {
+                        self.append(check);
+                    }
                 }
                 ast::FieldDesc::Count { field_id, width, .. } => {
                     let ty = get_cxx_scalar_type(*width);
@@ -1108,6 +1645,9 @@ impl<'a> FieldParser<'a> {
                         self.target_prefix,
                         width / 8
                     ));
+                    if let Some(check) = self.trailing_size_check(field) {
+                        self.append(check);
+                    }
                 }
                 ast::FieldDesc::ElementSize { field_id, width, .. } => {
                     let ty = get_cxx_scalar_type(*width);
@@ -1172,8 +1712,10 @@ struct FieldSerializer<'a> {
     scope: &'a analyzer::Scope<'a>,
     schema: &'a analyzer::Schema,
     endianness: ast::EndiannessValue,
+    bit_order: ast::BitOrder,
     shift: usize,
-    values: Vec<(String, usize)>,
+    // Bit-field values with their offset and width in the current chunk.
+    values: Vec<(String, usize, usize)>,
     code: Vec<String>,
     indent_level: usize,
 }
@@ -1188,6 +1730,7 @@ impl<'a> FieldSerializer<'a> {
             scope,
             schema,
             endianness,
+            bit_order: scope.file.bit_order,
             shift: 0,
             values: Vec::new(),
             code: Vec::new(),
@@ -1231,15 +1774,7 @@ impl<'a> FieldSerializer<'a> {
 
             match &f.desc {
                 ast::FieldDesc::Scalar { width, .. } => {
-                    if let Some(cond) = &f.cond {
-                        let cond_field = self
-                            .scope
-                            .iter_fields(decl)
-                            .find(|f| f.id() == Some(&cond.id))
-                            .expect("Cond field not found");
-                        let _cond_field_var =
-                            format!("{0}.has_value() ? ...", cond_field.id().unwrap()); // This is getting complex
-                        // Actually, I'll just use a simpler approach for now.
+                    if f.is_optional() {
                         format!("(({}_.has_value()) ? {} : 0)", f.id().unwrap(), width / 8)
                     } else {
                         format!("{}", width / 8)
@@ -1248,10 +1783,12 @@ impl<'a> FieldSerializer<'a> {
                 ast::FieldDesc::Typedef { id, type_id, .. } => {
                     let type_decl = self.scope.typedef.get(type_id).unwrap();
                     let width = match &type_decl.desc {
-                        ast::DeclDesc::Enum { width, .. } => *width,
+                        ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                            *width
+                        }
                         _ => 0,
                     };
-                    if f.cond.is_some() {
+                    if f.is_optional() {
                         if width > 0 {
                             format!("(({}_.has_value()) ? {} : 0)", id, width / 8)
                         } else {
@@ -1270,7 +1807,10 @@ impl<'a> FieldSerializer<'a> {
                         let tid = type_id.as_ref().unwrap();
                         let td = self.scope.typedef.get(tid).unwrap();
                         match &td.desc {
-                            ast::DeclDesc::Enum { width, .. } => format!("{}", width / 8),
+                            ast::DeclDesc::Enum { width, .. }
+                            | ast::DeclDesc::Flags { width, .. } => {
+                                format!("{}", width / 8)
+                            }
                             _ => "element.GetSize()".to_string(),
                         }
                     };
@@ -1349,8 +1889,9 @@ impl<'a> FieldSerializer<'a> {
             let packed_val = self
                 .values
                 .iter()
-                .map(|(v, s)| {
-                    if *s == 0 {
+                .map(|(v, offset, width)| {
+                    let s = self.bit_order.shift(self.shift, *offset, *width);
+                    if s == 0 {
                         format!("(static_cast<{backing_type}>({v}))")
                     } else {
                         format!("(static_cast<{backing_type}>({v}) << {s})")
@@ -1368,6 +1909,7 @@ impl<'a> FieldSerializer<'a> {
     }
 
     fn serialize(&mut self, field: &'a ast::Field, decl: &'a ast::Decl, var: Option<&str>) {
+        self.endianness = field_endianness(self.scope, decl, field);
         let parent_constraints = self
             .scope
             .iter_parents_and_self(decl)
@@ -1408,15 +1950,20 @@ impl<'a> FieldSerializer<'a> {
             ast::EndiannessValue::BigEndian => "be",
         };
 
-        if let Some(cond) = &field.cond {
-            let cond_field = self
-                .scope
-                .iter_fields(decl)
-                .find(|f| f.id() == Some(&cond.id))
-                .expect("Cond field not found");
-            let cond_field_var = get_field_expr(cond_field);
-            let cond_val = cond.value.unwrap();
-            self.append(&format!("if ({} == {}) {{", cond_field_var, cond_val));
+        if field.is_optional() {
+            let condition = match &field.cond {
+                Some(cond) => {
+                    let cond_field = self
+                        .scope
+                        .iter_fields(decl)
+                        .find(|f| f.id() == Some(&cond.id))
+                        .expect("Cond field not found");
+                    format!("{} == {}", get_field_expr(cond_field), cond.value.unwrap())
+                }
+                // Extension fields are written when present.
+                None => format!("{}.has_value()", field_var.as_ref().unwrap()),
+            };
+            self.append(&format!("if ({condition}) {{"));
             self.indent();
             match &field.desc {
                 ast::FieldDesc::Scalar { width, .. } => {
@@ -1431,7 +1978,9 @@ impl<'a> FieldSerializer<'a> {
                 }
                 ast::FieldDesc::Typedef { type_id, .. } => {
                     let td = self.scope.typedef.get(type_id).unwrap();
-                    if let ast::DeclDesc::Enum { width, .. } = &td.desc {
+                    if let ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } =
+                        &td.desc
+                    {
                         let ty = get_cxx_scalar_type(*width);
                         self.append(&format!(
                             "pdl::packet::Builder::write_{}<{}, {}>(output, static_cast<{}>(*{}));",
@@ -1455,19 +2004,31 @@ impl<'a> FieldSerializer<'a> {
         } else if self.scope.is_bitfield(field) {
             let width = self.schema.field_size(field.key).static_().unwrap();
             let shift = self.shift;
+            if shift == 0 {
+                // Chunks do not span declarations, the bit order is the one
+                // of the declaration owning the first field of the chunk.
+                self.bit_order = field_bit_order(self.scope, decl, field);
+            }
             match &field.desc {
                 ast::FieldDesc::Scalar { .. } => {
-                    self.values.push((format!("{} & {}", field_var.unwrap(), mask(width)), shift));
+                    self.values.push((
+                        format!("{} & {}", field_var.unwrap(), mask(width)),
+                        shift,
+                        width,
+                    ));
                 }
                 ast::FieldDesc::FixedScalar { value, .. } => {
-                    self.values.push((format!("{:#x}", value), shift));
+                    self.values.push((format!("{:#x}", value), shift, width));
                 }
                 ast::FieldDesc::FixedEnum { enum_id, tag_id, .. } => {
-                    self.values.push((format!("{}::{}", enum_id, tag_id), shift));
+                    self.values.push((format!("{}::{}", enum_id, tag_id), shift, width));
                 }
                 ast::FieldDesc::Typedef { id: _, type_id, .. } => {
                     let type_decl = self.scope.typedef.get(type_id).unwrap();
-                    if matches!(type_decl.desc, ast::DeclDesc::Enum { .. }) {
+                    if matches!(
+                        type_decl.desc,
+                        ast::DeclDesc::Enum { .. } | ast::DeclDesc::Flags { .. }
+                    ) {
                         self.values.push((
                             format!(
                                 "static_cast<{}>({})",
@@ -1475,12 +2036,13 @@ impl<'a> FieldSerializer<'a> {
                                 field_var.unwrap()
                             ),
                             shift,
+                            width,
                         ));
                     } else {
-                        self.values.push((field_var.unwrap(), shift));
+                        self.values.push((field_var.unwrap(), shift, width));
                     }
                 }
-                ast::FieldDesc::Size { field_id, .. } => {
+                ast::FieldDesc::Size { field_id, unit, .. } => {
                     let field_name = if field_id == "_payload_" || field_id == "_body_" {
                         "payload"
                     } else {
@@ -1488,7 +2050,14 @@ impl<'a> FieldSerializer<'a> {
                     };
                     let size_expr = self.get_payload_field_size(var, field_id, decl);
                     self.append(&format!("size_t {field_name}_size = {size_expr};"));
-                    self.values.push((format!("{}_size", field_name), shift));
+                    for check in size_checks(&format!("{field_name}_size"), width, *unit) {
+                        self.append(&check);
+                    }
+                    self.values.push((
+                        size_from_octets(&format!("{field_name}_size"), *unit),
+                        shift,
+                        width,
+                    ));
                 }
                 ast::FieldDesc::Count { field_id, .. } => {
                     let f = self
@@ -1496,7 +2065,7 @@ impl<'a> FieldSerializer<'a> {
                         .iter_fields(decl)
                         .find(|f| f.id() == Some(field_id))
                         .expect("Field not found");
-                    self.values.push((format!("{}.size()", get_field_expr(f)), shift));
+                    self.values.push((format!("{}.size()", get_field_expr(f)), shift, width));
                 }
                 ast::FieldDesc::ElementSize { field_id, .. } => {
                     let f = self
@@ -1516,10 +2085,10 @@ impl<'a> FieldSerializer<'a> {
                         _ => format!("{array_expr}.empty() ? 0 : {array_expr}[0].GetSize()"),
                     };
                     self.append(&format!("size_t {field_name}_element_size = {size_expr};"));
-                    self.values.push((format!("{}_element_size", field_name), shift));
+                    self.values.push((format!("{}_element_size", field_name), shift, width));
                 }
                 ast::FieldDesc::Flag { .. } => {
-                    self.values.push((field_var.unwrap(), shift));
+                    self.values.push((field_var.unwrap(), shift, width));
                 }
                 _ => {}
             }
@@ -1530,18 +2099,22 @@ impl<'a> FieldSerializer<'a> {
         } else {
             match &field.desc {
                 ast::FieldDesc::Padding { .. } => {}
-                ast::FieldDesc::Array { id, type_id, width, .. } => {
+                ast::FieldDesc::Array { id, type_id, width, max_count, .. } => {
                     let padded_size = self.schema.padded_size(field.key);
                     if padded_size.is_some() {
                         self.append(&format!("size_t {0}_start = output.size();", id));
                     }
+                    if let (Some(v), Some(max_count)) = (&field_var, max_count) {
+                        self.append(&format!("_ASSERT_VALID({v}.size() <= {max_count});"));
+                    }
                     if let Some(v) = field_var {
                         self.append(&format!("for (auto const& element : {}) {{", v));
                         self.indent();
                         if let Some(tid) = type_id {
                             let td = self.scope.typedef.get(tid).unwrap();
                             match &td.desc {
-                                ast::DeclDesc::Enum { width, .. } => {
+                                ast::DeclDesc::Enum { width, .. }
+                                | ast::DeclDesc::Flags { width, .. } => {
                                     let backing_type = get_cxx_scalar_type(*width);
                                     self.append(&format!("pdl::packet::Builder::write_{}<{}, {}>(output, static_cast<{}>(element));", byteorder, backing_type, width / 8, backing_type));
                                 }
@@ -1580,7 +2153,9 @@ impl<'a> FieldSerializer<'a> {
                 }
                 ast::FieldDesc::Typedef { type_id, .. } => {
                     let td = self.scope.typedef.get(type_id).unwrap();
-                    if let ast::DeclDesc::Enum { width, .. } = &td.desc {
+                    if let ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } =
+                        &td.desc
+                    {
                         let ty = get_cxx_scalar_type(*width);
                         self.append(&format!(
                             "pdl::packet::Builder::write_{}<{}, {}>(output, static_cast<{}>( {}));",
@@ -1594,9 +2169,19 @@ impl<'a> FieldSerializer<'a> {
                         self.append(&format!("{}.Serialize(output);", field_var.unwrap()));
                     }
                 }
-                ast::FieldDesc::Size { field_id, width, .. } => {
+                ast::FieldDesc::Size { field_id, width, unit } => {
                     let ty = get_cxx_scalar_type(*width);
+                    let field_name = if field_id == "_payload_" || field_id == "_body_" {
+                        "payload"
+                    } else {
+                        field_id
+                    };
                     let size_expr = self.get_payload_field_size(var, field_id, decl);
+                    self.append(&format!("size_t {field_name}_size = {size_expr};"));
+                    for check in size_checks(&format!("{field_name}_size"), *width, *unit) {
+                        self.append(&check);
+                    }
+                    let size_expr = size_from_octets(&format!("{field_name}_size"), *unit);
                     self.append(&format!(
                         "pdl::packet::Builder::write_{}<{}, {}>(output, static_cast<{}>( {}));",
                         byteorder,
@@ -1637,6 +2222,12 @@ impl<'a> FieldSerializer<'a> {
                     }
                 }
                 ast::FieldDesc::Payload { .. } | ast::FieldDesc::Body => {
+                    if let ast::FieldDesc::Payload { max_size: Some(max_size), .. } = &field.desc {
+                        self.append(&format!(
+                            "_ASSERT_VALID({}.size() <= {max_size});",
+                            deref(var, "payload_")
+                        ));
+                    }
                     self.append(&format!(
                         "output.insert(output.end(), {0}.begin(), {0}.end());",
                         deref(var, "payload_")
@@ -1648,6 +2239,205 @@ impl<'a> FieldSerializer<'a> {
     }
 }
 
+/// Generate the scaled accessors for the scalar fields declared with a
+/// scale factor. Views only expose the getter, builders and structs
+/// expose both the getter and the setter.
+fn generate_scaled_accessors(fields: &[&ast::Field], is_view: bool) -> Vec<String> {
+    let mut accessors = Vec::new();
+    for field in fields {
+        let ast::FieldDesc::Scalar { id, width, scale: Some(scale), .. } = &field.desc else {
+            continue;
+        };
+        let ty = get_cxx_scalar_type(*width);
+        let accessor_name = id.to_upper_camel_case();
+        let scale = format!("{:?}", scale.parse::<f64>().unwrap());
+        let assert_valid = if is_view { "_ASSERT_VALID(valid_); " } else { "" };
+        let raw = format!("pdl::packet::from_scaled<{ty}, {width}>(value, {scale})");
+        if field.is_optional() {
+            accessors.push(format!(
+                "    std::optional<double> Get{accessor_name}Scaled() const {{ {assert_valid}return {id}_.has_value() ? std::optional<double>(*{id}_ * {scale}) : std::nullopt; }}\n"
+            ));
+            if !is_view {
+                accessors.push(format!(
+                    "    void Set{accessor_name}Scaled(std::optional<double> value) {{ {id}_ = value.has_value() ? std::optional<{ty}>(pdl::packet::from_scaled<{ty}, {width}>(*value, {scale})) : std::nullopt; }}\n"
+                ));
+            }
+        } else {
+            accessors.push(format!(
+                "    double Get{accessor_name}Scaled() const {{ {assert_valid}return {id}_ * {scale}; }}\n"
+            ));
+            if !is_view {
+                accessors.push(format!(
+                    "    void Set{accessor_name}Scaled(double value) {{ {id}_ = {raw}; }}\n"
+                ));
+            }
+        }
+    }
+    accessors
+}
+
+/// Return the bound declarations and matching conditions of the
+/// bind declarations targeting the payload of the packet, in order
+/// of declaration. Bindings following an unconditional binding
+/// are dropped.
+fn payload_bindings<'d>(
+    scope: &analyzer::Scope<'d>,
+    decl: &'d ast::Decl,
+) -> Vec<(&'d str, String)> {
+    let mut cases = Vec::new();
+    for binding in scope.file.iter_bindings(decl) {
+        let ast::DeclDesc::Bind { type_id, constraints, .. } = &binding.desc else {
+            unreachable!()
+        };
+        let conditions = constraints
+            .iter()
+            .map(|c| {
+                let accessor = format!("Get{}()", c.id.to_upper_camel_case());
+                match c {
+                    ast::Constraint { value: Some(value), .. } => format!("{accessor} == {value}"),
+                    ast::Constraint { tag_id: Some(tag_id), .. } => {
+                        let type_id = scope
+                            .iter_fields(decl)
+                            .find_map(|f| match &f.desc {
+                                ast::FieldDesc::Typedef { id, type_id, .. } if id == &c.id => {
+                                    Some(type_id)
+                                }
+                                _ => None,
+                            })
+                            .unwrap();
+                        format!("{accessor} == {type_id}::{tag_id}")
+                    }
+                    _ => unreachable!(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let unconditional = conditions.is_empty();
+        cases.push((type_id.as_str(), conditions.join(" && ")));
+        if unconditional {
+            break;
+        }
+    }
+    cases
+}
+
+/// Return the declarations bound to the payload of the packet.
+fn payload_binding_types<'d>(scope: &analyzer::Scope<'d>, decl: &'d ast::Decl) -> Vec<&'d str> {
+    let mut type_ids = Vec::new();
+    for (type_id, _) in payload_bindings(scope, decl) {
+        if !type_ids.contains(&type_id) {
+            type_ids.push(type_id);
+        }
+    }
+    type_ids
+}
+
+/// Generate the typed payload accessors for the bind declarations
+/// targeting the payload of the packet. The accessors are defined
+/// with `generate_payload_binding_definitions` after all declarations,
+/// as the bound declarations can be incomplete at this point.
+fn generate_payload_bindings(scope: &analyzer::Scope, decl: &ast::Decl) -> Vec<String> {
+    let cases = payload_bindings(scope, decl);
+    let mut accessors = Vec::new();
+    for type_id in payload_binding_types(scope, decl) {
+        let accessor_name = type_id.to_upper_camel_case();
+        if let ast::DeclDesc::Struct { .. } = &scope.typedef[type_id].desc {
+            accessors.push(format!(
+                "    std::optional<{type_id}> GetPayloadAs{accessor_name}() const;\n"
+            ));
+        } else {
+            accessors.push(format!("    {type_id}View GetPayloadAs{accessor_name}() const;\n"));
+        }
+
+        // Bindings are matched in order of declaration, the bindings
+        // declared after the last binding to this type are irrelevant.
+        let last = cases.iter().rposition(|(case_type_id, _)| *case_type_id == type_id).unwrap();
+        let mut checks = Vec::new();
+        for (case_type_id, condition) in &cases[..=last] {
+            let result = if *case_type_id == type_id { "true" } else { "false" };
+            if condition.is_empty() {
+                checks.push(format!("        return {result};"));
+            } else {
+                checks.push(format!("        if ({condition}) return {result};"));
+            }
+        }
+        if !cases[last].1.is_empty() {
+            checks.push("        return false;".to_owned());
+        }
+        accessors.push(format!(
+            r#"    bool IsPayload{accessor_name}() const {{
+        _ASSERT_VALID(valid_);
+{}
+    }}
+"#,
+            checks.join("\n")
+        ));
+    }
+    accessors
+}
+
+/// Generate the definitions of the typed payload accessors declared
+/// by `generate_payload_bindings`.
+fn generate_payload_binding_definitions(scope: &analyzer::Scope, decl: &ast::Decl) -> String {
+    let id = decl.id().unwrap();
+    let mut code = String::new();
+    for type_id in payload_binding_types(scope, decl) {
+        let type_decl = scope.typedef[type_id];
+        let accessor_name = type_id.to_upper_camel_case();
+        if let ast::DeclDesc::Struct { .. } = &type_decl.desc {
+            code.push_str(&format!(
+                r#"
+inline std::optional<{type_id}> {id}View::GetPayloadAs{accessor_name}() const {{
+    _ASSERT_VALID(valid_);
+    pdl::packet::slice span = payload_;
+    {type_id} value;
+    if (!{type_id}::Parse(span, &value) || span.size() > 0) {{
+        return std::nullopt;
+    }}
+    return value;
+}}
+"#
+            ));
+        } else {
+            // Derived packet views are created from the views
+            // of their parents.
+            let view = scope
+                .iter_parents_and_self(type_decl)
+                .collect::<Vec<_>>()
+                .iter()
+                .rev()
+                .fold("payload_".to_owned(), |view, decl| {
+                    format!("{}View::Create({view})", decl.id().unwrap())
+                });
+            code.push_str(&format!(
+                r#"
+inline {type_id}View {id}View::GetPayloadAs{accessor_name}() const {{
+    _ASSERT_VALID(valid_);
+    return {view};
+}}
+"#
+            ));
+        }
+    }
+    code
+}
+
+/// Generate the C++ expression evaluating an invariant expression
+/// with the field accessors of the packet view.
+fn invariant_expr(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::Integer { value } => value.to_string(),
+        ast::Expr::Field { id } => {
+            format!("static_cast<int64_t>(Get{}())", id.to_upper_camel_case())
+        }
+        ast::Expr::Length { id } => {
+            format!("static_cast<int64_t>(Get{}().size())", id.to_upper_camel_case())
+        }
+        ast::Expr::Binary { op, lhs, rhs } => {
+            format!("({} {op} {})", invariant_expr(lhs), invariant_expr(rhs))
+        }
+    }
+}
+
 fn generate_packet_view(
     scope: &analyzer::Scope,
     schema: &analyzer::Schema,
@@ -1658,11 +2448,7 @@ fn generate_packet_view(
     let parent_class =
         parent_id.map(|p| format!("{}View", p)).unwrap_or_else(|| "pdl::packet::slice".to_string());
     let bytes_initializer = if parent_id.is_some() { "parent.bytes_" } else { "parent" };
-    let endianness = scope.file.endianness.value;
-    let byteorder = match endianness {
-        ast::EndiannessValue::LittleEndian => "le",
-        ast::EndiannessValue::BigEndian => "be",
-    };
+    let endianness = scope.file.decl_endianness(decl);
 
     let mut is_cond_for = HashSet::new();
     for field in decl.fields() {
@@ -1682,6 +2468,10 @@ fn generate_packet_view(
         let fid = field.id();
         let constraint = fid.and_then(|fid| parent_constraints.iter().find(|c| c.id == fid));
         let is_constrained = constraint.is_some();
+        let byteorder = match field_endianness(scope, decl, field) {
+            ast::EndiannessValue::LittleEndian => "le",
+            ast::EndiannessValue::BigEndian => "be",
+        };
 
         if is_constrained {
             // Constrained fields still get accessors returning their constant values.
@@ -1767,7 +2557,8 @@ fn generate_packet_view(
                     } else if let Some(tid) = type_id {
                         let td = scope.typedef.get(tid).unwrap();
                         match &td.desc {
-                            ast::DeclDesc::Enum { width, .. } => {
+                            ast::DeclDesc::Enum { width, .. }
+                            | ast::DeclDesc::Flags { width, .. } => {
                                 let backing_type = get_cxx_scalar_type(*width);
                                 accessor_code.push(format!(
                                     "    elements[n] = {}(span.read_{}<{}, {}>());",
@@ -1824,7 +2615,8 @@ fn generate_packet_view(
                     } else if let Some(tid) = type_id {
                         let td = scope.typedef.get(tid).unwrap();
                         match &td.desc {
-                            ast::DeclDesc::Enum { width, .. } => {
+                            ast::DeclDesc::Enum { width, .. }
+                            | ast::DeclDesc::Flags { width, .. } => {
                                 let backing_type = get_cxx_scalar_type(*width);
                                 accessor_code.push(format!(
                                     "while ({} && span.size() >= {}) {{",
@@ -1879,10 +2671,10 @@ fn generate_packet_view(
                     indent(&accessor_code.join("\n"), 2)
                 ));
             }
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let ty = get_cxx_scalar_type(*width);
                 let accessor_name = id.to_upper_camel_case();
-                if field.cond.is_some() {
+                if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     field_accessors.push(format!("    std::optional<{}> Get{}() const {{ _ASSERT_VALID(valid_); return {}_; }}\n", ty, accessor_name, id));
                 } else {
@@ -1896,7 +2688,7 @@ fn generate_packet_view(
             ast::FieldDesc::Typedef { id, type_id, .. } => {
                 let ty = type_id;
                 let accessor_name = id.to_upper_camel_case();
-                if field.cond.is_some() {
+                if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     field_accessors.push(format!("    std::optional<{}> Get{}() const {{ _ASSERT_VALID(valid_); return {}_; }}\n", ty, accessor_name, id));
                 } else {
@@ -1907,14 +2699,20 @@ fn generate_packet_view(
                             "    {} Get{}() const {{ _ASSERT_VALID(valid_); return {}_; }}\n",
                             ty, accessor_name, id
                         ));
+                    } else if let ast::DeclDesc::Flags { .. } = &type_decl.desc {
+                        field_members.push(format!("{} {}_{{}};", ty, id));
+                        field_accessors.push(format!(
+                            "    {} Get{}() const {{ _ASSERT_VALID(valid_); return {}_; }}\n",
+                            ty, accessor_name, id
+                        ));
                     } else {
                         field_members.push(format!("{} {}_;", ty, id));
                         field_accessors.push(format!("    {} const& Get{}() const {{ _ASSERT_VALID(valid_); return {}_; }}\n", ty, accessor_name, id));
                     }
                 }
             }
-            ast::FieldDesc::Size { field_id, width, .. } => {
-                let ty = get_cxx_scalar_type(*width);
+            ast::FieldDesc::Size { field_id, width, unit } => {
+                let ty = get_cxx_size_type(*width, *unit);
                 let field_name = if field_id == "_payload_" || field_id == "_body_" {
                     "payload"
                 } else {
@@ -1954,6 +2752,22 @@ fn generate_packet_view(
         }
     }
 
+    // Static sizes are checked by the analyzer.
+    if let Some(assertion) =
+        decl.size_assertion.filter(|_| schema.total_size(decl.key).static_().is_none())
+    {
+        let comparison = match assertion.comparison {
+            ast::SizeComparison::Equal => "==",
+            ast::SizeComparison::LessOrEqual => "<=",
+            ast::SizeComparison::GreaterOrEqual => ">=",
+        };
+        field_parsers.push("// Check the packet size assertion.".to_string());
+        field_parsers.push(format!(
+            "if (!(bytes_.size() {comparison} {})) {{ return false; }}",
+            assertion.size
+        ));
+    }
+
     if decl.fields().next().is_some() {
         field_parsers.push("// Parse packet field values.".to_string());
         let span = if parent_id.is_some() { "parent.payload_" } else { "parent" };
@@ -1971,7 +2785,25 @@ fn generate_packet_view(
     field_parsers.push("if (span.size() > 0) {".to_string());
     field_parsers.push("    return false;".to_string());
     field_parsers.push("}".to_string());
-    field_parsers.push("return true;".to_string());
+    if decl.invariants().next().is_none() {
+        field_parsers.push("return true;".to_string());
+    } else {
+        // The invariants are evaluated with the field accessors,
+        // which require the view to be valid.
+        field_parsers.push("// Check the packet invariants.".to_string());
+        field_parsers.push("valid_ = true;".to_string());
+        let conditions =
+            decl.invariants().map(|invariant| invariant_expr(&invariant.expr)).collect::<Vec<_>>();
+        field_parsers.push(format!("return {};", conditions.join(" && ")));
+    }
+
+    let scaled_fields = all_fields
+        .iter()
+        .copied()
+        .filter(|f| f.id().is_none_or(|fid| !parent_constraints.iter().any(|c| c.id == fid)))
+        .collect::<Vec<_>>();
+    field_accessors.extend(generate_scaled_accessors(&scaled_fields, true));
+    field_accessors.extend(generate_payload_bindings(scope, decl));
 
     let friend_classes = scope
         .iter_children(decl)
@@ -2030,7 +2862,7 @@ fn generate_packet_builder(
 ) -> String {
     let id = decl.id().unwrap();
     let class_name = format!("{}Builder", id);
-    let endianness = scope.file.endianness.value;
+    let endianness = scope.file.decl_endianness(decl);
 
     let mut field_members = Vec::new();
     let mut constructor_params: Vec<String> = Vec::new();
@@ -2069,26 +2901,29 @@ fn generate_packet_builder(
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, default, .. } => {
                 let ty = get_cxx_scalar_type(*width);
-                if field.cond.is_some() {
+                if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     constructor_params.push(format!("std::optional<{}> {}", ty, id));
                 } else {
-                    field_members.push(format!("{} {}_{{0}};", ty, id));
+                    field_members.push(format!("{} {}_{{{}}};", ty, id, default.unwrap_or(0)));
                     constructor_params.push(format!("{} {}", ty, id));
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Typedef { id, type_id, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, default } => {
                 let ty = type_id;
-                if field.cond.is_some() {
+                if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     constructor_params.push(format!("std::optional<{}> {}", ty, id));
                 } else {
                     let type_decl = scope.typedef.get(type_id).unwrap();
                     if let ast::DeclDesc::Enum { tags, .. } = &type_decl.desc {
-                        field_members.push(format!("{} {}_{{{}::{}}};", ty, id, ty, tags[0].id()));
+                        let tag_id = default.as_deref().unwrap_or(tags[0].id());
+                        field_members.push(format!("{} {}_{{{}::{}}};", ty, id, ty, tag_id));
+                    } else if let ast::DeclDesc::Flags { .. } = &type_decl.desc {
+                        field_members.push(format!("{} {}_{{}};", ty, id));
                     } else {
                         field_members.push(format!("{} {}_;", ty, id));
                     }
@@ -2096,8 +2931,8 @@ fn generate_packet_builder(
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Size { field_id, width, .. } => {
-                let ty = get_cxx_scalar_type(*width);
+            ast::FieldDesc::Size { field_id, width, unit } => {
+                let ty = get_cxx_size_type(*width, *unit);
                 let field_name = if field_id == "_payload_" || field_id == "_body_" {
                     "payload"
                 } else {
@@ -2150,8 +2985,10 @@ fn generate_packet_builder(
     for f in &all_fields {
         let field_size = schema.field_size(f.key);
         match &f.desc {
-            ast::FieldDesc::Scalar { width, .. } => {
-                if let Some(cond) = &f.cond {
+            ast::FieldDesc::Scalar { id, width, .. } => {
+                if f.extension.is_some() {
+                    variable_widths.push(format!("({}_.has_value() ? {} : 0)", id, width / 8));
+                } else if let Some(cond) = &f.cond {
                     let cond_field = scope
                         .iter_fields(decl)
                         .find(|f| f.id() == Some(&cond.id))
@@ -2167,13 +3004,22 @@ fn generate_packet_builder(
                     static_bits += width;
                 }
             }
-            ast::FieldDesc::Typedef { id: _, type_id, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, .. } => {
                 let type_decl = scope.typedef.get(type_id).unwrap();
                 let width = match &type_decl.desc {
-                    ast::DeclDesc::Enum { width, .. } => *width,
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                        *width
+                    }
                     _ => 0,
                 };
-                if let Some(cond) = &f.cond {
+                if f.extension.is_some() {
+                    if width > 0 {
+                        variable_widths.push(format!("({}_.has_value() ? {} : 0)", id, width / 8));
+                    } else {
+                        variable_widths
+                            .push(format!("({0}_.has_value() ? {0}_->GetSize() : 0)", id));
+                    }
+                } else if let Some(cond) = &f.cond {
                     let cond_field = scope
                         .iter_fields(decl)
                         .find(|f| f.id() == Some(&cond.id))
@@ -2207,7 +3053,7 @@ fn generate_packet_builder(
                 } else if let Some(tid) = type_id {
                     let td = scope.typedef.get(tid).unwrap();
                     match &td.desc {
-                        ast::DeclDesc::Enum { width, .. } => {
+                        ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                             format!("({0}_.size() * {1})", id, width / 8)
                         }
                         _ => format!(
@@ -2247,6 +3093,18 @@ fn generate_packet_builder(
         format!("return {};", variable_widths.join(" + "))
     };
 
+    let scaled_fields = all_fields
+        .iter()
+        .copied()
+        .filter(|f| f.id().is_none_or(|fid| !parent_constraints.iter().any(|c| c.id == fid)))
+        .collect::<Vec<_>>();
+    let scaled_accessors = generate_scaled_accessors(&scaled_fields, false);
+    let scaled_accessors = if scaled_accessors.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", scaled_accessors.concat())
+    };
+
     let constructor = if constructor_params.is_empty() {
         format!("    {0}() = default;", class_name)
     } else {
@@ -2276,7 +3134,7 @@ public:
         {size_expr}
     }}
 
-    std::string ToString() const {{ return ""; }}
+{scaled_accessors}    std::string ToString() const {{ return ""; }}
 
 {field_members}
 }};
@@ -2297,7 +3155,7 @@ fn generate_struct_declaration(
     let mut field_members = Vec::new();
     let mut constructor_params: Vec<String> = Vec::new();
     let mut constructor_inits = Vec::new();
-    let endianness = scope.file.endianness.value;
+    let endianness = scope.file.decl_endianness(decl);
 
     for field in decl.fields() {
         match &field.desc {
@@ -2320,26 +3178,32 @@ fn generate_struct_declaration(
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, default, .. } => {
                 let ty = get_cxx_scalar_type(*width);
-                if field.cond.is_some() {
+                if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     constructor_params.push(format!("std::optional<{}> {}", ty, id));
                 } else {
-                    field_members.push(format!("{} {}_{{0}};", ty, id));
+                    field_members.push(format!("{} {}_{{{}}};", ty, id, default.unwrap_or(0)));
                     constructor_params.push(format!("{} {}", ty, id));
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Typedef { id, type_id, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, default } => {
                 let ty = type_id;
-                if field.cond.is_some() {
+                if scope.is_indirect(field) {
+                    field_members.push(format!("pdl::packet::indirect<{}> {}_;", ty, id));
+                    constructor_params.push(format!("pdl::packet::indirect<{}> {}", ty, id));
+                } else if field.is_optional() {
                     field_members.push(format!("std::optional<{}> {}_;", ty, id));
                     constructor_params.push(format!("std::optional<{}> {}", ty, id));
                 } else {
                     let type_decl = scope.typedef.get(type_id).unwrap();
                     if let ast::DeclDesc::Enum { tags, .. } = &type_decl.desc {
-                        field_members.push(format!("{} {}_{{{}::{}}};", ty, id, ty, tags[0].id()));
+                        let tag_id = default.as_deref().unwrap_or(tags[0].id());
+                        field_members.push(format!("{} {}_{{{}::{}}};", ty, id, ty, tag_id));
+                    } else if let ast::DeclDesc::Flags { .. } = &type_decl.desc {
+                        field_members.push(format!("{} {}_{{}};", ty, id));
                     } else {
                         field_members.push(format!("{} {}_;", ty, id));
                     }
@@ -2347,8 +3211,8 @@ fn generate_struct_declaration(
                 }
                 constructor_inits.push(format!("{0}_(std::move({0}))", id));
             }
-            ast::FieldDesc::Size { field_id, width, .. } => {
-                let ty = get_cxx_scalar_type(*width);
+            ast::FieldDesc::Size { field_id, width, unit } => {
+                let ty = get_cxx_size_type(*width, *unit);
                 let field_name = if field_id == "_payload_" || field_id == "_body_" {
                     "payload"
                 } else {
@@ -2374,6 +3238,10 @@ fn generate_struct_declaration(
     }
 
     let mut field_parsers = Vec::new();
+    if scope.is_recursive(decl) {
+        field_parsers.push("pdl::packet::parse_depth_guard depth_guard;".to_string());
+        field_parsers.push("if (!depth_guard.ok()) return false;".to_string());
+    }
     field_parsers.push("pdl::packet::slice span = parent_span;".to_string());
 
     let mut parser = FieldParser::new(scope, schema, endianness, "output->", true, decl);
@@ -2408,8 +3276,10 @@ fn generate_struct_declaration(
     for f in decl.fields() {
         let field_size = schema.field_size(f.key);
         match &f.desc {
-            ast::FieldDesc::Scalar { width, .. } => {
-                if let Some(cond) = &f.cond {
+            ast::FieldDesc::Scalar { id, width, .. } => {
+                if f.extension.is_some() {
+                    variable_widths.push(format!("({}_.has_value() ? {} : 0)", id, width / 8));
+                } else if let Some(cond) = &f.cond {
                     let cond_field = scope
                         .iter_fields(decl)
                         .find(|f| f.id() == Some(&cond.id))
@@ -2425,13 +3295,22 @@ fn generate_struct_declaration(
                     static_bits += width;
                 }
             }
-            ast::FieldDesc::Typedef { id: _, type_id, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, .. } => {
                 let type_decl = scope.typedef.get(type_id).unwrap();
                 let width = match &type_decl.desc {
-                    ast::DeclDesc::Enum { width, .. } => *width,
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                        *width
+                    }
                     _ => 0,
                 };
-                if let Some(cond) = &f.cond {
+                if f.extension.is_some() {
+                    if width > 0 {
+                        variable_widths.push(format!("({}_.has_value() ? {} : 0)", id, width / 8));
+                    } else {
+                        variable_widths
+                            .push(format!("({0}_.has_value() ? {0}_->GetSize() : 0)", id));
+                    }
+                } else if let Some(cond) = &f.cond {
                     let cond_field = scope
                         .iter_fields(decl)
                         .find(|f| f.id() == Some(&cond.id))
@@ -2465,7 +3344,7 @@ fn generate_struct_declaration(
                 } else if let Some(tid) = type_id {
                     let td = scope.typedef.get(tid).unwrap();
                     match &td.desc {
-                        ast::DeclDesc::Enum { width, .. } => {
+                        ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                             format!("({0}_.size() * {1})", id, width / 8)
                         }
                         _ => format!(
@@ -2505,6 +3384,13 @@ fn generate_struct_declaration(
         variable_widths.join(" + ").to_string()
     };
 
+    let scaled_accessors = generate_scaled_accessors(&decl.fields().collect::<Vec<_>>(), false);
+    let scaled_accessors = if scaled_accessors.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", scaled_accessors.concat())
+    };
+
     let constructor = if !constructor_params.is_empty() {
         format!(
             "    explicit {id}({constructor_parameters}) : {initializer_list} {{}}",
@@ -2538,7 +3424,7 @@ public:
         return {size_expr};
     }}
 
-    std::string ToString() const {{ return ""; }}
+{scaled_accessors}    std::string ToString() const {{ return ""; }}
 
 {field_members}
 }};
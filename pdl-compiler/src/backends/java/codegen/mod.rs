@@ -17,7 +17,7 @@ use genco::{
     self, Tokens,
     prelude::{Java, java},
     quote, quote_in,
-    tokens::FormatInto,
+    tokens::{FormatInto, quoted},
 };
 use std::collections::HashMap;
 
@@ -110,6 +110,8 @@ impl Field {
                         )))
                         $(if let Some(modifier) = modifier => + "(+" + $(*modifier) + ")")
                     )
+                } else if let Some(WidthField::StructSize { .. }) = width_fields.get(array_name) {
+                    ty.stringify(quote!($array_name.width()))
                 } else {
                     ty.stringify(sum_array_elem_widths(array_name))
                 }
@@ -126,6 +128,7 @@ impl Field {
             Field::Integral { fixed_val: Some(val), .. } => quote!($(*val)),
             Field::Integral { name, width: 1, .. } => quote!(($name ? 1 : 0)),
             Field::Integral { name, ty, .. } => ty.stringify(name),
+            Field::BigIntegral { name, .. } => quote!("0x" + $name.toString(16)),
             Field::Reserved { .. } => quote!("..."),
             Field::EnumRef { ty, fixed_tag: Some(tag), .. } => quote!($ty.$tag),
             Field::EnumRef { name, .. } => quote!($name.toString()),
@@ -141,6 +144,7 @@ impl Field {
         match self {
             Field::Integral { width: 1, .. } => quote!(boolean),
             Field::Integral { ty, .. } => quote!($ty),
+            Field::BigIntegral { .. } => quote!($(&*import::BIG_INTEGER)),
             Field::EnumRef { ty, .. } => quote!($ty),
             Field::StructRef { ty, .. } => quote!($ty),
             Field::Payload { .. } => quote!(byte[]),
@@ -153,7 +157,9 @@ impl Field {
         match self {
             Field::Integral { name, width: 1, .. } => quote!(Boolean.hashCode($name)),
             Field::Integral { name, ty, .. } => quote!($(ty.boxed()).hashCode($name)),
-            Field::EnumRef { name, .. } | Field::StructRef { name, .. } => quote!($name.hashCode()),
+            Field::BigIntegral { name, .. }
+            | Field::EnumRef { name, .. }
+            | Field::StructRef { name, .. } => quote!($name.hashCode()),
             Field::Payload { .. } => quote!($(&*import::ARRAYS).hashCode(payload)),
             Field::ArrayElem { val, .. } => quote!($(&*import::ARRAYS).hashCode($(val.name()))),
             other => panic!("cannot hash {:?}", other),
@@ -163,7 +169,9 @@ impl Field {
     pub fn equals(&self, other: impl FormatInto<Java>) -> Tokens<Java> {
         match self {
             Field::Integral { name, .. } => quote!($name == $other),
-            Field::EnumRef { name, .. } | Field::StructRef { name, .. } => {
+            Field::BigIntegral { name, .. }
+            | Field::EnumRef { name, .. }
+            | Field::StructRef { name, .. } => {
                 quote!($name.equals($other))
             }
             Field::Payload { .. } => quote!($(&*import::ARRAYS).equals(payload, $other)),
@@ -179,6 +187,7 @@ impl Field {
             (Field::Integral { width: 1, .. }, Constraint::Integral(0)) => quote!(false),
             (Field::Integral { width: 1, .. }, Constraint::Integral(1)) => quote!(true),
             (Field::Integral { .. }, Constraint::Integral(i)) => quote!($(*i)),
+            (Field::BigIntegral { .. }, Constraint::Integral(i)) => big_integer(*i),
             (Field::EnumRef { ty, .. }, Constraint::EnumTag(tag)) => quote!($ty.$tag),
             _ => panic!("invalid constraint"),
         }
@@ -192,6 +201,20 @@ impl Field {
         }
     }
 
+    pub fn default_val(&self) -> Option<Tokens<Java>> {
+        match self {
+            Field::Integral { width: 1, default_val: Some(0), .. } => Some(quote!(false)),
+            Field::Integral { width: 1, default_val: Some(_), .. } => Some(quote!(true)),
+            Field::Integral { ty, default_val: Some(val), .. } => {
+                let t = ExprTree::new();
+                Some(t.gen_expr(t.cast(t.hex_num(*val), *ty)))
+            }
+            Field::BigIntegral { default_val, .. } => Some(big_integer(default_val.unwrap_or(0))),
+            Field::EnumRef { ty, default_tag: Some(tag), .. } => Some(quote!($ty.$tag)),
+            _ => None,
+        }
+    }
+
     pub fn from_num(
         &self,
         expr: impl FormatInto<Java>,
@@ -210,7 +233,7 @@ impl Field {
                 ))
             }
             Field::Integral { width: 1, .. } => quote!($expr != 0),
-            Field::Integral { .. } => quote!($expr),
+            Field::Integral { .. } | Field::BigIntegral { .. } => quote!($expr),
             Field::EnumRef { ty, width, .. } => {
                 quote!($ty.from$(Integral::fitting(*width).capitalized())($expr))
             }
@@ -265,6 +288,10 @@ impl Field {
                     ),
                     Integral::fitting(width),
                 ),
+                Some(WidthField::StructSize { .. }) => t.cast(
+                    t.symbol(quote!($array_name.width()), Integral::Int),
+                    Integral::fitting(width),
+                ),
                 Some(WidthField::Count { modifier, .. }) => t.cast(
                     t.add(
                         t.symbol(quote!($array_name.length), Integral::Int),
@@ -287,7 +314,7 @@ impl Field {
 }
 
 impl EndiannessValue {
-    /// Width in bits. Must be byte-divisible and <= 64
+    /// Width in bits. Must be byte-divisible and <= 128
     fn encode_bytes(&self, buf: Tokens<Java>, width: usize, val: Tokens<Java>) -> Tokens<Java> {
         match width {
             8 => quote!($buf.put($val)),
@@ -298,11 +325,14 @@ impl EndiannessValue {
             48 => quote!(Utils.put48($buf, $val)),
             56 => quote!(Utils.put56($buf, $val)),
             64 => quote!($buf.putLong($val)),
+            72..=128 if width.is_multiple_of(8) => {
+                quote!(Utils.putBigInteger($buf, $val, $(width / 8)))
+            }
             _ => panic!("can't encode value of width {width}"),
         }
     }
 
-    // Width in bits. Must be byte-divisible and <= 64
+    // Width in bits. Must be byte-divisible and <= 128
     fn decode_bytes(&self, buf: Tokens<Java>, width: usize) -> Tokens<Java> {
         match width {
             8 => quote!($buf.get()),
@@ -313,6 +343,7 @@ impl EndiannessValue {
             48 => quote!(Utils.get48($buf)),
             56 => quote!(Utils.get56($buf)),
             64 => quote!($buf.getLong()),
+            72..=128 if width.is_multiple_of(8) => quote!(Utils.getBigInteger($buf, $(width / 8))),
             _ => panic!("can't decode value of width {width}"),
         }
     }
@@ -362,6 +393,13 @@ impl FormatInto<Java> for &Integral {
     }
 }
 
+fn big_integer(val: u128) -> Tokens<Java> {
+    match val {
+        0 => quote!($(&*import::BIG_INTEGER).ZERO),
+        _ => quote!(new $(&*import::BIG_INTEGER)($(quoted(format!("{val:x}"))), 16)),
+    }
+}
+
 fn sum_array_elem_widths(name: impl FormatInto<Java>) -> Tokens<Java> {
     quote!($(&*import::ARRAYS).stream($name).mapToInt(elem -> elem.width()).sum())
 }
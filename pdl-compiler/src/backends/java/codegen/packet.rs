@@ -19,13 +19,10 @@ use heck::{self, ToLowerCamelCase, ToUpperCamelCase};
 
 use crate::{
     ast::EndiannessValue,
-    backends::{
-        common::alignment::Alignment,
-        java::{
-            Context, Field, WidthField,
-            codegen::expr::ExprId,
-            inheritance::{ClassHeirarchy, Constraint, InheritanceNode},
-        },
+    backends::java::{
+        Context, Field, WidthField,
+        codegen::expr::ExprId,
+        inheritance::{ClassHeirarchy, Constraint, InheritanceNode},
     },
 };
 
@@ -90,7 +87,7 @@ pub fn gen_packet(name: &String, def: &PacketDef, ctx: &Context) -> Tokens<Java>
                         .allocate(fieldWidth())
                         .order($endianness);
 
-                    $(encoder(&def.alignment, &def.width_fields, endianness))
+                    $(encoder(def, endianness))
                     return super.toBytes(buf);
                 }
             } else {
@@ -99,7 +96,7 @@ pub fn gen_packet(name: &String, def: &PacketDef, ctx: &Context) -> Tokens<Java>
                         .allocate(fieldWidth())
                         .order($endianness);
 
-                    $(encoder(&def.alignment, &def.width_fields, endianness))
+                    $(encoder(def, endianness))
                     return buf.array();
                 }
             })
@@ -215,7 +212,7 @@ pub fn gen_abstract_packet(
                     .allocate(fieldWidth() + payload.limit())
                     .order($endianness);
 
-                $(encoder(&def.alignment, &def.width_fields, endianness))
+                $(encoder(def, endianness))
                 $(if parent.is_some() { return super.toBytes(buf); } else { return buf.array(); })
             }
 
@@ -318,7 +315,8 @@ fn member_defs(
             $(let is_final = are_final || constraints.contains_key(member.name()))
             protected $(if is_final => final) $(member.ty()) $(member.name())
                 $(if let Some(constraint) = constraints.get(member.name()) => = $constraint)
-                $(if let Some(fixed_val) = member.fixed_val() => = $fixed_val);
+                $(if let Some(fixed_val) = member.fixed_val() => = $fixed_val)
+                $(if !are_final => $(if let Some(default_val) = member.default_val() => = $default_val));
         })
     }
 }
@@ -362,6 +360,19 @@ fn setter_defs(
                             );
                         }
                     }
+                    Field::BigIntegral { width, .. } => {
+                        if ($(member.name()).signum() < 0 || $(member.name()).bitLength() > $(*width)) {
+                            throw new IllegalArgumentException(
+                                "Value " +
+                                $(member.stringify(width_fields)) +
+                                $(quoted(format!(
+                                    " is too wide for field '{}' with width {}",
+                                    member.name(),
+                                    width
+                                )))
+                            );
+                        }
+                    }
                     Field::Payload { width_field_width: Some(width), size_modifier, .. } => {
                         $(assert_array_fits_width_field(
                             quote!($(member.name()).length),
@@ -404,6 +415,18 @@ fn setter_defs(
                             _ =>,
                         })
                     }
+                    Field::StructRef { .. } => {
+                        $(if let Some(WidthField::StructSize { field_width }) =
+                            width_fields.get(member.name())
+                        {
+                            $(assert_array_fits_width_field(
+                                member.width_expr(heirarchy),
+                                member.stringify(width_fields),
+                                *field_width,
+                                None,
+                            ))
+                        })
+                    }
                     _ =>, // No special checks for other members.
                 })
 
@@ -449,57 +472,91 @@ fn builder_assigns(members: &[Field]) -> Tokens<Java> {
     }
 }
 
-fn encoder(
-    alignment: &Alignment<Field>,
-    width_fields: &HashMap<String, WidthField>,
+/// Wrap the (de)serialization code for a chunk with the byte order
+/// changes required when its endianness differs from the buffer's.
+fn with_order(
+    tokens: Tokens<Java>,
     endianness: EndiannessValue,
+    buf_endianness: EndiannessValue,
 ) -> Tokens<Java> {
-    alignment
+    if endianness == buf_endianness {
+        tokens
+    } else {
+        quote! {
+            buf.order($endianness);
+            $tokens
+            buf.order($buf_endianness);
+        }
+    }
+}
+
+fn encoder(def: &PacketDef, buf_endianness: EndiannessValue) -> Tokens<Java> {
+    let width_fields = &def.width_fields;
+    def.alignment
         .iter()
-        .flat_map(|chunk| match chunk {
-            Chunk::Bitpack { fields, width } => {
-                let t = ExprTree::new();
-                let root = t.cast(
-                    t.or_all(
-                        fields
-                            .iter()
-                            .map(|field| {
-                                t.lshift(
-                                    field.symbol.to_num(&t, field.symbol.name(), width_fields),
-                                    t.num(field.offset),
-                                )
-                            })
-                            .collect(),
-                    ),
-                    Integral::fitting(*width),
-                );
+        .flat_map(|chunk| {
+            let endianness = def.chunk_endianness(chunk);
+            with_order(encode_chunk(chunk, width_fields, endianness), endianness, buf_endianness)
+        })
+        .collect::<Tokens<Java>>()
+}
 
-                quote!($(endianness.encode_bytes(quote!(buf), *width, t.gen_expr(root)));)
-            }
-            Chunk::Bytes { symbol: member @ Field::ArrayElem { val, .. }, width } => {
+fn encode_chunk(
+    chunk: &Chunk<Field>,
+    width_fields: &HashMap<String, WidthField>,
+    endianness: EndiannessValue,
+) -> Tokens<Java> {
+    match chunk {
+        Chunk::Bitpack { fields, width } => {
+            let t = ExprTree::new();
+            let root = t.cast(
+                t.or_all(
+                    fields
+                        .iter()
+                        .map(|field| {
+                            t.lshift(
+                                field.symbol.to_num(&t, field.symbol.name(), width_fields),
+                                t.num(field.offset),
+                            )
+                        })
+                        .collect(),
+                ),
+                Integral::fitting(*width),
+            );
+
+            quote!($(endianness.encode_bytes(quote!(buf), *width, t.gen_expr(root)));)
+        }
+        Chunk::Bytes { symbol: member @ Field::BigIntegral { .. }, width } => {
+            quote!($(endianness.encode_bytes(quote!(buf), *width, quote!($(member.name()))));)
+        }
+        Chunk::Bytes { symbol: member @ Field::ArrayElem { val, .. }, width } => {
+            let elem = if let Field::BigIntegral { .. } = **val {
+                quote!($(member.name())[i])
+            } else {
                 let t = ExprTree::new();
                 let root = val.to_num(&t, quote!($(member.name())[i]), width_fields);
-                quote!(
-                    for (int i = 0; i < $(member.name()).length; i++) {
-                        $(endianness.encode_bytes(quote!(buf), *width, t.gen_expr(root)));
-                    }
-                )
-            }
-            Chunk::DynBytes(Field::Payload { .. }) => quote!(buf.put(payload);),
-            Chunk::DynBytes(member @ Field::ArrayElem { .. }) => {
-                quote!(
-                    for (int i = 0; i < $(member.name()).length; i++) {
-                        buf.put($(member.name())[i].toBytes());
-                    }
-                )
-            }
-            Chunk::DynBytes(member) => quote!(buf.put($(member.name()).toBytes());),
-            other => {
-                dbg!(other);
-                unreachable!();
-            }
-        })
-        .collect::<Tokens<Java>>()
+                t.gen_expr(root)
+            };
+            quote!(
+                for (int i = 0; i < $(member.name()).length; i++) {
+                    $(endianness.encode_bytes(quote!(buf), *width, elem));
+                }
+            )
+        }
+        Chunk::DynBytes(Field::Payload { .. }) => quote!(buf.put(payload);),
+        Chunk::DynBytes(member @ Field::ArrayElem { .. }) => {
+            quote!(
+                for (int i = 0; i < $(member.name()).length; i++) {
+                    buf.put($(member.name())[i].toBytes());
+                }
+            )
+        }
+        Chunk::DynBytes(member) => quote!(buf.put($(member.name()).toBytes());),
+        other => {
+            dbg!(other);
+            unreachable!();
+        }
+    }
 }
 
 fn decoder(
@@ -511,18 +568,19 @@ fn decoder(
     let mut tokens = Tokens::new();
 
     for (i, chunk) in def.alignment.iter().enumerate() {
+        let mut chunk_tokens = Tokens::new();
         match chunk {
             Chunk::Bitpack { fields, width } => {
                 let chunk_name = &quote!(chunk$i);
                 let chunk_type = Integral::fitting(*width);
-                tokens.extend(
+                chunk_tokens.extend(
                     quote!($chunk_type $chunk_name = $(ctx.endianness.decode_bytes(quote!(buf), *width));)
                 );
 
                 for field in fields.iter().filter(|field| !field.symbol.is_reserved()) {
                     let t = ExprTree::new();
 
-                    tokens.extend(assign(
+                    chunk_tokens.extend(assign(
                         &field.symbol,
                         &field.symbol.from_num(
                             t.gen_expr(t.mask(
@@ -535,9 +593,18 @@ fn decoder(
                     ));
                 }
             }
+            Chunk::Bytes { symbol: member @ Field::BigIntegral { .. }, width } => {
+                chunk_tokens.extend(assign(
+                    member,
+                    &member.from_num(
+                        ctx.endianness.decode_bytes(quote!(buf), *width),
+                        &def.width_fields,
+                    ),
+                ));
+            }
             Chunk::Bytes { symbol: member @ Field::ArrayElem { val, count }, width } => {
                 let name = member.name();
-                tokens.extend(quote!(
+                chunk_tokens.extend(quote!(
                     $(declare_array_count(val, *count, &def.width_fields, &ctx.heirarchy).unwrap())
                     $(member.ty()) $(name) = new $(val.ty())[$(name)Count];
                     for (int i = 0; i < $(name)Count; i++) {
@@ -554,7 +621,7 @@ fn decoder(
                 if let Some(count_decl) =
                     declare_array_count(val, *count, &def.width_fields, &ctx.heirarchy)
                 {
-                    tokens.extend(quote!(
+                    chunk_tokens.extend(quote!(
                         $(count_decl)
                         $(member.ty()) $arr_name = new $(val.ty())[$(arr_name)Count];
                         for (int i = 0; i < $(arr_name)Count; i++) {
@@ -563,7 +630,7 @@ fn decoder(
                         $(assign(member, &quote!($arr_name)))
                     ))
                 } else {
-                    tokens.extend(quote!(
+                    chunk_tokens.extend(quote!(
                         $(declare_array_size(name, val, &def.width_fields, &ctx.heirarchy))
                         $(&*import::LIST)<$(val.ty())> $(arr_name)List = new $(&*import::LIST)<$(val.ty())>();
                         while ($(arr_name)Size != 0) {
@@ -577,7 +644,7 @@ fn decoder(
             }
             Chunk::DynBytes(member @ Field::Payload { .. }) => {
                 if def.width_fields.contains_key("payload") {
-                    tokens.extend(assign(
+                    chunk_tokens.extend(assign(
                         member,
                         &quote!(buf.slice(buf.position(), payloadSize).order($(ctx.endianness))),
                     ));
@@ -589,23 +656,36 @@ fn decoder(
                         t.symbol(quote!(buf.limit()), Integral::Int),
                         t.num(width_without_payload / 8),
                     );
-                    tokens.extend(assign(
+                    chunk_tokens.extend(assign(
                         member,
                         &quote!(buf.slice(buf.position(), $(t.gen_expr(root))).order($(ctx.endianness)))
                     ));
                 } else {
                     // Assume payload is the last field in the packet (this should really be enforced by the parser)
                     // and consume all remaining bytes in the buffer.
-                    tokens.extend(assign(member, &quote!(buf.slice().order($(ctx.endianness)))));
+                    chunk_tokens
+                        .extend(assign(member, &quote!(buf.slice().order($(ctx.endianness)))));
                 }
-                tokens.extend(quote!(buf.position(buf.position() + payload.limit());));
+                chunk_tokens.extend(quote!(buf.position(buf.position() + payload.limit());));
             }
             Chunk::DynBytes(member @ Field::StructRef { name, ty, .. }) => {
                 let var_name = &name.to_lower_camel_case();
-                // Assume struct is the last field in the packet (this should really be enforced by the parser) and decode it.
-                // Its decoder will consume all remaining bytes in the buffer.
-                tokens.extend(quote!(
-                    $ty $var_name = $ty.fromBytes(buf.slice().order($(ctx.endianness)));
+                if let Some(WidthField::StructSize { .. }) = def.width_fields.get(var_name) {
+                    // The struct is decoded from exactly the number of bytes given by its size field.
+                    chunk_tokens.extend(quote!(
+                        $ty $var_name = $ty.fromBytes(buf.slice(buf.position(), $(var_name)Size).order($(ctx.endianness)));
+                        if ($var_name.width() != $(var_name)Size) {
+                            throw new IllegalArgumentException("Struct size does not match its _size_ field");
+                        }
+                    ));
+                } else {
+                    // Assume struct is the last field in the packet (this should really be enforced by the parser) and decode it.
+                    // Its decoder will consume all remaining bytes in the buffer.
+                    chunk_tokens.extend(quote!(
+                        $ty $var_name = $ty.fromBytes(buf.slice().order($(ctx.endianness)));
+                    ));
+                }
+                chunk_tokens.extend(quote!(
                     $(assign(member, &quote!($var_name)))
                     buf.position(buf.position() + $var_name.width());
                 ));
@@ -615,6 +695,8 @@ fn decoder(
                 unreachable!();
             }
         }
+        let endianness = def.chunk_endianness(chunk);
+        tokens.extend(with_order(chunk_tokens, endianness, ctx.endianness));
     }
 
     tokens
@@ -868,6 +950,7 @@ fn declare_array_count(
                 // No-op: $(name)Count should already be declared
                 Some(quote!())
             }
+            Some(WidthField::StructSize { .. }) => unreachable!("struct size of array field"),
             None => {
                 if let Some(elem_width) =
                     val.width().or_else(|| val.class().and_then(|class| heirarchy.width(class)))
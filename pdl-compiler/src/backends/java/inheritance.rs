@@ -18,7 +18,7 @@ use crate::backends::java::Field;
 
 #[derive(Debug, Clone)]
 pub enum Constraint {
-    Integral(usize),
+    Integral(u128),
     EnumTag(String),
 }
 
@@ -163,6 +163,7 @@ impl ClassHeirarchy {
         for field in fields {
             match field {
                 Field::Integral { width, .. }
+                | Field::BigIntegral { width, .. }
                 | Field::EnumRef { width, .. }
                 | Field::Reserved { width } => static_width += width,
                 Field::StructRef { name, ty } => {
@@ -28,6 +28,7 @@ use std::{
 };
 
 use crate::{
+    analyzer,
     ast::{self, EndiannessValue, Tag, TagOther, TagRange, TagValue},
     backends::{
         common::alignment::{ByteAligner, Chunk},
@@ -49,6 +50,8 @@ pub mod import {
     pub static BB: Lazy<java::Import> = Lazy::new(|| java::import("java.nio", "ByteBuffer"));
     pub static ARRAYS: Lazy<java::Import> = Lazy::new(|| java::import("java.util", "Arrays"));
     pub static LIST: Lazy<java::Import> = Lazy::new(|| java::import("java.util", "ArrayList"));
+    pub static BIG_INTEGER: Lazy<java::Import> =
+        Lazy::new(|| java::import("java.math", "BigInteger"));
 }
 
 mod codegen;
@@ -62,17 +65,14 @@ pub fn generate(
     output_dir: &Path,
     package: &str,
 ) -> Result<(), String> {
+    check_supported_fields(file)?;
+
     let source = sources.get(file.file).expect("could not read source").name();
     let mut dir = PathBuf::from(output_dir);
     dir.extend(package.split("."));
     fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
 
-    Utils.write_to_fs(
-        &dir.join("Utils").with_extension("java"),
-        package,
-        source,
-        file.endianness.value,
-    )?;
+    Utils.write_to_fs(&dir.join("Utils").with_extension("java"), package, source, ())?;
 
     let (classes, heirarchy) = generate_classes(file);
     let context = Context { endianness: file.endianness.value, heirarchy };
@@ -84,6 +84,61 @@ pub fn generate(
     Ok(())
 }
 
+/// Check that the declarations and their fields can be generated by the
+/// java backend. Returns an error naming the first unsupported declaration
+/// or field.
+fn check_supported_fields(file: &ast::File) -> Result<(), String> {
+    let scope = analyzer::Scope::new(file).unwrap();
+    for decl in &file.declarations {
+        let unsupported = match &decl.desc {
+            _ if scope.is_recursive(decl) => Some("recursive declarations"),
+            ast::DeclDesc::Flags { .. } => Some("flags declarations"),
+            ast::DeclDesc::Checksum { .. } => Some("checksum declarations"),
+            ast::DeclDesc::CustomField { .. } => Some("custom field declarations"),
+            _ => None,
+        };
+        if let Some(feature) = unsupported {
+            return Err(format!(
+                "declaration `{}`: {feature} are not supported by the java backend",
+                decl.id().unwrap_or("_"),
+            ));
+        }
+        for field in decl.fields() {
+            let unsupported = match &field.desc {
+                _ if field.extension.is_some() => Some("extension fields"),
+                _ if field.cond.is_some() => Some("optional fields"),
+                ast::FieldDesc::Flag { .. } => Some("optional fields"),
+                ast::FieldDesc::Size { .. } | ast::FieldDesc::Count { .. }
+                    if decl.is_trailing_size(field) =>
+                {
+                    Some("size and count fields placed after the sized field")
+                }
+                ast::FieldDesc::Checksum { .. } => Some("checksum fields"),
+                ast::FieldDesc::Padding { .. } => Some("padding fields"),
+                ast::FieldDesc::ElementSize { .. } => Some("element size fields"),
+                ast::FieldDesc::Size { unit: ast::SizeUnit::Bits, .. }
+                | ast::FieldDesc::Size { unit: ast::SizeUnit::Words(_), .. } => {
+                    Some("size units other than octets")
+                }
+                ast::FieldDesc::Array { max_count: Some(_), .. }
+                | ast::FieldDesc::Payload { max_size: Some(_), .. } => Some("size bounds"),
+                _ => None,
+            };
+            if let Some(feature) = unsupported {
+                let field_name = match field.id() {
+                    Some(id) => format!("field `{id}`"),
+                    None => format!("{} field", field.kind()),
+                };
+                return Err(format!(
+                    "{field_name} of `{}`: {feature} are not supported by the java backend",
+                    decl.id().unwrap_or("_"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy) {
     let mut classes: HashMap<String, Class> = HashMap::new();
     let mut heirarchy = ClassHeirarchy::new();
@@ -91,12 +146,12 @@ fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy
     for decl in file.declarations.iter() {
         match &decl.desc {
             // If this is a parent packet, make a new abstract class and defer parenthood to it.
-            ast::DeclDesc::Packet { id, fields, parent_id, constraints }
-            | ast::DeclDesc::Struct { id, constraints, fields, parent_id }
+            ast::DeclDesc::Packet { id, fields, parent_id, constraints, .. }
+            | ast::DeclDesc::Struct { id, constraints, fields, parent_id, .. }
                 if has_payload_or_body(fields) =>
             {
                 let parent_name = Class::name_from_id(id);
-                let parent_def = PacketDef::from_fields(fields, &classes, &heirarchy);
+                let parent_def = PacketDef::from_fields(fields, file, decl, &classes, &heirarchy);
                 let child_name =
                     has_payload(fields).then(|| ClassHeirarchy::fallback_child_name(&parent_name));
 
@@ -131,8 +186,12 @@ fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy
                 );
 
                 if let Some(child_name) = child_name {
-                    let child =
-                        Class::new_fallback_child(&parent_name, width_field_width, size_modifier);
+                    let child = Class::new_fallback_child(
+                        &parent_name,
+                        file.decl_endianness(decl),
+                        width_field_width,
+                        size_modifier,
+                    );
 
                     heirarchy.add_child(
                         parent_name.clone(),
@@ -145,10 +204,14 @@ fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy
                 }
             }
             // If this is a child packet, set its parent to the appropriate abstract class.
-            ast::DeclDesc::Packet { id, constraints, fields, parent_id: Some(parent_id) }
-            | ast::DeclDesc::Struct { id, constraints, fields, parent_id: Some(parent_id) } => {
+            ast::DeclDesc::Packet {
+                id, constraints, fields, parent_id: Some(parent_id), ..
+            }
+            | ast::DeclDesc::Struct {
+                id, constraints, fields, parent_id: Some(parent_id), ..
+            } => {
                 let child_name = Class::name_from_id(id);
-                let def = PacketDef::from_fields(fields, &classes, &heirarchy);
+                let def = PacketDef::from_fields(fields, file, decl, &classes, &heirarchy);
 
                 let parent = classes
                     .get_mut(&Class::name_from_id(parent_id))
@@ -166,12 +229,12 @@ fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy
             ast::DeclDesc::Packet { id, fields, parent_id: None, .. }
             | ast::DeclDesc::Struct { id, fields, parent_id: None, .. } => {
                 let name = Class::name_from_id(id);
-                let def = PacketDef::from_fields(fields, &classes, &heirarchy);
+                let def = PacketDef::from_fields(fields, file, decl, &classes, &heirarchy);
 
                 heirarchy.add_class(name.clone(), &def.members);
                 classes.insert(name.clone(), Class::Packet { name, def });
             }
-            ast::DeclDesc::Enum { id, tags, width } => {
+            ast::DeclDesc::Enum { id, tags, width, .. } => {
                 let name = Class::name_from_id(id);
                 classes.insert(
                     name.clone(),
@@ -189,10 +252,18 @@ fn generate_classes(file: &ast::File) -> (HashMap<String, Class>, ClassHeirarchy
                     },
                 );
             }
-            _ => {
-                dbg!(decl);
-                todo!()
-            }
+            // Tests are generated separately with `test::generate_declared_tests`.
+            ast::DeclDesc::Test { .. } => {}
+            // Payload bindings are not supported by the java backend.
+            ast::DeclDesc::Bind { .. } => {}
+            // Response declarations are not supported by the java backend.
+            ast::DeclDesc::Response { .. } => {}
+            // Rejected by `check_supported_fields`.
+            ast::DeclDesc::Flags { .. }
+            | ast::DeclDesc::Checksum { .. }
+            | ast::DeclDesc::CustomField { .. } => unreachable!("unsupported declaration"),
+            // Groups are inlined by the analyzer.
+            ast::DeclDesc::Group { .. } => unreachable!("unexpected group declaration"),
         }
     }
 
@@ -261,6 +332,7 @@ impl Class {
 
     fn new_fallback_child(
         parent_name: &str,
+        endianness: EndiannessValue,
         width_field_width: Option<usize>,
         size_modifier: Option<usize>,
     ) -> Self {
@@ -278,6 +350,8 @@ impl Class {
                     aligner.align().unwrap()
                 },
                 width_fields: HashMap::new(),
+                endianness,
+                field_endianness: HashMap::new(),
             },
         }
     }
@@ -310,27 +384,49 @@ pub struct PacketDef {
     members: Vec<Field>,
     alignment: Alignment<Field>,
     width_fields: HashMap<String, WidthField>,
+    /// Endianness of the declaration.
+    endianness: EndiannessValue,
+    /// Endianness overrides of individual members.
+    field_endianness: HashMap<String, EndiannessValue>,
 }
 
 impl PacketDef {
     fn from_fields(
         fields: &[ast::Field],
+        file: &ast::File,
+        decl: &ast::Decl,
         classes: &HashMap<String, Class>,
         heirarchy: &ClassHeirarchy,
     ) -> Self {
         let mut members: Vec<Field> = Vec::new();
-        let mut aligner = ByteAligner::new();
+        let mut aligner = ByteAligner::with_bit_order(file.bit_order(decl));
         let mut width_fields: HashMap<String, WidthField> = HashMap::new();
+        let mut field_endianness: HashMap<String, EndiannessValue> = HashMap::new();
 
         for field in fields.iter() {
+            if let (Some(id), Some(endianness)) = (field.id(), field.endianness) {
+                field_endianness.insert(id.to_lower_camel_case(), endianness);
+            }
             match &field.desc {
-                ast::FieldDesc::Scalar { id, width } => {
+                ast::FieldDesc::Scalar { id, width, default, .. } if *width > 64 => {
+                    let member = Field::BigIntegral {
+                        name: id.to_lower_camel_case(),
+                        width: *width,
+                        default_val: *default,
+                    };
+
+                    members.push(member.clone());
+                    aligner.add_bytes(member, *width);
+                }
+                ast::FieldDesc::Scalar { id, width, default, .. } => {
                     let member = Field::Integral {
                         name: id.to_lower_camel_case(),
                         ty: if *width == 1 { Integral::Int } else { Integral::fitting(*width) },
                         width: *width,
                         is_member: true,
                         fixed_val: None,
+                        // Scalar fields up to 64 bits have values fitting `usize`.
+                        default_val: default.map(|value| value as usize),
                     };
 
                     members.push(member.clone());
@@ -342,7 +438,8 @@ impl PacketDef {
                         ty: if *width == 1 { Integral::Int } else { Integral::fitting(*width) },
                         width: *width,
                         is_member: false,
-                        fixed_val: Some(*value),
+                        fixed_val: Some(*value as usize),
+                        default_val: None,
                     };
 
                     members.push(member.clone());
@@ -357,6 +454,7 @@ impl PacketDef {
                         ty,
                         width,
                         fixed_tag: Some(tag_id.to_upper_camel_case()),
+                        default_tag: None,
                     };
 
                     members.push(member.clone());
@@ -379,7 +477,7 @@ impl PacketDef {
                         width_field.update_with_array_info(Some(8), None)
                     }
                 }
-                ast::FieldDesc::Payload { size_modifier } => {
+                ast::FieldDesc::Payload { size_modifier, .. } => {
                     let member = Field::Payload {
                         is_member: false,
                         width_field_width: width_fields.get("payload").map(WidthField::width),
@@ -396,7 +494,9 @@ impl PacketDef {
                         );
                     }
                 }
-                ast::FieldDesc::Size { field_id, width } => {
+                ast::FieldDesc::Size { field_id, width, unit: ast::SizeUnit::Octets }
+                    if !decl.is_trailing_size(field) =>
+                {
                     let field_name = if field_id == "body" {
                         String::from("payload")
                     } else {
@@ -408,6 +508,7 @@ impl PacketDef {
                         width: *width,
                         is_member: false,
                         fixed_val: None,
+                        default_val: None,
                     };
                     members.push(member.clone());
                     aligner.add_bitfield(member, *width);
@@ -416,7 +517,7 @@ impl PacketDef {
                         WidthField::Size { field_width: *width, elem_width: None, modifier: None },
                     );
                 }
-                ast::FieldDesc::Count { field_id, width } => {
+                ast::FieldDesc::Count { field_id, width } if !decl.is_trailing_size(field) => {
                     let field_name = if field_id == "body" {
                         String::from("payload")
                     } else {
@@ -428,6 +529,7 @@ impl PacketDef {
                         width: *width,
                         is_member: false,
                         fixed_val: None,
+                        default_val: None,
                     };
                     members.push(member.clone());
                     aligner.add_bitfield(member, *width);
@@ -436,7 +538,7 @@ impl PacketDef {
                         WidthField::Count { field_width: *width, modifier: None },
                     );
                 }
-                ast::FieldDesc::Typedef { id, type_id } => {
+                ast::FieldDesc::Typedef { id, type_id, default } => {
                     let class = classes.get(&Class::name_from_id(type_id)).unwrap();
                     match &class {
                         Class::Enum { width, .. } => {
@@ -445,6 +547,9 @@ impl PacketDef {
                                 ty: class.name().into(),
                                 width: *width,
                                 fixed_tag: None,
+                                default_tag: default
+                                    .as_ref()
+                                    .map(|tag_id| tag_id.to_upper_camel_case()),
                             };
 
                             members.push(member.clone());
@@ -455,21 +560,36 @@ impl PacketDef {
                                 name: id.to_lower_camel_case(),
                                 ty: class.name().into(),
                             };
+                            if let Some(width_field) = width_fields.get_mut(member.name()) {
+                                *width_field =
+                                    WidthField::StructSize { field_width: width_field.width() };
+                            }
                             members.push(member.clone());
                             aligner.add_dyn_bytes(member);
                         }
                     }
                 }
-                ast::FieldDesc::Array { id, width, type_id, size: count, size_modifier } => {
+                ast::FieldDesc::Array {
+                    id, width, type_id, size: count, size_modifier, ..
+                } => {
                     let (member, elem_width) = match (width, type_id) {
                         (Some(width), None) => {
                             let val = Field::ArrayElem {
-                                val: Box::new(Field::Integral {
-                                    name: id.to_lower_camel_case(),
-                                    ty: Integral::fitting(*width),
-                                    width: *width,
-                                    is_member: true,
-                                    fixed_val: None,
+                                val: Box::new(if *width > 64 {
+                                    Field::BigIntegral {
+                                        name: id.to_lower_camel_case(),
+                                        width: *width,
+                                        default_val: None,
+                                    }
+                                } else {
+                                    Field::Integral {
+                                        name: id.to_lower_camel_case(),
+                                        ty: Integral::fitting(*width),
+                                        width: *width,
+                                        is_member: true,
+                                        fixed_val: None,
+                                        default_val: None,
+                                    }
                                 }),
                                 count: *count,
                             };
@@ -487,6 +607,7 @@ impl PacketDef {
                                             ty: class.name().into(),
                                             width: *width,
                                             fixed_tag: None,
+                                            default_tag: None,
                                         }),
                                         count: *count,
                                     };
@@ -519,14 +640,38 @@ impl PacketDef {
                     }
                     members.push(member);
                 }
-                _ => {
-                    dbg!(field);
-                    todo!()
-                }
+                // Rejected by `check_supported_fields`.
+                ast::FieldDesc::Checksum { .. }
+                | ast::FieldDesc::Padding { .. }
+                | ast::FieldDesc::ElementSize { .. }
+                | ast::FieldDesc::Flag { .. }
+                | ast::FieldDesc::Size { .. }
+                | ast::FieldDesc::Count { .. } => unreachable!("unsupported field"),
+                // Groups are inlined by the analyzer.
+                ast::FieldDesc::Group { .. } => unreachable!("unexpected group field"),
             }
         }
 
-        Self { members, alignment: aligner.align().expect("failed to align members"), width_fields }
+        Self {
+            members,
+            alignment: aligner.align().expect("failed to align members"),
+            width_fields,
+            endianness: file.decl_endianness(decl),
+            field_endianness,
+        }
+    }
+
+    /// Return the endianness used to encode the selected chunk.
+    /// Members with an endianness override are alone in their chunk.
+    fn chunk_endianness(&self, chunk: &Chunk<Field>) -> EndiannessValue {
+        match chunk {
+            Chunk::Bitpack { fields, .. } => fields
+                .first()
+                .and_then(|field| self.field_endianness.get(field.symbol.name()))
+                .copied()
+                .unwrap_or(self.endianness),
+            _ => self.endianness,
+        }
     }
 }
 
@@ -574,6 +719,7 @@ impl ast::Constraint {
 pub enum WidthField {
     Size { field_width: usize, elem_width: Option<usize>, modifier: Option<usize> },
     Count { field_width: usize, modifier: Option<usize> },
+    StructSize { field_width: usize },
 }
 impl WidthField {
     fn update_with_array_info(&mut self, elem_width: Option<usize>, modifier: Option<usize>) {
@@ -591,38 +737,74 @@ impl WidthField {
                     m.take();
                 }
             }
+            WidthField::StructSize { .. } => (),
         }
     }
 
     fn width(&self) -> usize {
         match self {
-            WidthField::Size { field_width, .. } | WidthField::Count { field_width, .. } => {
-                *field_width
-            }
+            WidthField::Size { field_width, .. }
+            | WidthField::Count { field_width, .. }
+            | WidthField::StructSize { field_width } => *field_width,
         }
     }
 
     fn modifier(&self) -> Option<usize> {
         match self {
             WidthField::Size { modifier, .. } | WidthField::Count { modifier, .. } => *modifier,
+            WidthField::StructSize { .. } => None,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Field {
-    Integral { name: String, ty: Integral, width: usize, is_member: bool, fixed_val: Option<usize> },
-    Reserved { width: usize },
-    EnumRef { name: String, ty: String, width: usize, fixed_tag: Option<String> },
-    StructRef { name: String, ty: String },
-    Payload { is_member: bool, width_field_width: Option<usize>, size_modifier: Option<usize> },
-    ArrayElem { val: Box<Field>, count: Option<usize> },
+    Integral {
+        name: String,
+        ty: Integral,
+        width: usize,
+        is_member: bool,
+        fixed_val: Option<usize>,
+        default_val: Option<usize>,
+    },
+    /// Scalar wider than 64 bits, stored as a `BigInteger`.
+    /// These fields must be byte aligned.
+    BigIntegral {
+        name: String,
+        width: usize,
+        default_val: Option<u128>,
+    },
+    Reserved {
+        width: usize,
+    },
+    EnumRef {
+        name: String,
+        ty: String,
+        width: usize,
+        fixed_tag: Option<String>,
+        default_tag: Option<String>,
+    },
+    StructRef {
+        name: String,
+        ty: String,
+    },
+    Payload {
+        is_member: bool,
+        width_field_width: Option<usize>,
+        size_modifier: Option<usize>,
+    },
+    ArrayElem {
+        val: Box<Field>,
+        count: Option<usize>,
+    },
 }
 
 impl Field {
     pub fn name(&self) -> &str {
         match self {
-            Field::Integral { name, .. } | Field::EnumRef { name, .. } => name,
+            Field::Integral { name, .. }
+            | Field::BigIntegral { name, .. }
+            | Field::EnumRef { name, .. } => name,
             Field::Reserved { .. } => "reserved",
             Field::StructRef { name, .. } => name,
             Field::Payload { .. } => "payload",
@@ -633,6 +815,7 @@ impl Field {
     pub fn width(&self) -> Option<usize> {
         match self {
             Field::Integral { width, .. }
+            | Field::BigIntegral { width, .. }
             | Field::EnumRef { width, .. }
             | Field::Reserved { width } => Some(*width),
             _ => None,
@@ -730,3 +913,59 @@ impl TryFrom<&Field> for Integral {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_inline;
+    use crate::test_utils::assert_snapshot_eq;
+
+    /// Compare the Java classes generated for the PDL `code` with
+    /// the snapshots saved under `"tests/generated/java/{name}/"`.
+    /// The `Utils` class is shared by all grammars and is skipped.
+    ///
+    /// Use `UPDATE_SNAPSHOTS=1 cargo test --features java` to
+    /// populate the snapshots with the expected output.
+    fn assert_java_snapshots(name: &str, code: &str) {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(&mut db, "test", code.to_owned()).unwrap();
+        let file = analyzer::analyze(&file).unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        generate(&db, &file, &[], output_dir.path(), "test").unwrap();
+
+        let mut paths = fs::read_dir(output_dir.path().join("test"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.file_stem().unwrap() != "Utils")
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            assert_snapshot_eq(
+                format!(
+                    "tests/generated/java/{name}/{}",
+                    path.file_name().unwrap().to_str().unwrap()
+                ),
+                &fs::read_to_string(&path).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_struct_size_field() {
+        assert_java_snapshots(
+            "struct_size_field",
+            "
+            little_endian_packets
+            struct S {
+                _count_(a): 8,
+                a: 8[],
+            }
+            packet P {
+                _size_(s): 8,
+                s: S,
+                b: 8,
+            }
+            ",
+        );
+    }
+}
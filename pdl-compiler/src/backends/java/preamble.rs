@@ -61,21 +61,49 @@ impl Utils {
     }
 }
 
-impl JavaFile<EndiannessValue> for Utils {
-    fn generate(self, endianness: EndiannessValue) -> Tokens<Java> {
+impl JavaFile<()> for Utils {
+    fn generate(self, _: ()) -> Tokens<Java> {
+        // The byte order is read from the buffer at runtime, as declarations
+        // and fields can override the file endianness.
         quote! {
             class Utils {
                 $(for width in [24, 40, 48, 56] {
                     $(let ty = Integral::fitting(width))
 
                     static void put$width($(&*import::BB) buf, $ty value) {
-                        $(Self::byte_encoder(endianness, width))
+                        if (buf.order() == $(&*import::BO).LITTLE_ENDIAN) {
+                            $(Self::byte_encoder(EndiannessValue::LittleEndian, width))
+                        } else {
+                            $(Self::byte_encoder(EndiannessValue::BigEndian, width))
+                        }
                     }
 
                     static $ty get$width($(&*import::BB) buf) {
-                        return $(Self::byte_decoder(endianness, width));
+                        if (buf.order() == $(&*import::BO).LITTLE_ENDIAN) {
+                            return $(Self::byte_decoder(EndiannessValue::LittleEndian, width));
+                        } else {
+                            return $(Self::byte_decoder(EndiannessValue::BigEndian, width));
+                        }
                     }
                 })
+
+                static void putBigInteger($(&*import::BB) buf, $(&*import::BIG_INTEGER) value, int size) {
+                    byte[] bytes = value.toByteArray();
+                    for (int i = 0; i < size; i++) {
+                        int index = buf.order() == $(&*import::BO).LITTLE_ENDIAN
+                            ? bytes.length - 1 - i
+                            : bytes.length - size + i;
+                        buf.put(index >= 0 ? bytes[index] : 0);
+                    }
+                }
+
+                static $(&*import::BIG_INTEGER) getBigInteger($(&*import::BB) buf, int size) {
+                    byte[] bytes = new byte[size];
+                    for (int i = 0; i < size; i++) {
+                        bytes[buf.order() == $(&*import::BO).LITTLE_ENDIAN ? size - 1 - i : i] = buf.get();
+                    }
+                    return new $(&*import::BIG_INTEGER)(1, bytes);
+                }
             }
         }
     }
@@ -33,7 +33,7 @@ use super::{Class, Integral, JavaFile, import};
 use crate::{
     ast::{self, Decl, DeclDesc, Field, FieldDesc},
     backends::{
-        common::test::{Packet, TestVector},
+        common::test::{self, Packet, TestVector},
         java::{codegen::expr::literal, preamble::Utils},
     },
     parser,
@@ -53,12 +53,7 @@ pub fn generate_tests(
     let pdl_file = parser::parse_file(&mut ast::SourceDatabase::new(), pdl_file_under_test)
         .expect("failed to parse pdl file under test. Please verify that the file compiles.");
 
-    Utils.write_to_fs(
-        &dir.join("Utils").with_extension("java"),
-        &package,
-        test_file,
-        pdl_file.endianness.value,
-    )?;
+    Utils.write_to_fs(&dir.join("Utils").with_extension("java"), &package, test_file, ())?;
 
     JavaTest(get_test_cases(test_file, exclude_packets)?).write_to_fs(
         &dir.join("PdlTests").with_extension("java"),
@@ -72,6 +67,32 @@ pub fn generate_tests(
     )
 }
 
+/// Generate tests for the test declarations of an analyzed file.
+pub fn generate_declared_tests(
+    sources: &ast::SourceDatabase,
+    file: &ast::File,
+    output_dir: &Path,
+    package: String,
+) -> Result<(), String> {
+    let mut dir = PathBuf::from(output_dir);
+    dir.extend(package.split("."));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let source = sources.get(file.file).expect("could not read source");
+
+    Utils.write_to_fs(&dir.join("Utils").with_extension("java"), &package, source.name(), ())?;
+
+    JavaTest(test::test_vectors(file)).write_to_fs(
+        &dir.join("PdlTests").with_extension("java"),
+        &package,
+        source.name(),
+        file.declarations
+            .iter()
+            .flat_map(|decl| decl.id().map(String::from).map(|id| (id, decl.clone())))
+            .collect(),
+    )
+}
+
 fn get_test_cases(file: &str, exclude_packets: &[String]) -> Result<Vec<Packet>, String> {
     let data = fs::read_to_string(file).map_err(|err| err.to_string())?;
     let raw_packets: Vec<Packet> = serde_json::from_str(&data).map_err(|err| err.to_string())?;
@@ -162,11 +183,17 @@ impl TestVector {
         quote_fn! {
             $(java::block_comment(iter::once(format!("0x{}", &self.packed))))$['\n']
             static void testEncode$test_id() {
-                $(Class::name_from_id(maybe_child)) packet = $(build_packet_from_fields(
-                    self.packet.as_ref().unwrap_or(id),
-                    self.unpacked.as_ref().unwrap().as_object().unwrap(),
-                    decls
-                ));
+                $(if self.round_trip {
+                    // Partial vector: re-encode the decoded packet.
+                    $(Class::name_from_id(maybe_child)) packet =
+                        $(Class::name_from_id(maybe_child)).fromBytes($(hex_to_array(&self.packed)));
+                } else {
+                    $(Class::name_from_id(maybe_child)) packet = $(build_packet_from_fields(
+                        self.packet.as_ref().unwrap_or(id),
+                        self.unpacked.as_ref().unwrap().as_object().unwrap(),
+                        decls
+                    ));
+                })
                 byte[] encodedPacket = packet.toBytes();
                 byte[] expectedBytes = $(hex_to_array(&self.packed));
                 assert $(&*import::ARRAYS).equals(expectedBytes, encodedPacket);
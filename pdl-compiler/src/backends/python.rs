@@ -14,7 +14,9 @@
 
 //! Python compiler backend.
 
+use crate::backends::common::test::test_vectors;
 use crate::{analyzer, ast};
+use heck::ToSnakeCase;
 
 #[derive(Default)]
 struct CodeBlock {
@@ -55,7 +57,7 @@ fn mask(width: usize) -> String {
 
 fn generate_prelude() -> String {
     r#"from dataclasses import dataclass, field, fields
-from typing import Optional, List, Tuple, Union
+from typing import Optional, List, Tuple, Union, get_type_hints
 import enum
 import inspect
 
@@ -114,6 +116,56 @@ class TrailingBytesError(DecodeError):
         super().__init__(f"unexpected {packet_name} parsing remainder of size {trailing_size}")
 
 
+class SizeUnitError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, size: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.size = size
+        super().__init__(f"invalid {packet_name}.{field_name} size: {size} bits is not an integral number of octets")
+
+
+class TrailingSizeError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, expected_size: int, actual_size: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.expected_size = expected_size
+        self.actual_size = actual_size
+        super().__init__(f"invalid {packet_name}.{field_name} trailing size: expected {expected_size}, got {actual_size}")
+
+
+class BoundError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, count: int, bound: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.count = count
+        self.bound = bound
+        super().__init__(f"invalid {packet_name}.{field_name} count: {count} exceeds the bound of {bound}")
+
+
+class SizeBoundError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, size: int, bound: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.size = size
+        self.bound = bound
+        super().__init__(f"invalid {packet_name}.{field_name} size: {size} exceeds the bound of {bound}")
+
+
+class SizeAssertionError(DecodeError):
+    def __init__(self, packet_name: str, size: int, assertion: str):
+        self.packet_name = packet_name
+        self.size = size
+        self.assertion = assertion
+        super().__init__(f"invalid {packet_name} size: {size} octets does not satisfy the assertion {assertion}")
+
+
+class InvariantViolationError(DecodeError):
+    def __init__(self, packet_name: str, invariant: str):
+        self.packet_name = packet_name
+        self.invariant = invariant
+        super().__init__(f"invalid {packet_name}: the invariant {invariant} is violated")
+
+
 @dataclass
 class Packet:
     payload: Optional[bytes] = field(repr=False, default_factory=bytes, compare=False)
@@ -181,13 +233,14 @@ class Packet:
 
         last = len(fields(self)) - 1
         align = max((len(f.name) for f in fields(self) if f.name != 'payload'), default=0)
+        types = get_type_hints(type(self))
 
         for (idx, f) in enumerate(fields(self)):
             p  = prefix + ('├── ' if idx != last else '└── ')
             pp = prefix + ('│   ' if idx != last else '    ')
             val = getattr(self, f.name)
 
-            print_val(p, pp, f.name, align, f.type, val)
+            print_val(p, pp, f.name, align, types[f.name], val)
 "#
     .to_string()
 }
@@ -263,9 +316,12 @@ pub fn generate(
         }
 
         match &decl.desc {
-            ast::DeclDesc::Enum { id, tags, width } => {
+            ast::DeclDesc::Enum { id, tags, width, .. } => {
                 code.push_str(&generate_enum_declaration(id, tags, *width));
             }
+            ast::DeclDesc::Flags { id, flags, .. } => {
+                code.push_str(&generate_flags_declaration(id, flags));
+            }
             ast::DeclDesc::Packet { .. } | ast::DeclDesc::Struct { .. } => {
                 code.push_str(&generate_packet_declaration(&scope, &schema, file, decl));
             }
@@ -276,11 +332,102 @@ pub fn generate(
     code
 }
 
+/// Generate unit tests for the test declarations of an analyzed file.
+/// The tests are meant to be appended to the module generated for
+/// the same file.
+pub fn generate_tests(sources: &ast::SourceDatabase, file: &ast::File) -> String {
+    let mut code = String::new();
+    let source = sources.get(file.file).expect("could not read source");
+    let scope = analyzer::Scope::new(file).unwrap();
+
+    code.push_str(&format!("# Tests generated from {}, with the command:\n", source.name()));
+    code.push_str("#  pdlc ...\n");
+    code.push_str("# /!\\ Do not edit by hand.\n");
+    code.push_str("\nimport unittest\n");
+
+    for packet in test_vectors(file) {
+        // Derived packets are parsed from the root packet.
+        let decl = scope.typedef[&packet.name];
+        let root = scope.iter_parents_and_self(decl).last().unwrap().id().unwrap();
+        let mut methods = Vec::new();
+        for (i, test_vector) in packet.tests.iter().enumerate() {
+            let mut code = CodeBlock::default();
+            if let Some(expected_error) = &test_vector.expected_error {
+                code.append(format!("def test_decode_invalid_{i}(self):"));
+                let expected_error = match expected_error.as_str() {
+                    "InvariantViolation" => "InvariantViolationError",
+                    "DepthLimitError" => {
+                        // The python parsers do not limit the nesting depth.
+                        code.append(
+                            "    self.skipTest('depth limits are not enforced by the python backend')"
+                                .to_owned(),
+                        );
+                        methods.push(code.lines.join("\n"));
+                        continue;
+                    }
+                    expected_error => expected_error,
+                };
+                if root == packet.name {
+                    code.append(format!("    with self.assertRaises({expected_error}):"));
+                    code.append(format!(
+                        "        {root}.parse_all(bytes.fromhex('{}'))",
+                        test_vector.packed
+                    ));
+                } else {
+                    // Errors raised while specializing the root packet
+                    // are caught, and the parent packet is returned instead.
+                    code.append("    try:".to_owned());
+                    code.append(format!(
+                        "        packet = {root}.parse_all(bytes.fromhex('{}'))",
+                        test_vector.packed
+                    ));
+                    code.append(format!("    except {expected_error}:"));
+                    code.append("        return".to_owned());
+                    code.append(format!("    self.assertNotIsInstance(packet, {})", packet.name));
+                }
+            } else {
+                code.append(format!("def test_decode_{i}(self):"));
+                code.append(format!("    packed = bytes.fromhex('{}')", test_vector.packed));
+                code.append(format!("    packet = {root}.parse_all(packed)"));
+                if root != packet.name {
+                    code.append(format!("    self.assertIsInstance(packet, {})", packet.name));
+                }
+                for (field_id, value) in
+                    test_vector.unpacked.iter().flat_map(|v| v.as_object()).flatten()
+                {
+                    code.append(format!("    self.assertEqual(packet.{field_id}, {value})"));
+                }
+                code.append("    self.assertEqual(packet.serialize(), packed)".to_owned());
+            }
+            methods.push(code.lines.join("\n"));
+        }
+
+        code.push_str(&format!(
+            r#"
+
+class {packet_name}Test(unittest.TestCase):
+{methods}
+"#,
+            packet_name = packet.name,
+            methods = indent(&methods.join("\n\n"), 1)
+        ));
+    }
+
+    code
+}
+
 fn generate_enum_declaration(id: &str, tags: &[ast::Tag], _width: usize) -> String {
     let mut tag_decls = Vec::new();
+    let mut display_names = Vec::new();
     for tag in tags {
         if let ast::Tag::Value(t) = tag {
             tag_decls.push(format!("{} = {:#x}", t.id, t.value));
+            // Enum members declared with the same value are aliases
+            // of the first member.
+            tag_decls.extend(t.aliases.iter().map(|alias| format!("{} = {:#x}", alias, t.value)));
+            if let Some(display_name) = &t.display_name {
+                display_names.push(format!("{:#x}: \"{display_name}\",", t.value));
+            }
         }
     }
 
@@ -302,6 +449,16 @@ fn generate_enum_declaration(id: &str, tags: &[ast::Tag], _width: usize) -> Stri
         unknown_handler.push(format!(r#"raise EnumValueError("", "", "{id}", v)"#));
     }
 
+    // The display name defaults to the tag identifier.
+    let display_name = if display_names.is_empty() {
+        vec!["return self.name".to_owned()]
+    } else {
+        let mut code = vec!["return {".to_owned()];
+        code.extend(display_names.iter().map(|line| format!("    {line}")));
+        code.push("}.get(self.value, self.name)".to_owned());
+        code
+    };
+
     format!(
         r#"
 class {enum_name}(enum.IntEnum):
@@ -313,10 +470,35 @@ class {enum_name}(enum.IntEnum):
             return {enum_name}(v)
         except ValueError:
 {unknown_handler}
+
+    @property
+    def display_name(self) -> str:
+{display_name}
 "#,
         enum_name = id,
         tag_decls = indent(&tag_decls.join("\n"), 1),
-        unknown_handler = indent(&unknown_handler.join("\n"), 3)
+        unknown_handler = indent(&unknown_handler.join("\n"), 3),
+        display_name = indent(&display_name.join("\n"), 2)
+    )
+}
+
+/// Generate the declaration for a flags type. Bits that do not match
+/// any declared flag are preserved by `enum.IntFlag`.
+fn generate_flags_declaration(id: &str, flags: &[ast::TagValue]) -> String {
+    let flag_decls =
+        flags.iter().map(|flag| format!("{} = {:#x}", flag.id, flag.value)).collect::<Vec<_>>();
+
+    format!(
+        r#"
+class {flags_name}(enum.IntFlag):
+{flag_decls}
+
+    @staticmethod
+    def from_int(v: int) -> '{flags_name}':
+        return {flags_name}(v)
+"#,
+        flags_name = id,
+        flag_decls = indent(&flag_decls.join("\n"), 1),
     )
 }
 
@@ -328,9 +510,19 @@ fn generate_packet_declaration<'a>(
 ) -> String {
     let id = decl.id().unwrap();
 
+    // References to recursive declarations are quoted, as the
+    // referenced class may not be defined yet.
+    let type_annotation = |type_id: &str| {
+        if scope.typedef.get(type_id).is_some_and(|decl| scope.is_recursive(decl)) {
+            format!("'{type_id}'")
+        } else {
+            type_id.to_owned()
+        }
+    };
+
     let mut field_decls = Vec::new();
     for field in decl.fields() {
-        if field.cond.is_some() {
+        if field.is_optional() {
             match &field.desc {
                 ast::FieldDesc::Scalar { .. } => {
                     field_decls.push(format!(
@@ -342,7 +534,7 @@ fn generate_packet_declaration<'a>(
                     field_decls.push(format!(
                         "{}: Optional[{}] = field(kw_only=True, default=None)",
                         field.id().unwrap(),
-                        type_id
+                        type_annotation(type_id)
                     ));
                 }
                 _ => {}
@@ -351,8 +543,18 @@ fn generate_packet_declaration<'a>(
             match &field.desc {
                 // Handled via presence of optional fields
                 ast::FieldDesc::Flag { .. } => (),
-                ast::FieldDesc::Scalar { id: field_id, .. } => {
-                    field_decls.push(format!("{}: int = field(kw_only=True, default=0)", field_id));
+                ast::FieldDesc::Scalar { id: field_id, default, .. } => {
+                    field_decls.push(format!(
+                        "{}: int = field(kw_only=True, default={})",
+                        field_id,
+                        default.unwrap_or(0)
+                    ));
+                }
+                ast::FieldDesc::Typedef { id: field_id, type_id, default: Some(tag_id) } => {
+                    field_decls.push(format!(
+                        "{}: {} = field(kw_only=True, default={}.{})",
+                        field_id, type_id, type_id, tag_id
+                    ));
                 }
                 ast::FieldDesc::Typedef { id: field_id, type_id, .. } => {
                     let type_decl = scope.typedef.get(type_id.as_str()).unwrap();
@@ -372,6 +574,12 @@ fn generate_packet_declaration<'a>(
                                 ));
                             }
                         }
+                        ast::DeclDesc::Flags { .. } => {
+                            field_decls.push(format!(
+                                "{}: {} = field(kw_only=True, default={}(0))",
+                                field_id, type_id, type_id
+                            ));
+                        }
                         ast::DeclDesc::Checksum { .. } => {
                             field_decls.push(format!(
                                 "{}: int = field(kw_only=True, default=0)",
@@ -400,6 +608,7 @@ fn generate_packet_declaration<'a>(
                 ast::FieldDesc::Array {
                     id: field_id, width: None, type_id: Some(type_id), ..
                 } => {
+                    let type_id = type_annotation(type_id);
                     field_decls.push(format!(
                         "{field_id}: List[{type_id}] = field(kw_only=True, default_factory=list)",
                     ));
@@ -426,6 +635,8 @@ fn generate_packet_declaration<'a>(
     let parser = generate_packet_parser(scope, schema, file, decl);
     let size = generate_packet_size_property(scope, schema, decl);
     let post_init = generate_packet_post_init(scope, decl);
+    let scaled_properties = generate_packet_scaled_properties(decl);
+    let payload_bindings = generate_packet_payload_bindings(scope, decl);
 
     format!(
         r#"
@@ -445,7 +656,7 @@ class {packet_name}({parent_name}):
 
     @property
     def size(self) -> int:
-{size}
+{size}{scaled_properties}{payload_bindings}
 "#,
         packet_name = id,
         parent_name = parent_name,
@@ -454,10 +665,135 @@ class {packet_name}({parent_name}):
         post_init = indent(&post_init.join("\n"), 2),
         parser = indent(&parser.join("\n"), 2),
         serializer = indent(&serializer.join("\n"), 2),
-        size = indent(&size.join("\n"), 2)
+        size = indent(&size.join("\n"), 2),
+        scaled_properties = indent(&scaled_properties.concat(), 1),
+        payload_bindings = indent(&payload_bindings.concat(), 1),
     )
 }
 
+/// Generate the typed payload decoders for the bind declarations
+/// targeting the payload of the packet.
+fn generate_packet_payload_bindings(scope: &analyzer::Scope<'_>, decl: &ast::Decl) -> Vec<String> {
+    let mut code = Vec::new();
+    let mut type_ids = Vec::new();
+    let mut cases = Vec::new();
+    for binding in scope.file.iter_bindings(decl) {
+        let ast::DeclDesc::Bind { type_id, constraints, .. } = &binding.desc else {
+            unreachable!()
+        };
+        if !type_ids.contains(&type_id) {
+            type_ids.push(type_id);
+        }
+        let decode = format!("return self.decode_payload_as_{}()", type_id.to_snake_case());
+        if constraints.is_empty() {
+            cases.push(decode);
+            break;
+        }
+        let conditions = constraints
+            .iter()
+            .map(|c| match c {
+                ast::Constraint { value: Some(value), .. } => format!("self.{} == {}", c.id, value),
+                ast::Constraint { tag_id: Some(tag_id), .. } => {
+                    let type_id = scope
+                        .iter_fields(decl)
+                        .find_map(|f| match &f.desc {
+                            ast::FieldDesc::Typedef { id, type_id, .. } if id == &c.id => {
+                                Some(type_id)
+                            }
+                            _ => None,
+                        })
+                        .unwrap();
+                    format!("self.{} == {}.{}", c.id, type_id, tag_id)
+                }
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        cases.push(format!("if {}:\n    {decode}", conditions.join(" and ")));
+    }
+    if type_ids.is_empty() {
+        return code;
+    }
+    if !cases.last().unwrap().starts_with("return") {
+        cases.push("return None".to_owned());
+    }
+
+    for type_id in type_ids {
+        // Derived packets are parsed from the root packet.
+        let type_decl = scope.typedef[type_id];
+        let root = scope.iter_parents_and_self(type_decl).last().unwrap().id().unwrap();
+        let decode = if root == type_id {
+            format!("return {type_id}.parse_all(self.payload)")
+        } else {
+            format!(
+                r#"packet = {root}.parse_all(self.payload)
+if not isinstance(packet, {type_id}):
+    raise DecodeError("payload is not a {type_id} packet")
+return packet"#
+            )
+        };
+        code.push(format!(
+            r#"
+
+def decode_payload_as_{}(self) -> '{type_id}':
+    """Decode the payload as `{type_id}`."""
+{}"#,
+            type_id.to_snake_case(),
+            indent(&decode, 1)
+        ));
+    }
+    code.push(format!(
+        r#"
+
+def decode_payload(self) -> Optional[Packet]:
+    """Decode the payload as the declaration bound by the first
+    bind declaration matching the field values."""
+{}"#,
+        indent(&cases.join("\n"), 1)
+    ));
+    code
+}
+
+/// Generate the scaled properties for the scalar fields declared with
+/// a scale factor. The setter rounds the raw value to the nearest
+/// integer and saturates it to the field width.
+fn generate_packet_scaled_properties(decl: &ast::Decl) -> Vec<String> {
+    let mut code = Vec::new();
+    for field in decl.fields() {
+        let ast::FieldDesc::Scalar { id, width, scale: Some(scale), unit, .. } = &field.desc else {
+            continue;
+        };
+        let doc = match unit {
+            Some(unit) => format!("Value of `{id}` in {unit}."),
+            None => format!("Scaled value of `{id}`."),
+        };
+        // Round half up to match the Rust and C++ backends; negative
+        // values are saturated to zero.
+        let raw = format!("min(max(int(value / {scale} + 0.5), 0), {})", mask(*width));
+        let (ty, getter, setter) = if field.is_optional() {
+            (
+                "Optional[float]",
+                format!("None if self.{id} is None else self.{id} * {scale}"),
+                format!("None if value is None else {raw}"),
+            )
+        } else {
+            ("float", format!("self.{id} * {scale}"), raw)
+        };
+        code.push(format!(
+            r#"
+
+@property
+def {id}_scaled(self) -> {ty}:
+    """{doc}"""
+    return {getter}
+
+@{id}_scaled.setter
+def {id}_scaled(self, value: {ty}) -> None:
+    self.{id} = {setter}"#
+        ));
+    }
+    code
+}
+
 fn generate_packet_post_init<'a>(scope: &'a analyzer::Scope<'a>, decl: &ast::Decl) -> Vec<String> {
     let mut constraints = Vec::new();
     for parent in scope.iter_parents(decl) {
@@ -502,7 +838,7 @@ fn generate_packet_size_property<'a>(
     let mut variable_width = Vec::new();
 
     for field in decl.fields() {
-        if field.cond.is_some() {
+        if field.is_optional() {
             match &field.desc {
                 ast::FieldDesc::Scalar { id: field_id, width, .. } => {
                     variable_width
@@ -511,7 +847,7 @@ fn generate_packet_size_property<'a>(
                 ast::FieldDesc::Typedef { id: field_id, type_id, .. } => {
                     let type_decl = scope.typedef.get(type_id.as_str()).unwrap();
                     match &type_decl.desc {
-                        ast::DeclDesc::Enum { width, .. } => {
+                        ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                             variable_width
                                 .push(format!("(0 if self.{field_id} is None else {})", width / 8));
                         }
@@ -553,7 +889,7 @@ fn generate_packet_size_property<'a>(
             ast::FieldDesc::Array { id: field_id, width: None, type_id: Some(type_id), .. } => {
                 let type_decl = scope.typedef.get(type_id.as_str()).unwrap();
                 match &type_decl.desc {
-                    ast::DeclDesc::Enum { width, .. } => {
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                         variable_width.push(format!("len(self.{field_id}) * {}", width / 8));
                     }
                     _ => {
@@ -590,12 +926,21 @@ fn generate_toplevel_packet_serializer<'a>(
     file: &ast::File,
     decl: &ast::Decl,
 ) -> Vec<String> {
-    let mut serializer = FieldSerializer::new(scope, schema, file.endianness.value);
+    let mut serializer =
+        FieldSerializer::new(scope, schema, file.decl_endianness(decl), file.bit_order(decl));
     for field in decl.fields() {
         serializer.serialize(decl, field);
     }
-    let mut code = vec!["_span = bytearray()".to_string()];
+    let mut code = invariant_checks(decl);
+    code.push("_span = bytearray()".to_string());
     code.extend(serializer.code);
+    if let Some(assertion) = decl.size_assertion {
+        code.push(format!("if not {}:", size_assertion_condition(&assertion, "len(_span)")));
+        code.push(format!(
+            r#"    raise ValueError(f"Invalid size value {}: {{len(_span)}} does not satisfy the assertion {assertion}")"#,
+            decl.id().unwrap()
+        ));
+    }
     code.push("return bytes(_span)".to_string());
     code
 }
@@ -607,13 +952,49 @@ fn generate_derived_packet_serializer<'a>(
     decl: &ast::Decl,
 ) -> Vec<String> {
     let parent = scope.get_parent(decl).unwrap();
-    let mut serializer = FieldSerializer::new(scope, schema, file.endianness.value);
+    let mut serializer =
+        FieldSerializer::new(scope, schema, file.decl_endianness(decl), file.bit_order(decl));
     for field in decl.fields() {
         serializer.serialize(decl, field);
     }
-    let mut code = vec!["_span = bytearray()".to_string()];
+    let mut code = invariant_checks(decl);
+    code.push("_span = bytearray()".to_string());
     code.extend(serializer.code);
-    code.push(format!("return {}.serialize(self, payload = bytes(_span))", parent.id().unwrap()));
+    match decl.size_assertion {
+        Some(assertion) => {
+            code.push(format!(
+                "_packet = {}.serialize(self, payload = bytes(_span))",
+                parent.id().unwrap()
+            ));
+            code.push(format!("if not {}:", size_assertion_condition(&assertion, "len(_packet)")));
+            code.push(format!(
+                r#"    raise ValueError(f"Invalid size value {}: {{len(_packet)}} does not satisfy the assertion {assertion}")"#,
+                decl.id().unwrap()
+            ));
+            code.push("return _packet".to_string());
+        }
+        None => code.push(format!(
+            "return {}.serialize(self, payload = bytes(_span))",
+            parent.id().unwrap()
+        )),
+    }
+    code
+}
+
+/// Generate the serializer checks for the invariants of `decl`.
+/// The invariants of the parent declarations are checked by the
+/// parent serializers.
+fn invariant_checks(decl: &ast::Decl) -> Vec<String> {
+    let mut code = vec![];
+    for invariant in decl.invariants() {
+        let condition = invariant_expr(&invariant.expr, &|id| format!("self.{id}"));
+        code.push(format!("if not {condition}:"));
+        code.push(format!(
+            r#"    raise ValueError("Invalid {} value: the invariant {} is violated")"#,
+            decl.id().unwrap(),
+            invariant.text
+        ));
+    }
     code
 }
 
@@ -634,6 +1015,58 @@ fn get_specialized_children<'a>(file: &'a ast::File, decl: &'a ast::Decl) -> Vec
     children
 }
 
+/// Return the declarations derived from `decl`, directly or indirectly.
+fn iter_descendants<'a>(scope: &analyzer::Scope<'a>, decl: &'a ast::Decl) -> Vec<&'a ast::Decl> {
+    let mut descendants = Vec::new();
+    for child in scope.iter_children(decl) {
+        descendants.push(child);
+        descendants.extend(iter_descendants(scope, child));
+    }
+    descendants
+}
+
+/// Generate the parser check for a size assertion, where `size` is
+/// the expression evaluating to the size of the decoded packet.
+fn size_assertion_check(
+    packet_name: &str,
+    assertion: &ast::SizeAssertion,
+    size: &str,
+) -> Vec<String> {
+    vec![
+        format!("if not {}:", size_assertion_condition(assertion, size)),
+        format!(r#"    raise SizeAssertionError("{packet_name}", {size}, "{assertion}")"#),
+    ]
+}
+
+/// Generate the condition checking a size assertion against
+/// the expression `size`.
+fn size_assertion_condition(assertion: &ast::SizeAssertion, size: &str) -> String {
+    let comparison = match assertion.comparison {
+        ast::SizeComparison::Equal => "==",
+        ast::SizeComparison::LessOrEqual => "<=",
+        ast::SizeComparison::GreaterOrEqual => ">=",
+    };
+    format!("({size} {comparison} {})", assertion.size)
+}
+
+/// Generate the python expression evaluating an invariant expression.
+/// `field` maps a field identifier to the expression of its value.
+fn invariant_expr(expr: &ast::Expr, field: &dyn Fn(&str) -> String) -> String {
+    match expr {
+        ast::Expr::Integer { value } => value.to_string(),
+        ast::Expr::Field { id } => field(id),
+        ast::Expr::Length { id } => format!("len({})", field(id)),
+        ast::Expr::Binary { op, lhs, rhs } => {
+            let op = match op {
+                ast::BinaryOp::And => "and".to_owned(),
+                ast::BinaryOp::Or => "or".to_owned(),
+                op => op.to_string(),
+            };
+            format!("({} {op} {})", invariant_expr(lhs, field), invariant_expr(rhs, field))
+        }
+    }
+}
+
 /// Generate the parse() function for a toplevel Packet or Struct declaration.
 fn generate_packet_parser<'a>(
     scope: &'a analyzer::Scope<'a>,
@@ -666,12 +1099,42 @@ fn generate_packet_parser<'a>(
         }
     }
 
+    // The size assertions of derived packets are checked by the root
+    // packet parser, which knows the size of the full packet.
+    let assertion_decls = if scope.get_parent(decl).is_none() {
+        iter_descendants(scope, decl)
+            .into_iter()
+            .filter(|decl| decl.size_assertion.is_some())
+            .collect::<Vec<_>>()
+    } else {
+        vec![]
+    };
+    if decl.size_assertion.is_some() || !assertion_decls.is_empty() {
+        code.push("_input = span".to_string());
+    }
+
     // Parse fields iteratively.
     let mut parser = FieldParser::new(scope, schema, file, decl);
     for field in decl.fields() {
         parser.parse(field);
     }
     parser.done();
+    code.extend(parser.code.lines);
+
+    if decl.size_assertion.is_some() || !assertion_decls.is_empty() {
+        code.push("_packet_size = len(_input) - len(span)".to_string());
+    }
+    if let Some(assertion) = decl.size_assertion {
+        code.extend(size_assertion_check(packet_name, &assertion, "_packet_size"));
+    }
+    for invariant in decl.invariants() {
+        let condition = invariant_expr(&invariant.expr, &|id| format!("fields['{id}']"));
+        code.push(format!("if not {condition}:"));
+        code.push(format!(
+            r#"    raise InvariantViolationError("{packet_name}", "{}")"#,
+            invariant.text
+        ));
+    }
 
     // Specialize to child packets.
     let children = get_specialized_children(file, decl);
@@ -689,12 +1152,24 @@ fn generate_packet_parser<'a>(
         specialization.push("    if remainder:".to_string());
         specialization
             .push(format!(r#"        raise TrailingBytesError("{child_name}", len(remainder))"#));
+        for assertion_decl in &assertion_decls {
+            let assertion_name = assertion_decl.id().unwrap();
+            specialization.push(format!("    if isinstance(child, {assertion_name}):"));
+            specialization.extend(
+                size_assertion_check(
+                    assertion_name,
+                    &assertion_decl.size_assertion.unwrap(),
+                    "_packet_size",
+                )
+                .iter()
+                .map(|line| format!("        {line}")),
+            );
+        }
         specialization.push("    return child, span".to_string());
         specialization.push("except Exception:".to_string());
         specialization.push("    pass".to_string());
     }
 
-    code.extend(parser.code.lines);
     code.extend(specialization);
     code.push(format!("return {}(**fields), span", decl.id().unwrap()));
     code
@@ -792,7 +1267,7 @@ if len(span) < {size}:
         // extract field values.
         let size = self.shift / 8;
         let end_offset = self.offset + size;
-        let byteorder = match self.file.endianness.value {
+        let byteorder = match self.file.field_endianness(self.decl, field) {
             ast::EndiannessValue::LittleEndian => "little",
             ast::EndiannessValue::BigEndian => "big",
         };
@@ -809,8 +1284,10 @@ if len(span) < {size}:
 
         let packet_name = self.decl.id().unwrap();
         let chunk = std::mem::take(&mut self.chunk);
+        let bit_order = self.file.bit_order(self.decl);
 
         for (shift, width, field) in chunk {
+            let shift = bit_order.shift(self.shift, shift, width);
             let v = if shift == 0 && width == self.shift {
                 value.clone()
             } else {
@@ -840,8 +1317,50 @@ if {v} != {enum_id}.{tag_id}:
                 ast::FieldDesc::Typedef { id, type_id, .. } => {
                     self.unchecked_append(format!("fields['{id}'] = {type_id}.from_int({v})"));
                 }
-                ast::FieldDesc::Size { field_id, .. } => {
-                    self.unchecked_append(format!("{field_id}_size = {v}"));
+                ast::FieldDesc::Size { field_id, unit, .. } => {
+                    // Size fields placed after the sized field are
+                    // checked against the decoded size.
+                    let trailing = self.decl.is_trailing_size(field);
+                    let size = if trailing {
+                        format!("trailing_{field_id}_size")
+                    } else {
+                        format!("{field_id}_size")
+                    };
+                    match unit {
+                        ast::SizeUnit::Octets => {
+                            self.unchecked_append(format!("{size} = {v}"));
+                        }
+                        ast::SizeUnit::Bits => {
+                            self.unchecked_append(format!(
+                                r#"
+{size} = {v}
+if {size} % 8 != 0:
+    raise SizeUnitError("{packet_name}", "{field_id}", {size})
+{size} = {size} // 8
+                        "#
+                            ));
+                        }
+                        ast::SizeUnit::Words(width) => {
+                            self.unchecked_append(format!("{size} = {v} * {}", width / 8));
+                        }
+                    }
+                    if trailing {
+                        self.unchecked_append(format!(
+                            r#"
+if {size} != {field_id}_size:
+    raise TrailingSizeError("{packet_name}", "{field_id}", {field_id}_size, {size})
+                        "#
+                        ));
+                    }
+                }
+                ast::FieldDesc::Count { field_id, .. } if self.decl.is_trailing_size(field) => {
+                    self.unchecked_append(format!(
+                        r#"
+trailing_{field_id}_count = {v}
+if trailing_{field_id}_count != len({field_id}):
+    raise TrailingSizeError("{packet_name}", "{field_id}", len({field_id}), trailing_{field_id}_count)
+                        "#
+                    ));
                 }
                 ast::FieldDesc::Count { field_id, .. } => {
                     self.unchecked_append(format!("{field_id}_count = {v}"));
@@ -921,7 +1440,7 @@ if {v} != {enum_id}.{tag_id}:
             _ => unreachable!(),
         };
 
-        let byteorder = match self.file.endianness.value {
+        let byteorder = match self.file.decl_endianness(self.decl) {
             ast::EndiannessValue::LittleEndian => "little",
             ast::EndiannessValue::BigEndian => "big",
         };
@@ -995,6 +1514,16 @@ if computed_{id} != {id}:
             _ => unreachable!(),
         };
 
+        if self.decl.array_size(id).is_some() {
+            // The struct size is known by size field. The struct is parsed
+            // from the designated span, and must consume it entirely.
+            self.consume_span(0);
+            self.check_size(format!("{id}_size"));
+            self.append(format!("fields['{id}'] = {type_id}.parse_all(span[:{id}_size])"));
+            self.append(format!("span = span[{id}_size:]"));
+            return;
+        }
+
         match self.schema.field_size(field.key) {
             analyzer::Size::Static(w) => {
                 let size = w / 8;
@@ -1035,8 +1564,9 @@ fields['{id}'] = {id}
             ast::FieldDesc::Size { field_id, .. } => field_id == id,
             _ => false,
         });
+        let trailing_size = size_field.is_some_and(|f| self.decl.is_trailing_size(f));
 
-        if let Some(_f) = size_field {
+        if size_field.is_some() && !trailing_size {
             if let ast::FieldDesc::Payload { size_modifier: Some(modifier), .. } = &field.desc {
                 self.append(format!("{}_size -= {}", id, modifier));
             }
@@ -1049,20 +1579,7 @@ span = span[{id}_size:]
                 "#
             ));
         } else {
-            let mut offset_from_end = 0;
-            let mut found = false;
-            for f in self.decl.fields() {
-                if f == field {
-                    found = true;
-                    continue;
-                }
-                if found {
-                    if let analyzer::Size::Static(w) = self.schema.field_size(f.key) {
-                        offset_from_end += w;
-                    }
-                }
-            }
-
+            let offset_from_end = self.field_offset_from_end(field);
             if offset_from_end == 0 {
                 self.append(
                     r#"
@@ -1083,22 +1600,67 @@ span = span[-{offset_bytes}:]
                 ));
             }
         }
+        if trailing_size {
+            // Save the expected value of the size field placed after
+            // the payload.
+            let size_modifier = match &field.desc {
+                ast::FieldDesc::Payload { size_modifier: Some(modifier), .. } => {
+                    format!(" + {modifier}")
+                }
+                _ => String::new(),
+            };
+            self.append(format!("{id}_size = len(payload){size_modifier}"));
+        }
+        if let ast::FieldDesc::Payload { max_size: Some(max_size), .. } = &field.desc {
+            self.append(format!("if len(payload) > {max_size}:"));
+            self.append(format!(
+                r#"    raise SizeBoundError("{packet_name}", "_payload_", len(payload), {max_size})"#
+            ));
+        }
         self.append("fields['payload'] = payload".to_string());
     }
 
+    /// Return the sum of the static sizes of the fields placed after
+    /// `field`, in bits.
+    fn field_offset_from_end(&self, field: &ast::Field) -> usize {
+        let mut offset_from_end = 0;
+        let mut found = false;
+        for f in self.decl.fields() {
+            if f == field {
+                found = true;
+                continue;
+            }
+            if found {
+                if let analyzer::Size::Static(w) = self.schema.field_size(f.key) {
+                    offset_from_end += w;
+                }
+            }
+        }
+        offset_from_end
+    }
+
     fn parse_array_field(&mut self, field: &'a ast::Field) {
-        let ast::FieldDesc::Array { id, size_modifier, .. } = &field.desc else {
+        let ast::FieldDesc::Array { id, size_modifier, max_count, .. } = &field.desc else {
             return;
         };
 
         let element_size = analyzer::element_size(self.scope, self.schema, self.decl, field);
-        let array_size = analyzer::array_size(self.decl, field);
+        let mut array_size = analyzer::array_size(self.decl, field);
         let padded_size = self.schema.padded_size(field.key);
         let packet_name = self.decl.id().unwrap();
 
         // Shift the span to reset the offset to 0.
         self.consume_span(0);
 
+        // The size or count field is placed after the array: the array
+        // spans the octets not reserved for the following fields.
+        if self.decl.array_size(id).is_some_and(|f| self.decl.is_trailing_size(f)) {
+            let offset_bytes = self.field_offset_from_end(field) / 8;
+            self.check_size(format!("{offset_bytes}"));
+            self.append(format!("{id}_size = len(span) - {offset_bytes}"));
+            array_size = analyzer::ArraySize::DynamicSize;
+        }
+
         // Apply the size modifier.
         if let Some(size_modifier) = size_modifier {
             self.append(format!("{id}_size = {id}_size - {size_modifier}"));
@@ -1242,13 +1804,20 @@ span = span[-{offset_bytes}:]
             }
         }
 
+        if let Some(max_count) = max_count {
+            self.append(format!("if len({id}) > {max_count}:"));
+            self.append(format!(
+                r#"    raise BoundError("{packet_name}", "{id}", len({id}), {max_count})"#
+            ));
+        }
+
         if padded_size.is_some() {
             self.append("span = remaining_span".to_string());
         }
     }
 
     fn parse_array_element_static(&mut self, field: &'a ast::Field, span: String) {
-        let byteorder = match self.file.endianness.value {
+        let byteorder = match self.file.field_endianness(self.decl, field) {
             ast::EndiannessValue::LittleEndian => "little",
             ast::EndiannessValue::BigEndian => "big",
         };
@@ -1260,7 +1829,7 @@ span = span[-{offset_bytes}:]
             }
             ast::FieldDesc::Array { id, type_id: Some(type_id), .. } => {
                 match &self.scope.typedef.get(type_id).unwrap().desc {
-                    ast::DeclDesc::Enum { .. } => {
+                    ast::DeclDesc::Enum { .. } | ast::DeclDesc::Flags { .. } => {
                         let value = format!("int.from_bytes({span}, byteorder='{byteorder}')");
                         self.do_append(format!("    {id}.append({type_id}.from_int({value}))"));
                     }
@@ -1283,17 +1852,30 @@ span = span[-{offset_bytes}:]
 
     fn parse_optional_field(&mut self, field: &'a ast::Field) {
         self.consume_span(0);
-        let cond = field.cond.as_ref().unwrap();
+        let condition = match (&field.cond, field.extension) {
+            (Some(cond), _) => format!("{} == {}", cond.id, cond.value.unwrap()),
+            // The fields of an extension block are present if bytes remain
+            // at the start of the block.
+            (None, Some(extension)) => {
+                match self.decl.fields().find(|f| f.extension == Some(extension)) {
+                    Some(first) if first.key != field.key => {
+                        format!("'{}' in fields", first.id().unwrap())
+                    }
+                    _ => "len(span) > 0".to_owned(),
+                }
+            }
+            (None, None) => unreachable!(),
+        };
         let id = field.id().unwrap();
         let packet_name = self.decl.id().unwrap();
-        let byteorder = match self.file.endianness.value {
+        let byteorder = match self.file.field_endianness(self.decl, field) {
             ast::EndiannessValue::LittleEndian => "little",
             ast::EndiannessValue::BigEndian => "big",
         };
 
         match &field.desc {
             ast::FieldDesc::Scalar { width, .. } => {
-                self.append(format!("if {} == {}:", cond.id, cond.value.unwrap()));
+                self.append(format!("if {condition}:"));
                 self.append(format!("    if len(span) < {}:", width / 8));
                 self.append(format!(
                     r#"        raise LengthError("{packet_name}", {}, len(span))"#,
@@ -1310,8 +1892,8 @@ span = span[-{offset_bytes}:]
             ast::FieldDesc::Typedef { type_id, .. } => {
                 let type_decl = self.scope.typedef.get(type_id.as_str()).unwrap();
                 match &type_decl.desc {
-                    ast::DeclDesc::Enum { width, .. } => {
-                        self.append(format!("if {} == {}:", cond.id, cond.value.unwrap()));
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                        self.append(format!("if {condition}:"));
                         self.append(format!("    if len(span) < {}:", width / 8));
                         self.append(format!(
                             r#"        raise LengthError("{packet_name}", {}, len(span))"#,
@@ -1327,7 +1909,7 @@ span = span[-{offset_bytes}:]
                         self.append(format!("    span = span[{}:]", width / 8));
                     }
                     _ => {
-                        self.append(format!("if {} == {}:", cond.id, cond.value.unwrap()));
+                        self.append(format!("if {condition}:"));
                         self.append(format!("    {}, span = {}.parse(span)", id, type_id));
                         self.append(format!("    fields['{}'] = {}", id, id));
                     }
@@ -1338,7 +1920,7 @@ span = span[-{offset_bytes}:]
     }
 
     fn parse(&mut self, field: &'a ast::Field) {
-        if field.cond.is_some() {
+        if field.is_optional() {
             self.parse_optional_field(field);
             return;
         }
@@ -1356,7 +1938,9 @@ span = span[-{offset_bytes}:]
             ast::FieldDesc::Typedef { type_id, .. } => {
                 let type_decl = self.scope.typedef.get(type_id.as_str()).unwrap();
                 match &type_decl.desc {
-                    ast::DeclDesc::Enum { .. } => self.parse_bit_field(field),
+                    ast::DeclDesc::Enum { .. } | ast::DeclDesc::Flags { .. } => {
+                        self.parse_bit_field(field)
+                    }
                     _ => self.parse_typedef_field(field),
                 }
             }
@@ -1377,8 +1961,10 @@ struct FieldSerializer<'a> {
     scope: &'a analyzer::Scope<'a>,
     schema: &'a analyzer::Schema,
     byteorder: &'static str,
+    bit_order: ast::BitOrder,
     shift: usize,
-    values: Vec<String>,
+    // Bit-field values with their offset and width in the current chunk.
+    values: Vec<(String, usize, usize)>,
     code: Vec<String>,
 }
 
@@ -1387,6 +1973,7 @@ impl<'a> FieldSerializer<'a> {
         scope: &'a analyzer::Scope<'a>,
         schema: &'a analyzer::Schema,
         byteorder: ast::EndiannessValue,
+        bit_order: ast::BitOrder,
     ) -> Self {
         Self {
             scope,
@@ -1395,6 +1982,7 @@ impl<'a> FieldSerializer<'a> {
                 ast::EndiannessValue::LittleEndian => "little",
                 ast::EndiannessValue::BigEndian => "big",
             },
+            bit_order,
             shift: 0,
             values: Vec::new(),
             code: Vec::new(),
@@ -1418,15 +2006,22 @@ impl<'a> FieldSerializer<'a> {
 
     fn pack_bit_fields(&mut self) {
         let size = self.shift / 8;
-
-        if self.values.is_empty() {
+        let values = self
+            .values
+            .iter()
+            .map(|(value, offset, width)| {
+                format!("({value} << {})", self.bit_order.shift(self.shift, *offset, *width))
+            })
+            .collect::<Vec<_>>();
+
+        if values.is_empty() {
             // This condition is true when the bit fields are all reserved fields.
             self.append(format!("_span.extend([0] * {})", size));
-        } else if self.values.len() == 1 {
-            self.encode_int(&self.values[0].clone(), size);
+        } else if values.len() == 1 {
+            self.encode_int(&values[0], size);
         } else {
             self.append("_value = (".to_string());
-            self.append("    ".to_string() + &self.values.join(" |\n    "));
+            self.append("    ".to_string() + &values.join(" |\n    "));
             self.append(")".to_string());
             self.encode_int("_value", size);
         }
@@ -1447,19 +2042,27 @@ impl<'a> FieldSerializer<'a> {
                 let max_value = mask(width);
                 self.append(format!("if self.{id} > {max_value}:"));
                 self.append(format!("    raise ValueError(\"Invalid scalar value {decl_id}::{id}: {{self.{id}}} > {max_value}\")"));
-                self.values.push(format!("(self.{} << {})", id, shift));
+                self.values.push((format!("self.{id}"), shift, width));
             }
             ast::FieldDesc::FixedScalar { value, .. } => {
-                self.values.push(format!("({:#x} << {})", value, shift));
+                self.values.push((format!("{value:#x}"), shift, width));
             }
             ast::FieldDesc::FixedEnum { enum_id, tag_id, .. } => {
-                self.values.push(format!("({}.{} << {})", enum_id, tag_id, shift));
+                self.values.push((format!("{enum_id}.{tag_id}"), shift, width));
+            }
+            ast::FieldDesc::Typedef { id, type_id, .. }
+                if matches!(self.scope.typedef[type_id].desc, ast::DeclDesc::Flags { .. }) =>
+            {
+                let max_value = mask(width);
+                self.append(format!("if self.{id} > {max_value}:"));
+                self.append(format!("    raise ValueError(\"Invalid flags value {decl_id}::{id}: {{self.{id}}} > {max_value}\")"));
+                self.values.push((format!("self.{id}"), shift, width));
             }
             ast::FieldDesc::Typedef { id, .. } => {
-                self.values.push(format!("(self.{} << {})", id, shift));
+                self.values.push((format!("self.{id}"), shift, width));
             }
             ast::FieldDesc::Reserved { .. } => {}
-            ast::FieldDesc::Size { field_id, .. } => {
+            ast::FieldDesc::Size { field_id, unit, .. } => {
                 let max_size = mask(width);
                 let value_field = self
                     .scope
@@ -1472,7 +2075,7 @@ impl<'a> FieldSerializer<'a> {
                     .unwrap();
 
                 let size = match &value_field.desc {
-                    ast::FieldDesc::Payload { size_modifier: Some(size_modifier) } => {
+                    ast::FieldDesc::Payload { size_modifier: Some(size_modifier), .. } => {
                         self.append(format!(
                             "_payload_size = len(payload or self.payload or []) + {size_modifier}"
                         ));
@@ -1509,18 +2112,34 @@ impl<'a> FieldSerializer<'a> {
                         self.append(format!("{field_id}_size = {size_modifier}{array_size}"));
                         format!("{field_id}_size")
                     }
+                    ast::FieldDesc::Typedef { .. } => {
+                        self.append(format!("{field_id}_size = self.{field_id}.size"));
+                        format!("{field_id}_size")
+                    }
                     _ => unreachable!(),
                 };
 
+                // Convert the size from octets to the size unit.
+                let size = match unit {
+                    ast::SizeUnit::Octets => size,
+                    ast::SizeUnit::Bits => format!("({size} * 8)"),
+                    ast::SizeUnit::Words(width) => {
+                        let octets = width / 8;
+                        self.append(format!("if {size} % {octets} != 0:"));
+                        self.append(format!("    raise ValueError(\"Invalid size value {decl_id}::{field_id}: {{{size}}} is not a multiple of {octets}\")"));
+                        format!("({size} // {octets})")
+                    }
+                };
+
                 self.append(format!("if {size} > {max_size}:"));
                 self.append(format!("    raise ValueError(\"Invalid size value {decl_id}::{field_id}: {{{size}}} > {max_size}\")"));
-                self.values.push(format!("({size} << {shift})"));
+                self.values.push((size, shift, width));
             }
             ast::FieldDesc::Count { field_id, .. } => {
                 let max_count = mask(width);
                 self.append(format!("if len(self.{field_id}) > {max_count}:"));
                 self.append(format!("    raise ValueError(\"Invalid count value {decl_id}::{field_id}: {{len(self.{field_id})}} > {max_count}\")"));
-                self.values.push(format!("(len(self.{field_id}) << {shift})"));
+                self.values.push((format!("len(self.{field_id})"), shift, width));
             }
             ast::FieldDesc::ElementSize { .. } => {
                 todo!()
@@ -1529,9 +2148,12 @@ impl<'a> FieldSerializer<'a> {
                 let optional_field_id = &optional_field_ids[0].0;
                 let value_present = optional_field_ids[0].1;
                 let value_absent = if value_present == 0 { 1 } else { 0 };
-                self.values.push(format!(
-                    "(({} if self.{} is None else {}) << {})",
-                    value_absent, optional_field_id, value_present, shift
+                self.values.push((
+                    format!(
+                        "({value_absent} if self.{optional_field_id} is None else {value_present})"
+                    ),
+                    shift,
+                    width,
                 ));
             }
             _ => unreachable!(),
@@ -1569,14 +2191,24 @@ impl<'a> FieldSerializer<'a> {
         }
     }
 
-    fn serialize_payload_field(&mut self, _field: &ast::Field) {
+    fn serialize_payload_field(&mut self, decl: &ast::Decl, field: &ast::Field) {
         //self.pack_bit_fields();
+        if let ast::FieldDesc::Payload { max_size: Some(max_size), .. } = &field.desc {
+            let decl_id = decl.id().unwrap();
+            self.append(format!("if len(payload or self.payload or []) > {max_size}:"));
+            self.append(format!("    raise ValueError(\"Invalid payload size {decl_id}: {{len(payload or self.payload or [])}} > {max_size}\")"));
+        }
         self.append("_span.extend(payload or self.payload or [])".to_string());
     }
 
-    fn serialize_array_field(&mut self, field: &ast::Field) {
+    fn serialize_array_field(&mut self, decl: &ast::Decl, field: &ast::Field) {
         let id = field.id().unwrap();
         let padded_size = self.schema.padded_size(field.key);
+        if let ast::FieldDesc::Array { max_count: Some(max_count), .. } = &field.desc {
+            let decl_id = decl.id().unwrap();
+            self.append(format!("if len(self.{id}) > {max_count}:"));
+            self.append(format!("    raise ValueError(\"Invalid count value {decl_id}::{id}: {{len(self.{id})}} > {max_count}\")"));
+        }
         if padded_size.is_some() {
             self.append(format!("_{}_start = len(_span)", id));
         }
@@ -1596,10 +2228,11 @@ impl<'a> FieldSerializer<'a> {
             ast::FieldDesc::Array { id, type_id: Some(type_id), .. } => {
                 self.append(format!("for elt in self.{id}:"));
                 match &self.scope.typedef[type_id].desc {
-                    ast::DeclDesc::Enum { width: 8, .. } => {
+                    ast::DeclDesc::Enum { width: 8, .. }
+                    | ast::DeclDesc::Flags { width: 8, .. } => {
                         self.append("    _span.append(int(elt))".to_string());
                     }
-                    ast::DeclDesc::Enum { width, .. } => {
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                         self.append(format!(
                             "    _span.extend(int.to_bytes(elt, length={}, byteorder='{}'))",
                             width / 8,
@@ -1620,9 +2253,34 @@ impl<'a> FieldSerializer<'a> {
         }
     }
 
-    fn serialize_optional_field(&mut self, field: &ast::Field) {
+    fn serialize_optional_field(&mut self, decl: &ast::Decl, field: &ast::Field) {
         let id = field.id().unwrap();
 
+        // The fields of an extension block must be all present or all absent,
+        // and can only be present if the preceding extension blocks are.
+        if let Some(extension) = field.extension {
+            let first_field = |extension| {
+                decl.fields().find(|f| f.extension == Some(extension)).unwrap().id().unwrap()
+            };
+            let decl_id = decl.id().unwrap();
+            let inconsistent = match first_field(extension) {
+                first_id if first_id != id => {
+                    Some(format!("(self.{id} is None) != (self.{first_id} is None)"))
+                }
+                _ if extension > 0 => {
+                    let previous_id = first_field(extension - 1);
+                    Some(format!("self.{id} is not None and self.{previous_id} is None"))
+                }
+                _ => None,
+            };
+            if let Some(inconsistent) = inconsistent {
+                self.append(format!("if {inconsistent}:"));
+                self.append(format!(
+                    "    raise ValueError(\"Inconsistent extension field {decl_id}::{id}\")"
+                ));
+            }
+        }
+
         match &field.desc {
             ast::FieldDesc::Scalar { width, .. } => {
                 self.append(format!("if self.{} is not None:", id));
@@ -1636,7 +2294,7 @@ impl<'a> FieldSerializer<'a> {
             ast::FieldDesc::Typedef { type_id, .. } => {
                 let type_decl = self.scope.typedef.get(type_id.as_str()).unwrap();
                 match &type_decl.desc {
-                    ast::DeclDesc::Enum { width, .. } => {
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                         self.append(format!("if self.{} is not None:", id));
                         self.append(format!(
                             "    _span.extend(int.to_bytes(self.{}, length={}, byteorder='{}'))",
@@ -1656,8 +2314,13 @@ impl<'a> FieldSerializer<'a> {
     }
 
     fn serialize(&mut self, decl: &ast::Decl, field: &ast::Field) {
-        if field.cond.is_some() {
-            self.serialize_optional_field(field);
+        self.byteorder = match self.scope.file.field_endianness(decl, field) {
+            ast::EndiannessValue::LittleEndian => "little",
+            ast::EndiannessValue::BigEndian => "big",
+        };
+
+        if field.is_optional() {
+            self.serialize_optional_field(decl, field);
             return;
         }
 
@@ -1674,14 +2337,16 @@ impl<'a> FieldSerializer<'a> {
             ast::FieldDesc::Typedef { type_id, .. } => {
                 let type_decl = self.scope.typedef.get(type_id.as_str()).unwrap();
                 match &type_decl.desc {
-                    ast::DeclDesc::Enum { .. } => self.serialize_bit_field(decl, field),
+                    ast::DeclDesc::Enum { .. } | ast::DeclDesc::Flags { .. } => {
+                        self.serialize_bit_field(decl, field)
+                    }
                     _ => self.serialize_typedef_field(field),
                 }
             }
             ast::FieldDesc::Payload { .. } | ast::FieldDesc::Body => {
-                self.serialize_payload_field(field)
+                self.serialize_payload_field(decl, field)
             }
-            ast::FieldDesc::Array { .. } => self.serialize_array_field(field),
+            ast::FieldDesc::Array { .. } => self.serialize_array_field(decl, field),
             _ => {}
         }
     }
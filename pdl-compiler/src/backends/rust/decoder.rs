@@ -29,7 +29,8 @@ struct BitField<'a> {
 pub struct FieldParser<'a> {
     scope: &'a analyzer::Scope<'a>,
     schema: &'a analyzer::Schema,
-    endianness: ast::EndiannessValue,
+    decl_endianness: ast::EndiannessValue,
+    endianness: ast::EndiannessValue, // Endianness of the current field.
     decl: &'a ast::Decl,
     packet_name: &'a str,
     span: &'a proc_macro2::Ident,
@@ -50,6 +51,7 @@ impl<'a> FieldParser<'a> {
         FieldParser {
             scope,
             schema,
+            decl_endianness: endianness,
             endianness,
             decl: scope.typedef[packet_name],
             packet_name,
@@ -62,23 +64,27 @@ impl<'a> FieldParser<'a> {
     }
 
     pub fn add(&mut self, field: &'a ast::Field) {
+        self.endianness = field.endianness.unwrap_or(self.decl_endianness);
         match &field.desc {
             _ if field.cond.is_some() => self.add_optional_field(field),
+            _ if field.extension.is_some() => self.add_extension_field(field),
             _ if self.scope.is_bitfield(field) => self.add_bit_field(field),
             ast::FieldDesc::Padding { .. } => (),
-            ast::FieldDesc::Array { id, width, type_id, size, .. } => self.add_array_field(
-                id,
-                *width,
-                type_id.as_deref(),
-                *size,
-                self.schema.padded_size(field.key),
-                self.scope.get_type_declaration(field),
-            ),
-            ast::FieldDesc::Typedef { id, type_id } => self.add_typedef_field(id, type_id),
-            ast::FieldDesc::Payload { size_modifier, .. } => {
-                self.add_payload_field(size_modifier.as_deref())
+            ast::FieldDesc::Array { id, width, type_id, size, max_count, .. } => self
+                .add_array_field(
+                    id,
+                    *width,
+                    type_id.as_deref(),
+                    *size,
+                    *max_count,
+                    self.schema.padded_size(field.key),
+                    self.scope.get_type_declaration(field),
+                ),
+            ast::FieldDesc::Typedef { id, type_id, .. } => self.add_typedef_field(id, type_id),
+            ast::FieldDesc::Payload { size_modifier, max_size } => {
+                self.add_payload_field(size_modifier.as_deref(), *max_size)
             }
-            ast::FieldDesc::Body => self.add_payload_field(None),
+            ast::FieldDesc::Body => self.add_payload_field(None, None),
             _ => todo!("{field:?}"),
         }
     }
@@ -92,49 +98,140 @@ impl<'a> FieldParser<'a> {
         .unwrap();
 
         self.tokens.extend(match &field.desc {
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let id = id.to_ident();
                 let value = types::get_uint(self.endianness, *width, self.span);
                 quote! {
                     let #id = (#cond_id == #cond_value).then(|| #value);
                 }
             }
-            ast::FieldDesc::Typedef { id, type_id } => match &self.scope.typedef[type_id].desc {
-                ast::DeclDesc::Enum { width, .. } => {
-                    let name = id;
-                    let type_name = type_id;
-                    let id = id.to_ident();
-                    let type_id = type_id.to_ident();
-                    let decl_id = &self.packet_name;
-                    let value = types::get_uint(self.endianness, *width, self.span);
-                    quote! {
-                        let #id = (#cond_id == #cond_value)
-                            .then(||
+            ast::FieldDesc::Typedef { id, type_id, .. } => {
+                match &self.scope.typedef[type_id].desc {
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                        let name = id;
+                        let type_name = type_id;
+                        let id = id.to_ident();
+                        let type_id = type_id.to_ident();
+                        let decl_id = &self.packet_name;
+                        let value = types::get_uint(self.endianness, *width, self.span);
+                        quote! {
+                            let #id = (#cond_id == #cond_value)
+                                .then(||
+                                    #type_id::try_from(#value).map_err(|unknown_val| {
+                                        DecodeError::EnumValueError {
+                                            obj: #decl_id,
+                                            field: #name,
+                                            value: unknown_val as u64,
+                                            type_: #type_name,
+                                        }
+                                    }))
+                                .transpose()?;
+                        }
+                    }
+                    ast::DeclDesc::Struct { .. } if self.scope.is_indirect(field) => {
+                        let id = id.to_ident();
+                        let type_id = type_id.to_ident();
+                        let span = self.span;
+                        quote! {
+                            let #id = (#cond_id == #cond_value)
+                                .then(|| #type_id::decode_mut(&mut #span).map(Box::new))
+                                .transpose()?;
+                        }
+                    }
+                    ast::DeclDesc::Struct { .. } => {
+                        let id = id.to_ident();
+                        let type_id = type_id.to_ident();
+                        let span = self.span;
+                        quote! {
+                            let #id = (#cond_id == #cond_value)
+                                .then(|| #type_id::decode_mut(&mut #span))
+                                .transpose()?;
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    fn add_extension_field(&mut self, field: &'a ast::Field) {
+        // The fields of an extension block are present if bytes remain
+        // at the start of the block, the following fields of the block
+        // are then mandatory.
+        let extension = field.extension.unwrap();
+        let first_field =
+            self.decl.fields().find(|field| field.extension == Some(extension)).unwrap();
+        let span = self.span;
+        let present = if first_field.key == field.key {
+            quote! { #span.has_remaining() }
+        } else {
+            let first_id = first_field.id().unwrap().to_ident();
+            quote! { #first_id.is_some() }
+        };
+
+        let packet_name = &self.packet_name;
+        let check_size = |width: usize| {
+            let wanted = proc_macro2::Literal::usize_unsuffixed(width / 8);
+            quote! {
+                if #span.remaining() < #wanted {
+                    return Err(DecodeError::LengthError {
+                        obj: #packet_name,
+                        wanted: #wanted,
+                        got: #span.remaining(),
+                    });
+                }
+            }
+        };
+
+        let (id, check_size, value) = match &field.desc {
+            ast::FieldDesc::Scalar { id, width, .. } => {
+                (id, check_size(*width), types::get_uint(self.endianness, *width, span))
+            }
+            ast::FieldDesc::Typedef { id, type_id, .. } => {
+                match &self.scope.typedef[type_id].desc {
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
+                        let type_name = type_id;
+                        let type_id = type_id.to_ident();
+                        let value = types::get_uint(self.endianness, *width, span);
+                        (
+                            id,
+                            check_size(*width),
+                            quote! {
                                 #type_id::try_from(#value).map_err(|unknown_val| {
                                     DecodeError::EnumValueError {
-                                        obj: #decl_id,
-                                        field: #name,
+                                        obj: #packet_name,
+                                        field: #id,
                                         value: unknown_val as u64,
                                         type_: #type_name,
                                     }
-                                }))
-                            .transpose()?;
+                                })?
+                            },
+                        )
                     }
-                }
-                ast::DeclDesc::Struct { .. } => {
-                    let id = id.to_ident();
-                    let type_id = type_id.to_ident();
-                    let span = self.span;
-                    quote! {
-                        let #id = (#cond_id == #cond_value)
-                            .then(|| #type_id::decode_mut(&mut #span))
-                            .transpose()?;
+                    ast::DeclDesc::Struct { .. } if self.scope.is_indirect(field) => {
+                        let type_id = type_id.to_ident();
+                        (id, quote! {}, quote! { Box::new(#type_id::decode_mut(&mut #span)?) })
                     }
+                    ast::DeclDesc::Struct { .. } => {
+                        let type_id = type_id.to_ident();
+                        (id, quote! {}, quote! { #type_id::decode_mut(&mut #span)? })
+                    }
+                    _ => unreachable!(),
                 }
-                _ => unreachable!(),
-            },
+            }
             _ => unreachable!(),
-        })
+        };
+
+        let id = id.to_ident();
+        self.tokens.extend(quote! {
+            let #id = if #present {
+                #check_size
+                Some(#value)
+            } else {
+                None
+            };
+        });
     }
 
     fn add_bit_field(&mut self, field: &'a ast::Field) {
@@ -166,7 +263,11 @@ impl<'a> FieldParser<'a> {
         }
 
         let single_value = self.chunk.len() == 1; // && self.chunk[0].offset == 0;
+        let bit_order = self.scope.file.bit_order(self.decl);
+        let chunk_width = self.shift;
         for BitField { shift, field } in self.chunk.drain(..) {
+            let width = self.schema.field_size(field.key).static_().unwrap();
+            let shift = bit_order.shift(chunk_width, shift, width);
             let mut v = if single_value {
                 // Single value: read directly.
                 quote! { #get }
@@ -180,12 +281,11 @@ impl<'a> FieldParser<'a> {
                 v = quote! { (#v >> #shift) }
             }
 
-            let width = self.schema.field_size(field.key).static_().unwrap();
             let value_type = types::Integer::new(width);
             if !single_value && width < value_type.width {
                 // Mask value if we grabbed more than `width` and if
                 // `as #value_type` doesn't already do the masking.
-                let mask = mask_bits(width, "u64");
+                let mask = mask_bits(width, &format!("u{}", chunk_type.width));
                 v = quote! { (#v & #mask) };
             }
 
@@ -215,7 +315,7 @@ impl<'a> FieldParser<'a> {
                     }
                 }
                 ast::FieldDesc::FixedScalar { value, .. } => {
-                    let value = proc_macro2::Literal::usize_unsuffixed(*value);
+                    let value = proc_macro2::Literal::u128_unsuffixed(*value);
                     quote! {
                         let fixed_value = #v;
                         if fixed_value != #value {
@@ -226,7 +326,7 @@ impl<'a> FieldParser<'a> {
                         }
                     }
                 }
-                ast::FieldDesc::Typedef { id, type_id } => {
+                ast::FieldDesc::Typedef { id, type_id, .. } => {
                     let field_name = id;
                     let type_name = type_id;
                     let packet_name = &self.packet_name;
@@ -255,10 +355,51 @@ impl<'a> FieldParser<'a> {
                         quote! {}
                     }
                 }
-                ast::FieldDesc::Size { field_id, .. } => {
-                    let id = size_field_ident(field_id);
-                    quote! {
-                        let #id = #v as usize;
+                ast::FieldDesc::Size { field_id, unit, .. } => {
+                    let trailing = self.decl.is_trailing_size(field);
+                    let id = if trailing {
+                        format_ident!("trailing_{}", size_field_ident(field_id))
+                    } else {
+                        size_field_ident(field_id)
+                    };
+                    let packet_name = &self.packet_name;
+                    let value = match unit {
+                        ast::SizeUnit::Octets => quote! {
+                            let #id = #v as usize;
+                        },
+                        ast::SizeUnit::Bits => quote! {
+                            let #id = #v as usize;
+                            if #id % 8 != 0 {
+                                return Err(DecodeError::SizeUnitError {
+                                    obj: #packet_name,
+                                    field: #field_id,
+                                    size: #id,
+                                });
+                            }
+                            let #id = #id / 8;
+                        },
+                        ast::SizeUnit::Words(width) => {
+                            let octets = proc_macro2::Literal::usize_unsuffixed(width / 8);
+                            quote! {
+                                let #id = (#v as usize) * #octets;
+                            }
+                        }
+                    };
+                    if trailing {
+                        let expected = size_field_ident(field_id);
+                        quote! {
+                            #value
+                            if #id != #expected {
+                                return Err(DecodeError::TrailingSizeError {
+                                    obj: #packet_name,
+                                    field: #field_id,
+                                    expected: #expected,
+                                    actual: #id,
+                                });
+                            }
+                        }
+                    } else {
+                        value
                     }
                 }
                 ast::FieldDesc::ElementSize { field_id, .. } => {
@@ -267,6 +408,22 @@ impl<'a> FieldParser<'a> {
                         let #id = #v as usize;
                     }
                 }
+                ast::FieldDesc::Count { field_id, .. } if self.decl.is_trailing_size(field) => {
+                    let id = format_ident!("trailing_{field_id}_count");
+                    let array = field_id.to_ident();
+                    let packet_name = &self.packet_name;
+                    quote! {
+                        let #id = #v as usize;
+                        if #id != #array.len() {
+                            return Err(DecodeError::TrailingSizeError {
+                                obj: #packet_name,
+                                field: #field_id,
+                                expected: #array.len(),
+                                actual: #id,
+                            });
+                        }
+                    }
+                }
                 ast::FieldDesc::Count { field_id, .. } => {
                     let id = format_ident!("{field_id}_count");
                     quote! {
@@ -305,9 +462,18 @@ impl<'a> FieldParser<'a> {
     }
 
     fn payload_field_offset_from_end(&self) -> Option<usize> {
+        self.field_offset_from_end(|f| {
+            matches!(f.desc, ast::FieldDesc::Body | ast::FieldDesc::Payload { .. })
+        })
+    }
+
+    /// Return the static size in bits of the fields placed after the
+    /// first field matching `predicate`, or None if any of the
+    /// following fields does not have a static size.
+    fn field_offset_from_end(&self, predicate: impl Fn(&ast::Field) -> bool) -> Option<usize> {
         let decl = self.scope.typedef[self.packet_name];
         let mut fields = decl.fields();
-        fields.find(|f| matches!(f.desc, ast::FieldDesc::Body | ast::FieldDesc::Payload { .. }))?;
+        fields.find(|f| predicate(f))?;
 
         let mut offset = 0;
         for field in fields {
@@ -323,6 +489,42 @@ impl<'a> FieldParser<'a> {
         Some(offset)
     }
 
+    /// Check that the element count `count` of the field `id`
+    /// does not exceed the declared bound.
+    fn check_bound(&mut self, id: &str, count: &proc_macro2::TokenStream, bound: Option<usize>) {
+        let Some(bound) = bound else { return };
+        let packet_name = self.packet_name;
+        let bound = proc_macro2::Literal::usize_unsuffixed(bound);
+        self.tokens.extend(quote! {
+            if #count > #bound {
+                return Err(DecodeError::BoundError {
+                    obj: #packet_name,
+                    field: #id,
+                    count: #count,
+                    bound: #bound,
+                });
+            }
+        });
+    }
+
+    /// Check that the payload size `size` does not exceed the
+    /// declared bound.
+    fn check_size_bound(&mut self, size: &proc_macro2::TokenStream, bound: Option<usize>) {
+        let Some(bound) = bound else { return };
+        let packet_name = self.packet_name;
+        let bound = proc_macro2::Literal::usize_unsuffixed(bound);
+        self.tokens.extend(quote! {
+            if #size > #bound {
+                return Err(DecodeError::SizeBoundError {
+                    obj: #packet_name,
+                    field: "_payload_",
+                    size: #size,
+                    bound: #bound,
+                });
+            }
+        });
+    }
+
     fn check_size(&mut self, span: &proc_macro2::Ident, wanted: &proc_macro2::TokenStream) {
         let packet_name = &self.packet_name;
         self.tokens.extend(quote! {
@@ -336,6 +538,7 @@ impl<'a> FieldParser<'a> {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add_array_field(
         &mut self,
         id: &str,
@@ -347,6 +550,9 @@ impl<'a> FieldParser<'a> {
         // `size`: the size of the array in number of elements (if
         // known). If None, the array is a Vec with a dynamic size.
         size: Option<usize>,
+        // `max_count`: the upper bound on the number of elements of
+        // dynamic arrays (if Some).
+        max_count: Option<usize>,
         padding_size: Option<usize>,
         decl: Option<&ast::Decl>,
     ) {
@@ -377,6 +583,20 @@ impl<'a> FieldParser<'a> {
         }
         let array_shape = if let Some(count) = size {
             ArrayShape::Static(count)
+        } else if self.decl.array_size(id).is_some_and(|size| self.decl.is_trailing_size(size)) {
+            // The size or count field is placed after the array. The
+            // array spans the octets which are not reserved for the
+            // following fields, the size or count field is checked
+            // after it is decoded.
+            let span = self.span;
+            let size_field = size_field_ident(id);
+            let offset_from_end = self.field_offset_from_end(|f| f.id() == Some(id)).unwrap();
+            let offset_from_end = proc_macro2::Literal::usize_unsuffixed(offset_from_end / 8);
+            self.check_size(span, &quote!(#offset_from_end));
+            self.tokens.extend(quote! {
+                let #size_field = #span.len() - #offset_from_end;
+            });
+            ArrayShape::SizeField(size_field)
         } else if let Some(count_field) = self.find_count_field(id) {
             ArrayShape::CountField(count_field)
         } else if let Some(size_field) = self.find_size_field(id) {
@@ -422,6 +642,7 @@ impl<'a> FieldParser<'a> {
                         #id.push(#parse_element?);
                     }
                 });
+                self.check_bound(field_name, &quote!(#id.len()), max_count);
             }
             (ElementWidth::Unknown, ArrayShape::Static(count)) => {
                 // The element width is not known, but the array
@@ -445,6 +666,7 @@ impl<'a> FieldParser<'a> {
                 // The element width is not known, but the array
                 // element count is known by the count field. Parse
                 // elements item by item as a vector.
+                self.check_bound(field_name, &quote!(#count_field), max_count);
                 self.tokens.extend(quote! {
                     let #id = (0..#count_field)
                         .map(|_| #parse_element)
@@ -460,6 +682,7 @@ impl<'a> FieldParser<'a> {
                         #id.push(#parse_element?);
                     }
                 });
+                self.check_bound(field_name, &quote!(#id.len()), max_count);
             }
             (ElementWidth::Static(element_width), ArrayShape::Static(count)) => {
                 // The element width is known, and the array element
@@ -489,6 +712,7 @@ impl<'a> FieldParser<'a> {
             (ElementWidth::Static(element_width), ArrayShape::CountField(count_field)) => {
                 // The element width is known, and the array element
                 // count is known dynamically by the count field.
+                self.check_bound(field_name, &quote!(#count_field), max_count);
                 self.check_size(&span, &quote!(#count_field * #element_width));
                 self.tokens.extend(quote! {
                     let #id = (0..#count_field)
@@ -524,6 +748,7 @@ impl<'a> FieldParser<'a> {
                     array_size
                 };
 
+                self.check_bound(field_name, &array_count, max_count);
                 self.tokens.extend(quote! {
                     let mut #id = Vec::with_capacity(#array_count);
                     for _ in 0..#array_count {
@@ -571,6 +796,7 @@ impl<'a> FieldParser<'a> {
             (ElementWidth::Dynamic(element_size_field), ArrayShape::CountField(count_field)) => {
                 // The element width is known, and the array element
                 // count is known dynamically by the count field.
+                self.check_bound(field_name, &quote!(#count_field), max_count);
                 self.check_size(&span, &quote!(#count_field * #element_size_field));
 
                 let parse_element =
@@ -612,6 +838,7 @@ impl<'a> FieldParser<'a> {
                         });
                     }
                 });
+                self.check_bound(field_name, &quote!(#array_size / #element_size_field), max_count);
 
                 let parse_element =
                     self.parse_array_element(&format_ident!("chunk"), width, type_id, decl);
@@ -645,9 +872,23 @@ impl<'a> FieldParser<'a> {
 
         let decl = self.scope.typedef[type_id];
         let span = self.span;
+        let size_field = self.find_size_field(id);
         let id = id.to_ident();
         let type_id = type_id.to_ident();
 
+        if let Some(size_field) = size_field {
+            // The struct size is known by size field. The struct
+            // is parsed from the designated span, and must consume it
+            // entirely.
+            self.check_size(span, &quote!(#size_field));
+            self.tokens.extend(quote! {
+                let (head, tail) = #span.split_at(#size_field);
+                #span = tail;
+                let #id = #type_id::decode_full(head)?;
+            });
+            return;
+        }
+
         self.tokens.extend(match self.schema.total_size(decl.key) {
             analyzer::Size::Unknown | analyzer::Size::Dynamic => quote! {
                 let (#id, mut #span) = #type_id::decode(#span)?;
@@ -656,7 +897,7 @@ impl<'a> FieldParser<'a> {
                 assert_eq!(width % 8, 0, "Typedef field type size is not a multiple of 8");
                 match &decl.desc {
                     ast::DeclDesc::Checksum { .. } => todo!(),
-                    ast::DeclDesc::CustomField { .. } if [8, 16, 32, 64].contains(&width) => {
+                    ast::DeclDesc::CustomField { .. } if [8, 16, 32, 64, 128].contains(&width) => {
                         let get_uint = types::get_uint(self.endianness, width, span);
                         quote! {
                             let #id = #get_uint.into();
@@ -682,9 +923,12 @@ impl<'a> FieldParser<'a> {
     }
 
     /// Parse body and payload fields.
-    fn add_payload_field(&mut self, size_modifier: Option<&str>) {
+    fn add_payload_field(&mut self, size_modifier: Option<&str>, max_size: Option<usize>) {
         let span = self.span;
         let payload_size_field = self.decl.payload_size();
+        let trailing_size_field =
+            payload_size_field.filter(|field| self.decl.is_trailing_size(field));
+        let payload_size_field = payload_size_field.filter(|_| trailing_size_field.is_none());
         let offset_from_end = self.payload_field_offset_from_end();
 
         if self.shift != 0 {
@@ -716,6 +960,7 @@ impl<'a> FieldParser<'a> {
                 });
             }
             self.check_size(self.span, &quote!(#size_field ));
+            self.check_size_bound(&quote!(#size_field), max_size);
             self.tokens.extend(quote! {
                 let payload = #span[..#size_field].to_vec();
                 #span.advance(#size_field);
@@ -723,6 +968,7 @@ impl<'a> FieldParser<'a> {
         } else if offset_from_end == Some(0) {
             // The payload or body is the last field of a packet,
             // consume the remaining span.
+            self.check_size_bound(&quote!(#span.len()), max_size);
             self.tokens.extend(quote! {
                 let payload = #span.to_vec();
                 #span.advance(payload.len());
@@ -738,12 +984,27 @@ impl<'a> FieldParser<'a> {
             );
             let offset_from_end = proc_macro2::Literal::usize_unsuffixed(offset_from_end / 8);
             self.check_size(self.span, &quote!(#offset_from_end));
+            self.check_size_bound(&quote!((#span.len() - #offset_from_end)), max_size);
             self.tokens.extend(quote! {
                 let payload = #span[..#span.len() - #offset_from_end].to_vec();
                 #span.advance(payload.len());
             });
         }
 
+        if let Some(ast::FieldDesc::Size { field_id, .. }) = &trailing_size_field.map(|f| &f.desc) {
+            // The size field is placed after the payload, save the
+            // expected size field value to check it after it is
+            // decoded.
+            let size_field = size_field_ident(field_id);
+            let size_modifier =
+                proc_macro2::Literal::usize_unsuffixed(size_modifier.map_or(0, |size_modifier| {
+                    size_modifier.parse::<usize>().expect("failed to parse the size modifier")
+                }));
+            self.tokens.extend(quote! {
+                let #size_field = payload.len() + #size_modifier;
+            });
+        }
+
         let decl = self.scope.typedef[self.packet_name];
         if let ast::DeclDesc::Struct { .. } = &decl.desc {
             self.tokens.extend(quote! {
@@ -767,7 +1028,10 @@ impl<'a> FieldParser<'a> {
             };
         }
 
-        if let Some(ast::DeclDesc::Enum { id, width, .. }) = decl.map(|decl| &decl.desc) {
+        if let Some(
+            ast::DeclDesc::Enum { id, width, .. } | ast::DeclDesc::Flags { id, width, .. },
+        ) = decl.map(|decl| &decl.desc)
+        {
             let get_uint = types::get_uint(self.endianness, *width, span);
             let type_id = id.to_ident();
             let packet_name = &self.packet_name;
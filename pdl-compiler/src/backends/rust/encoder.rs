@@ -25,6 +25,18 @@ fn range_check(
     field_name: &str,
 ) -> proc_macro2::TokenStream {
     let max_value = mask_bits(width, "u64");
+    if width > 64 {
+        return quote! {
+            if #value > #max_value {
+                return Err(EncodeError::InvalidWideScalarValue {
+                    packet: #packet_name,
+                    field: #field_name,
+                    value: #value as u128,
+                    maximum_value: #max_value as u128,
+                })
+            }
+        };
+    }
     quote! {
         if #value > #max_value {
             return Err(EncodeError::InvalidScalarValue {
@@ -72,16 +84,23 @@ impl quote::ToTokens for RuntimeSize {
 struct BitField {
     value: proc_macro2::TokenStream,
     field_type: types::Integer,
-    shift: usize,
+    shift: usize, // Offset of the field from the start of the chunk.
+    width: usize,
 }
 
 struct Encoder {
-    endianness: ast::EndiannessValue,
+    decl_endianness: ast::EndiannessValue,
+    endianness: ast::EndiannessValue, // Endianness of the current field.
+    bit_order: ast::BitOrder,
     buf: proc_macro2::Ident,
     packet_name: String,
     packet_size: RuntimeSize,
     payload_size: RuntimeSize,
     tokens: proc_macro2::TokenStream,
+    /// Bound checks on array counts and payload sizes. They are emitted
+    /// ahead of the encoded fields so that nothing is written to the
+    /// buffer when a bound is exceeded.
+    bound_checks: proc_macro2::TokenStream,
     bit_shift: usize,
     bit_fields: Vec<BitField>,
 }
@@ -89,6 +108,7 @@ struct Encoder {
 impl Encoder {
     pub fn new(
         endianness: ast::EndiannessValue,
+        bit_order: ast::BitOrder,
         packet_name: &str,
         buf: proc_macro2::Ident,
         payload_size: RuntimeSize,
@@ -96,10 +116,13 @@ impl Encoder {
         Encoder {
             buf,
             packet_name: packet_name.to_owned(),
+            decl_endianness: endianness,
             endianness,
+            bit_order,
             packet_size: RuntimeSize { constant: 0, variable: vec![] },
             payload_size,
             tokens: quote! {},
+            bound_checks: quote! {},
             bit_shift: 0,
             bit_fields: vec![],
         }
@@ -152,8 +175,12 @@ impl Encoder {
     ) {
         assert_eq!(self.bit_shift, 0, "Optional field does not start on an octet boundary");
 
+        if let Some(extension) = field.extension {
+            self.check_extension_field(scope, field, extension);
+        }
+
         self.tokens.extend(match &field.desc {
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let field_name = id;
                 let id = id.to_ident();
                 let backing_type = types::Integer::new(*width);
@@ -167,8 +194,8 @@ impl Encoder {
                     }
                 }
             }
-            ast::FieldDesc::Typedef { id, type_id } => match &scope.typedef[type_id].desc {
-                ast::DeclDesc::Enum { width, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, .. } => match &scope.typedef[type_id].desc {
+                ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                     let id = id.to_ident();
                     let backing_type = types::Integer::new(*width);
                     let put_uint = types::put_uint(
@@ -200,22 +227,32 @@ impl Encoder {
         });
 
         self.packet_size.variable.push(match &field.desc {
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let id = id.to_ident();
                 let size = width / 8;
                 quote! { if self.#id.is_some() { #size } else { 0 } }
             }
-            ast::FieldDesc::Typedef { id, type_id } => match &scope.typedef[type_id].desc {
-                ast::DeclDesc::Enum { width, .. } => {
+            ast::FieldDesc::Typedef { id, type_id, .. } => match &scope.typedef[type_id].desc {
+                ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. } => {
                     let id = id.to_ident();
                     let size = width / 8;
                     quote! { if self.#id.is_some() { #size } else { 0 } }
                 }
+                ast::DeclDesc::Struct { .. } if scope.is_indirect(field) => {
+                    let id = id.to_ident();
+                    let type_id = type_id.to_ident();
+                    quote! {
+                        self.#id
+                            .as_deref()
+                            .map(#type_id::encoded_len)
+                            .unwrap_or(0)
+                    }
+                }
                 ast::DeclDesc::Struct { .. } => {
                     let id = id.to_ident();
                     let type_id = type_id.to_ident();
                     quote! {
-                        &self.#id
+                        self.#id
                             .as_ref()
                             .map(#type_id::encoded_len)
                             .unwrap_or(0)
@@ -227,6 +264,44 @@ impl Encoder {
         })
     }
 
+    /// Generate the check that the fields of an extension block are
+    /// either all present or all absent, and that the extension blocks
+    /// declared before are present.
+    fn check_extension_field(
+        &mut self,
+        scope: &analyzer::Scope<'_>,
+        field: &ast::Field,
+        extension: usize,
+    ) {
+        let decl = scope.typedef[&self.packet_name];
+        let first_field =
+            |extension| decl.fields().find(|field| field.extension == Some(extension)).unwrap();
+        let packet_name = &self.packet_name;
+        let field_name = field.id().unwrap();
+        let id = field_name.to_ident();
+
+        let inconsistent = match first_field(extension) {
+            first if first.key != field.key => {
+                let first_id = first.id().unwrap().to_ident();
+                quote! { self.#id.is_some() != self.#first_id.is_some() }
+            }
+            _ if extension > 0 => {
+                let previous_id = first_field(extension - 1).id().unwrap().to_ident();
+                quote! { self.#id.is_some() && self.#previous_id.is_none() }
+            }
+            _ => return,
+        };
+
+        self.tokens.extend(quote! {
+            if #inconsistent {
+                return Err(EncodeError::InconsistentExtensionField {
+                    packet: #packet_name,
+                    field: #field_name,
+                });
+            }
+        });
+    }
+
     fn encode_bit_field(
         &mut self,
         scope: &analyzer::Scope<'_>,
@@ -285,9 +360,10 @@ impl Encoder {
                     },
                     field_type: types::Integer::new(1),
                     shift,
+                    width,
                 });
             }
-            ast::FieldDesc::Scalar { id, width } => {
+            ast::FieldDesc::Scalar { id, width, .. } => {
                 let field_name = id;
                 let field_id = id.to_ident();
                 let field_type = types::Integer::new(*width);
@@ -303,6 +379,7 @@ impl Encoder {
                     value: quote! { self.#field_id() },
                     field_type,
                     shift,
+                    width: *width,
                 });
             }
             ast::FieldDesc::FixedEnum { enum_id, tag_id, .. } => {
@@ -313,12 +390,13 @@ impl Encoder {
                     value: quote!(#field_type::from(#enum_id::#tag_id)),
                     field_type,
                     shift,
+                    width,
                 });
             }
             ast::FieldDesc::FixedScalar { value, .. } => {
                 let field_type = types::Integer::new(width);
-                let value = proc_macro2::Literal::usize_unsuffixed(*value);
-                self.bit_fields.push(BitField { value: quote!(#value), field_type, shift });
+                let value = proc_macro2::Literal::u128_unsuffixed(*value);
+                self.bit_fields.push(BitField { value: quote!(#value), field_type, shift, width });
             }
             ast::FieldDesc::Typedef { id, .. } => {
                 let id = id.to_ident();
@@ -327,12 +405,13 @@ impl Encoder {
                     value: quote!(#field_type::from(self.#id())),
                     field_type,
                     shift,
+                    width,
                 });
             }
             ast::FieldDesc::Reserved { .. } => {
                 // Nothing to do here.
             }
-            ast::FieldDesc::Size { field_id, width, .. } => {
+            ast::FieldDesc::Size { field_id, width, unit } => {
                 let packet_name = &self.packet_name;
                 let max_value = mask_bits(*width, "usize");
 
@@ -353,7 +432,7 @@ impl Encoder {
                 let value_field_decl = scope.get_type_declaration(value_field);
                 let array_size = match (&value_field.desc, value_field_decl.map(|decl| &decl.desc))
                 {
-                    (ast::FieldDesc::Payload { size_modifier: Some(size_modifier) }, _) => {
+                    (ast::FieldDesc::Payload { size_modifier: Some(size_modifier), .. }, _) => {
                         let size_modifier = proc_macro2::Literal::usize_unsuffixed(
                             size_modifier
                                 .parse::<usize>()
@@ -367,7 +446,12 @@ impl Encoder {
                         quote! { #payload_size }
                     }
                     (ast::FieldDesc::Array { width: Some(width), .. }, _)
-                    | (ast::FieldDesc::Array { .. }, Some(ast::DeclDesc::Enum { width, .. })) => {
+                    | (
+                        ast::FieldDesc::Array { .. },
+                        Some(
+                            ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. },
+                        ),
+                    ) => {
                         let size = width / 8;
                         if size == 1 {
                             quote! { self.#field_name.len() }
@@ -386,9 +470,32 @@ impl Encoder {
                         });
                         quote! { #field_size_name }
                     }
+                    (ast::FieldDesc::Typedef { .. }, _) => {
+                        quote! { self.#field_name.encoded_len() }
+                    }
                     _ => panic!("Unexpected size field: {field:?}"),
                 };
 
+                // Convert the size from octets to the size unit.
+                let array_size = match unit {
+                    ast::SizeUnit::Octets => array_size,
+                    ast::SizeUnit::Bits => quote! { (#array_size * 8) },
+                    ast::SizeUnit::Words(width) => {
+                        let octets = proc_macro2::Literal::usize_unsuffixed(width / 8);
+                        self.tokens.extend(quote! {
+                            if #array_size % #octets != 0 {
+                                return Err(EncodeError::UnalignedSize {
+                                    packet: #packet_name,
+                                    field: #field_id,
+                                    size: #array_size,
+                                    unit: #octets,
+                                })
+                            }
+                        });
+                        quote! { (#array_size / #octets) }
+                    }
+                };
+
                 // TODO: this check is generated with an allow() directive since the size might
                 // be constant. It should be removed when always true.
                 self.tokens.extend(quote! {
@@ -407,6 +514,7 @@ impl Encoder {
                     value: quote!((#array_size) as #field_type),
                     field_type,
                     shift,
+                    width: *width,
                 });
             }
             ast::FieldDesc::ElementSize { field_id, width, .. } => {
@@ -445,6 +553,7 @@ impl Encoder {
                     value: quote!(#field_element_size_name),
                     field_type,
                     shift,
+                    width: *width,
                 });
             }
             ast::FieldDesc::Count { field_id, width, .. } => {
@@ -468,6 +577,7 @@ impl Encoder {
                     value: quote!(self.#field_name.len() as #field_type),
                     field_type,
                     shift,
+                    width: *width,
                 });
             }
             _ => todo!("{field:?}"),
@@ -482,10 +592,13 @@ impl Encoder {
     fn pack_bit_fields(&mut self) {
         assert_eq!(self.bit_shift % 8, 0);
         let chunk_type = types::Integer::new(self.bit_shift);
+        let chunk_width = self.bit_shift;
+        let bit_order = self.bit_order;
         let values = self
             .bit_fields
             .drain(..)
-            .map(|BitField { mut value, field_type, shift }| {
+            .map(|BitField { mut value, field_type, shift, width }| {
+                let shift = bit_order.shift(chunk_width, shift, width);
                 if field_type.width != chunk_type.width {
                     // We will be combining values with `|`, so we
                     // need to cast them first.
@@ -548,7 +661,10 @@ impl Encoder {
                 types::put_uint(self.endianness, &value, width, &self.buf)
             }
             None => {
-                if let Some(ast::DeclDesc::Enum { width, .. }) = decl.map(|decl| &decl.desc) {
+                if let Some(
+                    ast::DeclDesc::Enum { width, .. } | ast::DeclDesc::Flags { width, .. },
+                ) = decl.map(|decl| &decl.desc)
+                {
                     let element_type = types::Integer::new(*width);
                     types::put_uint(
                         self.endianness,
@@ -628,21 +744,54 @@ impl Encoder {
         payload: &proc_macro2::TokenStream,
         field: &ast::Field,
     ) {
+        self.endianness = field.endianness.unwrap_or(self.decl_endianness);
         match &field.desc {
-            _ if field.cond.is_some() => self.encode_optional_field(scope, schema, field),
+            _ if field.is_optional() => self.encode_optional_field(scope, schema, field),
             _ if scope.is_bitfield(field) => self.encode_bit_field(scope, schema, field),
-            ast::FieldDesc::Array { id, width, .. } => self.encode_array_field(
-                scope,
-                schema,
-                id,
-                *width,
-                schema.padded_size(field.key),
-                scope.get_type_declaration(field),
-            ),
-            ast::FieldDesc::Typedef { id, type_id } => {
+            ast::FieldDesc::Array { id, width, max_count, .. } => {
+                if let Some(max_count) = max_count {
+                    let packet_name = &self.packet_name;
+                    let field_name = id.to_ident();
+                    let max_count = proc_macro2::Literal::usize_unsuffixed(*max_count);
+                    self.bound_checks.extend(quote! {
+                        if self.#field_name.len() > #max_count {
+                            return Err(EncodeError::CountOverflow {
+                                packet: #packet_name,
+                                field: #id,
+                                count: self.#field_name.len(),
+                                maximum_count: #max_count,
+                            })
+                        }
+                    });
+                }
+                self.encode_array_field(
+                    scope,
+                    schema,
+                    id,
+                    *width,
+                    schema.padded_size(field.key),
+                    scope.get_type_declaration(field),
+                )
+            }
+            ast::FieldDesc::Typedef { id, type_id, .. } => {
                 self.encode_typedef_field(scope, schema, id, type_id)
             }
             ast::FieldDesc::Payload { .. } | ast::FieldDesc::Body => {
+                if let ast::FieldDesc::Payload { max_size: Some(max_size), .. } = &field.desc {
+                    let packet_name = &self.packet_name;
+                    let payload_size = &self.payload_size;
+                    let max_size = proc_macro2::Literal::usize_unsuffixed(*max_size);
+                    self.bound_checks.extend(quote! {
+                        if #payload_size > #max_size {
+                            return Err(EncodeError::SizeOverflow {
+                                packet: #packet_name,
+                                field: "_payload_",
+                                size: #payload_size,
+                                maximum_size: #max_size,
+                            })
+                        }
+                    });
+                }
                 self.tokens.extend(payload.clone());
                 self.packet_size += &self.payload_size
             }
@@ -656,31 +805,40 @@ impl Encoder {
 fn encode_with_parents(
     scope: &analyzer::Scope<'_>,
     schema: &analyzer::Schema,
-    endianness: ast::EndiannessValue,
     buf: proc_macro2::Ident,
     decl: &ast::Decl,
     payload_size: RuntimeSize,
     payload: proc_macro2::TokenStream,
+    bound_checks: proc_macro2::TokenStream,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let packet_name = decl.id().unwrap();
-    let mut encoder = Encoder::new(endianness, packet_name, buf.clone(), payload_size);
+    let mut encoder = Encoder::new(
+        scope.file.decl_endianness(decl),
+        scope.file.bit_order(decl),
+        packet_name,
+        buf.clone(),
+        payload_size,
+    );
     for field in decl.fields() {
         encoder.encode_field(scope, schema, &payload, field);
     }
 
+    let encoder_bound_checks = encoder.bound_checks;
+    let bound_checks = quote! { #encoder_bound_checks #bound_checks };
     match scope.get_parent(decl) {
         Some(parent_decl) => encode_with_parents(
             scope,
             schema,
-            endianness,
             buf,
             parent_decl,
             encoder.packet_size,
             encoder.tokens,
+            bound_checks,
         ),
         None => {
             let packet_size = encoder.packet_size;
-            (encoder.tokens, quote! { #packet_size })
+            let tokens = encoder.tokens;
+            (quote! { #bound_checks #tokens }, quote! { #packet_size })
         }
     }
 }
@@ -688,32 +846,35 @@ fn encode_with_parents(
 pub fn encode(
     scope: &analyzer::Scope<'_>,
     schema: &analyzer::Schema,
-    endianness: ast::EndiannessValue,
     buf: proc_macro2::Ident,
     decl: &ast::Decl,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     encode_with_parents(
         scope,
         schema,
-        endianness,
         buf.clone(),
         decl,
         RuntimeSize::payload_size(),
         quote! { #buf.put_slice(&self.payload); },
+        quote! {},
     )
 }
 
 pub fn encode_partial(
     scope: &analyzer::Scope<'_>,
     schema: &analyzer::Schema,
-    endianness: ast::EndiannessValue,
     buf: proc_macro2::Ident,
     decl: &ast::Decl,
 ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let parent_decl = scope.get_parent(decl).unwrap();
 
-    let mut encoder =
-        Encoder::new(endianness, decl.id().unwrap(), buf.clone(), RuntimeSize::payload_size());
+    let mut encoder = Encoder::new(
+        scope.file.decl_endianness(decl),
+        scope.file.bit_order(decl),
+        decl.id().unwrap(),
+        buf.clone(),
+        RuntimeSize::payload_size(),
+    );
 
     for field in decl.fields() {
         encoder.encode_field(scope, schema, &quote! { #buf.put_slice(&self.payload); }, field);
@@ -722,12 +883,14 @@ pub fn encode_partial(
     let (encode_parents, encoded_len) = encode_with_parents(
         scope,
         schema,
-        endianness,
         buf,
         parent_decl,
         encoder.packet_size,
         quote! { self.encode_partial(buf)?; },
+        encoder.bound_checks.clone(),
     );
 
-    (encoder.tokens, encode_parents, encoded_len)
+    let bound_checks = encoder.bound_checks;
+    let tokens = encoder.tokens;
+    (quote! { #bound_checks #tokens }, encode_parents, encoded_len)
 }
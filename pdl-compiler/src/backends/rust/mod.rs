@@ -28,6 +28,7 @@ mod types;
 
 use decoder::FieldParser;
 pub use heck::ToUpperCamelCase;
+use heck::{ToShoutySnakeCase, ToSnakeCase};
 
 pub trait ToIdent {
     /// Generate a sanitized rust identifier.
@@ -55,11 +56,16 @@ impl ToIdent for &'_ str {
 /// Literal integers in Rust default to the `i32` type. For this
 /// reason, if `n` is larger than 31, a suffix is added to the
 /// `LitInt` returned. This should either be `u64` or `usize`
-/// depending on where the result is used.
+/// depending on where the result is used. Masks larger than 64 bits
+/// always use the `u128` suffix.
 pub fn mask_bits(n: usize, suffix: &str) -> syn::LitInt {
-    let suffix = if n > 31 { format!("_{suffix}") } else { String::new() };
+    let suffix = match n {
+        0..=31 => String::new(),
+        32..=64 => format!("_{suffix}"),
+        _ => "_u128".to_owned(),
+    };
     // Format the hex digits as 0x1111_2222_3333_usize.
-    let hex_digits = format!("{:x}", (1u64 << n) - 1)
+    let hex_digits = format!("{:x}", u128::MAX.checked_shr(128 - n as u32).unwrap_or(0))
         .as_bytes()
         .rchunks(4)
         .rev()
@@ -115,7 +121,7 @@ fn packet_constant_fields<'a>(
 
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Debug, Hash)]
 enum ConstraintValue {
-    Scalar(usize),
+    Scalar(u128),
     Tag(String, String),
 }
 
@@ -123,7 +129,7 @@ impl quote::ToTokens for ConstraintValue {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.extend(match self {
             ConstraintValue::Scalar(s) => {
-                let s = proc_macro2::Literal::usize_unsuffixed(*s);
+                let s = proc_macro2::Literal::u128_unsuffixed(*s);
                 quote!(#s)
             }
             ConstraintValue::Tag(e, t) => {
@@ -145,7 +151,7 @@ fn constraint_value_ast(
             let type_id = fields
                 .iter()
                 .filter_map(|f| match &f.desc {
-                    ast::FieldDesc::Typedef { id, type_id } if id == &constraint.id => {
+                    ast::FieldDesc::Typedef { id, type_id, .. } if id == &constraint.id => {
                         Some(type_id)
                     }
                     _ => None,
@@ -164,7 +170,7 @@ fn constraint_value(
 ) -> proc_macro2::TokenStream {
     match constraint {
         ast::Constraint { value: Some(value), .. } => {
-            let value = proc_macro2::Literal::usize_unsuffixed(*value);
+            let value = proc_macro2::Literal::u128_unsuffixed(*value);
             quote!(#value)
         }
         // TODO(mgeisler): include type_id in `ast::Constraint` and
@@ -174,7 +180,7 @@ fn constraint_value(
             let type_id = fields
                 .iter()
                 .filter_map(|f| match &f.desc {
-                    ast::FieldDesc::Typedef { id, type_id } if id == &constraint.id => {
+                    ast::FieldDesc::Typedef { id, type_id, .. } if id == &constraint.id => {
                         Some(type_id.to_ident())
                     }
                     _ => None,
@@ -197,7 +203,7 @@ fn constraint_value_str(fields: &[&'_ ast::Field], constraint: &ast::Constraint)
             let type_id = fields
                 .iter()
                 .filter_map(|f| match &f.desc {
-                    ast::FieldDesc::Typedef { id, type_id } if id == &constraint.id => {
+                    ast::FieldDesc::Typedef { id, type_id, .. } if id == &constraint.id => {
                         Some(type_id.to_ident())
                     }
                     _ => None,
@@ -211,12 +217,22 @@ fn constraint_value_str(fields: &[&'_ ast::Field], constraint: &ast::Constraint)
 }
 
 /// Return the default value for a field.
+/// The default value declared in the PDL source is used when present.
 /// Only concrete data fields are considered for inclusion,
 /// other kinds will yield an unreachable! error.
 fn data_field_default(field: &ast::Field) -> proc_macro2::TokenStream {
     match &field.desc {
-        _ if field.cond.is_some() => quote! { None },
+        _ if field.is_optional() => quote! { None },
+        ast::FieldDesc::Scalar { default: Some(value), .. } => {
+            let value = proc_macro2::Literal::u128_unsuffixed(*value);
+            quote! { #value }
+        }
         ast::FieldDesc::Scalar { .. } => quote! { 0 },
+        ast::FieldDesc::Typedef { type_id, default: Some(tag_id), .. } => {
+            let type_id = type_id.to_ident();
+            let tag_id = format_ident!("{}", tag_id.to_upper_camel_case());
+            quote! { #type_id::#tag_id }
+        }
         ast::FieldDesc::Typedef { .. } => quote! { Default::default() },
         ast::FieldDesc::Array { width: Some(_), size: Some(size), .. } => quote! { [0; #size] },
         ast::FieldDesc::Array { size: Some(_), .. } => {
@@ -231,7 +247,9 @@ fn implements_copy(scope: &analyzer::Scope<'_>, field: &ast::Field) -> bool {
     match &field.desc {
         ast::FieldDesc::Scalar { .. } => true,
         ast::FieldDesc::Typedef { type_id, .. } => match &scope.typedef[type_id].desc {
-            ast::DeclDesc::Enum { .. } | ast::DeclDesc::CustomField { .. } => true,
+            ast::DeclDesc::Enum { .. }
+            | ast::DeclDesc::Flags { .. }
+            | ast::DeclDesc::CustomField { .. } => true,
             ast::DeclDesc::Struct { .. } => false,
             desc => unreachable!("unexpected declaration: {desc:?}"),
         },
@@ -240,6 +258,340 @@ fn implements_copy(scope: &analyzer::Scope<'_>, field: &ast::Field) -> bool {
     }
 }
 
+/// Generate the nesting depth check for the decoder of recursive
+/// declarations. The guard is held until the end of the decoding.
+fn decode_depth_guard(scope: &analyzer::Scope<'_>, decl: &ast::Decl) -> proc_macro2::TokenStream {
+    if scope.is_recursive(decl) {
+        let name = decl.id().unwrap();
+        quote! { let _depth_guard = pdl_runtime::DecodeDepthGuard::enter(#name)?; }
+    } else {
+        quote! {}
+    }
+}
+
+/// Generate the scaled accessors for the scalar fields declared
+/// with a scale factor. The raw value is the scaled value divided by
+/// the scale, rounded to the nearest integer and saturated to the
+/// field width.
+fn scaled_field_accessors(fields: &[&ast::Field]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .filter_map(|f| match &f.desc {
+            ast::FieldDesc::Scalar { id, width, scale: Some(scale), unit, .. } => {
+                let field_id = id.to_ident();
+                let getter = format_ident!("{id}_scaled");
+                let setter = format_ident!("set_{id}_scaled");
+                let field_type = types::Integer::new(*width);
+                let scale = proc_macro2::Literal::f64_unsuffixed(scale.parse().unwrap());
+                let doc = match unit {
+                    Some(unit) => format!(" Value of `{id}` in {unit}."),
+                    None => format!(" Scaled value of `{id}`."),
+                };
+                // Float to integer casts saturate to the bounds of the
+                // integer type, only narrower fields need to be clamped.
+                let raw = if *width == field_type.width {
+                    quote! { (value / #scale).round() as #field_type }
+                } else {
+                    let max_value = mask_bits(*width, "u64");
+                    quote! { (value / #scale).round().clamp(0.0, #max_value as f64) as #field_type }
+                };
+                Some(if f.is_optional() {
+                    quote! {
+                        #[doc = #doc]
+                        pub fn #getter(&self) -> Option<f64> {
+                            self.#field_id.map(|raw| raw as f64 * #scale)
+                        }
+                        #[doc = #doc]
+                        pub fn #setter(&mut self, value: Option<f64>) {
+                            self.#field_id = value.map(|value| #raw);
+                        }
+                    }
+                } else {
+                    quote! {
+                        #[doc = #doc]
+                        pub fn #getter(&self) -> f64 {
+                            self.#field_id as f64 * #scale
+                        }
+                        #[doc = #doc]
+                        pub fn #setter(&mut self, value: f64) {
+                            self.#field_id = #raw;
+                        }
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate the associated constants exposing the size bounds
+/// of the bounded array and payload fields.
+fn size_bound_constants(decl: &ast::Decl, fields: &[&ast::Field]) -> Vec<proc_macro2::TokenStream> {
+    let array_bounds = fields.iter().filter_map(|f| match &f.desc {
+        ast::FieldDesc::Array { id, max_count: Some(max_count), .. } => {
+            let name = format_ident!("{}_MAX_COUNT", id.to_uppercase());
+            let max_count = proc_macro2::Literal::usize_unsuffixed(*max_count);
+            let doc = format!(" Maximum number of elements of `{id}`.");
+            Some(quote! {
+                #[doc = #doc]
+                pub const #name: usize = #max_count;
+            })
+        }
+        _ => None,
+    });
+    let payload_bound = decl.payload().and_then(|f| match &f.desc {
+        ast::FieldDesc::Payload { max_size: Some(max_size), .. } => {
+            let max_size = proc_macro2::Literal::usize_unsuffixed(*max_size);
+            Some(quote! {
+                /// Maximum size of the payload in octets.
+                pub const PAYLOAD_MAX_SIZE: usize = #max_size;
+            })
+        }
+        _ => None,
+    });
+    array_bounds.chain(payload_bound).collect()
+}
+
+/// Generate the condition checking the size assertion against the
+/// encoded size `size`, in octets.
+fn size_assertion_condition(
+    assertion: &ast::SizeAssertion,
+    size: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let value = proc_macro2::Literal::usize_unsuffixed(assertion.size);
+    match assertion.comparison {
+        ast::SizeComparison::Equal => quote!(#size == #value),
+        ast::SizeComparison::LessOrEqual => quote!(#size <= #value),
+        ast::SizeComparison::GreaterOrEqual => quote!(#size >= #value),
+    }
+}
+
+/// Generate the decoder check for the size assertion of `decl`.
+/// The check is omitted when the size is static, as it is verified
+/// by the analyzer.
+fn decode_size_assertion(
+    schema: &analyzer::Schema,
+    decl: &ast::Decl,
+    size: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Some(assertion) = decl.size_assertion else {
+        return quote!();
+    };
+    if schema.total_size(decl.key).static_().is_some() {
+        return quote!();
+    }
+    let name = decl.id().unwrap();
+    let condition = size_assertion_condition(&assertion, &quote!(size));
+    let assertion = assertion.to_string();
+    quote! {
+        let size = #size;
+        if !(#condition) {
+            return Err(DecodeError::SizeAssertionError {
+                obj: #name,
+                size,
+                assertion: #assertion,
+            });
+        }
+    }
+}
+
+/// Generate the encoder checks for the size assertions of `decl`
+/// and its parents, which all apply to the encoded packet.
+fn encode_size_assertions(
+    scope: &analyzer::Scope<'_>,
+    schema: &analyzer::Schema,
+    decl: &ast::Decl,
+) -> proc_macro2::TokenStream {
+    if schema.total_size(decl.key).static_().is_some() {
+        return quote!();
+    }
+    let name = decl.id().unwrap();
+    let checks = scope
+        .iter_parents_and_self(decl)
+        .filter_map(|decl| decl.size_assertion)
+        .map(|assertion| {
+            let condition = size_assertion_condition(&assertion, &quote!(size));
+            let assertion = assertion.to_string();
+            quote! {
+                if !(#condition) {
+                    return Err(EncodeError::SizeAssertion {
+                        packet: #name,
+                        size,
+                        assertion: #assertion,
+                    });
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    if checks.is_empty() {
+        return quote!();
+    }
+    quote! {
+        let size = self.encoded_len();
+        #( #checks )*
+    }
+}
+
+/// Generate the expression evaluating an invariant expression
+/// over the fields of the packet `packet`. Values are evaluated as
+/// `i128` to prevent overflows.
+fn invariant_expr(expr: &ast::Expr, packet: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match expr {
+        ast::Expr::Integer { value } => {
+            let value = proc_macro2::Literal::usize_unsuffixed(*value);
+            quote!(#value)
+        }
+        ast::Expr::Field { id } => {
+            let id = id.to_ident();
+            quote!((#packet.#id() as i128))
+        }
+        ast::Expr::Length { id } => {
+            let id = id.to_ident();
+            quote!((#packet.#id().len() as i128))
+        }
+        ast::Expr::Binary { op, lhs, rhs } => {
+            let lhs = invariant_expr(lhs, packet);
+            let rhs = invariant_expr(rhs, packet);
+            let op = match op {
+                ast::BinaryOp::Mul => quote!(*),
+                ast::BinaryOp::Add => quote!(+),
+                ast::BinaryOp::Sub => quote!(-),
+                ast::BinaryOp::Eq => quote!(==),
+                ast::BinaryOp::Ne => quote!(!=),
+                ast::BinaryOp::Lt => quote!(<),
+                ast::BinaryOp::Le => quote!(<=),
+                ast::BinaryOp::Gt => quote!(>),
+                ast::BinaryOp::Ge => quote!(>=),
+                ast::BinaryOp::And => quote!(&&),
+                ast::BinaryOp::Or => quote!(||),
+            };
+            quote!((#lhs #op #rhs))
+        }
+    }
+}
+
+/// Generate the decoder checks for the invariants of `decl`, evaluated
+/// on the decoded `packet`. Parent invariants are expected to have been
+/// validated when decoding the parent packet.
+fn decode_invariants(decl: &ast::Decl) -> proc_macro2::TokenStream {
+    let name = decl.id().unwrap();
+    let checks = decl.invariants().map(|invariant| {
+        let condition = invariant_expr(&invariant.expr, &quote!(packet));
+        let text = &invariant.text;
+        quote! {
+            if !#condition {
+                return Err(DecodeError::InvariantViolation {
+                    packet: #name,
+                    invariant: #text,
+                });
+            }
+        }
+    });
+    quote!(#( #checks )*)
+}
+
+/// Generate the encoder checks for the invariants of `decl`
+/// and its parents, which all apply to the encoded packet.
+fn encode_invariants(scope: &analyzer::Scope<'_>, decl: &ast::Decl) -> proc_macro2::TokenStream {
+    let name = decl.id().unwrap();
+    let checks =
+        scope.iter_parents_and_self(decl).flat_map(|decl| decl.invariants()).map(|invariant| {
+            let condition = invariant_expr(&invariant.expr, &quote!(self));
+            let text = &invariant.text;
+            quote! {
+                if !#condition {
+                    return Err(EncodeError::InvariantViolation {
+                        packet: #name,
+                        invariant: #text,
+                    });
+                }
+            }
+        });
+    quote!(#( #checks )*)
+}
+
+/// Generate the typed payload decoders for the bind declarations
+/// targeting the payload of `decl`. Returns the definition of the enum
+/// listing the bound declarations, and the implementation of the
+/// decoding methods.
+fn generate_payload_bindings(
+    scope: &analyzer::Scope<'_>,
+    decl: &ast::Decl,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let bindings = scope.file.iter_bindings(decl).collect::<Vec<_>>();
+    if bindings.is_empty() {
+        return (quote!(), quote!());
+    }
+
+    let name = decl.id().unwrap();
+    let binding_name = format_ident!("{name}Binding");
+    let all_fields = scope.iter_fields(decl).collect::<Vec<_>>();
+    let mut type_ids = vec![];
+    let mut cases = vec![];
+    let mut fallback = Some(quote!(Ok(#binding_name::None)));
+    for binding in bindings {
+        let ast::DeclDesc::Bind { type_id, constraints, .. } = &binding.desc else {
+            unreachable!()
+        };
+        if !type_ids.contains(&type_id) {
+            type_ids.push(type_id);
+        }
+        let variant = type_id.to_ident();
+        let decode = format_ident!("decode_payload_as_{}", type_id.to_snake_case());
+        let value = quote!(#binding_name::#variant(self.#decode()?));
+        if constraints.is_empty() {
+            cases.push(quote!(Ok(#value)));
+            fallback = None;
+            break;
+        }
+        let conditions = constraints.iter().map(|c| {
+            let id = c.id.to_ident();
+            let value = constraint_value(&all_fields, c);
+            quote!(self.#id() == #value)
+        });
+        cases.push(quote! {
+            if #( #conditions )&&* {
+                return Ok(#value);
+            }
+        });
+    }
+
+    let variants = type_ids.iter().map(|type_id| type_id.to_ident()).collect::<Vec<_>>();
+    let decoders = type_ids.iter().map(|type_id| {
+        let decode = format_ident!("decode_payload_as_{}", type_id.to_snake_case());
+        let doc = format!(" Decode the payload as `{type_id}`.");
+        let type_id = type_id.to_ident();
+        quote! {
+            #[doc = #doc]
+            pub fn #decode(&self) -> Result<#type_id, DecodeError> {
+                #type_id::decode_full(&self.payload)
+            }
+        }
+    });
+
+    (
+        quote! {
+            #[derive(Default, Debug, Clone, PartialEq, Eq)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            pub enum #binding_name {
+                #( #variants(#variants), )*
+                #[default]
+                None,
+            }
+        },
+        quote! {
+            #( #decoders )*
+
+            /// Decode the payload as the declaration bound by the first
+            /// bind declaration matching the field values.
+            pub fn decode_payload(&self) -> Result<#binding_name, DecodeError> {
+                #( #cases )*
+                #fallback
+            }
+        },
+    )
+}
+
 /// Generate the implementation of the specialize method.
 ///
 /// The function is generated after selecting the information from the parent
@@ -443,7 +795,7 @@ fn generate_specialize_impl(
 /// Generate code for a root packet declaration.
 ///
 /// # Arguments
-/// * `endianness` - File endianness
+/// * `endianness` - Declaration endianness
 /// * `id` - Packet identifier.
 fn generate_root_packet_decl(
     scope: &analyzer::Scope<'_>,
@@ -460,7 +812,8 @@ fn generate_root_packet_decl(
     // The payload field _ if declared _ is handled separately.
     let data_fields = packet_data_fields(scope, decl);
     let data_field_ids = data_fields.iter().map(|f| f.id().unwrap().to_ident()).collect::<Vec<_>>();
-    let data_field_types = data_fields.iter().map(|f| types::rust_type(f)).collect::<Vec<_>>();
+    let data_field_types =
+        data_fields.iter().map(|f| types::rust_type(scope, f)).collect::<Vec<_>>();
     let data_field_borrows = data_fields
         .iter()
         .map(|f| {
@@ -473,6 +826,9 @@ fn generate_root_packet_decl(
         .collect::<Vec<_>>();
     let data_field_defaults =
         data_fields.iter().copied().map(data_field_default).collect::<Vec<_>>();
+    let scaled_field_accessors = scaled_field_accessors(&data_fields);
+    let size_bound_constants = size_bound_constants(decl, &data_fields);
+    let (binding_enum, payload_bindings) = generate_payload_bindings(scope, decl);
     let payload_field = decl.payload().map(|_| quote! { pub payload: Vec<u8>, });
     let payload_default = decl.payload().map(|_| quote! { payload: vec![], });
     let payload_accessor =
@@ -496,11 +852,14 @@ fn generate_root_packet_decl(
         parsed_field_ids.push(id);
     }
 
-    let (encode_fields, encoded_len) =
-        encoder::encode(scope, schema, endianness, "buf".to_ident(), decl);
+    let (encode_fields, encoded_len) = encoder::encode(scope, schema, "buf".to_ident(), decl);
 
+    let size_assertions = encode_size_assertions(scope, schema, decl);
+    let invariants = encode_invariants(scope, decl);
     let encode = quote! {
          fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+            #size_assertions
+            #invariants
             #encode_fields
             Ok(())
         }
@@ -515,10 +874,26 @@ fn generate_root_packet_decl(
 
     // The implementation of decode for root packets contains the full
     // parser implementation.
+    let depth_guard = decode_depth_guard(scope, decl);
+    let size_assertion = decode_size_assertion(schema, decl, quote!(input.len() - buf.len()));
+    let input = (!size_assertion.is_empty()).then(|| quote!(let input = buf;));
+    let invariants = decode_invariants(decl);
+    let decoded_packet = if invariants.is_empty() {
+        quote!(Ok((Self { #( #parsed_field_ids, )* }, buf)))
+    } else {
+        quote! {
+            let packet = Self { #( #parsed_field_ids, )* };
+            #invariants
+            Ok((packet, buf))
+        }
+    };
     let decode = quote! {
        fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+           #depth_guard
+           #input
            #field_parser
-           Ok((Self { #( #parsed_field_ids, )* }, buf))
+           #size_assertion
+           #decoded_packet
        }
     };
 
@@ -569,16 +944,21 @@ fn generate_root_packet_decl(
         }
 
         #child_struct
+        #binding_enum
 
         impl #name {
+            #( #size_bound_constants )*
             #specialize
             #payload_accessor
+            #payload_bindings
 
             #(
             pub fn #data_field_ids(&self) -> #data_field_borrows #data_field_types {
                 #data_field_borrows self.#data_field_ids
             }
             )*
+
+            #( #scaled_field_accessors )*
         }
 
         #default_impl
@@ -594,7 +974,7 @@ fn generate_root_packet_decl(
 /// Generate code for a derived packet declaration
 ///
 /// # Arguments
-/// * `endianness` - File endianness
+/// * `endianness` - Declaration endianness
 /// * `id` - Packet identifier.
 fn generate_derived_packet_decl(
     scope: &analyzer::Scope<'_>,
@@ -620,7 +1000,8 @@ fn generate_derived_packet_decl(
     // The payload field _ if declared _ is handled separately.
     let data_fields = packet_data_fields(scope, decl);
     let data_field_ids = data_fields.iter().map(|f| f.id().unwrap().to_ident()).collect::<Vec<_>>();
-    let data_field_types = data_fields.iter().map(|f| types::rust_type(f)).collect::<Vec<_>>();
+    let data_field_types =
+        data_fields.iter().map(|f| types::rust_type(scope, f)).collect::<Vec<_>>();
     let data_field_borrows = data_fields
         .iter()
         .map(|f| {
@@ -633,6 +1014,9 @@ fn generate_derived_packet_decl(
         .collect::<Vec<_>>();
     let data_field_defaults =
         data_fields.iter().copied().map(data_field_default).collect::<Vec<_>>();
+    let scaled_field_accessors = scaled_field_accessors(&data_fields);
+    let size_bound_constants = size_bound_constants(decl, &data_fields);
+    let (binding_enum, payload_bindings) = generate_payload_bindings(scope, decl);
     let payload_field = decl.payload().map(|_| quote! { pub payload: Vec<u8>, });
     let payload_default = decl.payload().map(|_| quote! { payload: vec![], });
     let payload_accessor =
@@ -645,7 +1029,7 @@ fn generate_derived_packet_decl(
     let constant_field_ids =
         constant_fields.iter().map(|f| f.id().unwrap().to_ident()).collect::<Vec<_>>();
     let constant_field_types =
-        constant_fields.iter().map(|f| types::rust_type(f)).collect::<Vec<_>>();
+        constant_fields.iter().map(|f| types::rust_type(scope, f)).collect::<Vec<_>>();
     let constant_field_values = constant_fields.iter().map(|f| {
         let c = all_constraints.get(f.id().unwrap()).unwrap();
         constraint_value(&all_fields, c)
@@ -679,7 +1063,7 @@ fn generate_derived_packet_decl(
     }
 
     let (partial_field_serializer, field_serializer, encoded_len) =
-        encoder::encode_partial(scope, schema, endianness, "buf".to_ident(), decl);
+        encoder::encode_partial(scope, schema, "buf".to_ident(), decl);
 
     let encode_partial = quote! {
         pub fn encode_partial(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
@@ -688,8 +1072,12 @@ fn generate_derived_packet_decl(
         }
     };
 
+    let size_assertions = encode_size_assertions(scope, schema, decl);
+    let invariants = encode_invariants(scope, decl);
     let encode = quote! {
          fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+            #size_assertions
+            #invariants
             #field_serializer
             Ok(())
         }
@@ -723,7 +1111,27 @@ fn generate_derived_packet_decl(
         }
     });
 
+    // The size of the derived packet is the size of the decoded parent.
+    let size_assertion = decode_size_assertion(schema, decl, quote!(parent.encoded_len()));
+    let invariants = decode_invariants(decl);
+    let decoded_packet = |fields: proc_macro2::TokenStream| {
+        if invariants.is_empty() {
+            quote!(Ok(Self { #fields }))
+        } else {
+            quote! {
+                let packet = Self { #fields };
+                #invariants
+                Ok(packet)
+            }
+        }
+    };
+
     let decode_partial = if parent_decl.payload().is_some() {
+        let decoded_packet = decoded_packet(quote! {
+            #( #parsed_field_ids, )*
+            #( #copied_field_ids: parent.#copied_field_ids, )*
+            #( #cloned_field_ids: parent.#cloned_field_ids.clone(), )*
+        });
         // Generate an implementation of decode_partial that will decode
         // data fields present in the parent payload.
         // TODO(henrichataing) add constraint validation to decode_partial,
@@ -732,19 +1140,18 @@ fn generate_derived_packet_decl(
             fn decode_partial(parent: &#parent_name) -> Result<Self, DecodeError> {
                 let mut buf: &[u8] = &parent.payload;
                 #( #constraint_checks )*
+                #size_assertion
                 #field_parser
                 if buf.is_empty() {
-                    Ok(Self {
-                        #( #parsed_field_ids, )*
-                        #( #copied_field_ids: parent.#copied_field_ids, )*
-                        #( #cloned_field_ids: parent.#cloned_field_ids.clone(), )*
-                    })
+                    #decoded_packet
                 } else {
                     Err(DecodeError::TrailingBytesError)
                 }
             }
         }
     } else {
+        let decoded_packet =
+            decoded_packet(quote! { #( #copied_field_ids: parent.#copied_field_ids, )* });
         // Generate an implementation of decode_partial that will only copy
         // data fields present in the parent.
         // TODO(henrichataing) add constraint validation to decode_partial,
@@ -752,18 +1159,19 @@ fn generate_derived_packet_decl(
         quote! {
             fn decode_partial(parent: &#parent_name) -> Result<Self, DecodeError> {
                 #( #constraint_checks )*
-                Ok(Self {
-                    #( #copied_field_ids: parent.#copied_field_ids, )*
-                })
+                #size_assertion
+                #decoded_packet
             }
         }
     };
 
+    let depth_guard = decode_depth_guard(scope, decl);
     let decode =
         // The implementation of decode for derived packets relies on
         // the parent packet parser.
         quote! {
             fn decode(buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+                #depth_guard
                 let (parent, trailing_bytes) = #parent_name::decode(buf)?;
                 let packet = Self::decode_partial(&parent)?;
                 Ok((packet, trailing_bytes))
@@ -935,12 +1343,15 @@ fn generate_derived_packet_decl(
         #( #from_ancestors )*
 
         #child_struct
+        #binding_enum
 
         impl #name {
+            #( #size_bound_constants )*
             #specialize
             #decode_partial
             #encode_partial
             #payload_accessor
+            #payload_bindings
 
             #(
             pub fn #data_field_ids(&self) -> #data_field_borrows #data_field_types {
@@ -948,6 +1359,8 @@ fn generate_derived_packet_decl(
             }
             )*
 
+            #( #scaled_field_accessors )*
+
             #(
             pub fn #constant_field_ids(&self) -> #constant_field_types {
                 #constant_field_values
@@ -1136,6 +1549,55 @@ fn generate_enum_decl(id: &str, tags: &[ast::Tag], width: usize) -> proc_macro2:
         from_cases.push(quote! { _ => Err(value) });
     }
 
+    // Generate the constants for the tag aliases, and the cases for
+    // converting the enum value to and from its display name.
+    // Tags declared in ranges are flattened in the same declaration.
+    let value_tags = tags
+        .iter()
+        .flat_map(|tag| match tag {
+            ast::Tag::Value(tag) => vec![tag],
+            ast::Tag::Range(tag) => tag.tags.iter().collect(),
+            ast::Tag::Other(_) => vec![],
+        })
+        .collect::<Vec<_>>();
+    let mut alias_consts = vec![];
+    let mut name_cases = vec![];
+    let mut from_str_cases = vec![];
+    for tag in &value_tags {
+        let id = format_tag_ident(&tag.id);
+        let display_name = tag.display_name.as_deref().unwrap_or(&tag.id);
+        let mut names = vec![];
+        for name in
+            [display_name, &tag.id].into_iter().chain(tag.aliases.iter().map(String::as_str))
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        name_cases.push(quote! { #name::#id => #display_name });
+        from_str_cases.push(quote! { #( #names )|* => Ok(#name::#id) });
+        alias_consts.extend(tag.aliases.iter().map(|alias| {
+            let alias = format_tag_ident(alias);
+            quote! {
+                #[allow(non_upper_case_globals)]
+                pub const #alias: #name = #name::#id;
+            }
+        }));
+    }
+    for tag in tags.iter() {
+        if let ast::Tag::Range(tag) = tag {
+            let id = format_tag_ident(&tag.id);
+            let range_name = &tag.id;
+            name_cases.push(quote! { #name::#id(_) => #range_name });
+        }
+    }
+    if !is_complete && is_open {
+        let default_tag = enum_default_tag(tags).unwrap();
+        let unknown_id = format_tag_ident(&default_tag.id);
+        let unknown_name = &default_tag.id;
+        name_cases.push(quote! { #name::#unknown_id(_) => #unknown_name });
+    }
+
     // Derive other Into<uN> and Into<iN> implementations from the explicit
     // implementation, where the type is larger than the backing type.
     let derived_signed_into_types = [8, 16, 32, 64]
@@ -1163,6 +1625,33 @@ fn generate_enum_decl(id: &str, tags: &[ast::Tag], width: usize) -> proc_macro2:
             }
         }
 
+        impl #name {
+            #( #alias_consts )*
+
+            /// Return the display name of the enum value.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#name_cases,)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = pdl_runtime::ParseEnumError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    #(#from_str_cases,)*
+                    _ => Err(pdl_runtime::ParseEnumError { type_: #id, value: s.to_owned() }),
+                }
+            }
+        }
+
         impl TryFrom<#backing_type> for #name {
             type Error = #backing_type;
             fn try_from(value: #backing_type) -> Result<Self, Self::Error> {
@@ -1194,77 +1683,346 @@ fn generate_enum_decl(id: &str, tags: &[ast::Tag], width: usize) -> proc_macro2:
     }
 }
 
-/// Generate the declaration for a custom field of static size.
+/// Generate the conversions between an enum extension and its base enums.
+/// All values of a base enum are valid values of the extension, while
+/// values of the extension are converted back to a base enum only
+/// if declared by the base enum.
 ///
-/// * `id` - Enum identifier.
-/// * `width` - Width of the backing type of the enum, in bits.
-fn generate_custom_field_decl(
-    endianness: ast::EndiannessValue,
+/// * `id` - Enum extension identifier.
+/// * `parent_id` - Base enum identifier.
+/// * `width` - Width of the backing type of the enums, in bits.
+fn generate_enum_extension_conversions(
+    scope: &analyzer::Scope<'_>,
     id: &str,
+    parent_id: &str,
     width: usize,
 ) -> proc_macro2::TokenStream {
-    let name = id;
-    let id = id.to_ident();
     let backing_type = types::Integer::new(width);
-    let backing_type_str = proc_macro2::Literal::string(&format!("u{}", backing_type.width));
-    let size = proc_macro2::Literal::usize_unsuffixed(width / 8);
+    let name = id.to_ident();
+    let base_names =
+        std::iter::successors(Some(parent_id), |parent_id| match &scope.typedef[*parent_id].desc {
+            ast::DeclDesc::Enum { parent_id, .. } => parent_id.as_deref(),
+            _ => unreachable!(),
+        })
+        .map(|parent_id| parent_id.to_ident());
 
-    let read_value = types::get_uint(endianness, width, &format_ident!("buf"));
-    let read_value = if [8, 16, 32, 64].contains(&width) {
-        quote! { #read_value.into() }
+    quote! {
+        #(
+            impl From<#base_names> for #name {
+                fn from(value: #base_names) -> Self {
+                    #name::try_from(#backing_type::from(value)).unwrap()
+                }
+            }
+
+            impl TryFrom<#name> for #base_names {
+                type Error = #name;
+                fn try_from(value: #name) -> Result<Self, Self::Error> {
+                    #base_names::try_from(#backing_type::from(value)).map_err(|_| value)
+                }
+            }
+        )*
+    }
+}
+
+/// Generate the declaration for a flags type.
+///
+/// The flags type is a newtype over the backing integer type;
+/// the declared flags are generated as associated constants. Bits that
+/// do not match any declared flag are preserved when decoding and
+/// encoding the flags value.
+///
+/// * `id` - Flags identifier.
+/// * `flags` - Declared flags.
+/// * `width` - Width of the flags value, in bits.
+fn generate_flags_decl(
+    id: &str,
+    flags: &[ast::TagValue],
+    width: usize,
+) -> proc_macro2::TokenStream {
+    // Format a constant value as hexadecimal constant.
+    fn format_value(value: usize) -> LitInt {
+        syn::parse_str::<syn::LitInt>(&format!("{value:#x}")).unwrap()
+    }
+
+    let backing_type = types::Integer::new(width);
+    let backing_type_str = proc_macro2::Literal::string(&format!("u{}", backing_type.width));
+    let name = id.to_ident();
+    let flag_ids = flags
+        .iter()
+        .map(|flag| format_ident!("{}", flag.id.to_shouty_snake_case()))
+        .collect::<Vec<_>>();
+    let flag_names = flags.iter().map(|flag| flag.id.as_str()).collect::<Vec<_>>();
+    let flag_values = flags.iter().map(|flag| format_value(flag.value));
+    let flag_count = proc_macro2::Literal::usize_unsuffixed(flags.len());
+    let all = format_value(flags.iter().fold(0, |all, flag| all | flag.value));
+    let mask =
+        format_value(if width >= usize::BITS as usize { usize::MAX } else { (1 << width) - 1 });
+
+    // Reject values that do not fit the declared width if the flags
+    // value is smaller than the backing type.
+    let try_from_value = if backing_type.width != width {
+        quote! {
+            if value & !#mask != 0 {
+                Err(value)
+            } else {
+                Ok(#name(value))
+            }
+        }
     } else {
-        // The value is masked when read, and the conversion must succeed.
-        quote! { (#read_value).try_into().unwrap() }
+        quote! { Ok(#name(value)) }
     };
 
-    let write_value = types::put_uint(
-        endianness,
-        &quote! { #backing_type::from(self) },
-        width,
-        &format_ident!("buf"),
-    );
+    // Derive other Into<uN> and Into<iN> implementations from the explicit
+    // implementation, where the type is larger than the backing type.
+    let derived_signed_into_types = [8, 16, 32, 64]
+        .into_iter()
+        .filter(|w| *w > width)
+        .map(|w| syn::parse_str::<syn::Type>(&format!("i{w}")).unwrap());
+    let derived_unsigned_into_types = [8, 16, 32, 64]
+        .into_iter()
+        .filter(|w| *w >= width && *w != backing_type.width)
+        .map(|w| syn::parse_str::<syn::Type>(&format!("u{w}")).unwrap());
+    let derived_into_types = derived_signed_into_types.chain(derived_unsigned_into_types);
 
-    let common = quote! {
-        impl From<&#id> for #backing_type {
-            fn from(value: &#id) -> #backing_type {
-                value.0
+    quote! {
+        #[derive(Default, Clone, Copy, Hash, Eq, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(try_from = #backing_type_str, into = #backing_type_str))]
+        pub struct #name(#backing_type);
+
+        impl #name {
+            #(pub const #flag_ids: #name = #name(#flag_values);)*
+
+            const FLAGS: [(&'static str, #name); #flag_count] = [
+                #((#flag_names, #name::#flag_ids),)*
+            ];
+
+            /// Return the empty flag set.
+            pub const fn empty() -> Self {
+                #name(0)
             }
-        }
 
-        impl From<#id> for #backing_type {
-            fn from(value: #id) -> #backing_type {
-                value.0
+            /// Return the flag set containing all declared flags.
+            pub const fn all() -> Self {
+                #name(#all)
             }
-        }
 
-        impl Packet for #id {
-            fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
-                if buf.len() < #size {
-                    return Err(DecodeError::LengthError {
-                        obj: #name,
-                        wanted: #size,
-                        got: buf.len(),
-                    })
-                }
+            /// Return the raw value of the flag set.
+            pub const fn bits(&self) -> #backing_type {
+                self.0
+            }
 
-                Ok((#read_value, buf))
+            /// Return the bits of the flag set that do not match
+            /// any declared flag.
+            pub const fn unknown_bits(&self) -> #backing_type {
+                self.0 & !#all
             }
 
-            fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
-                #write_value;
-                Ok(())
+            pub const fn is_empty(&self) -> bool {
+                self.0 == 0
             }
 
-            fn encoded_len(&self) -> usize {
-                #size
+            /// Return true if all flags of `other` are set.
+            pub const fn contains(&self, other: Self) -> bool {
+                self.0 & other.0 == other.0
             }
-        }
-    };
 
-    if backing_type.width == width {
-        quote! {
-            #[derive(Default, Debug, Clone, Copy, Hash, Eq, PartialEq)]
-            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            /// Return true if any flag of `other` is set.
+            pub const fn intersects(&self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            pub fn insert(&mut self, other: Self) {
+                self.0 |= other.0
+            }
+
+            pub fn remove(&mut self, other: Self) {
+                self.0 &= !other.0
+            }
+
+            pub fn toggle(&mut self, other: Self) {
+                self.0 ^= other.0
+            }
+
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other)
+                } else {
+                    self.remove(other)
+                }
+            }
+
+            /// Iterate over the declared flags contained in the flag set.
+            pub fn iter(&self) -> impl Iterator<Item = #name> {
+                let value = *self;
+                #name::FLAGS.into_iter().map(|(_, flag)| flag).filter(move |flag| value.contains(*flag))
+            }
+        }
+
+        impl fmt::Debug for #name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut names = #name::FLAGS
+                    .into_iter()
+                    .filter(|(_, flag)| self.contains(*flag))
+                    .map(|(name, _)| name.to_owned())
+                    .collect::<Vec<_>>();
+                if self.unknown_bits() != 0 {
+                    names.push(format!("{:#x}", self.unknown_bits()));
+                }
+                write!(f, "{}({})", #id, names.join(" | "))
+            }
+        }
+
+        impl std::ops::BitOr for #name {
+            type Output = Self;
+            fn bitor(self, other: Self) -> Self {
+                #name(self.0 | other.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for #name {
+            fn bitor_assign(&mut self, other: Self) {
+                self.0 |= other.0
+            }
+        }
+
+        impl std::ops::BitAnd for #name {
+            type Output = Self;
+            fn bitand(self, other: Self) -> Self {
+                #name(self.0 & other.0)
+            }
+        }
+
+        impl std::ops::BitAndAssign for #name {
+            fn bitand_assign(&mut self, other: Self) {
+                self.0 &= other.0
+            }
+        }
+
+        impl std::ops::BitXor for #name {
+            type Output = Self;
+            fn bitxor(self, other: Self) -> Self {
+                #name(self.0 ^ other.0)
+            }
+        }
+
+        impl std::ops::BitXorAssign for #name {
+            fn bitxor_assign(&mut self, other: Self) {
+                self.0 ^= other.0
+            }
+        }
+
+        impl std::ops::Not for #name {
+            type Output = Self;
+            fn not(self) -> Self {
+                #name(!self.0 & #mask)
+            }
+        }
+
+        impl std::iter::FromIterator<#name> for #name {
+            fn from_iter<T: IntoIterator<Item = #name>>(iter: T) -> Self {
+                iter.into_iter().fold(#name::empty(), |all, flag| all | flag)
+            }
+        }
+
+        impl TryFrom<#backing_type> for #name {
+            type Error = #backing_type;
+            fn try_from(value: #backing_type) -> Result<Self, Self::Error> {
+                #try_from_value
+            }
+        }
+
+        impl From<&#name> for #backing_type {
+            fn from(value: &#name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<#name> for #backing_type {
+            fn from(value: #name) -> Self {
+                value.0
+            }
+        }
+
+        #(impl From<#name> for #derived_into_types {
+            fn from(value: #name) -> Self {
+                #backing_type::from(value) as Self
+            }
+        })*
+    }
+}
+
+/// Generate the declaration for a custom field of static size.
+///
+/// * `id` - Enum identifier.
+/// * `width` - Width of the backing type of the enum, in bits.
+fn generate_custom_field_decl(
+    endianness: ast::EndiannessValue,
+    id: &str,
+    width: usize,
+) -> proc_macro2::TokenStream {
+    let name = id;
+    let id = id.to_ident();
+    let backing_type = types::Integer::new(width);
+    let backing_type_str = proc_macro2::Literal::string(&format!("u{}", backing_type.width));
+    let size = proc_macro2::Literal::usize_unsuffixed(width / 8);
+
+    let read_value = types::get_uint(endianness, width, &format_ident!("buf"));
+    let read_value = if [8, 16, 32, 64, 128].contains(&width) {
+        quote! { #read_value.into() }
+    } else {
+        // The value is masked when read, and the conversion must succeed.
+        quote! { (#read_value).try_into().unwrap() }
+    };
+
+    let write_value = types::put_uint(
+        endianness,
+        &quote! { #backing_type::from(self) },
+        width,
+        &format_ident!("buf"),
+    );
+
+    let common = quote! {
+        impl From<&#id> for #backing_type {
+            fn from(value: &#id) -> #backing_type {
+                value.0
+            }
+        }
+
+        impl From<#id> for #backing_type {
+            fn from(value: #id) -> #backing_type {
+                value.0
+            }
+        }
+
+        impl Packet for #id {
+            fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+                if buf.len() < #size {
+                    return Err(DecodeError::LengthError {
+                        obj: #name,
+                        wanted: #size,
+                        got: buf.len(),
+                    })
+                }
+
+                Ok((#read_value, buf))
+            }
+
+            fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+                #write_value;
+                Ok(())
+            }
+
+            fn encoded_len(&self) -> usize {
+                #size
+            }
+        }
+    };
+
+    if backing_type.width == width {
+        quote! {
+            #[derive(Default, Debug, Clone, Copy, Hash, Eq, PartialEq)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             #[cfg_attr(feature = "serde", serde(from = #backing_type_str, into = #backing_type_str))]
             pub struct #id(#backing_type);
 
@@ -1308,12 +2066,23 @@ fn generate_decl(
 ) -> proc_macro2::TokenStream {
     match &decl.desc {
         ast::DeclDesc::Packet { id, .. } | ast::DeclDesc::Struct { id, .. } => {
+            let endianness = file.decl_endianness(decl);
             match scope.get_parent(decl) {
-                None => generate_root_packet_decl(scope, schema, file.endianness.value, id),
-                Some(_) => generate_derived_packet_decl(scope, schema, file.endianness.value, id),
+                None => generate_root_packet_decl(scope, schema, endianness, id),
+                Some(_) => generate_derived_packet_decl(scope, schema, endianness, id),
             }
         }
-        ast::DeclDesc::Enum { id, tags, width } => generate_enum_decl(id, tags, *width),
+        ast::DeclDesc::Enum { id, tags, width, parent_id } => {
+            let enum_decl = generate_enum_decl(id, tags, *width);
+            let conversions = parent_id
+                .as_ref()
+                .map(|parent_id| generate_enum_extension_conversions(scope, id, parent_id, *width));
+            quote! {
+                #enum_decl
+                #conversions
+            }
+        }
+        ast::DeclDesc::Flags { id, flags, width } => generate_flags_decl(id, flags, *width),
         ast::DeclDesc::CustomField { id, width: Some(width), .. } => {
             generate_custom_field_decl(file.endianness.value, id, *width)
         }
@@ -1322,6 +2091,23 @@ fn generate_decl(
             // we just assume it will be in scope.
             quote!()
         }
+        ast::DeclDesc::Test { .. } => {
+            // Tests are generated separately with `test::generate_declared_tests`.
+            quote!()
+        }
+        ast::DeclDesc::Bind { .. } => {
+            // Bindings are generated with the declaration of the bound packet.
+            quote!()
+        }
+        ast::DeclDesc::Response { request_id, response_id } => {
+            let request_id = request_id.to_ident();
+            let response_id = response_id.to_ident();
+            quote! {
+                impl pdl_runtime::Command for #request_id {
+                    type Response = #response_id;
+                }
+            }
+        }
         _ => todo!("unsupported Decl::{:?}", decl),
     }
 }
@@ -1556,6 +2342,232 @@ mod tests {
     test_pdl!(packet_decl_24bit_enum, "enum Foo : 24 { A = 1, B = 2 } packet Bar { x: Foo }");
     test_pdl!(packet_decl_64bit_enum, "enum Foo : 64 { A = 1, B = 2 } packet Bar { x: Foo }");
 
+    test_pdl!(
+        enum_declaration_display_names,
+        r#"
+        enum OpCode : 16 {
+            LE_SET_ADVERTISING_PARAMETERS | LE_SET_ADV_PARAMS = 0x2006 "LE Set Advertising Parameters",
+            RESET = 0x0c03,
+            VENDOR = 0xfc00..0xfcff {
+                VENDOR_RESET = 0xfc00 "Vendor Reset",
+            },
+            UNKNOWN = ..,
+        }
+        "#
+    );
+
+    test_pdl!(
+        enum_declaration_extension,
+        "
+          enum Base : 8 {
+            A = 1,
+            B = 2,
+          }
+
+          enum Extension : Base {
+            C = 3,
+          }
+
+          packet Foo {
+            x: Extension,
+          }
+        "
+    );
+
+    test_pdl!(
+        struct_decl_recursive,
+        "
+          struct Tlv {
+            tag: 8,
+            _count_(children): 8,
+            children: Tlv[],
+          }
+
+          struct Node {
+            c: 1,
+            _reserved_: 7,
+            next: Node if c = 1,
+          }
+
+          packet Foo {
+            a: Tlv,
+            b: Node,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_scaled_scalars,
+        "
+          packet Foo {
+            a: 12 scale 1.25 unit \"ms\",
+            b: 16 scale 0.5,
+            c: 1,
+            _reserved_: 3,
+            d: 8 scale 2 unit \"dBm\" if c = 1,
+            _payload_,
+          }
+
+          packet Bar : Foo {
+            e: 32 scale 0.001 unit \"s\",
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_wide_scalars,
+        "
+          custom_field Uuid : 128 \"uuid\"
+
+          packet Foo {
+            a: 128,
+            b: 72,
+            c: 1,
+            _reserved_: 3,
+            d: 96,
+            e: 4,
+            u: Uuid,
+            f: 128 if c = 1,
+            g: 128[],
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_extension_fields,
+        "
+          enum Enum8 : 8 {
+            A = 1,
+            B = 2,
+          }
+
+          struct Bar {
+            x: 8,
+          }
+
+          packet Foo {
+            a: 8,
+            _extension_ {
+              b: 16,
+              c: Enum8,
+            },
+            _extension_ {
+              d: Bar,
+            }
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_array_bounds,
+        "
+          struct Foo {
+            a: 16,
+          }
+
+          packet Bar {
+            _count_(x) : 8,
+            x: 8[..32],
+            _size_(y) : 8,
+            y: Foo[..16],
+            z: 24[..8],
+          }
+
+          packet Baz {
+            _size_(_payload_) : 8,
+            _payload_ : [..251],
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_payload_bindings,
+        "
+          enum Kind : 8 {
+            A = 1,
+            B = 2,
+          }
+
+          packet Frame {
+            channel: 16,
+            kind: Kind,
+            _payload_,
+          }
+
+          packet Att {
+            opcode: 8,
+            _payload_,
+          }
+
+          struct Smp {
+            code: 8,
+          }
+
+          bind Frame.payload as Att if channel = 4
+          bind Frame.payload as Smp if channel = 6, kind = B
+        "
+    );
+
+    test_pdl!(
+        packet_decl_response,
+        "
+          packet ReadBdAddr {
+            opcode: 16,
+          }
+
+          packet ReadBdAddrComplete {
+            status: 8,
+            bd_addr: 48,
+          }
+
+          response ReadBdAddr -> ReadBdAddrComplete
+        "
+    );
+
+    test_pdl!(
+        packet_decl_size_assertions,
+        "
+          packet Frame : size <= 16 {
+            _size_(_payload_): 8,
+            opcode: 8,
+            _payload_,
+          }
+
+          packet Command : Frame (opcode = 1) : size >= 4 {
+            _count_(args): 8,
+            args: 8[],
+          }
+
+          struct Header : size == 2 {
+            a: 16,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_invariants,
+        "
+          packet Frame {
+            length: 8,
+            opcode: 8,
+            _payload_,
+            assert length >= 2 && length <= 64
+          }
+
+          packet Command : Frame (opcode = 1) {
+            _count_(args): 8,
+            args: 8[],
+            assert len(args) * 2 + 2 == length
+          }
+
+          struct Range {
+            low: 16,
+            high: 16,
+            assert low <= high
+          }
+        "
+    );
+
     test_pdl!(
         packet_decl_mixed_scalars_enums,
         "
@@ -1578,6 +2590,134 @@ mod tests {
         "
     );
 
+    test_pdl!(
+        packet_decl_flags,
+        "
+          flags Flags12 : 12 {
+            READ = 0x1,
+            WRITE = 0x2,
+          }
+
+          flags Flags16 : 16 {
+            A = 0x1,
+            B = 0x100,
+          }
+
+          packet Foo {
+            x: Flags12,
+            y: 4,
+            z: Flags16,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_default_values,
+        "
+          enum Enum8 : 8 {
+            A = 1,
+            B = 2,
+          }
+
+          packet Foo {
+            x: 8 = 64,
+            y: Enum8 = B,
+            z: 24,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_endianness_override,
+        "
+          enum Enum16 : 16 {
+            A = 1,
+            B = 2,
+          }
+
+          struct Bar big_endian {
+            a: 16,
+            b: 16 little_endian,
+          }
+
+          packet Foo {
+            a: 16 big_endian,
+            b: Enum16 little_endian,
+            c: 24 big_endian,
+            d: 32,
+            bar: Bar,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_size_units,
+        "
+          packet Foo {
+            _size_(a, bits): 16,
+            _size_(b, words32): 8,
+            a: 8[],
+            b: 8[],
+            _size_(_payload_, words16): 8,
+            _payload_,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_trailing_size_fields,
+        "
+          packet Foo {
+            a: 8[],
+            _size_(a, bits): 16,
+            c: 8,
+          }
+
+          packet Bar {
+            b: 16[],
+            _count_(b): 8,
+          }
+
+          packet Baz {
+            _payload_ : [+1],
+            _size_(_payload_): 8,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_struct_size_field,
+        "
+          struct Bar {
+            a: 8,
+            _payload_,
+          }
+
+          packet Foo {
+            _size_(b, words16): 8,
+            b: Bar,
+            c: 8,
+          }
+        "
+    );
+
+    test_pdl!(
+        packet_decl_msb_first_bit_order,
+        "
+          enum Enum4 : 4 {
+            A = 1,
+            B = 2,
+          }
+
+          packet Foo msb_first {
+            a: 4,
+            b: Enum4,
+            c: 12,
+            d: 4,
+          }
+        "
+    );
+
     test_pdl!(packet_decl_8bit_scalar_array, " packet Foo { x:  8[3] }");
     test_pdl!(packet_decl_24bit_scalar_array, "packet Foo { x: 24[5] }");
     test_pdl!(packet_decl_64bit_scalar_array, "packet Foo { x: 64[7] }");
@@ -17,7 +17,8 @@
 use quote::{format_ident, quote};
 use serde::Serialize;
 
-use crate::backends::common::test::Packet;
+use crate::ast;
+use crate::backends::common::test::{self, Packet};
 
 /// Convert a string of hexadecimal characters into a Rust vector of
 /// bytes.
@@ -61,15 +62,9 @@ fn error_variant_pattern(variant: &str) -> proc_macro2::TokenStream {
     }
 }
 
-fn generate_unit_tests(input: &str, packet_names: &[&str]) -> Result<String, String> {
-    eprintln!("Reading test vectors from {input}, will use {} packets", packet_names.len());
-
-    let data = std::fs::read_to_string(input)
-        .unwrap_or_else(|err| panic!("Could not read {input}: {err}"));
-    let packets: Vec<Packet> = serde_json::from_str(&data).expect("Could not parse JSON");
-
+fn generate_unit_tests(packets: &[Packet], packet_names: &[&str]) -> Result<String, String> {
     let mut tests = Vec::new();
-    for packet in &packets {
+    for packet in packets {
         for (i, test_vector) in packet.tests.iter().enumerate() {
             let test_packet = test_vector.packet.as_deref().unwrap_or(packet.name.as_str());
             if !packet_names.contains(&test_packet) {
@@ -132,7 +127,6 @@ fn generate_unit_tests(input: &str, packet_names: &[&str]) -> Result<String, Str
                     }
                 });
 
-                let json = to_json(&object);
                 tests.push(quote! {
                     #[test]
                     fn #parse_test_name() {
@@ -141,16 +135,31 @@ fn generate_unit_tests(input: &str, packet_names: &[&str]) -> Result<String, Str
                         assert_eq!(actual.encoded_len(), packed.len());
                         #(#assertions)*
                     }
-
-                    #[test]
-                    fn #serialize_test_name() {
-                        let packet: #packet_name = serde_json::from_str(#json)
-                            .expect("Could not create packet from canonical JSON data");
-                        let packed: Vec<u8> = #packed;
-                        assert_eq!(packet.encoded_len(), packed.len());
-                        assert_eq!(packet.encode_to_vec(), Ok(packed));
-                    }
                 });
+
+                if test_vector.round_trip {
+                    // Partial vector: re-encode the decoded packet.
+                    tests.push(quote! {
+                        #[test]
+                        fn #serialize_test_name() {
+                            let packed: Vec<u8> = #packed;
+                            let packet = #packet_name::decode_full(&packed).unwrap();
+                            assert_eq!(packet.encode_to_vec(), Ok(packed));
+                        }
+                    });
+                } else {
+                    let json = to_json(&object);
+                    tests.push(quote! {
+                        #[test]
+                        fn #serialize_test_name() {
+                            let packet: #packet_name = serde_json::from_str(#json)
+                                .expect("Could not create packet from canonical JSON data");
+                            let packed: Vec<u8> = #packed;
+                            assert_eq!(packet.encoded_len(), packed.len());
+                            assert_eq!(packet.encode_to_vec(), Ok(packed));
+                        }
+                    });
+                }
             } else {
                 panic!(
                     "Test vector for {test_packet} (index {i}) has neither \
@@ -176,11 +185,24 @@ fn generate_unit_tests(input: &str, packet_names: &[&str]) -> Result<String, Str
     Ok(prettyplease::unparse(&syntax_tree))
 }
 
+/// Generate unit tests for the test declarations of an analyzed file.
+pub fn generate_declared_tests(file: &ast::File) -> Result<String, String> {
+    let packets = test::test_vectors(file);
+    let packet_names = packets.iter().map(|packet| packet.name.as_str()).collect::<Vec<_>>();
+    generate_unit_tests(&packets, &packet_names)
+}
+
 pub fn generate_tests(input_file: &str) -> Result<String, String> {
+    eprintln!("Reading test vectors from {input_file}");
+
+    let data = std::fs::read_to_string(input_file)
+        .unwrap_or_else(|err| panic!("Could not read {input_file}: {err}"));
+    let packets: Vec<Packet> = serde_json::from_str(&data).expect("Could not parse JSON");
+
     // TODO(mgeisler): remove the `packet_names` argument when we
     // support all canonical packets.
     generate_unit_tests(
-        input_file,
+        &packets,
         &[
             "EnumChild_A",
             "EnumChild_B",
@@ -274,6 +296,7 @@ pub fn generate_tests(input_file: &str) -> Result<String, String> {
             "Struct_Optional_Scalar_Field",
             "Struct_Optional_Enum_Field",
             "Struct_Optional_Struct_Field",
+            "Struct_Recursive_Array_Field",
             "Struct_Enum_Field",
             "Struct_FixedEnum_Field",
             "Struct_FixedScalar_Field",
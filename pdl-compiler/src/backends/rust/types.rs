@@ -14,8 +14,8 @@
 
 //! Utility functions for dealing with Rust integer types.
 
-use crate::ast;
 use crate::backends::rust::ToIdent;
+use crate::{analyzer, ast};
 use quote::{format_ident, quote};
 
 /// A Rust integer type such as `u8`.
@@ -28,10 +28,10 @@ impl Integer {
     /// Get the Rust integer type for the given bit width.
     ///
     /// This will round up the size to the nearest Rust integer size.
-    /// PDL supports integers up to 64 bit, so it is an error to call
-    /// this with a width larger than 64.
+    /// PDL supports integers up to 128 bit, so it is an error to call
+    /// this with a width larger than 128.
     pub fn new(width: usize) -> Integer {
-        for integer_width in [8, 16, 32, 64] {
+        for integer_width in [8, 16, 32, 64, 128] {
             if width <= integer_width {
                 return Integer { width: integer_width };
             }
@@ -48,9 +48,9 @@ impl quote::ToTokens for Integer {
     }
 }
 
-pub fn rust_type(field: &ast::Field) -> proc_macro2::TokenStream {
+pub fn rust_type(scope: &analyzer::Scope<'_>, field: &ast::Field) -> proc_macro2::TokenStream {
     match &field.desc {
-        ast::FieldDesc::Scalar { width, .. } if field.cond.is_some() => {
+        ast::FieldDesc::Scalar { width, .. } if field.is_optional() => {
             let field_type = Integer::new(*width);
             quote!(Option<#field_type>)
         }
@@ -58,7 +58,11 @@ pub fn rust_type(field: &ast::Field) -> proc_macro2::TokenStream {
             let field_type = Integer::new(*width);
             quote!(#field_type)
         }
-        ast::FieldDesc::Typedef { type_id, .. } if field.cond.is_some() => {
+        ast::FieldDesc::Typedef { type_id, .. } if scope.is_indirect(field) => {
+            let field_type = type_id.to_ident();
+            quote!(Option<Box<#field_type>>)
+        }
+        ast::FieldDesc::Typedef { type_id, .. } if field.is_optional() => {
             let field_type = type_id.to_ident();
             quote!(Option<#field_type>)
         }
@@ -111,6 +115,25 @@ pub fn get_uint(
         quote! {
             #span.#get_u()
         }
+    } else if value_type.width == 128 {
+        // `Buf::get_uint` is limited to 8 bytes, wider values are
+        // copied to a zero extended byte array.
+        let value_nbytes = proc_macro2::Literal::usize_unsuffixed(width / 8);
+        let (bytes, from_bytes) = match endianness {
+            ast::EndiannessValue::LittleEndian => {
+                (quote!(bytes[..#value_nbytes]), quote!(from_le_bytes))
+            }
+            ast::EndiannessValue::BigEndian => {
+                (quote!(bytes[16 - #value_nbytes..]), quote!(from_be_bytes))
+            }
+        };
+        quote! {
+            {
+                let mut bytes = [0; 16];
+                #span.copy_to_slice(&mut #bytes);
+                u128::#from_bytes(bytes)
+            }
+        }
     } else {
         let get_uint = format_ident!("get_uint{}", suffix);
         let value_nbytes = proc_macro2::Literal::usize_unsuffixed(width / 8);
@@ -138,6 +161,19 @@ pub fn put_uint(
         quote! {
             #span.#put_u(#value)
         }
+    } else if value_type.width == 128 {
+        // `BufMut::put_uint` is limited to 8 bytes, wider values are
+        // truncated from the full byte representation.
+        let value_nbytes = proc_macro2::Literal::usize_unsuffixed(width / 8);
+        let bytes = match endianness {
+            ast::EndiannessValue::LittleEndian => quote!((#value).to_le_bytes()[..#value_nbytes]),
+            ast::EndiannessValue::BigEndian => {
+                quote!((#value).to_be_bytes()[16 - #value_nbytes..])
+            }
+        };
+        quote! {
+            #span.put_slice(&#bytes)
+        }
     } else {
         let put_uint = format_ident!("put_uint{}", suffix);
         let value_nbytes = proc_macro2::Literal::usize_unsuffixed(width / 8);
@@ -158,11 +194,13 @@ mod tests {
         assert_eq!(Integer::new(8).width, 8);
         assert_eq!(Integer::new(9).width, 16);
         assert_eq!(Integer::new(64).width, 64);
+        assert_eq!(Integer::new(65).width, 128);
+        assert_eq!(Integer::new(128).width, 128);
     }
 
     #[test]
     #[should_panic]
     fn test_integer_new_panics_on_large_width() {
-        Integer::new(65);
+        Integer::new(129);
     }
 }
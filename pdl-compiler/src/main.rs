@@ -15,7 +15,12 @@
 //! PDL parser and analyzer.
 
 use argh::FromArgs;
-use codespan_reporting::term::{self, termcolor};
+use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::term::termcolor;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::process::ExitCode;
 
 use pdl_compiler::{analyzer, ast, backends, parser};
 
@@ -49,6 +54,28 @@ impl std::str::FromStr for OutputFormat {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum DiagnosticsFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+impl std::str::FromStr for DiagnosticsFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            _ => {
+                Err(format!("could not parse {input:?}, valid option are 'text', 'json', 'sarif'."))
+            }
+        }
+    }
+}
+
 #[derive(FromArgs, Debug)]
 /// PDL analyzer and generator.
 struct Opt {
@@ -56,6 +83,11 @@ struct Opt {
     /// print tool version and exit.
     version: bool,
 
+    #[argh(option)]
+    /// print the explanation of an analyzer error or warning code
+    /// (e.g. "E33") and exit.
+    explain: Option<String>,
+
     #[argh(option, default = "OutputFormat::JSON")]
     /// generate output in this format ("json", "rust", "java", "python").
     /// The output will be printed on stdout in all cases.
@@ -70,6 +102,12 @@ struct Opt {
     /// Valid for the output formats "rust", "java".
     test_file: Option<String>,
 
+    #[argh(switch)]
+    /// generate tests for the selected output format from the test declarations
+    /// of the input file.
+    /// Valid for the output formats "cxx", "java", "python", "rust".
+    test_declarations: bool,
+
     #[argh(positional)]
     /// input files.
     input_file: Option<String>,
@@ -84,6 +122,29 @@ struct Opt {
     /// list is used.
     include_declaration: Vec<String>,
 
+    #[argh(option)]
+    /// select a build profile. Declarations and fields conditional on
+    /// profiles that are not selected are removed before analysis.
+    profile: Vec<String>,
+
+    #[argh(option, default = "DiagnosticsFormat::Text")]
+    /// format of the parser and analyzer diagnostics printed on stderr
+    /// ("text", "json", "sarif"). The formats "json" and "sarif" print
+    /// a single document listing all the diagnostics.
+    diagnostics_format: DiagnosticsFormat,
+
+    #[argh(option)]
+    /// allow an analyzer warning, selected by code (e.g. "W1") or name
+    /// (e.g. "unused_decl"). The value "warnings" selects all warnings.
+    allow: Vec<String>,
+
+    #[argh(option)]
+    /// deny an analyzer warning, selected by code (e.g. "W1") or name
+    /// (e.g. "unused_decl"). The value "warnings" selects all warnings.
+    /// Denied warnings are reported as errors and fail the analysis.
+    /// Options selecting a single warning take precedence over "warnings".
+    deny: Vec<String>,
+
     #[argh(option)]
     /// custom_field import paths.
     /// For the rust backend, declares a list of qualified paths like "module::CustomField".
@@ -140,95 +201,294 @@ fn filter_declarations(
     }
 }
 
-fn generate_backend(opt: &Opt, input_file: &str) -> Result<(), String> {
-    let mut sources = ast::SourceDatabase::new();
-    match parser::parse_file(&mut sources, input_file) {
+/// Reporting level of analyzer warnings.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum WarningLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// Check the warning names selected by the `--allow` and `--deny` options.
+fn check_warning_names(opt: &Opt) -> Result<(), String> {
+    for name in opt.allow.iter().chain(opt.deny.iter()) {
+        if name != "warnings" {
+            name.parse::<analyzer::WarningCode>()?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the reporting level of a warning selected by
+/// the `--allow` and `--deny` options.
+fn warning_level(opt: &Opt, code: analyzer::WarningCode) -> WarningLevel {
+    let selects_code = |names: &[String]| names.iter().any(|name| name.parse() == Ok(code));
+    let selects_all = |names: &[String]| names.iter().any(|name| name == "warnings");
+    if selects_code(&opt.allow) {
+        WarningLevel::Allow
+    } else if selects_code(&opt.deny) {
+        WarningLevel::Deny
+    } else if selects_all(&opt.allow) {
+        WarningLevel::Allow
+    } else if selects_all(&opt.deny) {
+        WarningLevel::Deny
+    } else {
+        WarningLevel::Warn
+    }
+}
+
+/// Format structured diagnostics as a SARIF 2.1.0 log.
+fn sarif_log(diagnostics: &[analyzer::DiagnosticData]) -> serde_json::Value {
+    let location = |label: &analyzer::DiagnosticLabel| {
+        let mut location = json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": label.file },
+                "region": {
+                    "startLine": label.start.line,
+                    "startColumn": label.start.column,
+                    "endLine": label.end.line,
+                    "endColumn": label.end.column,
+                },
+            },
+        });
+        if !label.message.is_empty() {
+            location["message"] = json!({ "text": label.message });
+        }
+        location
+    };
+
+    let rules = diagnostics
+        .iter()
+        .filter_map(|d| d.code.as_deref())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|code| {
+            let explanation = match (
+                code.parse::<analyzer::ErrorCode>(),
+                code.parse::<analyzer::WarningCode>(),
+            ) {
+                (Ok(code), _) => code.explanation(),
+                (_, Ok(code)) => code.explanation(),
+                _ => "",
+            };
+            json!({
+                "id": code,
+                "shortDescription": { "text": explanation.lines().next().unwrap_or(code) },
+                "fullDescription": { "markdown": explanation },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = diagnostics
+        .iter()
+        .map(|d| {
+            let mut text = d.message.clone();
+            for note in &d.notes {
+                text.push('\n');
+                text.push_str(note);
+            }
+            let mut result = json!({
+                "level": match d.severity {
+                    analyzer::DiagnosticSeverity::Bug | analyzer::DiagnosticSeverity::Error => "error",
+                    analyzer::DiagnosticSeverity::Warning => "warning",
+                    analyzer::DiagnosticSeverity::Note | analyzer::DiagnosticSeverity::Help => "note",
+                },
+                "message": { "text": text },
+                "locations": d.labels.iter().filter(|l| l.primary).map(location).collect::<Vec<_>>(),
+                "relatedLocations":
+                    d.labels.iter().filter(|l| !l.primary).map(location).collect::<Vec<_>>(),
+            });
+            if let Some(code) = &d.code {
+                result["ruleId"] = json!(code);
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pdlc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/google/pdl",
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Report parser or analyzer diagnostics on stderr, in the format
+/// selected by the `--diagnostics-format` option.
+fn report_diagnostics(
+    opt: &Opt,
+    sources: &ast::SourceDatabase,
+    diagnostics: &analyzer::Diagnostics,
+) {
+    match opt.diagnostics_format {
+        DiagnosticsFormat::Text => {
+            let color_choice = if std::io::stderr().is_terminal() {
+                termcolor::ColorChoice::Auto
+            } else {
+                termcolor::ColorChoice::Never
+            };
+            diagnostics
+                .emit(sources, &mut termcolor::StandardStream::stderr(color_choice).lock())
+                .expect("Could not print diagnostics");
+            if let Some(code) = diagnostics
+                .diagnostics
+                .iter()
+                .find(|d| d.severity == Severity::Error)
+                .and_then(|d| d.code.as_ref())
+            {
+                eprintln!("For more information about an error, try `pdlc --explain {code}`.");
+            }
+        }
+        DiagnosticsFormat::Json => {
+            let diagnostics = diagnostics.structured(sources).expect("Could not print diagnostics");
+            eprintln!("{}", serde_json::to_string_pretty(&diagnostics).unwrap());
+        }
+        DiagnosticsFormat::Sarif => {
+            let diagnostics = diagnostics.structured(sources).expect("Could not print diagnostics");
+            eprintln!("{}", serde_json::to_string_pretty(&sarif_log(&diagnostics)).unwrap());
+        }
+    }
+}
+
+/// Return the error for a failure reported with diagnostics.
+/// With machine-readable diagnostics formats, the message is empty
+/// and not printed, so that stderr holds the diagnostics document only.
+fn diagnostics_failure(opt: &Opt, message: &str) -> String {
+    if opt.diagnostics_format != DiagnosticsFormat::Text {
+        return String::new();
+    }
+    message.to_owned()
+}
+
+/// Report the analyzer warnings not allowed by the `--allow` option.
+/// Returns an error if any warning is denied by the `--deny` option.
+fn report_warnings(
+    opt: &Opt,
+    sources: &ast::SourceDatabase,
+    mut warnings: analyzer::Diagnostics,
+) -> Result<(), String> {
+    let mut denied = false;
+    warnings.diagnostics.retain_mut(|diagnostic| {
+        let code = diagnostic.code.as_deref().and_then(|code| code.parse().ok()).unwrap();
+        match warning_level(opt, code) {
+            WarningLevel::Allow => false,
+            WarningLevel::Warn => true,
+            WarningLevel::Deny => {
+                diagnostic.severity = Severity::Error;
+                denied = true;
+                true
+            }
+        }
+    });
+    // A SARIF log is expected even when there is nothing to report.
+    if !warnings.diagnostics.is_empty() || opt.diagnostics_format == DiagnosticsFormat::Sarif {
+        report_diagnostics(opt, sources, &warnings);
+    }
+    if denied { Err(diagnostics_failure(opt, "Analysis failed")) } else { Ok(()) }
+}
+
+/// Parse and analyze the input file.
+/// Returns the parsed and analyzed files, after reporting
+/// diagnostics on failure.
+fn parse_and_analyze(
+    opt: &Opt,
+    sources: &mut ast::SourceDatabase,
+    input_file: &str,
+) -> Result<(ast::File, ast::File), String> {
+    match parser::parse_file_with_profiles(sources, input_file, &opt.profile) {
         Ok(file) => {
             let file =
                 filter_declarations(file, &opt.exclude_declaration, &opt.include_declaration);
-            let analyzed_file = match analyzer::analyze(&file) {
-                Ok(file) => file,
-                Err(diagnostics) => {
-                    diagnostics
-                        .emit(
-                            &sources,
-                            &mut termcolor::StandardStream::stderr(termcolor::ColorChoice::Always)
-                                .lock(),
-                        )
-                        .expect("Could not print analyzer diagnostics");
-                    return Err(String::from("Analysis failed"));
-                }
-            };
-
-            match opt.output_format {
-                OutputFormat::JSON => {
-                    println!("{}", backends::json::generate(&file).unwrap());
-                    Ok(())
-                }
-                OutputFormat::Cxx => {
-                    println!(
-                        "{}",
-                        backends::cxx::generate(
-                            &sources,
-                            &analyzed_file,
-                            opt.namespace.as_deref(),
-                            &opt.include_header,
-                            &opt.using_namespace,
-                            &opt.exclude_declaration,
-                        )
-                    );
-                    Ok(())
+            match analyzer::analyze_with_warnings(&file) {
+                Ok((analyzed_file, warnings)) => {
+                    report_warnings(opt, sources, warnings)?;
+                    Ok((file, analyzed_file))
                 }
-                OutputFormat::Python => {
-                    println!(
-                        "{}",
-                        backends::python::generate(
-                            &sources,
-                            &analyzed_file,
-                            opt.custom_field.first().map(String::as_str),
-                            &opt.exclude_declaration
-                        )
-                    );
-                    Ok(())
-                }
-                OutputFormat::Rust => {
-                    println!(
-                        "{}",
-                        backends::rust::generate(&sources, &analyzed_file, &opt.custom_field)
-                    );
-                    Ok(())
-                }
-                #[cfg(feature = "java")]
-                OutputFormat::Java => {
-                    let output_dir = opt.output_dir.as_ref().ok_or(String::from(
-                        "'--output-dir' is required for '--output-format java'",
-                    ))?;
-                    let package = opt
-                        .java_package
-                        .as_ref()
-                        .ok_or("'--java-package' is required for '--output-format java'")?;
-
-                    backends::java::generate(
-                        &sources,
-                        &analyzed_file,
-                        &opt.custom_field,
-                        std::path::Path::new(output_dir),
-                        package,
-                    )
-                }
-                #[cfg(not(feature = "java"))]
-                OutputFormat::Java => {
-                    Err(String::from("For Java support, please recompile with the 'java' feature"))
+                Err(diagnostics) => {
+                    report_diagnostics(opt, sources, &diagnostics);
+                    Err(diagnostics_failure(opt, "Analysis failed"))
                 }
             }
         }
 
         Err(err) => {
-            let writer = termcolor::StandardStream::stderr(termcolor::ColorChoice::Always);
-            let config = term::Config::default();
-            term::emit_to_write_style(&mut writer.lock(), &config, &sources, &err)
-                .expect("Could not print error");
-            Err(String::from("Error while parsing input"))
+            report_diagnostics(opt, sources, &analyzer::Diagnostics { diagnostics: vec![err] });
+            Err(diagnostics_failure(opt, "Error while parsing input"))
+        }
+    }
+}
+
+fn generate_backend(opt: &Opt, input_file: &str) -> Result<(), String> {
+    let mut sources = ast::SourceDatabase::new();
+    let (file, analyzed_file) = parse_and_analyze(opt, &mut sources, input_file)?;
+
+    match opt.output_format {
+        OutputFormat::JSON => {
+            println!("{}", backends::json::generate(&file).unwrap());
+            Ok(())
+        }
+        OutputFormat::Cxx => {
+            println!(
+                "{}",
+                backends::cxx::generate(
+                    &sources,
+                    &analyzed_file,
+                    opt.namespace.as_deref(),
+                    &opt.include_header,
+                    &opt.using_namespace,
+                    &opt.exclude_declaration,
+                )
+            );
+            Ok(())
+        }
+        OutputFormat::Python => {
+            println!(
+                "{}",
+                backends::python::generate(
+                    &sources,
+                    &analyzed_file,
+                    opt.custom_field.first().map(String::as_str),
+                    &opt.exclude_declaration
+                )
+            );
+            Ok(())
+        }
+        OutputFormat::Rust => {
+            println!("{}", backends::rust::generate(&sources, &analyzed_file, &opt.custom_field));
+            Ok(())
+        }
+        #[cfg(feature = "java")]
+        OutputFormat::Java => {
+            let output_dir = opt
+                .output_dir
+                .as_ref()
+                .ok_or(String::from("'--output-dir' is required for '--output-format java'"))?;
+            let package = opt
+                .java_package
+                .as_ref()
+                .ok_or("'--java-package' is required for '--output-format java'")?;
+
+            backends::java::generate(
+                &sources,
+                &analyzed_file,
+                &opt.custom_field,
+                std::path::Path::new(output_dir),
+                package,
+            )
+        }
+        #[cfg(not(feature = "java"))]
+        OutputFormat::Java => {
+            Err(String::from("For Java support, please recompile with the 'java' feature"))
         }
     }
 }
@@ -265,22 +525,99 @@ fn generate_tests(opt: &Opt, test_file: &str, _input_file: &str) -> Result<(), S
     }
 }
 
-fn main() -> Result<(), String> {
+fn generate_declared_tests(opt: &Opt, input_file: &str) -> Result<(), String> {
+    let mut sources = ast::SourceDatabase::new();
+    let (_, analyzed_file) = parse_and_analyze(opt, &mut sources, input_file)?;
+
+    match opt.output_format {
+        OutputFormat::Cxx => {
+            println!(
+                "{}",
+                backends::cxx::generate_tests(
+                    &sources,
+                    &analyzed_file,
+                    opt.namespace.as_deref(),
+                    &opt.include_header,
+                    &opt.using_namespace,
+                )
+            );
+            Ok(())
+        }
+        OutputFormat::Python => {
+            println!("{}", backends::python::generate_tests(&sources, &analyzed_file));
+            Ok(())
+        }
+        OutputFormat::Rust => {
+            println!("{}", backends::rust::test::generate_declared_tests(&analyzed_file)?);
+            Ok(())
+        }
+        #[cfg(feature = "java")]
+        OutputFormat::Java => {
+            let output_dir = opt
+                .output_dir
+                .as_ref()
+                .ok_or(String::from("'--output-dir' is required for '--output-format java'"))?;
+            let package = opt
+                .java_package
+                .as_ref()
+                .ok_or("'--java-package' is required for '--output-format java'")?;
+
+            backends::java::test::generate_declared_tests(
+                &sources,
+                &analyzed_file,
+                std::path::Path::new(output_dir),
+                package.clone(),
+            )
+        }
+        _ => Err(format!(
+            "Declared tests cannot be generated for the format {:?}",
+            opt.output_format
+        )),
+    }
+}
+
+fn main() -> ExitCode {
     let opt: Opt = argh::from_env();
+    match run(&opt) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("Error: {message:?}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
 
+fn run(opt: &Opt) -> Result<(), String> {
     if opt.version {
         println!("pdlc {}\nCopyright (C) 2026 Google LLC", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
 
+    if let Some(code) = opt.explain.as_ref() {
+        let explanation =
+            match (code.parse::<analyzer::ErrorCode>(), code.parse::<analyzer::WarningCode>()) {
+                (Ok(code), _) => code.explanation(),
+                (_, Ok(code)) => code.explanation(),
+                _ => return Err(format!("unknown error code {code:?}")),
+            };
+        print!("{explanation}");
+        return Ok(());
+    }
+
+    check_warning_names(opt)?;
+
     let Some(input_file) = opt.input_file.as_ref() else {
         return Err("No input file is specified".to_owned());
     };
 
     if let Some(test_file) = opt.test_file.as_ref() {
-        generate_tests(&opt, test_file, input_file)?
+        generate_tests(opt, test_file, input_file)?
+    } else if opt.test_declarations {
+        generate_declared_tests(opt, input_file)?
     } else {
-        generate_backend(&opt, input_file)?
+        generate_backend(opt, input_file)?
     }
 
     Ok(())
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use crate::ast;
-use codespan_reporting::diagnostic::Diagnostic;
+use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::files;
+use heck::ToUpperCamelCase;
 use pest::iterators::{Pair, Pairs};
 use pest::{Parser, Token};
 use std::iter::{Filter, Peekable};
@@ -30,7 +31,7 @@ use std::iter::{Filter, Peekable};
 //   currently not implemented in pest:
 //   https://github.com/pest-parser/pest/issues/520
 #[derive(pest_derive::Parser)]
-#[grammar_inline = r#"
+#[grammar_inline = r##"
 WHITESPACE = _{ " " | "\n" | "\r" | "\t" }
 COMMENT = { block_comment | line_comment }
 
@@ -50,19 +51,36 @@ hexvalue = @{ ("0x"|"0X") ~ hexdigit+ }
 integer = @{ hexvalue | intvalue }
 string = @{ "\"" ~ (!"\"" ~ ANY)* ~ "\"" }
 size_modifier = @{ "+" ~ intvalue }
+size_bound = { ".." ~ integer }
+decimal = @{ digit+ ~ ("." ~ digit+)? }
 
 ENUM = @{ "enum" ~ WHITESPACE }
+FLAGS = @{ "flags" ~ WHITESPACE }
 PACKET = @{ "packet" ~ WHITESPACE }
 STRUCT = @{ "struct" ~ WHITESPACE }
 GROUP = @{ "group" ~ WHITESPACE }
 CHECKSUM = @{ "checksum" ~ WHITESPACE }
 CUSTOM_FIELD = @{ "custom_field" ~ WHITESPACE }
 TEST = @{ "test" ~ WHITESPACE }
+FAIL = @{ "fail" ~ WHITESPACE }
+SCALE = @{ "scale" ~ WHITESPACE }
+UNIT = @{ "unit" ~ WHITESPACE }
+BIND = @{ "bind" ~ WHITESPACE }
+AS = @{ "as" ~ WHITESPACE }
+RESPONSE = @{ "response" ~ WHITESPACE }
+SIZE = @{ "size" ~ WHITESPACE }
+ASSERT = @{ "assert" ~ WHITESPACE }
 
 endianness_declaration = ${ ("little_endian_packets" | "big_endian_packets") ~ WHITESPACE }
+endianness = @{ ("little_endian" | "big_endian") ~ !alphanum }
+bit_order = @{ ("lsb_first" | "msb_first") ~ !alphanum }
+size_comparison = @{ "==" | "<=" | ">=" }
+size_assertion = { SIZE ~ size_comparison ~ integer }
 
-enum_value = { identifier ~ "=" ~ integer }
+enum_value = { identifier ~ ("|" ~ identifier)* ~ "=" ~ integer ~ string? }
 enum_value_list = { enum_value ~ ("," ~ enum_value)* ~ ","? }
+flag_value = { identifier ~ "=" ~ integer }
+flag_value_list = { flag_value ~ ("," ~ flag_value)* ~ ","? }
 enum_range = {
     identifier ~ "=" ~ integer ~ ".." ~ integer ~ ("{" ~
         enum_value_list ~
@@ -72,31 +90,46 @@ enum_other = { identifier ~ "=" ~ ".." }
 enum_tag = { enum_range | enum_value | enum_other }
 enum_tag_list = { enum_tag ~ ("," ~ enum_tag)* ~ ","? }
 enum_declaration = {
-    ENUM ~ identifier ~ ":" ~ integer ~ "{" ~
+    ENUM ~ identifier ~ ":" ~ (integer | identifier ~ (":" ~ integer)?) ~ "{" ~
         enum_tag_list ~
     "}"
 }
 
+flags_declaration = {
+    FLAGS ~ identifier ~ ":" ~ integer ~ "{" ~
+        flag_value_list ~
+    "}"
+}
+
 constraint = { identifier ~ "=" ~ (identifier|integer) }
 constraint_list = { constraint ~ ("," ~ constraint)* }
 
 checksum_field = { "_checksum_start_" ~ "(" ~ identifier ~ ")" }
 padding_field = { "_padding_" ~ "[" ~ integer ~ "]" }
-size_field = { "_size_" ~ "(" ~ (identifier|payload_identifier|body_identifier)  ~ ")" ~ ":" ~ integer }
+size_unit = @{ "bits" | "octets" | ("words" ~ intvalue) }
+size_field = { "_size_" ~ "(" ~ (identifier|payload_identifier|body_identifier) ~ ("," ~ size_unit)? ~ ")" ~ ":" ~ integer }
 count_field = { "_count_" ~ "(" ~ identifier ~ ")" ~ ":" ~ integer }
 elementsize_field = { "_elementsize_" ~ "(" ~ identifier ~ ")" ~ ":" ~ integer }
 body_field = @{ "_body_" }
-payload_field = { "_payload_" ~ (":" ~ "[" ~ size_modifier ~ "]")? }
+payload_field = { "_payload_" ~ (":" ~ "[" ~ (size_modifier|size_bound) ~ "]")? }
 fixed_field = { "_fixed_" ~ "=" ~ (
     (integer ~ ":" ~ integer) |
     (identifier ~ ":" ~ identifier)
 )}
 reserved_field = { "_reserved_" ~ ":" ~ integer }
-array_field = { identifier ~ ":" ~ (integer|identifier) ~
-    "[" ~ (size_modifier|integer)? ~ "]"
+inline_struct = { "{" ~ field_list? ~ "}" }
+array_field = { identifier ~ ":" ~ (integer|identifier|inline_struct) ~
+    "[" ~ (size_modifier|size_bound|integer)? ~ "]"
+}
+scalar_field = {
+    identifier ~ ":" ~ integer ~ endianness? ~
+    (SCALE ~ decimal ~ (UNIT ~ string)?)? ~
+    ("=" ~ integer)?
+}
+typedef_field = {
+    identifier ~ ":" ~
+    (inline_struct | identifier ~ endianness? ~ ("=" ~ identifier)?)
 }
-scalar_field = { identifier ~ ":" ~ integer }
-typedef_field = { identifier ~ ":" ~ identifier }
 group_field = { identifier ~ ("{" ~ constraint_list? ~ "}")? }
 
 field_desc = _{
@@ -114,24 +147,52 @@ field_desc = _{
     typedef_field |
     group_field
 }
-field = { field_desc ~ ("if" ~ constraint)? }
-field_list = { field ~ ("," ~ field)* ~ ","? }
+field = { !ASSERT ~ field_desc ~ ("if" ~ constraint)? }
+extension_block = { "_extension_" ~ "{" ~ field ~ ("," ~ field)* ~ ","? ~ "}" }
+profile_condition = { "profile" ~ "(" ~ identifier ~ ("," ~ identifier)* ~ ")" }
+profile_attribute = { "#[" ~ profile_condition ~ "]" }
+profile_block = { "if" ~ profile_condition ~ "{" ~ field_list? ~ "}" }
+field_list = {
+    (extension_block | profile_block | field) ~
+    ("," ~ (extension_block | profile_block | field))* ~ ","?
+}
+
+product_op = @{ "*" }
+sum_op = @{ "+" | "-" }
+comparison_op = @{ "==" | "!=" | "<=" | ">=" | "<" | ">" }
+length_expr = { "len" ~ "(" ~ identifier ~ ")" }
+atom_expr = _{ integer | length_expr | identifier | "(" ~ invariant_expr ~ ")" }
+product_expr = { atom_expr ~ (product_op ~ atom_expr)* }
+sum_expr = { product_expr ~ (sum_op ~ product_expr)* }
+comparison_expr = { sum_expr ~ (comparison_op ~ sum_expr)? }
+and_expr = { comparison_expr ~ ("&&" ~ comparison_expr)* }
+invariant_expr = { and_expr ~ ("||" ~ and_expr)* }
+invariant = { ASSERT ~ invariant_expr }
+invariant_list = { invariant ~ ("," ~ invariant)* ~ ","? }
 
 packet_declaration = {
    PACKET ~ identifier ~
-        (":" ~ identifier)? ~
+        (":" ~ !size_assertion ~ identifier)? ~
            ("(" ~ constraint_list ~ ")")? ~
+           endianness? ~
+           bit_order? ~
+           (":" ~ size_assertion)? ~
     "{" ~
         field_list? ~
+        invariant_list? ~
     "}"
 }
 
 struct_declaration = {
     STRUCT ~ identifier ~
-        (":" ~ identifier)? ~
+        (":" ~ !size_assertion ~ identifier)? ~
            ("(" ~ constraint_list ~ ")")? ~
+           endianness? ~
+           bit_order? ~
+           (":" ~ size_assertion)? ~
     "{" ~
         field_list? ~
+        invariant_list? ~
     "}"
 }
 
@@ -147,7 +208,17 @@ custom_field_declaration = {
     CUSTOM_FIELD ~ identifier ~ (":" ~ integer)? ~ string
 }
 
-test_case = { string }
+test_error = @{
+    ("array_size" | "bound" | "constraint_value" | "depth_limit" | "enum_value" |
+     "fixed_value" | "invariant" | "length" | "size_assertion" | "size_bound" |
+     "size_unit" | "trailing_bytes" | "trailing_size") ~ !alphanum
+}
+test_field = { identifier ~ ":" ~ (identifier|integer) }
+test_field_list = _{ test_field ~ ("," ~ test_field)* ~ ","? }
+test_case = {
+    FAIL ~ string ~ "=>" ~ test_error |
+    string ~ ("=>" ~ "{" ~ test_field_list? ~ "}")?
+}
 test_case_list = _{ test_case ~ ("," ~ test_case)* ~ ","? }
 test_declaration = {
     TEST ~ identifier ~ "{" ~
@@ -155,23 +226,36 @@ test_declaration = {
     "}"
 }
 
+bind_declaration = {
+    BIND ~ identifier ~ "." ~ (payload_identifier | body_identifier | identifier) ~
+        AS ~ identifier ~ ("if" ~ constraint_list)?
+}
+
+response_declaration = {
+    RESPONSE ~ identifier ~ "->" ~ identifier
+}
+
 declaration = _{
     enum_declaration |
+    flags_declaration |
     packet_declaration |
     struct_declaration |
     group_declaration |
     checksum_declaration |
     custom_field_declaration |
-    test_declaration
+    test_declaration |
+    bind_declaration |
+    response_declaration
 }
 
 file = {
     SOI ~
     endianness_declaration ~
-    declaration* ~
+    bit_order? ~
+    (profile_attribute? ~ declaration)* ~
     EOI
 }
-"#]
+"##]
 pub struct PDLParser;
 
 type Node<'i> = Pair<'i, Rule>;
@@ -180,6 +264,11 @@ struct Context<'a> {
     file: ast::FileId,
     line_starts: &'a Vec<usize>,
     key: std::cell::Cell<usize>,
+    /// Declarations generated for the inline struct definitions
+    /// of the declaration being parsed.
+    inline_decls: std::cell::RefCell<Vec<ast::Decl>>,
+    /// Selected build profiles.
+    profiles: &'a [String],
 }
 
 trait Helpers<'i> {
@@ -187,6 +276,7 @@ trait Helpers<'i> {
     fn as_loc(&self, context: &Context) -> ast::SourceRange;
     fn as_string(&self) -> String;
     fn as_usize(&self) -> Result<usize, String>;
+    fn as_u128(&self) -> Result<u128, String>;
 }
 
 impl Context<'_> {
@@ -197,6 +287,15 @@ impl Context<'_> {
     fn decl_key(&self) -> ast::DeclKey {
         ast::DeclKey(self.key.replace(self.key.get() + 1))
     }
+
+    /// Check whether the profile condition `node` names one of the
+    /// selected build profiles.
+    fn is_profile_selected(&self, node: Node<'_>) -> Result<bool, String> {
+        if node.as_rule() != Rule::profile_condition {
+            return err_unexpected_rule(Rule::profile_condition, node.as_rule());
+        }
+        Ok(node.children().any(|profile| self.profiles.contains(&profile.as_string())))
+    }
 }
 
 impl<'i> Helpers<'i> for Node<'i> {
@@ -228,6 +327,16 @@ impl<'i> Helpers<'i> for Node<'i> {
                 .map_err(|_| format!("cannot convert '{}' to usize", self.as_str()))
         }
     }
+
+    fn as_u128(&self) -> Result<u128, String> {
+        let text = self.as_str();
+        if let Some(num) = text.strip_prefix("0x") {
+            u128::from_str_radix(num, 16)
+                .map_err(|_| format!("cannot convert '{}' to u128", self.as_str()))
+        } else {
+            text.parse::<u128>().map_err(|_| format!("cannot convert '{}' to u128", self.as_str()))
+        }
+    }
 }
 
 fn err_unexpected_rule<T>(expected: Rule, found: Rule) -> Result<T, String> {
@@ -266,6 +375,31 @@ fn parse_integer_opt(iter: &mut NodeIterator<'_>) -> Result<Option<usize>, Strin
     maybe(iter, Rule::integer).map(|n| n.as_usize()).transpose()
 }
 
+/// Parse an optional field value. Values are parsed as `u128`
+/// to cover the scalar fields wider than 64 bits.
+fn parse_value_opt(iter: &mut NodeIterator<'_>) -> Result<Option<u128>, String> {
+    maybe(iter, Rule::integer).map(|n| n.as_u128()).transpose()
+}
+
+/// Parse a tag identifier or a field value.
+fn parse_identifier_or_value(
+    iter: &mut NodeIterator<'_>,
+) -> Result<(Option<String>, Option<u128>), String> {
+    match iter.next() {
+        Some(n) if n.as_rule() == Rule::identifier => Ok((Some(n.as_string()), None)),
+        Some(n) if n.as_rule() == Rule::integer => Ok((None, Some(n.as_u128()?))),
+        Some(n) => Err(format!(
+            "expected rule {:?} or {:?}, got {:?}",
+            Rule::identifier,
+            Rule::integer,
+            n.as_rule()
+        )),
+        None => {
+            Err(format!("expected rule {:?} or {:?}, got nothing", Rule::identifier, Rule::integer))
+        }
+    }
+}
+
 fn parse_identifier_or_integer(
     iter: &mut NodeIterator<'_>,
 ) -> Result<(Option<String>, Option<usize>), String> {
@@ -292,10 +426,32 @@ fn parse_string<'i>(iter: &mut impl Iterator<Item = Node<'i>>) -> Result<String,
         .map(|s| s.to_owned())
 }
 
+fn parse_string_opt(iter: &mut NodeIterator<'_>) -> Result<Option<String>, String> {
+    maybe(iter, Rule::string).map(|n| parse_string(&mut std::iter::once(n))).transpose()
+}
+
 fn parse_size_modifier_opt(iter: &mut NodeIterator<'_>) -> Option<String> {
     maybe(iter, Rule::size_modifier).map(|n| n.as_string())
 }
 
+fn parse_size_bound_opt(iter: &mut NodeIterator<'_>) -> Result<Option<usize>, String> {
+    maybe(iter, Rule::size_bound).map(|n| parse_integer(&mut n.children())).transpose()
+}
+
+fn parse_size_unit_opt(iter: &mut NodeIterator<'_>) -> Result<ast::SizeUnit, String> {
+    match maybe(iter, Rule::size_unit) {
+        None => Ok(ast::SizeUnit::default()),
+        Some(n) => match n.as_str() {
+            "bits" => Ok(ast::SizeUnit::Bits),
+            "octets" => Ok(ast::SizeUnit::Octets),
+            s => s["words".len()..]
+                .parse::<usize>()
+                .map(ast::SizeUnit::Words)
+                .map_err(|e| format!("can't parse `{s}` as a size unit: {e}")),
+        },
+    }
+}
+
 fn parse_endianness(node: Node<'_>, context: &Context) -> Result<ast::Endianness, String> {
     if node.as_rule() != Rule::endianness_declaration {
         err_unexpected_rule(Rule::endianness_declaration, node.as_rule())
@@ -311,6 +467,118 @@ fn parse_endianness(node: Node<'_>, context: &Context) -> Result<ast::Endianness
     }
 }
 
+fn parse_endianness_value_opt(
+    iter: &mut NodeIterator<'_>,
+) -> Result<Option<ast::EndiannessValue>, String> {
+    maybe(iter, Rule::endianness)
+        .map(|n| match n.as_str() {
+            "little_endian" => Ok(ast::EndiannessValue::LittleEndian),
+            "big_endian" => Ok(ast::EndiannessValue::BigEndian),
+            _ => unreachable!(),
+        })
+        .transpose()
+}
+
+fn parse_bit_order(node: Node<'_>) -> Result<ast::BitOrder, String> {
+    if node.as_rule() != Rule::bit_order {
+        err_unexpected_rule(Rule::bit_order, node.as_rule())
+    } else {
+        Ok(match node.as_str() {
+            "lsb_first" => ast::BitOrder::LsbFirst,
+            "msb_first" => ast::BitOrder::MsbFirst,
+            _ => unreachable!(),
+        })
+    }
+}
+
+fn parse_bit_order_opt(iter: &mut NodeIterator<'_>) -> Result<Option<ast::BitOrder>, String> {
+    maybe(iter, Rule::bit_order).map(parse_bit_order).transpose()
+}
+
+fn parse_size_assertion_opt(
+    iter: &mut NodeIterator<'_>,
+) -> Result<Option<ast::SizeAssertion>, String> {
+    maybe(iter, Rule::size_assertion)
+        .map(|n| {
+            let mut children = n.children();
+            expect(&mut children, Rule::SIZE)?;
+            let comparison = match expect(&mut children, Rule::size_comparison)?.as_str() {
+                "==" => ast::SizeComparison::Equal,
+                "<=" => ast::SizeComparison::LessOrEqual,
+                ">=" => ast::SizeComparison::GreaterOrEqual,
+                _ => unreachable!(),
+            };
+            let size = parse_integer(&mut children)?;
+            Ok(ast::SizeAssertion { comparison, size })
+        })
+        .transpose()
+}
+
+fn parse_expr(node: Node<'_>) -> Result<ast::Expr, String> {
+    match node.as_rule() {
+        Rule::integer => Ok(ast::Expr::Integer { value: node.as_usize()? }),
+        Rule::identifier => Ok(ast::Expr::Field { id: node.as_string() }),
+        Rule::length_expr => Ok(ast::Expr::Length { id: parse_identifier(&mut node.children())? }),
+        Rule::invariant_expr | Rule::and_expr => {
+            let op = if node.as_rule() == Rule::invariant_expr {
+                ast::BinaryOp::Or
+            } else {
+                ast::BinaryOp::And
+            };
+            let mut children = node.children();
+            let mut expr = parse_expr(children.next().ok_or("expected expression")?)?;
+            for rhs in children {
+                expr =
+                    ast::Expr::Binary { op, lhs: Box::new(expr), rhs: Box::new(parse_expr(rhs)?) };
+            }
+            Ok(expr)
+        }
+        Rule::comparison_expr | Rule::sum_expr | Rule::product_expr => {
+            let mut children = node.children();
+            let mut expr = parse_expr(children.next().ok_or("expected expression")?)?;
+            while let Some(op) = children.next() {
+                let op = match op.as_str() {
+                    "*" => ast::BinaryOp::Mul,
+                    "+" => ast::BinaryOp::Add,
+                    "-" => ast::BinaryOp::Sub,
+                    "==" => ast::BinaryOp::Eq,
+                    "!=" => ast::BinaryOp::Ne,
+                    "<" => ast::BinaryOp::Lt,
+                    "<=" => ast::BinaryOp::Le,
+                    ">" => ast::BinaryOp::Gt,
+                    ">=" => ast::BinaryOp::Ge,
+                    _ => unreachable!(),
+                };
+                let rhs = parse_expr(children.next().ok_or("expected expression")?)?;
+                expr = ast::Expr::Binary { op, lhs: Box::new(expr), rhs: Box::new(rhs) };
+            }
+            Ok(expr)
+        }
+        rule => Err(format!("expected expression, got {:?}", rule)),
+    }
+}
+
+fn parse_invariant(node: Node<'_>, context: &Context) -> Result<ast::Invariant, String> {
+    if node.as_rule() != Rule::invariant {
+        err_unexpected_rule(Rule::invariant, node.as_rule())
+    } else {
+        let loc = node.as_loc(context);
+        let mut children = node.children();
+        expect(&mut children, Rule::ASSERT)?;
+        let expr = expect(&mut children, Rule::invariant_expr)?;
+        let text = expr.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+        Ok(ast::Invariant { loc, text, expr: parse_expr(expr)? })
+    }
+}
+
+fn parse_invariant_list_opt(
+    iter: &mut NodeIterator<'_>,
+    context: &Context,
+) -> Result<Vec<ast::Invariant>, String> {
+    maybe(iter, Rule::invariant_list)
+        .map_or(Ok(vec![]), |n| n.children().map(|n| parse_invariant(n, context)).collect())
+}
+
 fn parse_constraint(node: Node<'_>, context: &Context) -> Result<ast::Constraint, String> {
     if node.as_rule() != Rule::constraint {
         err_unexpected_rule(Rule::constraint, node.as_rule())
@@ -318,7 +586,7 @@ fn parse_constraint(node: Node<'_>, context: &Context) -> Result<ast::Constraint
         let loc = node.as_loc(context);
         let mut children = node.children();
         let id = parse_identifier(&mut children)?;
-        let (tag_id, value) = parse_identifier_or_integer(&mut children)?;
+        let (tag_id, value) = parse_identifier_or_value(&mut children)?;
         Ok(ast::Constraint { id, loc, value, tag_id })
     }
 }
@@ -331,6 +599,62 @@ fn parse_constraint_list_opt(
         .map_or(Ok(vec![]), |n| n.children().map(|n| parse_constraint(n, context)).collect())
 }
 
+fn parse_test_field(node: Node<'_>, context: &Context) -> Result<ast::Constraint, String> {
+    if node.as_rule() != Rule::test_field {
+        err_unexpected_rule(Rule::test_field, node.as_rule())
+    } else {
+        let loc = node.as_loc(context);
+        let mut children = node.children();
+        let id = parse_identifier(&mut children)?;
+        let (tag_id, value) = parse_identifier_or_value(&mut children)?;
+        Ok(ast::Constraint { id, loc, value, tag_id })
+    }
+}
+
+fn parse_test_error(node: Node<'_>) -> Result<ast::TestError, String> {
+    match node.as_str() {
+        "array_size" => Ok(ast::TestError::ArraySize),
+        "bound" => Ok(ast::TestError::Bound),
+        "constraint_value" => Ok(ast::TestError::ConstraintValue),
+        "depth_limit" => Ok(ast::TestError::DepthLimit),
+        "enum_value" => Ok(ast::TestError::EnumValue),
+        "fixed_value" => Ok(ast::TestError::FixedValue),
+        "invariant" => Ok(ast::TestError::Invariant),
+        "length" => Ok(ast::TestError::Length),
+        "size_assertion" => Ok(ast::TestError::SizeAssertion),
+        "size_bound" => Ok(ast::TestError::SizeBound),
+        "size_unit" => Ok(ast::TestError::SizeUnit),
+        "trailing_bytes" => Ok(ast::TestError::TrailingBytes),
+        "trailing_size" => Ok(ast::TestError::TrailingSize),
+        _ => Err(format!("unexpected test error {}", node.as_str())),
+    }
+}
+
+fn parse_test_case(node: Node<'_>, context: &Context) -> Result<ast::TestCase, String> {
+    if node.as_rule() != Rule::test_case {
+        err_unexpected_rule(Rule::test_case, node.as_rule())
+    } else {
+        let loc = node.as_loc(context);
+        let mut children = node.children();
+        let fail = maybe(&mut children, Rule::FAIL).is_some();
+        let input = parse_string(&mut children)?;
+        let error = if fail {
+            Some(parse_test_error(expect(&mut children, Rule::test_error)?)?)
+        } else {
+            None
+        };
+        let fields = children.map(|n| parse_test_field(n, context)).collect::<Result<_, _>>()?;
+        Ok(ast::TestCase { loc, input, fields, error })
+    }
+}
+
+fn parse_test_case_list(
+    iter: &mut NodeIterator<'_>,
+    context: &Context,
+) -> Result<Vec<ast::TestCase>, String> {
+    iter.map(|n| parse_test_case(n, context)).collect()
+}
+
 fn parse_enum_value(node: Node<'_>, context: &Context) -> Result<ast::TagValue, String> {
     if node.as_rule() != Rule::enum_value {
         err_unexpected_rule(Rule::enum_value, node.as_rule())
@@ -338,8 +662,13 @@ fn parse_enum_value(node: Node<'_>, context: &Context) -> Result<ast::TagValue,
         let loc = node.as_loc(context);
         let mut children = node.children();
         let id = parse_identifier(&mut children)?;
+        let mut aliases = vec![];
+        while let Some(alias) = parse_identifier_opt(&mut children)? {
+            aliases.push(alias);
+        }
         let value = parse_integer(&mut children)?;
-        Ok(ast::TagValue { id, loc, value })
+        let display_name = parse_string_opt(&mut children)?;
+        Ok(ast::TagValue { id, loc, value, display_name, aliases })
     }
 }
 
@@ -351,6 +680,26 @@ fn parse_enum_value_list_opt(
         .map_or(Ok(vec![]), |n| n.children().map(|n| parse_enum_value(n, context)).collect())
 }
 
+fn parse_flag_value(node: Node<'_>, context: &Context) -> Result<ast::TagValue, String> {
+    if node.as_rule() != Rule::flag_value {
+        err_unexpected_rule(Rule::flag_value, node.as_rule())
+    } else {
+        let loc = node.as_loc(context);
+        let mut children = node.children();
+        let id = parse_identifier(&mut children)?;
+        let value = parse_integer(&mut children)?;
+        Ok(ast::TagValue { id, loc, value, display_name: None, aliases: vec![] })
+    }
+}
+
+fn parse_flag_value_list(
+    iter: &mut NodeIterator<'_>,
+    context: &Context,
+) -> Result<Vec<ast::TagValue>, String> {
+    expect(iter, Rule::flag_value_list)
+        .and_then(|n| n.children().map(|n| parse_flag_value(n, context)).collect())
+}
+
 fn parse_enum_range(node: Node<'_>, context: &Context) -> Result<ast::TagRange, String> {
     if node.as_rule() != Rule::enum_range {
         err_unexpected_rule(Rule::enum_range, node.as_rule())
@@ -413,13 +762,42 @@ fn parse_enum_tag_list(
         .and_then(|n| n.children().map(|n| parse_enum_tag(n, context)).collect())
 }
 
-fn parse_field(node: Node<'_>, context: &Context) -> Result<ast::Field, String> {
+/// Parse an inline struct definition. The struct is desugared into
+/// a declaration named after the parent declaration and field.
+fn parse_inline_struct(
+    node: Node<'_>,
+    parent_id: &str,
+    field_id: &str,
+    context: &Context,
+) -> Result<String, String> {
+    let loc = node.as_loc(context);
+    let id = format!("{parent_id}{}", field_id.to_upper_camel_case());
+    let fields = parse_field_list_opt(&mut node.children(), &id, context)?;
+    context.inline_decls.borrow_mut().push(ast::Decl {
+        loc,
+        key: context.decl_key(),
+        desc: ast::DeclDesc::Struct {
+            id: id.clone(),
+            parent_id: None,
+            constraints: vec![],
+            fields,
+            invariants: vec![],
+        },
+        endianness: None,
+        bit_order: None,
+        size_assertion: None,
+    });
+    Ok(id)
+}
+
+fn parse_field(node: Node<'_>, parent_id: &str, context: &Context) -> Result<ast::Field, String> {
     let loc = node.as_loc(context);
     let mut children = node.children();
     let desc = children.next().unwrap();
     let cond = children.next();
     let rule = desc.as_rule();
     let mut children = desc.children();
+    let mut endianness = None;
     Ok(ast::Field {
         loc,
         key: context.field_key(),
@@ -441,8 +819,9 @@ fn parse_field(node: Node<'_>, context: &Context) -> Result<ast::Field, String>
                     Some(n) => err_unexpected_rule(Rule::identifier, n.as_rule())?,
                     None => err_missing_rule(Rule::identifier)?,
                 };
+                let unit = parse_size_unit_opt(&mut children)?;
                 let width = parse_integer(&mut children)?;
-                ast::FieldDesc::Size { field_id, width }
+                ast::FieldDesc::Size { field_id, width, unit }
             }
             Rule::count_field => {
                 let field_id = parse_identifier(&mut children)?;
@@ -457,11 +836,12 @@ fn parse_field(node: Node<'_>, context: &Context) -> Result<ast::Field, String>
             Rule::body_field => ast::FieldDesc::Body,
             Rule::payload_field => {
                 let size_modifier = parse_size_modifier_opt(&mut children);
-                ast::FieldDesc::Payload { size_modifier }
+                let max_size = parse_size_bound_opt(&mut children)?;
+                ast::FieldDesc::Payload { size_modifier, max_size }
             }
             Rule::fixed_field => match children.next() {
                 Some(n) if n.as_rule() == Rule::integer => {
-                    let value = n.as_usize()?;
+                    let value = n.as_u128()?;
                     let width = parse_integer(&mut children)?;
                     ast::FieldDesc::FixedScalar { width, value }
                 }
@@ -478,31 +858,58 @@ fn parse_field(node: Node<'_>, context: &Context) -> Result<ast::Field, String>
             }
             Rule::array_field => {
                 let id = parse_identifier(&mut children)?;
-                let (type_id, width) = parse_identifier_or_integer(&mut children)?;
-                let (size, size_modifier) = match children.next() {
-                    Some(n) if n.as_rule() == Rule::integer => (Some(n.as_usize()?), None),
-                    Some(n) if n.as_rule() == Rule::size_modifier => (None, Some(n.as_string())),
+                let (type_id, width) = match maybe(&mut children, Rule::inline_struct) {
+                    Some(n) => (Some(parse_inline_struct(n, parent_id, &id, context)?), None),
+                    None => parse_identifier_or_integer(&mut children)?,
+                };
+                let (size, size_modifier, max_count) = match children.next() {
+                    Some(n) if n.as_rule() == Rule::integer => (Some(n.as_usize()?), None, None),
+                    Some(n) if n.as_rule() == Rule::size_modifier => {
+                        (None, Some(n.as_string()), None)
+                    }
+                    Some(n) if n.as_rule() == Rule::size_bound => {
+                        (None, None, Some(parse_integer(&mut n.children())?))
+                    }
                     Some(n) => {
                         return Err(format!(
-                            "expected rule {:?} or {:?}, got {:?}",
+                            "expected rule {:?}, {:?} or {:?}, got {:?}",
                             Rule::integer,
                             Rule::size_modifier,
+                            Rule::size_bound,
                             n.as_rule()
                         ));
                     }
-                    None => (None, None),
+                    None => (None, None, None),
                 };
-                ast::FieldDesc::Array { id, type_id, width, size, size_modifier }
+                ast::FieldDesc::Array { id, type_id, width, size, size_modifier, max_count }
             }
             Rule::scalar_field => {
                 let id = parse_identifier(&mut children)?;
                 let width = parse_integer(&mut children)?;
-                ast::FieldDesc::Scalar { id, width }
+                endianness = parse_endianness_value_opt(&mut children)?;
+                let (scale, unit) = match maybe(&mut children, Rule::SCALE) {
+                    Some(_) => {
+                        let scale = expect(&mut children, Rule::decimal)?.as_string();
+                        let unit = match maybe(&mut children, Rule::UNIT) {
+                            Some(_) => Some(parse_string(&mut children)?),
+                            None => None,
+                        };
+                        (Some(scale), unit)
+                    }
+                    None => (None, None),
+                };
+                let default = parse_value_opt(&mut children)?;
+                ast::FieldDesc::Scalar { id, width, default, scale, unit }
             }
             Rule::typedef_field => {
                 let id = parse_identifier(&mut children)?;
-                let type_id = parse_identifier(&mut children)?;
-                ast::FieldDesc::Typedef { id, type_id }
+                let type_id = match maybe(&mut children, Rule::inline_struct) {
+                    Some(n) => parse_inline_struct(n, parent_id, &id, context)?,
+                    None => parse_identifier(&mut children)?,
+                };
+                endianness = parse_endianness_value_opt(&mut children)?;
+                let default = parse_identifier_opt(&mut children)?;
+                ast::FieldDesc::Typedef { id, type_id, default }
             }
             Rule::group_field => {
                 let group_id = parse_identifier(&mut children)?;
@@ -511,20 +918,87 @@ fn parse_field(node: Node<'_>, context: &Context) -> Result<ast::Field, String>
             }
             _ => return Err(format!("expected rule *_field, got {rule:?}")),
         },
+        endianness,
+        extension: None,
     })
 }
 
-fn parse_field_list(iter: &mut NodeIterator, context: &Context) -> Result<Vec<ast::Field>, String> {
-    expect(iter, Rule::field_list)
-        .and_then(|n| n.children().map(|n| parse_field(n, context)).collect())
+/// Parse the fields of a field list, flattening extension and profile blocks.
+/// Fields declared in an extension block are tagged with the index
+/// of the block in the declaration. Fields declared in a profile block
+/// are dropped unless the profile is selected.
+fn parse_fields(
+    node: Node<'_>,
+    parent_id: &str,
+    context: &Context,
+) -> Result<Vec<ast::Field>, String> {
+    let mut fields = vec![];
+    let mut extension = 0;
+    append_fields(node, parent_id, context, &mut fields, &mut extension)?;
+    Ok(fields)
+}
+
+fn append_fields(
+    node: Node<'_>,
+    parent_id: &str,
+    context: &Context,
+    fields: &mut Vec<ast::Field>,
+    extension: &mut usize,
+) -> Result<(), String> {
+    for node in node.children() {
+        match node.as_rule() {
+            Rule::extension_block => {
+                for node in node.children() {
+                    fields.push(ast::Field {
+                        extension: Some(*extension),
+                        ..parse_field(node, parent_id, context)?
+                    });
+                }
+                *extension += 1;
+            }
+            Rule::profile_block => {
+                let mut children = node.children();
+                let condition = expect(&mut children, Rule::profile_condition)?;
+                if context.is_profile_selected(condition)? {
+                    if let Some(node) = maybe(&mut children, Rule::field_list) {
+                        append_fields(node, parent_id, context, fields, extension)?;
+                    }
+                }
+            }
+            _ => fields.push(parse_field(node, parent_id, context)?),
+        }
+    }
+    Ok(())
+}
+
+fn parse_field_list(
+    iter: &mut NodeIterator,
+    parent_id: &str,
+    context: &Context,
+) -> Result<Vec<ast::Field>, String> {
+    expect(iter, Rule::field_list).and_then(|n| parse_fields(n, parent_id, context))
 }
 
 fn parse_field_list_opt(
     iter: &mut NodeIterator,
+    parent_id: &str,
     context: &Context,
 ) -> Result<Vec<ast::Field>, String> {
-    maybe(iter, Rule::field_list)
-        .map_or(Ok(vec![]), |n| n.children().map(|n| parse_field(n, context)).collect())
+    maybe(iter, Rule::field_list).map_or(Ok(vec![]), |n| parse_fields(n, parent_id, context))
+}
+
+/// Append the declarations generated for the inline struct definitions
+/// of the declaration being parsed. The generated declarations inherit
+/// the endianness and bit order of the parent declaration.
+fn push_inline_decls(
+    file: &mut ast::File,
+    context: &Context,
+    endianness: Option<ast::EndiannessValue>,
+    bit_order: Option<ast::BitOrder>,
+) {
+    for decl in context.inline_decls.borrow_mut().drain(..) {
+        file.declarations.push(ast::Decl { endianness, bit_order, ..decl })
+    }
 }
 
 fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String> {
@@ -550,11 +1024,21 @@ fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String
         }
     }
 
-    for node in root.children() {
+    let mut nodes = root.children();
+    while let Some(node) = nodes.next() {
         let loc = node.as_loc(context);
         let rule = node.as_rule();
         match rule {
             Rule::endianness_declaration => file.endianness = parse_endianness(node, context)?,
+            Rule::profile_attribute => {
+                // Drop the declaration following the attribute unless
+                // the profile is selected.
+                let condition = expect(&mut node.children(), Rule::profile_condition)?;
+                if !context.is_profile_selected(condition)? {
+                    nodes.next();
+                }
+            }
+            Rule::bit_order => file.bit_order = parse_bit_order(node)?,
             Rule::checksum_declaration => {
                 let mut children = node.children();
                 expect(&mut children, Rule::CHECKSUM)?;
@@ -565,6 +1049,9 @@ fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String
                     loc,
                     key: context.decl_key(),
                     desc: ast::DeclDesc::Checksum { id, function, width },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
                 })
             }
             Rule::custom_field_declaration => {
@@ -577,18 +1064,40 @@ fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String
                     loc,
                     key: context.decl_key(),
                     desc: ast::DeclDesc::CustomField { id, function, width },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
                 })
             }
             Rule::enum_declaration => {
                 let mut children = node.children();
                 expect(&mut children, Rule::ENUM)?;
                 let id = parse_identifier(&mut children)?;
-                let width = parse_integer(&mut children)?;
+                let parent_id = parse_identifier_opt(&mut children)?;
+                let width = parse_integer_opt(&mut children)?.unwrap_or(0);
                 let tags = parse_enum_tag_list(&mut children, context)?;
                 file.declarations.push(ast::Decl {
                     loc,
                     key: context.decl_key(),
-                    desc: ast::DeclDesc::Enum { id, width, tags },
+                    desc: ast::DeclDesc::Enum { id, width, tags, parent_id },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
+                })
+            }
+            Rule::flags_declaration => {
+                let mut children = node.children();
+                expect(&mut children, Rule::FLAGS)?;
+                let id = parse_identifier(&mut children)?;
+                let width = parse_integer(&mut children)?;
+                let flags = parse_flag_value_list(&mut children, context)?;
+                file.declarations.push(ast::Decl {
+                    loc,
+                    key: context.decl_key(),
+                    desc: ast::DeclDesc::Flags { id, width, flags },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
                 })
             }
             Rule::packet_declaration => {
@@ -597,11 +1106,19 @@ fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String
                 let id = parse_identifier(&mut children)?;
                 let parent_id = parse_identifier_opt(&mut children)?;
                 let constraints = parse_constraint_list_opt(&mut children, context)?;
-                let fields = parse_field_list_opt(&mut children, context)?;
+                let endianness = parse_endianness_value_opt(&mut children)?;
+                let bit_order = parse_bit_order_opt(&mut children)?;
+                let size_assertion = parse_size_assertion_opt(&mut children)?;
+                let fields = parse_field_list_opt(&mut children, &id, context)?;
+                let invariants = parse_invariant_list_opt(&mut children, context)?;
+                push_inline_decls(&mut file, context, endianness, bit_order);
                 file.declarations.push(ast::Decl {
                     loc,
                     key: context.decl_key(),
-                    desc: ast::DeclDesc::Packet { id, parent_id, constraints, fields },
+                    desc: ast::DeclDesc::Packet { id, parent_id, constraints, fields, invariants },
+                    endianness,
+                    bit_order,
+                    size_assertion,
                 })
             }
             Rule::struct_declaration => {
@@ -610,25 +1127,84 @@ fn parse_toplevel(root: Node<'_>, context: &Context) -> Result<ast::File, String
                 let id = parse_identifier(&mut children)?;
                 let parent_id = parse_identifier_opt(&mut children)?;
                 let constraints = parse_constraint_list_opt(&mut children, context)?;
-                let fields = parse_field_list_opt(&mut children, context)?;
+                let endianness = parse_endianness_value_opt(&mut children)?;
+                let bit_order = parse_bit_order_opt(&mut children)?;
+                let size_assertion = parse_size_assertion_opt(&mut children)?;
+                let fields = parse_field_list_opt(&mut children, &id, context)?;
+                let invariants = parse_invariant_list_opt(&mut children, context)?;
+                push_inline_decls(&mut file, context, endianness, bit_order);
                 file.declarations.push(ast::Decl {
                     loc,
                     key: context.decl_key(),
-                    desc: ast::DeclDesc::Struct { id, parent_id, constraints, fields },
+                    desc: ast::DeclDesc::Struct { id, parent_id, constraints, fields, invariants },
+                    endianness,
+                    bit_order,
+                    size_assertion,
                 })
             }
             Rule::group_declaration => {
                 let mut children = node.children();
                 expect(&mut children, Rule::GROUP)?;
                 let id = parse_identifier(&mut children)?;
-                let fields = parse_field_list(&mut children, context)?;
+                let fields = parse_field_list(&mut children, &id, context)?;
+                push_inline_decls(&mut file, context, None, None);
                 file.declarations.push(ast::Decl {
                     loc,
                     key: context.decl_key(),
                     desc: ast::DeclDesc::Group { id, fields },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
+                })
+            }
+            Rule::test_declaration => {
+                let mut children = node.children();
+                expect(&mut children, Rule::TEST)?;
+                let type_id = parse_identifier(&mut children)?;
+                let test_cases = parse_test_case_list(&mut children, context)?;
+                file.declarations.push(ast::Decl {
+                    loc,
+                    key: context.decl_key(),
+                    desc: ast::DeclDesc::Test { type_id, test_cases },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
+                })
+            }
+            Rule::bind_declaration => {
+                let mut children = node.children();
+                expect(&mut children, Rule::BIND)?;
+                let packet_id = parse_identifier(&mut children)?;
+                let field_id = children
+                    .next()
+                    .map(|n| n.as_string())
+                    .ok_or_else(|| "expected payload identifier, got nothing".to_owned())?;
+                expect(&mut children, Rule::AS)?;
+                let type_id = parse_identifier(&mut children)?;
+                let constraints = parse_constraint_list_opt(&mut children, context)?;
+                file.declarations.push(ast::Decl {
+                    loc,
+                    key: context.decl_key(),
+                    desc: ast::DeclDesc::Bind { packet_id, field_id, type_id, constraints },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
+                })
+            }
+            Rule::response_declaration => {
+                let mut children = node.children();
+                expect(&mut children, Rule::RESPONSE)?;
+                let request_id = parse_identifier(&mut children)?;
+                let response_id = parse_identifier(&mut children)?;
+                file.declarations.push(ast::Decl {
+                    loc,
+                    key: context.decl_key(),
+                    desc: ast::DeclDesc::Response { request_id, response_id },
+                    endianness: None,
+                    bit_order: None,
+                    size_assertion: None,
                 })
             }
-            Rule::test_declaration => {}
             Rule::EOI => (),
             _ => unreachable!(),
         }
@@ -647,16 +1223,48 @@ pub fn parse_inline(
     name: &str,
     source: String,
 ) -> Result<ast::File, Diagnostic<ast::FileId>> {
+    parse_inline_with_profiles(sources, name, source, &[])
+}
+
+/// Parse PDL source code from a string, resolving the profile-conditional
+/// declarations and fields against the selected build profiles.
+///
+/// Declarations and fields conditional on a profile not in `profiles`
+/// are removed from the parsed file.
+pub fn parse_inline_with_profiles(
+    sources: &mut ast::SourceDatabase,
+    name: &str,
+    source: String,
+    profiles: &[String],
+) -> Result<ast::File, Diagnostic<ast::FileId>> {
+    let file = sources.add(name.to_owned(), source.clone());
     let root = PDLParser::parse(Rule::file, &source)
         .map_err(|e| {
-            Diagnostic::error().with_message(format!("failed to parse input file '{name}': {e}"))
+            let range = match e.location {
+                pest::error::InputLocation::Pos(pos) => pos..pos,
+                pest::error::InputLocation::Span((start, end)) => start..end,
+            };
+            Diagnostic::error()
+                .with_message(format!(
+                    "failed to parse input file '{name}': {}",
+                    e.variant.message()
+                ))
+                .with_labels(vec![Label::primary(file, range)])
         })?
         .next()
         .unwrap();
     let line_starts: Vec<_> = files::line_starts(&source).collect();
-    let file = sources.add(name.to_owned(), source.clone());
-    parse_toplevel(root, &Context { file, line_starts: &line_starts, key: std::cell::Cell::new(0) })
-        .map_err(|e| Diagnostic::error().with_message(e))
+    parse_toplevel(
+        root,
+        &Context {
+            file,
+            line_starts: &line_starts,
+            key: std::cell::Cell::new(0),
+            inline_decls: Default::default(),
+            profiles,
+        },
+    )
+    .map_err(|e| Diagnostic::error().with_message(e))
 }
 
 /// Parse a new source file.
@@ -667,11 +1275,21 @@ pub fn parse_inline(
 pub fn parse_file(
     sources: &mut ast::SourceDatabase,
     name: &str,
+) -> Result<ast::File, Diagnostic<ast::FileId>> {
+    parse_file_with_profiles(sources, name, &[])
+}
+
+/// Parse a new source file, resolving the profile-conditional
+/// declarations and fields against the selected build profiles.
+pub fn parse_file_with_profiles(
+    sources: &mut ast::SourceDatabase,
+    name: &str,
+    profiles: &[String],
 ) -> Result<ast::File, Diagnostic<ast::FileId>> {
     let source = std::fs::read_to_string(name).map_err(|e| {
         Diagnostic::error().with_message(format!("failed to read input file '{name}': {e}"))
     })?;
-    parse_inline(sources, name, source)
+    parse_inline_with_profiles(sources, name, source, profiles)
 }
 
 #[cfg(test)]
@@ -742,4 +1360,494 @@ mod test {
         println!("{result:?}");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_default_values() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A { a: 8 = 0x40, b: B = X, c: 8 }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let fields = file.declarations[0].fields().map(|f| &f.desc).collect::<Vec<_>>();
+        assert!(matches!(fields[0], ast::FieldDesc::Scalar { default: Some(0x40), .. }));
+        assert!(
+            matches!(fields[1], ast::FieldDesc::Typedef { default: Some(tag_id), .. } if tag_id == "X")
+        );
+        assert!(matches!(fields[2], ast::FieldDesc::Scalar { default: None, .. }));
+    }
+
+    #[test]
+    fn test_parse_endianness_override() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A big_endian { a: 16, b: 16 little_endian, c: E big_endian = X }
+            struct B : A msb_first { d: 8 }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let a = &file.declarations[0];
+        let b = &file.declarations[1];
+        let fields = a.fields().collect::<Vec<_>>();
+        assert_eq!(a.endianness, Some(ast::EndiannessValue::BigEndian));
+        assert_eq!(b.endianness, None);
+        assert_eq!(b.bit_order, Some(ast::BitOrder::MsbFirst));
+        assert_eq!(fields[0].endianness, None);
+        assert_eq!(fields[1].endianness, Some(ast::EndiannessValue::LittleEndian));
+        assert_eq!(fields[2].endianness, Some(ast::EndiannessValue::BigEndian));
+        assert!(
+            matches!(&fields[2].desc, ast::FieldDesc::Typedef { default: Some(tag_id), .. } if tag_id == "X")
+        );
+        assert_eq!(file.field_endianness(a, fields[0]), ast::EndiannessValue::BigEndian);
+        assert_eq!(file.field_endianness(a, fields[1]), ast::EndiannessValue::LittleEndian);
+        assert_eq!(file.decl_endianness(b), ast::EndiannessValue::LittleEndian);
+    }
+
+    #[test]
+    fn test_parse_size_unit() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A {
+                _size_(a): 8,
+                _size_(b, bits): 16,
+                _size_(c, words32): 8,
+                a: 8[],
+                b: 8[],
+                c: 8[],
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let units = file.declarations[0]
+            .fields()
+            .filter_map(|field| match &field.desc {
+                ast::FieldDesc::Size { unit, .. } => Some(*unit),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            units,
+            vec![ast::SizeUnit::Octets, ast::SizeUnit::Bits, ast::SizeUnit::Words(32)]
+        );
+    }
+
+    #[test]
+    fn test_parse_test_declaration() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            test A {
+                "\x00",
+                "\x00\x04" => { a: 0, b: X },
+                fail "\x00\x01" => trailing_bytes,
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let ast::DeclDesc::Test { type_id, test_cases } = &file.declarations[0].desc else {
+            panic!("expected test declaration");
+        };
+        assert_eq!(type_id, "A");
+        assert_eq!(
+            test_cases
+                .iter()
+                .map(|test_case| (
+                    test_case.input.as_str(),
+                    test_case.fields.len(),
+                    test_case.error
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (r"\x00", 0, None),
+                (r"\x00\x04", 2, None),
+                (r"\x00\x01", 0, Some(ast::TestError::TrailingBytes)),
+            ]
+        );
+        assert_eq!(test_cases[1].fields[0].value, Some(0));
+        assert_eq!(test_cases[1].fields[1].tag_id.as_deref(), Some("X"));
+        assert_eq!(test_cases[1].bytes(), Ok(vec![0x00, 0x04]));
+    }
+
+    #[test]
+    fn test_parse_test_errors() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            test A {
+                fail "\x00" => bound,
+                fail "\x00" => size_bound,
+                fail "\x00" => depth_limit,
+                fail "\x00" => size_assertion,
+                fail "\x00" => invariant,
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let ast::DeclDesc::Test { test_cases, .. } = &file.declarations[0].desc else {
+            panic!("expected test declaration");
+        };
+        assert_eq!(
+            test_cases.iter().map(|test_case| test_case.error).collect::<Vec<_>>(),
+            vec![
+                Some(ast::TestError::Bound),
+                Some(ast::TestError::SizeBound),
+                Some(ast::TestError::DepthLimit),
+                Some(ast::TestError::SizeAssertion),
+                Some(ast::TestError::Invariant),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bit_order() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            big_endian_packets msb_first
+            packet A { a: 4, b: 4 }
+            struct B lsb_first { a: 4, b: 4 }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        assert_eq!(file.bit_order, ast::BitOrder::MsbFirst);
+        assert_eq!(file.declarations[0].bit_order, None);
+        assert_eq!(file.declarations[1].bit_order, Some(ast::BitOrder::LsbFirst));
+        assert_eq!(file.bit_order(&file.declarations[0]), ast::BitOrder::MsbFirst);
+        assert_eq!(file.bit_order(&file.declarations[1]), ast::BitOrder::LsbFirst);
+    }
+
+    #[test]
+    fn test_parse_flags_declaration() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            flags Permissions : 16 {
+                READ = 0x1,
+                WRITE = 0x2,
+                EXECUTE = 0x4,
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let ast::DeclDesc::Flags { id, flags, width } = &file.declarations[0].desc else {
+            panic!("expected flags declaration");
+        };
+        assert_eq!(id, "Permissions");
+        assert_eq!(*width, 16);
+        assert_eq!(
+            flags.iter().map(|flag| (flag.id.as_str(), flag.value)).collect::<Vec<_>>(),
+            vec![("READ", 0x1), ("WRITE", 0x2), ("EXECUTE", 0x4)]
+        );
+    }
+
+    #[test]
+    fn test_parse_enum_extension() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            enum A : 8 { X = 1 }
+            enum B : A { Y = 2 }
+            enum C : B : 8 { Z = 3 }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let enums = file
+            .declarations
+            .iter()
+            .map(|decl| match &decl.desc {
+                ast::DeclDesc::Enum { width, parent_id, .. } => (*width, parent_id.as_deref()),
+                _ => panic!("expected enum declaration"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(enums, vec![(8, None), (0, Some("A")), (8, Some("B"))]);
+    }
+
+    #[test]
+    fn test_parse_scalar_scale() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A {
+                a: 16 scale 1.25 unit "ms",
+                b: 8 big_endian scale 2 = 4,
+                c: 8,
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let scales = file.declarations[0]
+            .fields()
+            .map(|field| match &field.desc {
+                ast::FieldDesc::Scalar { scale, unit, .. } => (scale.as_deref(), unit.as_deref()),
+                _ => panic!("expected scalar field"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scales, vec![(Some("1.25"), Some("ms")), (Some("2"), None), (None, None)]);
+        assert!(matches!(
+            file.declarations[0].fields().nth(1).unwrap().desc,
+            ast::FieldDesc::Scalar { default: Some(4), .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_extension_blocks() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A {
+                a: 8,
+                _extension_ { b: 8, c: 16, },
+                _extension_ { d: 8 }
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let extensions = file.declarations[0]
+            .fields()
+            .map(|field| (field.id().unwrap(), field.extension))
+            .collect::<Vec<_>>();
+        assert_eq!(extensions, vec![("a", None), ("b", Some(0)), ("c", Some(0)), ("d", Some(1))]);
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let source = r#"
+            little_endian_packets
+            #[profile(vendor_x)]
+            packet VendorCommand {
+                opcode: 8,
+            }
+            packet A {
+                a: 8,
+                if profile(v5_3, v5_4) {
+                    b: 8,
+                    if profile(vendor_x) { c: 8 },
+                },
+                d: 8,
+            }
+            "#;
+        let parse = |profiles: &[&str]| {
+            let mut db = ast::SourceDatabase::new();
+            let profiles = profiles.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+            let file =
+                parse_inline_with_profiles(&mut db, "test", source.to_owned(), &profiles).unwrap();
+            let ids =
+                file.declarations.iter().map(|d| d.id().unwrap().to_owned()).collect::<Vec<_>>();
+            let fields = file
+                .declarations
+                .last()
+                .unwrap()
+                .fields()
+                .map(|f| f.id().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            (ids, fields)
+        };
+        assert_eq!(parse(&[]), (vec!["A".to_owned()], vec!["a".to_owned(), "d".to_owned()]));
+        assert_eq!(
+            parse(&["v5_4"]),
+            (vec!["A".to_owned()], vec!["a".to_owned(), "b".to_owned(), "d".to_owned()])
+        );
+        assert_eq!(
+            parse(&["v5_3", "vendor_x"]),
+            (
+                vec!["VendorCommand".to_owned(), "A".to_owned()],
+                vec!["a".to_owned(), "b".to_owned(), "c".to_owned(), "d".to_owned()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_structs() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A : B big_endian {
+                _count_(completed_packets): 8,
+                completed_packets: { handle: 12, _reserved_: 4, count: 16 }[],
+                header: { x: { y: 8 }, },
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let decls = file
+            .declarations
+            .iter()
+            .map(|decl| (decl.id().unwrap(), decl.kind(), decl.endianness))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decls,
+            vec![
+                ("ACompletedPackets", "struct", Some(ast::EndiannessValue::BigEndian)),
+                ("AHeaderX", "struct", Some(ast::EndiannessValue::BigEndian)),
+                ("AHeader", "struct", Some(ast::EndiannessValue::BigEndian)),
+                ("A", "packet", Some(ast::EndiannessValue::BigEndian)),
+            ]
+        );
+        let fields = file.declarations[3]
+            .fields()
+            .map(|field| &field.desc)
+            .filter_map(|desc| match desc {
+                ast::FieldDesc::Array { type_id, .. } => type_id.as_deref(),
+                ast::FieldDesc::Typedef { type_id, .. } => Some(type_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["ACompletedPackets", "AHeader"]);
+    }
+
+    #[test]
+    fn test_parse_response_declaration() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            response ReadBdAddr -> ReadBdAddrComplete
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let ast::DeclDesc::Response { request_id, response_id } = &file.declarations[0].desc else {
+            panic!("expected response declaration");
+        };
+        assert_eq!(request_id, "ReadBdAddr");
+        assert_eq!(response_id, "ReadBdAddrComplete");
+    }
+
+    #[test]
+    fn test_parse_size_assertion() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            struct A : size == 16 { a: 8 }
+            packet B : A (a = 1) big_endian : size <= 255 { }
+            packet C : size { }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let decls = file
+            .declarations
+            .iter()
+            .map(|decl| (decl.parent_id(), decl.size_assertion))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decls,
+            vec![
+                (
+                    None,
+                    Some(ast::SizeAssertion { comparison: ast::SizeComparison::Equal, size: 16 })
+                ),
+                (
+                    Some("A"),
+                    Some(ast::SizeAssertion {
+                        comparison: ast::SizeComparison::LessOrEqual,
+                        size: 255
+                    })
+                ),
+                (Some("size"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invariants() {
+        let mut db = ast::SourceDatabase::new();
+        let file = parse_inline(
+            &mut db,
+            "test",
+            r#"
+            little_endian_packets
+            packet A {
+                a: 8,
+                b: 8,
+                c: 8[],
+                assert a <= b,
+                assert a + 2 * b == len(c) || (b == 0 && a != 1),
+            }
+            "#
+            .to_owned(),
+        )
+        .unwrap();
+        let invariants = file.declarations[0].invariants().collect::<Vec<_>>();
+        assert_eq!(invariants.len(), 2);
+        assert_eq!(invariants[0].text, "a <= b");
+        assert_eq!(invariants[1].text, "a + 2 * b == len(c) || (b == 0 && a != 1)");
+
+        use ast::{BinaryOp, Expr};
+        let field = |id: &str| Box::new(Expr::Field { id: id.to_owned() });
+        let integer = |value| Box::new(Expr::Integer { value });
+        let binary = |op, lhs, rhs| Box::new(Expr::Binary { op, lhs, rhs });
+        assert_eq!(invariants[0].expr, *binary(BinaryOp::Le, field("a"), field("b")));
+        assert_eq!(
+            invariants[1].expr,
+            *binary(
+                BinaryOp::Or,
+                binary(
+                    BinaryOp::Eq,
+                    binary(
+                        BinaryOp::Add,
+                        field("a"),
+                        binary(BinaryOp::Mul, integer(2), field("b"))
+                    ),
+                    Box::new(Expr::Length { id: "c".to_owned() }),
+                ),
+                binary(
+                    BinaryOp::And,
+                    binary(BinaryOp::Eq, field("b"), integer(0)),
+                    binary(BinaryOp::Ne, field("a"), integer(1)),
+                ),
+            )
+        );
+    }
 }
@@ -115,6 +115,7 @@ class Struct_Array_Field_UnsizedElement_VariableCount_PaddedView;
 class Struct_Optional_Scalar_FieldView;
 class Struct_Optional_Enum_FieldView;
 class Struct_Optional_Struct_FieldView;
+class Struct_Recursive_Array_FieldView;
 class Enum_Incomplete_Truncated_ClosedView;
 class Enum_Incomplete_Truncated_OpenView;
 class Enum_Incomplete_Truncated_Closed_WithRangeView;
@@ -237,6 +238,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 1);
+        _ASSERT_VALID(array_size <= 0x3);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint8_t, 1>(output, static_cast<uint8_t>(element));
@@ -358,6 +360,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(a_ & 0xff)));
         size_t payload_size = payload_.size();
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -444,6 +447,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(static_cast<uint16_t>(a_))));
         size_t payload_size = payload_.size();
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -524,6 +528,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(a_ & 0xff)));
         size_t payload_size = payload_.size();
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -836,6 +841,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t b_size = (b_.size() * 1);
+        _ASSERT_VALID(b_size <= 0x7);
         pdl::packet::Builder::write_be<uint64_t, 8>(output, (static_cast<uint64_t>(b_size)) | (static_cast<uint64_t>(a_ & 0x1fffffffffffffff) << 3));
         for (auto const& element : b_) {
             pdl::packet::Builder::write_be<uint8_t, 1>(output, static_cast<uint8_t>(element));
@@ -1145,6 +1151,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t payload_size = payload_.size();
+        _ASSERT_VALID(payload_size <= 0x7);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -1221,6 +1228,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t payload_size = (payload_.size() +2);
+        _ASSERT_VALID(payload_size <= 0x7);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -1448,6 +1456,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t payload_size = payload_.size();
+        _ASSERT_VALID(payload_size <= 0x7);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         output.insert(output.end(), payload_.begin(), payload_.end());
     }
@@ -1964,6 +1973,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 1);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint8_t, 1>(output, static_cast<uint8_t>(element));
@@ -2292,6 +2302,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint16_t, 2>(output, static_cast<uint16_t>(element));
@@ -2620,6 +2631,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint16_t, 2>(output, static_cast<uint16_t>(element));
@@ -2950,6 +2962,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); });
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -3284,6 +3297,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); });
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -3545,6 +3559,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); }) +2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -3639,6 +3654,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         size_t array_start = output.size();
         for (auto const& element : array_) {
@@ -3941,6 +3957,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); });
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         size_t array_element_size = array_.empty() ? 0 : array_[0].GetSize();
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_element_size)));
@@ -4508,6 +4525,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(0x0 & 0xff)));
         size_t payload_size = 1;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(b_ & 0xff)));
     }
@@ -4583,6 +4601,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(0x1 & 0xff)));
         size_t payload_size = 2;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(c_ & 0xffff)));
     }
@@ -4658,6 +4677,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(static_cast<uint16_t>(Enum16::A))));
         size_t payload_size = 1;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(b_ & 0xff)));
     }
@@ -4733,6 +4753,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(static_cast<uint16_t>(Enum16::B))));
         size_t payload_size = 2;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(c_ & 0xffff)));
     }
@@ -4808,6 +4829,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(0x2 & 0xff)));
         size_t payload_size = 1;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(b_ & 0xff)));
     }
@@ -4883,6 +4905,7 @@ public:
     void Serialize(std::vector<uint8_t>& output) const override {
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(0x3 & 0xff)));
         size_t payload_size = 2;
+        _ASSERT_VALID(payload_size <= 0xff);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(payload_size)));
         pdl::packet::Builder::write_be<uint16_t, 2>(output, (static_cast<uint16_t>(c_ & 0xffff)));
     }
@@ -5165,6 +5188,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t b_size = (b_.size() * 1);
+        _ASSERT_VALID(b_size <= 0x7);
         pdl::packet::Builder::write_be<uint64_t, 8>(output, (static_cast<uint64_t>(b_size)) | (static_cast<uint64_t>(a_ & 0x1fffffffffffffff) << 3));
         for (auto const& element : b_) {
             pdl::packet::Builder::write_be<uint8_t, 1>(output, static_cast<uint8_t>(element));
@@ -5945,6 +5969,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 1);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint8_t, 1>(output, static_cast<uint8_t>(element));
@@ -6361,6 +6386,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint16_t, 2>(output, static_cast<uint16_t>(element));
@@ -6785,6 +6811,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             pdl::packet::Builder::write_be<uint16_t, 2>(output, static_cast<uint16_t>(element));
@@ -7217,6 +7244,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); });
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -7644,6 +7672,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); });
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -7965,6 +7994,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (std::accumulate(array_.begin(), array_.end(), static_cast<size_t>(0), [](size_t s, auto const& element) { return s + element.GetSize(); }) +2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         for (auto const& element : array_) {
             element.Serialize(output);
@@ -8081,6 +8111,7 @@ public:
 
     void Serialize(std::vector<uint8_t>& output) const override {
         size_t array_size = (array_.size() * 2);
+        _ASSERT_VALID(array_size <= 0xf);
         pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(array_size)));
         size_t array_start = output.size();
         for (auto const& element : array_) {
@@ -8635,6 +8666,117 @@ public:
     Struct_Optional_Struct_Field_ s_;
 };
 
+class Struct_Recursive_Array_Field_ : public pdl::packet::Builder {
+public:
+    ~Struct_Recursive_Array_Field_() override = default;
+    Struct_Recursive_Array_Field_() = default;
+    Struct_Recursive_Array_Field_(Struct_Recursive_Array_Field_ const&) = default;
+    Struct_Recursive_Array_Field_(Struct_Recursive_Array_Field_&&) = default;
+    explicit Struct_Recursive_Array_Field_(uint8_t a, std::vector<Struct_Recursive_Array_Field_> children) : a_(std::move(a)), children_(std::move(children)) {}
+    Struct_Recursive_Array_Field_& operator=(Struct_Recursive_Array_Field_ const&) = default;
+
+    static bool Parse(pdl::packet::slice& parent_span, Struct_Recursive_Array_Field_* output) {
+        pdl::packet::parse_depth_guard depth_guard;
+        if (!depth_guard.ok()) return false;
+        pdl::packet::slice span = parent_span;
+        if (span.size() < 2) {
+            return false;
+        }
+        output->a_ = span.read_be<uint8_t, 1>();
+        output->children_count_ = span.read_be<uint8_t, 1>();
+        for (size_t n = 0; n < output->children_count_; n++) {
+            Struct_Recursive_Array_Field_ out;
+            if (!Struct_Recursive_Array_Field_::Parse(span, &out)) {
+                return false;
+            }
+            output->children_.push_back(std::move(out));
+        }
+        parent_span = span;
+        return true;
+    }
+
+    void Serialize(std::vector<uint8_t>& output) const override {
+        pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(a_ & 0xff)));
+        pdl::packet::Builder::write_be<uint8_t, 1>(output, (static_cast<uint8_t>(children_.size())));
+        for (auto const& element : children_) {
+            element.Serialize(output);
+        }
+    }
+
+    size_t GetSize() const override {
+        return 2 + (std::accumulate(children_.begin(), children_.end(), static_cast<size_t>(0), [](size_t s, auto const& e) { return s + e.GetSize(); }));
+    }
+
+    std::string ToString() const { return ""; }
+
+    uint8_t a_{0};
+    uint8_t children_count_ {0};
+    std::vector<Struct_Recursive_Array_Field_> children_;
+};
+
+class Struct_Recursive_Array_FieldView {
+public:
+    static Struct_Recursive_Array_FieldView Create(pdl::packet::slice const& parent) {
+        return Struct_Recursive_Array_FieldView(parent);
+    }
+
+    Struct_Recursive_Array_Field_ const& GetS() const { _ASSERT_VALID(valid_); return s_; }
+
+    std::string ToString() const { return ""; }
+
+    bool IsValid() const {
+        return valid_;
+    }
+
+    pdl::packet::slice bytes() const {
+        return bytes_;
+    }
+
+protected:
+    explicit Struct_Recursive_Array_FieldView(pdl::packet::slice const& parent)
+          : bytes_(parent) {
+        valid_ = Parse(parent);
+    }
+
+    bool Parse(pdl::packet::slice const& parent) {
+        // Parse packet field values.
+        pdl::packet::slice span = parent;
+        if (!Struct_Recursive_Array_Field_::Parse(span, &s_)) return false;
+        if (span.size() > 0) {
+            return false;
+        }
+        return true;
+    }
+
+    bool valid_{false};
+    pdl::packet::slice bytes_;
+    Struct_Recursive_Array_Field_ s_;
+
+
+};
+
+class Struct_Recursive_Array_FieldBuilder : public pdl::packet::Builder {
+public:
+    ~Struct_Recursive_Array_FieldBuilder() override = default;
+    Struct_Recursive_Array_FieldBuilder() = default;
+    explicit Struct_Recursive_Array_FieldBuilder(Struct_Recursive_Array_Field_ s) : s_(std::move(s)) {}
+    Struct_Recursive_Array_FieldBuilder(Struct_Recursive_Array_FieldBuilder const&) = default;
+    Struct_Recursive_Array_FieldBuilder(Struct_Recursive_Array_FieldBuilder&&) = default;
+    Struct_Recursive_Array_FieldBuilder& operator=(Struct_Recursive_Array_FieldBuilder const&) = default;
+
+    void Serialize(std::vector<uint8_t>& output) const override {
+        s_.Serialize(output);
+    }
+
+    size_t GetSize() const override {
+        return s_.GetSize();
+    }
+
+    std::string ToString() const { return ""; }
+
+    Struct_Recursive_Array_Field_ s_;
+};
+
 enum class Enum_Incomplete_Truncated_Closed_ : uint8_t {
     A = 0x0,
     B = 0x1,
@@ -0,0 +1,8 @@
+package test;
+
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+
+/**
+ * GENERATED BY PDL COMPILER FROM test
+ */public final class P { protected final S s ; protected final byte b ; private P() { throw new UnsupportedOperationException(); } private P(Builder builder) { s = builder.s;b = builder.b; } public static P fromBytes(byte[] bytes) { ByteBuffer buf = ByteBuffer.wrap(bytes).order(ByteOrder.LITTLE_ENDIAN); P result = P.fromBytes(buf); if (buf.hasRemaining()) { throw new IllegalArgumentException("Trailing bytes after packet"); } return result; } protected static P fromBytes(ByteBuffer buf) { Builder builder = new Builder(); byte chunk0 = buf.get();int sSize = chunk0;S s = S.fromBytes(buf.slice(buf.position(), sSize).order(ByteOrder.LITTLE_ENDIAN)); if (s.width() != sSize) { throw new IllegalArgumentException("Struct size does not match its _size_ field"); }builder.setS(s); buf.position(buf.position() + s.width());byte chunk2 = buf.get();builder.setB(chunk2); return builder.build(); } public byte[] toBytes() { ByteBuffer buf = ByteBuffer .allocate(fieldWidth()) .order(ByteOrder.LITTLE_ENDIAN); buf.put((byte) s.width());buf.put(s.toBytes());buf.put(b); return buf.array(); } public int width() { return fieldWidth(); } private final int fieldWidth() { return s.width() + 2; } public S getS() { return s; }public byte getB() { return b; } @Override public String toString() { return "P{ " + "sSize[8]=" + "0x" + Integer.toHexString( s.width() ) + ", "+ "s=" + s.toString() + ", "+ "b[8]=" + "0x" + Integer.toHexString( Byte.toUnsignedInt(b) ) + ", " + "}"; } @Override public boolean equals(Object o) { if (this == o) return true; if (!(o instanceof P other)) return false; return s.equals(other.s) && b == other.b; } @Override public int hashCode() { int result = s.hashCode(); result = 31 * result + Byte.hashCode(b); return result; } public static final class Builder { protected S s ; protected byte b ; public Builder() { } protected Builder self() { return this; } public P build() { return new P(this); } public Builder setS( S s ) { if (Integer.compareUnsigned( s.width(), 0xff ) > 0) { throw new IllegalArgumentException( "Value " + s.toString() + " is too wide for its _size_ or _count_ field with width 8" ); } this.s = s; return self(); }public Builder setB( byte b ) { if (Integer.compareUnsigned( Byte.toUnsignedInt(b), Byte.toUnsignedInt((byte) 0xff) ) > 0) { throw new IllegalArgumentException( "Value " + "0x" + Integer.toHexString( Byte.toUnsignedInt(b) ) + " is too wide for field \'b\' with width 8" ); } this.b = b; return self(); } } }
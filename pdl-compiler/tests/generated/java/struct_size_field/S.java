@@ -0,0 +1,9 @@
+package test;
+
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.util.Arrays;
+
+/**
+ * GENERATED BY PDL COMPILER FROM test
+ */public final class S { protected final byte[] a ; private S() { throw new UnsupportedOperationException(); } private S(Builder builder) { a = builder.a; } public static S fromBytes(byte[] bytes) { ByteBuffer buf = ByteBuffer.wrap(bytes).order(ByteOrder.LITTLE_ENDIAN); S result = S.fromBytes(buf); if (buf.hasRemaining()) { throw new IllegalArgumentException("Trailing bytes after packet"); } return result; } protected static S fromBytes(ByteBuffer buf) { Builder builder = new Builder(); byte chunk0 = buf.get();int aCount = chunk0; byte[] a = new byte[aCount]; for (int i = 0; i < aCount; i++) { a[i] = buf.get(); } builder.setA(a); return builder.build(); } public byte[] toBytes() { ByteBuffer buf = ByteBuffer .allocate(fieldWidth()) .order(ByteOrder.LITTLE_ENDIAN); buf.put((byte) a.length);for (int i = 0; i < a.length; i++) { buf.put(a[i]); } return buf.array(); } public int width() { return fieldWidth(); } private final int fieldWidth() { return a.length + 1; } public byte[] getA() { return a; } @Override public String toString() { return "S{ " + "aCount[8]=" + "0x" + Integer.toHexString( a.length ) + ", "+ "a=" + Arrays.toString(a) + ", " + "}"; } @Override public boolean equals(Object o) { if (this == o) return true; if (!(o instanceof S other)) return false; return Arrays.equals(a, other.a); } @Override public int hashCode() { int result = Arrays.hashCode(a); return result; } public static final class Builder { protected byte[] a ; public Builder() { } protected Builder self() { return this; } public S build() { return new S(this); } public Builder setA( byte[] a ) { if (Integer.compareUnsigned( a.length, 0xff ) > 0) { throw new IllegalArgumentException( "Value " + Arrays.toString(a) + " is too wide for its _size_ or _count_ field with width 8" ); } this.a = a; return self(); } } }
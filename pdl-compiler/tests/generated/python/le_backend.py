@@ -4,7 +4,7 @@
 
 from tests.custom_types import SizedCustomField, UnsizedCustomField, Checksum
 from dataclasses import dataclass, field, fields
-from typing import Optional, List, Tuple, Union
+from typing import Optional, List, Tuple, Union, get_type_hints
 import enum
 import inspect
 
@@ -63,6 +63,56 @@ class TrailingBytesError(DecodeError):
         super().__init__(f"unexpected {packet_name} parsing remainder of size {trailing_size}")
 
 
+class SizeUnitError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, size: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.size = size
+        super().__init__(f"invalid {packet_name}.{field_name} size: {size} bits is not an integral number of octets")
+
+
+class TrailingSizeError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, expected_size: int, actual_size: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.expected_size = expected_size
+        self.actual_size = actual_size
+        super().__init__(f"invalid {packet_name}.{field_name} trailing size: expected {expected_size}, got {actual_size}")
+
+
+class BoundError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, count: int, bound: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.count = count
+        self.bound = bound
+        super().__init__(f"invalid {packet_name}.{field_name} count: {count} exceeds the bound of {bound}")
+
+
+class SizeBoundError(DecodeError):
+    def __init__(self, packet_name: str, field_name: str, size: int, bound: int):
+        self.packet_name = packet_name
+        self.field_name = field_name
+        self.size = size
+        self.bound = bound
+        super().__init__(f"invalid {packet_name}.{field_name} size: {size} exceeds the bound of {bound}")
+
+
+class SizeAssertionError(DecodeError):
+    def __init__(self, packet_name: str, size: int, assertion: str):
+        self.packet_name = packet_name
+        self.size = size
+        self.assertion = assertion
+        super().__init__(f"invalid {packet_name} size: {size} octets does not satisfy the assertion {assertion}")
+
+
+class InvariantViolationError(DecodeError):
+    def __init__(self, packet_name: str, invariant: str):
+        self.packet_name = packet_name
+        self.invariant = invariant
+        super().__init__(f"invalid {packet_name}: the invariant {invariant} is violated")
+
+
 @dataclass
 class Packet:
     payload: Optional[bytes] = field(repr=False, default_factory=bytes, compare=False)
@@ -130,13 +180,14 @@ class Packet:
 
         last = len(fields(self)) - 1
         align = max((len(f.name) for f in fields(self) if f.name != 'payload'), default=0)
+        types = get_type_hints(type(self))
 
         for (idx, f) in enumerate(fields(self)):
             p  = prefix + ('├── ' if idx != last else '└── ')
             pp = prefix + ('│   ' if idx != last else '    ')
             val = getattr(self, f.name)
 
-            print_val(p, pp, f.name, align, f.type, val)
+            print_val(p, pp, f.name, align, types[f.name], val)
 
 if (not callable(getattr(SizedCustomField, 'parse', None)) or
     not callable(getattr(SizedCustomField, 'parse_all', None))):
@@ -160,6 +211,10 @@ class Enum7(enum.IntEnum):
         except ValueError:
             raise EnumValueError("", "", "Enum7", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 class Enum16(enum.IntEnum):
     A = 0xaabb
     B = 0xccdd
@@ -171,6 +226,10 @@ class Enum16(enum.IntEnum):
         except ValueError:
             raise EnumValueError("", "", "Enum16", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class SizedStruct(Packet):
     a: int = field(kw_only=True, default=0)
@@ -4544,6 +4603,68 @@ class Struct_Optional_Struct_Field(Packet):
     def size(self) -> int:
         return self.s.size
 
+@dataclass
+class Struct_Recursive_Array_Field_(Packet):
+    a: int = field(kw_only=True, default=0)
+    children: List['Struct_Recursive_Array_Field_'] = field(kw_only=True, default_factory=list)
+
+    def __post_init__(self) -> None:
+        pass
+
+    @staticmethod
+    def parse(span: bytes) -> Tuple['Struct_Recursive_Array_Field_', bytes]:
+        fields = {'payload': None}
+        if len(span) < 2:
+            raise LengthError("Struct_Recursive_Array_Field_", 2, len(span))
+        fields['a'] = span[0]
+        children_count = span[1]
+        span = span[2:]
+        children = []
+        for n in range(children_count):
+            _elt, span = Struct_Recursive_Array_Field_.parse(span)
+            children.append(_elt)
+        fields['children'] = children
+        return Struct_Recursive_Array_Field_(**fields), span
+
+    def serialize(self, payload: Optional[bytes] = None) -> bytes:
+        _span = bytearray()
+        if self.a > 0xff:
+            raise ValueError("Invalid scalar value Struct_Recursive_Array_Field_::a: {self.a} > 0xff")
+        _span.append((self.a << 0))
+        if len(self.children) > 0xff:
+            raise ValueError("Invalid count value Struct_Recursive_Array_Field_::children: {len(self.children)} > 0xff")
+        _span.append((len(self.children) << 0))
+        for elt in self.children:
+            _span.extend(elt.serialize())
+        return bytes(_span)
+
+    @property
+    def size(self) -> int:
+        return sum([elt.size for elt in self.children]) + 2
+
+@dataclass
+class Struct_Recursive_Array_Field(Packet):
+    s: Struct_Recursive_Array_Field_ = field(kw_only=True, default_factory=Struct_Recursive_Array_Field_)
+
+    def __post_init__(self) -> None:
+        pass
+
+    @staticmethod
+    def parse(span: bytes) -> Tuple['Struct_Recursive_Array_Field', bytes]:
+        fields = {'payload': None}
+        s, span = Struct_Recursive_Array_Field_.parse(span)
+        fields['s'] = s
+        return Struct_Recursive_Array_Field(**fields), span
+
+    def serialize(self, payload: Optional[bytes] = None) -> bytes:
+        _span = bytearray()
+        _span.extend(self.s.serialize())
+        return bytes(_span)
+
+    @property
+    def size(self) -> int:
+        return self.s.size
+
 class Enum_Incomplete_Truncated_Closed_(enum.IntEnum):
     A = 0x0
     B = 0x1
@@ -4555,6 +4676,10 @@ class Enum_Incomplete_Truncated_Closed_(enum.IntEnum):
         except ValueError:
             raise EnumValueError("", "", "Enum_Incomplete_Truncated_Closed_", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Incomplete_Truncated_Closed(Packet):
     e: Enum_Incomplete_Truncated_Closed_ = field(kw_only=True, default=Enum_Incomplete_Truncated_Closed_.A)
@@ -4591,6 +4716,10 @@ class Enum_Incomplete_Truncated_Open_(enum.IntEnum):
         except ValueError:
             return v
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Incomplete_Truncated_Open(Packet):
     e: Enum_Incomplete_Truncated_Open_ = field(kw_only=True, default=Enum_Incomplete_Truncated_Open_.A)
@@ -4628,6 +4757,10 @@ class Enum_Incomplete_Truncated_Closed_WithRange_(enum.IntEnum):
                 return v
             raise EnumValueError("", "", "Enum_Incomplete_Truncated_Closed_WithRange_", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Incomplete_Truncated_Closed_WithRange(Packet):
     e: Enum_Incomplete_Truncated_Closed_WithRange_ = field(kw_only=True, default=Enum_Incomplete_Truncated_Closed_WithRange_.A)
@@ -4663,6 +4796,10 @@ class Enum_Incomplete_Truncated_Open_WithRange_(enum.IntEnum):
         except ValueError:
             return v
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Incomplete_Truncated_Open_WithRange(Packet):
     e: Enum_Incomplete_Truncated_Open_WithRange_ = field(kw_only=True, default=Enum_Incomplete_Truncated_Open_WithRange_.A)
@@ -4705,6 +4842,10 @@ class Enum_Complete_Truncated_(enum.IntEnum):
         except ValueError:
             raise EnumValueError("", "", "Enum_Complete_Truncated_", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Complete_Truncated(Packet):
     e: Enum_Complete_Truncated_ = field(kw_only=True, default=Enum_Complete_Truncated_.A)
@@ -4742,6 +4883,10 @@ class Enum_Complete_Truncated_WithRange_(enum.IntEnum):
                 return v
             raise EnumValueError("", "", "Enum_Complete_Truncated_WithRange_", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Complete_Truncated_WithRange(Packet):
     e: Enum_Complete_Truncated_WithRange_ = field(kw_only=True, default=Enum_Complete_Truncated_WithRange_.A)
@@ -4780,6 +4925,10 @@ class Enum_Complete_WithRange_(enum.IntEnum):
                 return v
             raise EnumValueError("", "", "Enum_Complete_WithRange_", v)
 
+    @property
+    def display_name(self) -> str:
+        return self.name
+
 @dataclass
 class Enum_Complete_WithRange(Packet):
     e: Enum_Complete_WithRange_ = field(kw_only=True, default=Enum_Complete_WithRange_.A)
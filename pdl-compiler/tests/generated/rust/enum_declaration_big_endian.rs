@@ -36,6 +36,35 @@ impl Default for IncompleteTruncatedClosed {
         IncompleteTruncatedClosed::A
     }
 }
+impl IncompleteTruncatedClosed {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IncompleteTruncatedClosed::A => "A",
+            IncompleteTruncatedClosed::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for IncompleteTruncatedClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for IncompleteTruncatedClosed {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(IncompleteTruncatedClosed::A),
+            "B" => Ok(IncompleteTruncatedClosed::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "IncompleteTruncatedClosed",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for IncompleteTruncatedClosed {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -107,6 +136,36 @@ impl Default for IncompleteTruncatedOpen {
         IncompleteTruncatedOpen::A
     }
 }
+impl IncompleteTruncatedOpen {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IncompleteTruncatedOpen::A => "A",
+            IncompleteTruncatedOpen::B => "B",
+            IncompleteTruncatedOpen::Unknown(_) => "UNKNOWN",
+        }
+    }
+}
+impl std::fmt::Display for IncompleteTruncatedOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for IncompleteTruncatedOpen {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(IncompleteTruncatedOpen::A),
+            "B" => Ok(IncompleteTruncatedOpen::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "IncompleteTruncatedOpen",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for IncompleteTruncatedOpen {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -181,6 +240,38 @@ impl Default for IncompleteTruncatedClosedWithRange {
         IncompleteTruncatedClosedWithRange::A
     }
 }
+impl IncompleteTruncatedClosedWithRange {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IncompleteTruncatedClosedWithRange::A => "A",
+            IncompleteTruncatedClosedWithRange::X => "X",
+            IncompleteTruncatedClosedWithRange::Y => "Y",
+            IncompleteTruncatedClosedWithRange::B(_) => "B",
+        }
+    }
+}
+impl std::fmt::Display for IncompleteTruncatedClosedWithRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for IncompleteTruncatedClosedWithRange {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(IncompleteTruncatedClosedWithRange::A),
+            "X" => Ok(IncompleteTruncatedClosedWithRange::X),
+            "Y" => Ok(IncompleteTruncatedClosedWithRange::Y),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "IncompleteTruncatedClosedWithRange",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for IncompleteTruncatedClosedWithRange {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -258,6 +349,39 @@ impl Default for IncompleteTruncatedOpenWithRange {
         IncompleteTruncatedOpenWithRange::A
     }
 }
+impl IncompleteTruncatedOpenWithRange {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IncompleteTruncatedOpenWithRange::A => "A",
+            IncompleteTruncatedOpenWithRange::X => "X",
+            IncompleteTruncatedOpenWithRange::Y => "Y",
+            IncompleteTruncatedOpenWithRange::B(_) => "B",
+            IncompleteTruncatedOpenWithRange::Unknown(_) => "UNKNOWN",
+        }
+    }
+}
+impl std::fmt::Display for IncompleteTruncatedOpenWithRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for IncompleteTruncatedOpenWithRange {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(IncompleteTruncatedOpenWithRange::A),
+            "X" => Ok(IncompleteTruncatedOpenWithRange::X),
+            "Y" => Ok(IncompleteTruncatedOpenWithRange::Y),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "IncompleteTruncatedOpenWithRange",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for IncompleteTruncatedOpenWithRange {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -341,6 +465,47 @@ impl Default for CompleteTruncated {
         CompleteTruncated::A
     }
 }
+impl CompleteTruncated {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompleteTruncated::A => "A",
+            CompleteTruncated::B => "B",
+            CompleteTruncated::C => "C",
+            CompleteTruncated::D => "D",
+            CompleteTruncated::E => "E",
+            CompleteTruncated::F => "F",
+            CompleteTruncated::G => "G",
+            CompleteTruncated::H => "H",
+        }
+    }
+}
+impl std::fmt::Display for CompleteTruncated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for CompleteTruncated {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(CompleteTruncated::A),
+            "B" => Ok(CompleteTruncated::B),
+            "C" => Ok(CompleteTruncated::C),
+            "D" => Ok(CompleteTruncated::D),
+            "E" => Ok(CompleteTruncated::E),
+            "F" => Ok(CompleteTruncated::F),
+            "G" => Ok(CompleteTruncated::G),
+            "H" => Ok(CompleteTruncated::H),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "CompleteTruncated",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for CompleteTruncated {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -425,6 +590,38 @@ impl Default for CompleteTruncatedWithRange {
         CompleteTruncatedWithRange::A
     }
 }
+impl CompleteTruncatedWithRange {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompleteTruncatedWithRange::A => "A",
+            CompleteTruncatedWithRange::X => "X",
+            CompleteTruncatedWithRange::Y => "Y",
+            CompleteTruncatedWithRange::B(_) => "B",
+        }
+    }
+}
+impl std::fmt::Display for CompleteTruncatedWithRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for CompleteTruncatedWithRange {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(CompleteTruncatedWithRange::A),
+            "X" => Ok(CompleteTruncatedWithRange::X),
+            "Y" => Ok(CompleteTruncatedWithRange::Y),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "CompleteTruncatedWithRange",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for CompleteTruncatedWithRange {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -500,6 +697,36 @@ impl Default for CompleteWithRange {
         CompleteWithRange::A
     }
 }
+impl CompleteWithRange {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompleteWithRange::A => "A",
+            CompleteWithRange::B => "B",
+            CompleteWithRange::C(_) => "C",
+        }
+    }
+}
+impl std::fmt::Display for CompleteWithRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for CompleteWithRange {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(CompleteWithRange::A),
+            "B" => Ok(CompleteWithRange::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "CompleteWithRange",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for CompleteWithRange {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
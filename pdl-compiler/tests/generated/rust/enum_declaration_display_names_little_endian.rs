@@ -0,0 +1,124 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16", into = "u16"))]
+pub enum OpCode {
+    LeSetAdvertisingParameters,
+    Reset,
+    VendorReset,
+    Vendor(Private<u16>),
+    Unknown(Private<u16>),
+}
+impl Default for OpCode {
+    fn default() -> OpCode {
+        OpCode::LeSetAdvertisingParameters
+    }
+}
+impl OpCode {
+    #[allow(non_upper_case_globals)]
+    pub const LeSetAdvParams: OpCode = OpCode::LeSetAdvertisingParameters;
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OpCode::LeSetAdvertisingParameters => "LE Set Advertising Parameters",
+            OpCode::Reset => "RESET",
+            OpCode::VendorReset => "Vendor Reset",
+            OpCode::Vendor(_) => "VENDOR",
+            OpCode::Unknown(_) => "UNKNOWN",
+        }
+    }
+}
+impl std::fmt::Display for OpCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for OpCode {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LE Set Advertising Parameters" | "LE_SET_ADVERTISING_PARAMETERS"
+            | "LE_SET_ADV_PARAMS" => Ok(OpCode::LeSetAdvertisingParameters),
+            "RESET" => Ok(OpCode::Reset),
+            "Vendor Reset" | "VENDOR_RESET" => Ok(OpCode::VendorReset),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "OpCode",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u16> for OpCode {
+    type Error = u16;
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x2006 => Ok(OpCode::LeSetAdvertisingParameters),
+            0xc03 => Ok(OpCode::Reset),
+            0xfc00 => Ok(OpCode::VendorReset),
+            0xfc00..=0xfcff => Ok(OpCode::Vendor(Private(value))),
+            0..=0xffff => Ok(OpCode::Unknown(Private(value))),
+        }
+    }
+}
+impl From<&OpCode> for u16 {
+    fn from(value: &OpCode) -> Self {
+        match value {
+            OpCode::LeSetAdvertisingParameters => 0x2006,
+            OpCode::Reset => 0xc03,
+            OpCode::VendorReset => 0xfc00,
+            OpCode::Vendor(Private(value)) => *value,
+            OpCode::Unknown(Private(value)) => *value,
+        }
+    }
+}
+impl From<OpCode> for u16 {
+    fn from(value: OpCode) -> Self {
+        (&value).into()
+    }
+}
+impl From<OpCode> for i32 {
+    fn from(value: OpCode) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<OpCode> for i64 {
+    fn from(value: OpCode) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<OpCode> for u32 {
+    fn from(value: OpCode) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<OpCode> for u64 {
+    fn from(value: OpCode) -> Self {
+        u16::from(value) as Self
+    }
+}
@@ -0,0 +1,272 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub enum Base {
+    A = 0x1,
+    B = 0x2,
+}
+impl Default for Base {
+    fn default() -> Base {
+        Base::A
+    }
+}
+impl Base {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Base::A => "A",
+            Base::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Base {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Base {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Base::A),
+            "B" => Ok(Base::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Base",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u8> for Base {
+    type Error = u8;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Base::A),
+            0x2 => Ok(Base::B),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Base> for u8 {
+    fn from(value: &Base) -> Self {
+        match value {
+            Base::A => 0x1,
+            Base::B => 0x2,
+        }
+    }
+}
+impl From<Base> for u8 {
+    fn from(value: Base) -> Self {
+        (&value).into()
+    }
+}
+impl From<Base> for i16 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for i32 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for i64 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for u16 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for u32 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for u64 {
+    fn from(value: Base) -> Self {
+        u8::from(value) as Self
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub enum Extension {
+    A = 0x1,
+    B = 0x2,
+    C = 0x3,
+}
+impl Default for Extension {
+    fn default() -> Extension {
+        Extension::A
+    }
+}
+impl Extension {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Extension::A => "A",
+            Extension::B => "B",
+            Extension::C => "C",
+        }
+    }
+}
+impl std::fmt::Display for Extension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Extension {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Extension::A),
+            "B" => Ok(Extension::B),
+            "C" => Ok(Extension::C),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Extension",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u8> for Extension {
+    type Error = u8;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Extension::A),
+            0x2 => Ok(Extension::B),
+            0x3 => Ok(Extension::C),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Extension> for u8 {
+    fn from(value: &Extension) -> Self {
+        match value {
+            Extension::A => 0x1,
+            Extension::B => 0x2,
+            Extension::C => 0x3,
+        }
+    }
+}
+impl From<Extension> for u8 {
+    fn from(value: Extension) -> Self {
+        (&value).into()
+    }
+}
+impl From<Extension> for i16 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Extension> for i32 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Extension> for i64 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Extension> for u16 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Extension> for u32 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Extension> for u64 {
+    fn from(value: Extension) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Base> for Extension {
+    fn from(value: Base) -> Self {
+        Extension::try_from(u8::from(value)).unwrap()
+    }
+}
+impl TryFrom<Extension> for Base {
+    type Error = Extension;
+    fn try_from(value: Extension) -> Result<Self, Self::Error> {
+        Base::try_from(u8::from(value)).map_err(|_| value)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub x: Extension,
+}
+impl Foo {
+    pub fn x(&self) -> Extension {
+        self.x
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo { x: Default::default() }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        1
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(u8::from(self.x()));
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let x = Extension::try_from(buf.get_u8())
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Foo",
+                field: "x",
+                value: unknown_val as u64,
+                type_: "Extension",
+            })?;
+        Ok((Self { x }, buf))
+    }
+}
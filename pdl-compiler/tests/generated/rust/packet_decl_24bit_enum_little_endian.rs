@@ -36,6 +36,35 @@ impl Default for Foo {
         Foo::A
     }
 }
+impl Foo {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Foo::A => "A",
+            Foo::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Foo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Foo {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Foo::A),
+            "B" => Ok(Foo::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Foo",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u32> for Foo {
     type Error = u32;
     fn try_from(value: u32) -> Result<Self, Self::Error> {
@@ -36,6 +36,35 @@ impl Default for Foo {
         Foo::FooBar
     }
 }
+impl Foo {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Foo::FooBar => "FOO_BAR",
+            Foo::Baz => "BAZ",
+        }
+    }
+}
+impl std::fmt::Display for Foo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Foo {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "FOO_BAR" => Ok(Foo::FooBar),
+            "BAZ" => Ok(Foo::Baz),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Foo",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u64> for Foo {
     type Error = u64;
     fn try_from(value: u64) -> Result<Self, Self::Error> {
@@ -0,0 +1,300 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u16,
+}
+impl Foo {
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo { a: 0 }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        2
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16_le(self.a());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u16_le();
+        Ok((Self { a }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub x: Vec<u8>,
+    pub y: Vec<Foo>,
+    pub z: Vec<u32>,
+}
+impl Bar {
+    /// Maximum number of elements of `x`.
+    pub const X_MAX_COUNT: usize = 32;
+    /// Maximum number of elements of `y`.
+    pub const Y_MAX_COUNT: usize = 16;
+    /// Maximum number of elements of `z`.
+    pub const Z_MAX_COUNT: usize = 8;
+    pub fn x(&self) -> &Vec<u8> {
+        &self.x
+    }
+    pub fn y(&self) -> &Vec<Foo> {
+        &self.y
+    }
+    pub fn z(&self) -> &Vec<u32> {
+        &self.z
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar {
+            x: vec![],
+            y: vec![],
+            z: vec![],
+        }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        2 + self.x.len() + (self.y.len() * 2) + (self.z.len() * 3)
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.x.len() > 32 {
+            return Err(EncodeError::CountOverflow {
+                packet: "Bar",
+                field: "x",
+                count: self.x.len(),
+                maximum_count: 32,
+            });
+        }
+        if self.y.len() > 16 {
+            return Err(EncodeError::CountOverflow {
+                packet: "Bar",
+                field: "y",
+                count: self.y.len(),
+                maximum_count: 16,
+            });
+        }
+        if self.z.len() > 8 {
+            return Err(EncodeError::CountOverflow {
+                packet: "Bar",
+                field: "z",
+                count: self.z.len(),
+                maximum_count: 8,
+            });
+        }
+        buf.put_u8(self.x.len() as u8);
+        for elem in &self.x {
+            buf.put_u8(*elem);
+        }
+        let y_size = self.y.iter().map(Packet::encoded_len).sum::<usize>();
+        #[allow(unused_comparisons)]
+        if y_size > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Bar",
+                field: "y",
+                size: y_size,
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8((y_size) as u8);
+        for elem in &self.y {
+            elem.encode(buf)?;
+        }
+        for elem in &self.z {
+            buf.put_uint_le(*elem as u64, 3);
+        }
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let x_count = buf.get_u8() as usize;
+        if x_count > 32 {
+            return Err(DecodeError::BoundError {
+                obj: "Bar",
+                field: "x",
+                count: x_count,
+                bound: 32,
+            });
+        }
+        if buf.remaining() < x_count * 1usize {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: x_count * 1usize,
+                got: buf.remaining(),
+            });
+        }
+        let x = (0..x_count)
+            .map(|_| Ok::<_, DecodeError>(buf.get_u8()))
+            .collect::<Result<Vec<_>, DecodeError>>()?;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let y_size = buf.get_u8() as usize;
+        if buf.remaining() < y_size {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: y_size,
+                got: buf.remaining(),
+            });
+        }
+        if y_size % 2 != 0 {
+            return Err(DecodeError::ArraySizeError {
+                array: y_size,
+                element: 2,
+            });
+        }
+        let y_count = y_size / 2;
+        if y_count > 16 {
+            return Err(DecodeError::BoundError {
+                obj: "Bar",
+                field: "y",
+                count: y_count,
+                bound: 16,
+            });
+        }
+        let mut y = Vec::with_capacity(y_count);
+        for _ in 0..y_count {
+            y.push(Foo::decode_mut(&mut buf)?);
+        }
+        if buf.remaining() % 3 != 0 {
+            return Err(DecodeError::ArraySizeError {
+                array: buf.remaining(),
+                element: 3,
+            });
+        }
+        let z_count = buf.remaining() / 3;
+        if z_count > 8 {
+            return Err(DecodeError::BoundError {
+                obj: "Bar",
+                field: "z",
+                count: z_count,
+                bound: 8,
+            });
+        }
+        let mut z = Vec::with_capacity(z_count);
+        for _ in 0..z_count {
+            z.push(Ok::<_, DecodeError>(buf.get_uint_le(3) as u32)?);
+        }
+        Ok((Self { x, y, z }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Baz {
+    pub payload: Vec<u8>,
+}
+impl Baz {
+    /// Maximum size of the payload in octets.
+    pub const PAYLOAD_MAX_SIZE: usize = 251;
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+impl Default for Baz {
+    fn default() -> Baz {
+        Baz { payload: vec![] }
+    }
+}
+impl Packet for Baz {
+    fn encoded_len(&self) -> usize {
+        1 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.payload.len() > 251 {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Baz",
+                field: "_payload_",
+                size: self.payload.len(),
+                maximum_size: 251,
+            });
+        }
+        #[allow(unused_comparisons)]
+        if self.payload.len() > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Baz",
+                field: "_payload_",
+                size: self.payload.len(),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8((self.payload.len()) as u8);
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Baz",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let payload_size = buf.get_u8() as usize;
+        if buf.remaining() < payload_size {
+            return Err(DecodeError::LengthError {
+                obj: "Baz",
+                wanted: payload_size,
+                got: buf.remaining(),
+            });
+        }
+        if payload_size > 251 {
+            return Err(DecodeError::SizeBoundError {
+                obj: "Baz",
+                field: "_payload_",
+                size: payload_size,
+                bound: 251,
+            });
+        }
+        let payload = buf[..payload_size].to_vec();
+        buf.advance(payload_size);
+        Ok((Self { payload }, buf))
+    }
+}
@@ -36,6 +36,35 @@ impl Default for Enum16 {
         Enum16::A
     }
 }
+impl Enum16 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum16::A => "A",
+            Enum16::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum16 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum16::A),
+            "B" => Ok(Enum16::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum16",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u16> for Enum16 {
     type Error = u16;
     fn try_from(value: u16) -> Result<Self, Self::Error> {
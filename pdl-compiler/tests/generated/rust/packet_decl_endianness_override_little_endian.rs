@@ -0,0 +1,258 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16", into = "u16"))]
+pub enum Enum16 {
+    A = 0x1,
+    B = 0x2,
+}
+impl Default for Enum16 {
+    fn default() -> Enum16 {
+        Enum16::A
+    }
+}
+impl Enum16 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum16::A => "A",
+            Enum16::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum16 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum16::A),
+            "B" => Ok(Enum16::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum16",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u16> for Enum16 {
+    type Error = u16;
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Enum16::A),
+            0x2 => Ok(Enum16::B),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Enum16> for u16 {
+    fn from(value: &Enum16) -> Self {
+        match value {
+            Enum16::A => 0x1,
+            Enum16::B => 0x2,
+        }
+    }
+}
+impl From<Enum16> for u16 {
+    fn from(value: Enum16) -> Self {
+        (&value).into()
+    }
+}
+impl From<Enum16> for i32 {
+    fn from(value: Enum16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Enum16> for i64 {
+    fn from(value: Enum16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Enum16> for u32 {
+    fn from(value: Enum16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Enum16> for u64 {
+    fn from(value: Enum16) -> Self {
+        u16::from(value) as Self
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub a: u16,
+    pub b: u16,
+}
+impl Bar {
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+    pub fn b(&self) -> u16 {
+        self.b
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar { a: 0, b: 0 }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        4
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16(self.a());
+        buf.put_u16_le(self.b());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u16();
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let b = buf.get_u16_le();
+        Ok((Self { a, b }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u16,
+    pub b: Enum16,
+    pub c: u32,
+    pub d: u32,
+    pub bar: Bar,
+}
+impl Foo {
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+    pub fn b(&self) -> Enum16 {
+        self.b
+    }
+    pub fn c(&self) -> u32 {
+        self.c
+    }
+    pub fn d(&self) -> u32 {
+        self.d
+    }
+    pub fn bar(&self) -> &Bar {
+        &self.bar
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: 0,
+            b: Default::default(),
+            c: 0,
+            d: 0,
+            bar: Default::default(),
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        15
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16(self.a());
+        buf.put_u16_le(u16::from(self.b()));
+        if self.c() > 0xff_ffff {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "c",
+                value: self.c() as u64,
+                maximum_value: 0xff_ffff as u64,
+            });
+        }
+        buf.put_uint(self.c() as u64, 3);
+        buf.put_u32_le(self.d());
+        self.bar.encode(buf)?;
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u16();
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let b = Enum16::try_from(buf.get_u16_le())
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Foo",
+                field: "b",
+                value: unknown_val as u64,
+                type_: "Enum16",
+            })?;
+        if buf.remaining() < 3 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 3,
+                got: buf.remaining(),
+            });
+        }
+        let c = buf.get_uint(3) as u32;
+        if buf.remaining() < 4 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 4,
+                got: buf.remaining(),
+            });
+        }
+        let d = buf.get_u32_le();
+        let (bar, mut buf) = Bar::decode(buf)?;
+        Ok((Self { a, b, c, d, bar }, buf))
+    }
+}
@@ -0,0 +1,269 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub enum Enum8 {
+    A = 0x1,
+    B = 0x2,
+}
+impl Default for Enum8 {
+    fn default() -> Enum8 {
+        Enum8::A
+    }
+}
+impl Enum8 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum8::A => "A",
+            Enum8::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum8 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum8::A),
+            "B" => Ok(Enum8::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum8",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u8> for Enum8 {
+    type Error = u8;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Enum8::A),
+            0x2 => Ok(Enum8::B),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Enum8> for u8 {
+    fn from(value: &Enum8) -> Self {
+        match value {
+            Enum8::A => 0x1,
+            Enum8::B => 0x2,
+        }
+    }
+}
+impl From<Enum8> for u8 {
+    fn from(value: Enum8) -> Self {
+        (&value).into()
+    }
+}
+impl From<Enum8> for i16 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum8> for i32 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum8> for i64 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum8> for u16 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum8> for u32 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum8> for u64 {
+    fn from(value: Enum8) -> Self {
+        u8::from(value) as Self
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub x: u8,
+}
+impl Bar {
+    pub fn x(&self) -> u8 {
+        self.x
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar { x: 0 }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        1
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.x());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let x = buf.get_u8();
+        Ok((Self { x }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u8,
+    pub b: Option<u16>,
+    pub c: Option<Enum8>,
+    pub d: Option<Bar>,
+}
+impl Foo {
+    pub fn a(&self) -> u8 {
+        self.a
+    }
+    pub fn b(&self) -> Option<u16> {
+        self.b
+    }
+    pub fn c(&self) -> Option<Enum8> {
+        self.c
+    }
+    pub fn d(&self) -> &Option<Bar> {
+        &self.d
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: 0,
+            b: None,
+            c: None,
+            d: None,
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        1 + if self.b.is_some() { 2usize } else { 0 }
+            + if self.c.is_some() { 1usize } else { 0 }
+            + self.d.as_ref().map(Bar::encoded_len).unwrap_or(0)
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.a());
+        if let Some(b) = &self.b {
+            buf.put_u16_le(*b);
+        }
+        if self.c.is_some() != self.b.is_some() {
+            return Err(EncodeError::InconsistentExtensionField {
+                packet: "Foo",
+                field: "c",
+            });
+        }
+        if let Some(c) = &self.c {
+            buf.put_u8(u8::from(c));
+        }
+        if self.d.is_some() && self.b.is_none() {
+            return Err(EncodeError::InconsistentExtensionField {
+                packet: "Foo",
+                field: "d",
+            });
+        }
+        if let Some(d) = &self.d {
+            d.encode(buf)?;
+        }
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u8();
+        let b = if buf.has_remaining() {
+            if buf.remaining() < 2 {
+                return Err(DecodeError::LengthError {
+                    obj: "Foo",
+                    wanted: 2,
+                    got: buf.remaining(),
+                });
+            }
+            Some(buf.get_u16_le())
+        } else {
+            None
+        };
+        let c = if b.is_some() {
+            if buf.remaining() < 1 {
+                return Err(DecodeError::LengthError {
+                    obj: "Foo",
+                    wanted: 1,
+                    got: buf.remaining(),
+                });
+            }
+            Some(
+                Enum8::try_from(buf.get_u8())
+                    .map_err(|unknown_val| {
+                        DecodeError::EnumValueError {
+                            obj: "Foo",
+                            field: "c",
+                            value: unknown_val as u64,
+                            type_: "Enum8",
+                        }
+                    })?,
+            )
+        } else {
+            None
+        };
+        let d = if buf.has_remaining() {
+            Some(Bar::decode_mut(&mut buf)?)
+        } else {
+            None
+        };
+        Ok((Self { a, b, c, d }, buf))
+    }
+}
@@ -0,0 +1,412 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Default, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16", into = "u16"))]
+pub struct Flags12(u16);
+impl Flags12 {
+    pub const READ: Flags12 = Flags12(0x1);
+    pub const WRITE: Flags12 = Flags12(0x2);
+    const FLAGS: [(&'static str, Flags12); 2] = [
+        ("READ", Flags12::READ),
+        ("WRITE", Flags12::WRITE),
+    ];
+    /// Return the empty flag set.
+    pub const fn empty() -> Self {
+        Flags12(0)
+    }
+    /// Return the flag set containing all declared flags.
+    pub const fn all() -> Self {
+        Flags12(0x3)
+    }
+    /// Return the raw value of the flag set.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+    /// Return the bits of the flag set that do not match
+    /// any declared flag.
+    pub const fn unknown_bits(&self) -> u16 {
+        self.0 & !0x3
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    /// Return true if all flags of `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    /// Return true if any flag of `other` is set.
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+    pub fn toggle(&mut self, other: Self) {
+        self.0 ^= other.0;
+    }
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value { self.insert(other) } else { self.remove(other) }
+    }
+    /// Iterate over the declared flags contained in the flag set.
+    pub fn iter(&self) -> impl Iterator<Item = Flags12> {
+        let value = *self;
+        Flags12::FLAGS
+            .into_iter()
+            .map(|(_, flag)| flag)
+            .filter(move |flag| value.contains(*flag))
+    }
+}
+impl fmt::Debug for Flags12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = Flags12::FLAGS
+            .into_iter()
+            .filter(|(_, flag)| self.contains(*flag))
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        if self.unknown_bits() != 0 {
+            names.push(format!("{:#x}", self.unknown_bits()));
+        }
+        write!(f, "{}({})", "Flags12", names.join(" | "))
+    }
+}
+impl std::ops::BitOr for Flags12 {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        Flags12(self.0 | other.0)
+    }
+}
+impl std::ops::BitOrAssign for Flags12 {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+impl std::ops::BitAnd for Flags12 {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        Flags12(self.0 & other.0)
+    }
+}
+impl std::ops::BitAndAssign for Flags12 {
+    fn bitand_assign(&mut self, other: Self) {
+        self.0 &= other.0;
+    }
+}
+impl std::ops::BitXor for Flags12 {
+    type Output = Self;
+    fn bitxor(self, other: Self) -> Self {
+        Flags12(self.0 ^ other.0)
+    }
+}
+impl std::ops::BitXorAssign for Flags12 {
+    fn bitxor_assign(&mut self, other: Self) {
+        self.0 ^= other.0;
+    }
+}
+impl std::ops::Not for Flags12 {
+    type Output = Self;
+    fn not(self) -> Self {
+        Flags12(!self.0 & 0xfff)
+    }
+}
+impl std::iter::FromIterator<Flags12> for Flags12 {
+    fn from_iter<T: IntoIterator<Item = Flags12>>(iter: T) -> Self {
+        iter.into_iter().fold(Flags12::empty(), |all, flag| all | flag)
+    }
+}
+impl TryFrom<u16> for Flags12 {
+    type Error = u16;
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if value & !0xfff != 0 { Err(value) } else { Ok(Flags12(value)) }
+    }
+}
+impl From<&Flags12> for u16 {
+    fn from(value: &Flags12) -> Self {
+        value.0
+    }
+}
+impl From<Flags12> for u16 {
+    fn from(value: Flags12) -> Self {
+        value.0
+    }
+}
+impl From<Flags12> for i16 {
+    fn from(value: Flags12) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags12> for i32 {
+    fn from(value: Flags12) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags12> for i64 {
+    fn from(value: Flags12) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags12> for u32 {
+    fn from(value: Flags12) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags12> for u64 {
+    fn from(value: Flags12) -> Self {
+        u16::from(value) as Self
+    }
+}
+#[derive(Default, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16", into = "u16"))]
+pub struct Flags16(u16);
+impl Flags16 {
+    pub const A: Flags16 = Flags16(0x1);
+    pub const B: Flags16 = Flags16(0x100);
+    const FLAGS: [(&'static str, Flags16); 2] = [("A", Flags16::A), ("B", Flags16::B)];
+    /// Return the empty flag set.
+    pub const fn empty() -> Self {
+        Flags16(0)
+    }
+    /// Return the flag set containing all declared flags.
+    pub const fn all() -> Self {
+        Flags16(0x101)
+    }
+    /// Return the raw value of the flag set.
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+    /// Return the bits of the flag set that do not match
+    /// any declared flag.
+    pub const fn unknown_bits(&self) -> u16 {
+        self.0 & !0x101
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    /// Return true if all flags of `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    /// Return true if any flag of `other` is set.
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+    pub fn toggle(&mut self, other: Self) {
+        self.0 ^= other.0;
+    }
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value { self.insert(other) } else { self.remove(other) }
+    }
+    /// Iterate over the declared flags contained in the flag set.
+    pub fn iter(&self) -> impl Iterator<Item = Flags16> {
+        let value = *self;
+        Flags16::FLAGS
+            .into_iter()
+            .map(|(_, flag)| flag)
+            .filter(move |flag| value.contains(*flag))
+    }
+}
+impl fmt::Debug for Flags16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = Flags16::FLAGS
+            .into_iter()
+            .filter(|(_, flag)| self.contains(*flag))
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        if self.unknown_bits() != 0 {
+            names.push(format!("{:#x}", self.unknown_bits()));
+        }
+        write!(f, "{}({})", "Flags16", names.join(" | "))
+    }
+}
+impl std::ops::BitOr for Flags16 {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        Flags16(self.0 | other.0)
+    }
+}
+impl std::ops::BitOrAssign for Flags16 {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+impl std::ops::BitAnd for Flags16 {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        Flags16(self.0 & other.0)
+    }
+}
+impl std::ops::BitAndAssign for Flags16 {
+    fn bitand_assign(&mut self, other: Self) {
+        self.0 &= other.0;
+    }
+}
+impl std::ops::BitXor for Flags16 {
+    type Output = Self;
+    fn bitxor(self, other: Self) -> Self {
+        Flags16(self.0 ^ other.0)
+    }
+}
+impl std::ops::BitXorAssign for Flags16 {
+    fn bitxor_assign(&mut self, other: Self) {
+        self.0 ^= other.0;
+    }
+}
+impl std::ops::Not for Flags16 {
+    type Output = Self;
+    fn not(self) -> Self {
+        Flags16(!self.0 & 0xffff)
+    }
+}
+impl std::iter::FromIterator<Flags16> for Flags16 {
+    fn from_iter<T: IntoIterator<Item = Flags16>>(iter: T) -> Self {
+        iter.into_iter().fold(Flags16::empty(), |all, flag| all | flag)
+    }
+}
+impl TryFrom<u16> for Flags16 {
+    type Error = u16;
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Ok(Flags16(value))
+    }
+}
+impl From<&Flags16> for u16 {
+    fn from(value: &Flags16) -> Self {
+        value.0
+    }
+}
+impl From<Flags16> for u16 {
+    fn from(value: Flags16) -> Self {
+        value.0
+    }
+}
+impl From<Flags16> for i32 {
+    fn from(value: Flags16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags16> for i64 {
+    fn from(value: Flags16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags16> for u32 {
+    fn from(value: Flags16) -> Self {
+        u16::from(value) as Self
+    }
+}
+impl From<Flags16> for u64 {
+    fn from(value: Flags16) -> Self {
+        u16::from(value) as Self
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub x: Flags12,
+    pub y: u8,
+    pub z: Flags16,
+}
+impl Foo {
+    pub fn x(&self) -> Flags12 {
+        self.x
+    }
+    pub fn y(&self) -> u8 {
+        self.y
+    }
+    pub fn z(&self) -> Flags16 {
+        self.z
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            x: Default::default(),
+            y: 0,
+            z: Default::default(),
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        4
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.y() > 0xf {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "y",
+                value: self.y() as u64,
+                maximum_value: 0xf as u64,
+            });
+        }
+        let value = u16::from(self.x()) | ((self.y() as u16) << 12);
+        buf.put_u16_le(value);
+        buf.put_u16_le(u16::from(self.z()));
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = buf.get_u16_le();
+        let x = Flags12::try_from((chunk & 0xfff))
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Foo",
+                field: "x",
+                value: unknown_val as u64,
+                type_: "Flags12",
+            })?;
+        let y = ((chunk >> 12) & 0xf) as u8;
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let z = Flags16::try_from(buf.get_u16_le())
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Foo",
+                field: "z",
+                value: unknown_val as u64,
+                type_: "Flags16",
+            })?;
+        Ok((Self { x, y, z }, buf))
+    }
+}
@@ -0,0 +1,305 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub length: u8,
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameChild {
+    Command(Command),
+    #[default]
+    None,
+}
+impl Frame {
+    pub fn specialize(&self) -> Result<FrameChild, DecodeError> {
+        Ok(
+            match (self.opcode) {
+                (1) => FrameChild::Command(self.try_into()?),
+                _ => FrameChild::None,
+            },
+        )
+    }
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    pub fn opcode(&self) -> u8 {
+        self.opcode
+    }
+}
+impl Default for Frame {
+    fn default() -> Frame {
+        Frame {
+            length: 0,
+            opcode: 0,
+            payload: vec![],
+        }
+    }
+}
+impl Packet for Frame {
+    fn encoded_len(&self) -> usize {
+        2 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if !(((self.length() as i128) >= 2) && ((self.length() as i128) <= 64)) {
+            return Err(EncodeError::InvariantViolation {
+                packet: "Frame",
+                invariant: "length >= 2 && length <= 64",
+            });
+        }
+        buf.put_u8(self.length());
+        buf.put_u8(self.opcode());
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let length = buf.get_u8();
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let opcode = buf.get_u8();
+        let payload = buf.to_vec();
+        buf.advance(payload.len());
+        let packet = Self { payload, length, opcode };
+        if !(((packet.length() as i128) >= 2) && ((packet.length() as i128) <= 64)) {
+            return Err(DecodeError::InvariantViolation {
+                packet: "Frame",
+                invariant: "length >= 2 && length <= 64",
+            });
+        }
+        Ok((packet, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    pub args: Vec<u8>,
+    pub length: u8,
+}
+impl TryFrom<&Command> for Frame {
+    type Error = EncodeError;
+    fn try_from(packet: &Command) -> Result<Frame, Self::Error> {
+        let mut payload = Vec::new();
+        packet.encode_partial(&mut payload)?;
+        Ok(Frame {
+            length: packet.length,
+            opcode: 1,
+            payload,
+        })
+    }
+}
+impl TryFrom<Command> for Frame {
+    type Error = EncodeError;
+    fn try_from(packet: Command) -> Result<Frame, Self::Error> {
+        (&packet).try_into()
+    }
+}
+impl TryFrom<&Frame> for Command {
+    type Error = DecodeError;
+    fn try_from(parent: &Frame) -> Result<Command, Self::Error> {
+        Command::decode_partial(&parent)
+    }
+}
+impl TryFrom<Frame> for Command {
+    type Error = DecodeError;
+    fn try_from(parent: Frame) -> Result<Command, Self::Error> {
+        (&parent).try_into()
+    }
+}
+impl Command {
+    fn decode_partial(parent: &Frame) -> Result<Self, DecodeError> {
+        let mut buf: &[u8] = &parent.payload;
+        if parent.opcode() != 1 {
+            return Err(DecodeError::ConstraintValueError {
+                packet: "Command",
+                field: "opcode",
+                expected: "1",
+                actual: format!("{:?}", parent.opcode()),
+            });
+        }
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Command",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let args_count = buf.get_u8() as usize;
+        if buf.remaining() < args_count * 1usize {
+            return Err(DecodeError::LengthError {
+                obj: "Command",
+                wanted: args_count * 1usize,
+                got: buf.remaining(),
+            });
+        }
+        let args = (0..args_count)
+            .map(|_| Ok::<_, DecodeError>(buf.get_u8()))
+            .collect::<Result<Vec<_>, DecodeError>>()?;
+        if buf.is_empty() {
+            let packet = Self {
+                args,
+                length: parent.length,
+            };
+            if !((((packet.args().len() as i128) * 2) + 2) == (packet.length() as i128))
+            {
+                return Err(DecodeError::InvariantViolation {
+                    packet: "Command",
+                    invariant: "len(args) * 2 + 2 == length",
+                });
+            }
+            Ok(packet)
+        } else {
+            Err(DecodeError::TrailingBytesError)
+        }
+    }
+    pub fn encode_partial(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.args.len() as u8);
+        for elem in &self.args {
+            buf.put_u8(*elem);
+        }
+        Ok(())
+    }
+    pub fn args(&self) -> &Vec<u8> {
+        &self.args
+    }
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+    pub fn opcode(&self) -> u8 {
+        1
+    }
+}
+impl Default for Command {
+    fn default() -> Command {
+        Command { args: vec![], length: 0 }
+    }
+}
+impl Packet for Command {
+    fn encoded_len(&self) -> usize {
+        3 + self.args.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if !((((self.args().len() as i128) * 2) + 2) == (self.length() as i128)) {
+            return Err(EncodeError::InvariantViolation {
+                packet: "Command",
+                invariant: "len(args) * 2 + 2 == length",
+            });
+        }
+        if !(((self.length() as i128) >= 2) && ((self.length() as i128) <= 64)) {
+            return Err(EncodeError::InvariantViolation {
+                packet: "Command",
+                invariant: "length >= 2 && length <= 64",
+            });
+        }
+        buf.put_u8(self.length());
+        buf.put_u8(self.opcode());
+        self.encode_partial(buf)?;
+        Ok(())
+    }
+    fn decode(buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let (parent, trailing_bytes) = Frame::decode(buf)?;
+        let packet = Self::decode_partial(&parent)?;
+        Ok((packet, trailing_bytes))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    pub low: u16,
+    pub high: u16,
+}
+impl Range {
+    pub fn low(&self) -> u16 {
+        self.low
+    }
+    pub fn high(&self) -> u16 {
+        self.high
+    }
+}
+impl Default for Range {
+    fn default() -> Range {
+        Range { low: 0, high: 0 }
+    }
+}
+impl Packet for Range {
+    fn encoded_len(&self) -> usize {
+        4
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if !((self.low() as i128) <= (self.high() as i128)) {
+            return Err(EncodeError::InvariantViolation {
+                packet: "Range",
+                invariant: "low <= high",
+            });
+        }
+        buf.put_u16_le(self.low());
+        buf.put_u16_le(self.high());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Range",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let low = buf.get_u16_le();
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Range",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let high = buf.get_u16_le();
+        let packet = Self { low, high };
+        if !((packet.low() as i128) <= (packet.high() as i128)) {
+            return Err(DecodeError::InvariantViolation {
+                packet: "Range",
+                invariant: "low <= high",
+            });
+        }
+        Ok((packet, buf))
+    }
+}
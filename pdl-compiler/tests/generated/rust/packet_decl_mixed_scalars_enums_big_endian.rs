@@ -36,6 +36,35 @@ impl Default for Enum7 {
         Enum7::A
     }
 }
+impl Enum7 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum7::A => "A",
+            Enum7::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum7 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum7 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum7::A),
+            "B" => Ok(Enum7::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum7",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for Enum7 {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -107,6 +136,35 @@ impl Default for Enum9 {
         Enum9::A
     }
 }
+impl Enum9 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum9::A => "A",
+            Enum9::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum9 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum9 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum9::A),
+            "B" => Ok(Enum9::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum9",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u16> for Enum9 {
     type Error = u16;
     fn try_from(value: u16) -> Result<Self, Self::Error> {
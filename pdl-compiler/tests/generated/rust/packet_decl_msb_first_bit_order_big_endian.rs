@@ -0,0 +1,222 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub enum Enum4 {
+    A = 0x1,
+    B = 0x2,
+}
+impl Default for Enum4 {
+    fn default() -> Enum4 {
+        Enum4::A
+    }
+}
+impl Enum4 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum4::A => "A",
+            Enum4::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Enum4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum4 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum4::A),
+            "B" => Ok(Enum4::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum4",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u8> for Enum4 {
+    type Error = u8;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Enum4::A),
+            0x2 => Ok(Enum4::B),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Enum4> for u8 {
+    fn from(value: &Enum4) -> Self {
+        match value {
+            Enum4::A => 0x1,
+            Enum4::B => 0x2,
+        }
+    }
+}
+impl From<Enum4> for u8 {
+    fn from(value: Enum4) -> Self {
+        (&value).into()
+    }
+}
+impl From<Enum4> for i8 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for i16 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for i32 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for i64 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for u16 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for u32 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Enum4> for u64 {
+    fn from(value: Enum4) -> Self {
+        u8::from(value) as Self
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u8,
+    pub b: Enum4,
+    pub c: u16,
+    pub d: u8,
+}
+impl Foo {
+    pub fn a(&self) -> u8 {
+        self.a
+    }
+    pub fn b(&self) -> Enum4 {
+        self.b
+    }
+    pub fn c(&self) -> u16 {
+        self.c
+    }
+    pub fn d(&self) -> u8 {
+        self.d
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: 0,
+            b: Default::default(),
+            c: 0,
+            d: 0,
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        3
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.a() > 0xf {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "a",
+                value: self.a() as u64,
+                maximum_value: 0xf as u64,
+            });
+        }
+        let value = (self.a() << 4) | u8::from(self.b());
+        buf.put_u8(value);
+        if self.c() > 0xfff {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "c",
+                value: self.c() as u64,
+                maximum_value: 0xfff as u64,
+            });
+        }
+        if self.d() > 0xf {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "d",
+                value: self.d() as u64,
+                maximum_value: 0xf as u64,
+            });
+        }
+        let value = (self.c() << 4) | (self.d() as u16);
+        buf.put_u16(value);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = buf.get_u8();
+        let a = ((chunk >> 4) & 0xf);
+        let b = Enum4::try_from((chunk & 0xf))
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Foo",
+                field: "b",
+                value: unknown_val as u64,
+                type_: "Enum4",
+            })?;
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = buf.get_u16();
+        let c = ((chunk >> 4) & 0xfff);
+        let d = (chunk & 0xf) as u8;
+        Ok((Self { a, b, c, d }, buf))
+    }
+}
@@ -37,6 +37,37 @@ impl Default for Enum8 {
         Enum8::A
     }
 }
+impl Enum8 {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Enum8::A => "A",
+            Enum8::B => "B",
+            Enum8::C => "C",
+        }
+    }
+}
+impl std::fmt::Display for Enum8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Enum8 {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Enum8::A),
+            "B" => Ok(Enum8::B),
+            "C" => Ok(Enum8::C),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Enum8",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
 impl TryFrom<u8> for Enum8 {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -0,0 +1,289 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[repr(u64)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
+pub enum Kind {
+    A = 0x1,
+    B = 0x2,
+}
+impl Default for Kind {
+    fn default() -> Kind {
+        Kind::A
+    }
+}
+impl Kind {
+    /// Return the display name of the enum value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::A => "A",
+            Kind::B => "B",
+        }
+    }
+}
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl std::str::FromStr for Kind {
+    type Err = pdl_runtime::ParseEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Kind::A),
+            "B" => Ok(Kind::B),
+            _ => {
+                Err(pdl_runtime::ParseEnumError {
+                    type_: "Kind",
+                    value: s.to_owned(),
+                })
+            }
+        }
+    }
+}
+impl TryFrom<u8> for Kind {
+    type Error = u8;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x1 => Ok(Kind::A),
+            0x2 => Ok(Kind::B),
+            _ => Err(value),
+        }
+    }
+}
+impl From<&Kind> for u8 {
+    fn from(value: &Kind) -> Self {
+        match value {
+            Kind::A => 0x1,
+            Kind::B => 0x2,
+        }
+    }
+}
+impl From<Kind> for u8 {
+    fn from(value: Kind) -> Self {
+        (&value).into()
+    }
+}
+impl From<Kind> for i16 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Kind> for i32 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Kind> for i64 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Kind> for u16 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Kind> for u32 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+impl From<Kind> for u64 {
+    fn from(value: Kind) -> Self {
+        u8::from(value) as Self
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub channel: u16,
+    pub kind: Kind,
+    pub payload: Vec<u8>,
+}
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameBinding {
+    Att(Att),
+    Smp(Smp),
+    #[default]
+    None,
+}
+impl Frame {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    /// Decode the payload as `Att`.
+    pub fn decode_payload_as_att(&self) -> Result<Att, DecodeError> {
+        Att::decode_full(&self.payload)
+    }
+    /// Decode the payload as `Smp`.
+    pub fn decode_payload_as_smp(&self) -> Result<Smp, DecodeError> {
+        Smp::decode_full(&self.payload)
+    }
+    /// Decode the payload as the declaration bound by the first
+    /// bind declaration matching the field values.
+    pub fn decode_payload(&self) -> Result<FrameBinding, DecodeError> {
+        if self.channel() == 4 {
+            return Ok(FrameBinding::Att(self.decode_payload_as_att()?));
+        }
+        if self.channel() == 6 && self.kind() == Kind::B {
+            return Ok(FrameBinding::Smp(self.decode_payload_as_smp()?));
+        }
+        Ok(FrameBinding::None)
+    }
+    pub fn channel(&self) -> u16 {
+        self.channel
+    }
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+impl Default for Frame {
+    fn default() -> Frame {
+        Frame {
+            channel: 0,
+            kind: Default::default(),
+            payload: vec![],
+        }
+    }
+}
+impl Packet for Frame {
+    fn encoded_len(&self) -> usize {
+        3 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16(self.channel());
+        buf.put_u8(u8::from(self.kind()));
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let channel = buf.get_u16();
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let kind = Kind::try_from(buf.get_u8())
+            .map_err(|unknown_val| DecodeError::EnumValueError {
+                obj: "Frame",
+                field: "kind",
+                value: unknown_val as u64,
+                type_: "Kind",
+            })?;
+        let payload = buf.to_vec();
+        buf.advance(payload.len());
+        Ok((Self { payload, channel, kind }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Att {
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+impl Att {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn opcode(&self) -> u8 {
+        self.opcode
+    }
+}
+impl Default for Att {
+    fn default() -> Att {
+        Att { opcode: 0, payload: vec![] }
+    }
+}
+impl Packet for Att {
+    fn encoded_len(&self) -> usize {
+        1 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.opcode());
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Att",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let opcode = buf.get_u8();
+        let payload = buf.to_vec();
+        buf.advance(payload.len());
+        Ok((Self { payload, opcode }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Smp {
+    pub code: u8,
+}
+impl Smp {
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+}
+impl Default for Smp {
+    fn default() -> Smp {
+        Smp { code: 0 }
+    }
+}
+impl Packet for Smp {
+    fn encoded_len(&self) -> usize {
+        1
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.code());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Smp",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let code = buf.get_u8();
+        Ok((Self { code }, buf))
+    }
+}
@@ -0,0 +1,122 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadBdAddr {
+    pub opcode: u16,
+}
+impl ReadBdAddr {
+    pub fn opcode(&self) -> u16 {
+        self.opcode
+    }
+}
+impl Default for ReadBdAddr {
+    fn default() -> ReadBdAddr {
+        ReadBdAddr { opcode: 0 }
+    }
+}
+impl Packet for ReadBdAddr {
+    fn encoded_len(&self) -> usize {
+        2
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16_le(self.opcode());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "ReadBdAddr",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let opcode = buf.get_u16_le();
+        Ok((Self { opcode }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadBdAddrComplete {
+    pub status: u8,
+    pub bd_addr: u64,
+}
+impl ReadBdAddrComplete {
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+    pub fn bd_addr(&self) -> u64 {
+        self.bd_addr
+    }
+}
+impl Default for ReadBdAddrComplete {
+    fn default() -> ReadBdAddrComplete {
+        ReadBdAddrComplete {
+            status: 0,
+            bd_addr: 0,
+        }
+    }
+}
+impl Packet for ReadBdAddrComplete {
+    fn encoded_len(&self) -> usize {
+        7
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.status());
+        if self.bd_addr() > 0xffff_ffff_ffff_u64 {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "ReadBdAddrComplete",
+                field: "bd_addr",
+                value: self.bd_addr() as u64,
+                maximum_value: 0xffff_ffff_ffff_u64 as u64,
+            });
+        }
+        buf.put_uint_le(self.bd_addr(), 6);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "ReadBdAddrComplete",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let status = buf.get_u8();
+        if buf.remaining() < 6 {
+            return Err(DecodeError::LengthError {
+                obj: "ReadBdAddrComplete",
+                wanted: 6,
+                got: buf.remaining(),
+            });
+        }
+        let bd_addr = buf.get_uint_le(6);
+        Ok((Self { status, bd_addr }, buf))
+    }
+}
+impl pdl_runtime::Command for ReadBdAddr {
+    type Response = ReadBdAddrComplete;
+}
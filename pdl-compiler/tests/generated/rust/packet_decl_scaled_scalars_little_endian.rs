@@ -0,0 +1,278 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u16,
+    pub b: u16,
+    pub d: Option<u8>,
+    pub payload: Vec<u8>,
+}
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FooChild {
+    Bar(Bar),
+    #[default]
+    None,
+}
+impl Foo {
+    pub fn specialize(&self) -> Result<FooChild, DecodeError> {
+        Ok(
+            match () {
+                _ => FooChild::None,
+            },
+        )
+    }
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+    pub fn b(&self) -> u16 {
+        self.b
+    }
+    pub fn d(&self) -> Option<u8> {
+        self.d
+    }
+    /// Value of `a` in ms.
+    pub fn a_scaled(&self) -> f64 {
+        self.a as f64 * 1.25
+    }
+    /// Value of `a` in ms.
+    pub fn set_a_scaled(&mut self, value: f64) {
+        self.a = (value / 1.25).round().clamp(0.0, 0xfff as f64) as u16;
+    }
+    /// Scaled value of `b`.
+    pub fn b_scaled(&self) -> f64 {
+        self.b as f64 * 0.5
+    }
+    /// Scaled value of `b`.
+    pub fn set_b_scaled(&mut self, value: f64) {
+        self.b = (value / 0.5).round() as u16;
+    }
+    /// Value of `d` in dBm.
+    pub fn d_scaled(&self) -> Option<f64> {
+        self.d.map(|raw| raw as f64 * 2.0)
+    }
+    /// Value of `d` in dBm.
+    pub fn set_d_scaled(&mut self, value: Option<f64>) {
+        self.d = value.map(|value| (value / 2.0).round() as u8);
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: 0,
+            b: 0,
+            d: None,
+            payload: vec![],
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        4 + if self.d.is_some() { 1usize } else { 0 } + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.a() > 0xfff {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "a",
+                value: self.a() as u64,
+                maximum_value: 0xfff as u64,
+            });
+        }
+        let value = (self.a() as u32) | ((self.b() as u32) << 12)
+            | ((if self.d.is_some() { 1 } else { 0 } as u32) << 28);
+        buf.put_u32_le(value);
+        if let Some(d) = &self.d {
+            buf.put_u8(*d);
+        }
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 4 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 4,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = buf.get_u32_le();
+        let a = (chunk & 0xfff) as u16;
+        let b = (chunk >> 12) as u16;
+        let c = ((chunk >> 28) & 0x1) as u8;
+        let d = (c == 1).then(|| buf.get_u8());
+        let payload = buf.to_vec();
+        buf.advance(payload.len());
+        Ok((Self { payload, a, b, d }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub e: u32,
+    pub a: u16,
+    pub b: u16,
+    pub d: Option<u8>,
+}
+impl TryFrom<&Bar> for Foo {
+    type Error = EncodeError;
+    fn try_from(packet: &Bar) -> Result<Foo, Self::Error> {
+        let mut payload = Vec::new();
+        packet.encode_partial(&mut payload)?;
+        Ok(Foo {
+            a: packet.a,
+            b: packet.b,
+            d: packet.d,
+            payload,
+        })
+    }
+}
+impl TryFrom<Bar> for Foo {
+    type Error = EncodeError;
+    fn try_from(packet: Bar) -> Result<Foo, Self::Error> {
+        (&packet).try_into()
+    }
+}
+impl TryFrom<&Foo> for Bar {
+    type Error = DecodeError;
+    fn try_from(parent: &Foo) -> Result<Bar, Self::Error> {
+        Bar::decode_partial(&parent)
+    }
+}
+impl TryFrom<Foo> for Bar {
+    type Error = DecodeError;
+    fn try_from(parent: Foo) -> Result<Bar, Self::Error> {
+        (&parent).try_into()
+    }
+}
+impl Bar {
+    fn decode_partial(parent: &Foo) -> Result<Self, DecodeError> {
+        let mut buf: &[u8] = &parent.payload;
+        if buf.remaining() < 4 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 4,
+                got: buf.remaining(),
+            });
+        }
+        let e = buf.get_u32_le();
+        if buf.is_empty() {
+            Ok(Self {
+                e,
+                a: parent.a,
+                b: parent.b,
+                d: parent.d,
+            })
+        } else {
+            Err(DecodeError::TrailingBytesError)
+        }
+    }
+    pub fn encode_partial(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u32_le(self.e());
+        Ok(())
+    }
+    pub fn e(&self) -> u32 {
+        self.e
+    }
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+    pub fn b(&self) -> u16 {
+        self.b
+    }
+    pub fn d(&self) -> Option<u8> {
+        self.d
+    }
+    /// Value of `e` in s.
+    pub fn e_scaled(&self) -> f64 {
+        self.e as f64 * 0.001
+    }
+    /// Value of `e` in s.
+    pub fn set_e_scaled(&mut self, value: f64) {
+        self.e = (value / 0.001).round() as u32;
+    }
+    /// Value of `a` in ms.
+    pub fn a_scaled(&self) -> f64 {
+        self.a as f64 * 1.25
+    }
+    /// Value of `a` in ms.
+    pub fn set_a_scaled(&mut self, value: f64) {
+        self.a = (value / 1.25).round().clamp(0.0, 0xfff as f64) as u16;
+    }
+    /// Scaled value of `b`.
+    pub fn b_scaled(&self) -> f64 {
+        self.b as f64 * 0.5
+    }
+    /// Scaled value of `b`.
+    pub fn set_b_scaled(&mut self, value: f64) {
+        self.b = (value / 0.5).round() as u16;
+    }
+    /// Value of `d` in dBm.
+    pub fn d_scaled(&self) -> Option<f64> {
+        self.d.map(|raw| raw as f64 * 2.0)
+    }
+    /// Value of `d` in dBm.
+    pub fn set_d_scaled(&mut self, value: Option<f64>) {
+        self.d = value.map(|value| (value / 2.0).round() as u8);
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar { e: 0, a: 0, b: 0, d: None }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        8 + if self.d.is_some() { 1usize } else { 0 }
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.a() > 0xfff {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "a",
+                value: self.a() as u64,
+                maximum_value: 0xfff as u64,
+            });
+        }
+        let value = (self.a() as u32) | ((self.b() as u32) << 12)
+            | ((if self.d.is_some() { 1 } else { 0 } as u32) << 28);
+        buf.put_u32_le(value);
+        if let Some(d) = &self.d {
+            buf.put_u8(*d);
+        }
+        self.encode_partial(buf)?;
+        Ok(())
+    }
+    fn decode(buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let (parent, trailing_bytes) = Foo::decode(buf)?;
+        let packet = Self::decode_partial(&parent)?;
+        Ok((packet, trailing_bytes))
+    }
+}
@@ -0,0 +1,295 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameChild {
+    Command(Command),
+    #[default]
+    None,
+}
+impl Frame {
+    pub fn specialize(&self) -> Result<FrameChild, DecodeError> {
+        Ok(
+            match (self.opcode) {
+                (1) => FrameChild::Command(self.try_into()?),
+                _ => FrameChild::None,
+            },
+        )
+    }
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn opcode(&self) -> u8 {
+        self.opcode
+    }
+}
+impl Default for Frame {
+    fn default() -> Frame {
+        Frame {
+            opcode: 0,
+            payload: vec![],
+        }
+    }
+}
+impl Packet for Frame {
+    fn encoded_len(&self) -> usize {
+        2 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        let size = self.encoded_len();
+        if !(size <= 16) {
+            return Err(EncodeError::SizeAssertion {
+                packet: "Frame",
+                size,
+                assertion: "size <= 16",
+            });
+        }
+        #[allow(unused_comparisons)]
+        if self.payload.len() > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Frame",
+                field: "_payload_",
+                size: self.payload.len(),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8((self.payload.len()) as u8);
+        buf.put_u8(self.opcode());
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let input = buf;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let payload_size = buf.get_u8() as usize;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let opcode = buf.get_u8();
+        if buf.remaining() < payload_size {
+            return Err(DecodeError::LengthError {
+                obj: "Frame",
+                wanted: payload_size,
+                got: buf.remaining(),
+            });
+        }
+        let payload = buf[..payload_size].to_vec();
+        buf.advance(payload_size);
+        let size = input.len() - buf.len();
+        if !(size <= 16) {
+            return Err(DecodeError::SizeAssertionError {
+                obj: "Frame",
+                size,
+                assertion: "size <= 16",
+            });
+        }
+        Ok((Self { payload, opcode }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    pub args: Vec<u8>,
+}
+impl TryFrom<&Command> for Frame {
+    type Error = EncodeError;
+    fn try_from(packet: &Command) -> Result<Frame, Self::Error> {
+        let mut payload = Vec::new();
+        packet.encode_partial(&mut payload)?;
+        Ok(Frame { opcode: 1, payload })
+    }
+}
+impl TryFrom<Command> for Frame {
+    type Error = EncodeError;
+    fn try_from(packet: Command) -> Result<Frame, Self::Error> {
+        (&packet).try_into()
+    }
+}
+impl TryFrom<&Frame> for Command {
+    type Error = DecodeError;
+    fn try_from(parent: &Frame) -> Result<Command, Self::Error> {
+        Command::decode_partial(&parent)
+    }
+}
+impl TryFrom<Frame> for Command {
+    type Error = DecodeError;
+    fn try_from(parent: Frame) -> Result<Command, Self::Error> {
+        (&parent).try_into()
+    }
+}
+impl Command {
+    fn decode_partial(parent: &Frame) -> Result<Self, DecodeError> {
+        let mut buf: &[u8] = &parent.payload;
+        if parent.opcode() != 1 {
+            return Err(DecodeError::ConstraintValueError {
+                packet: "Command",
+                field: "opcode",
+                expected: "1",
+                actual: format!("{:?}", parent.opcode()),
+            });
+        }
+        let size = parent.encoded_len();
+        if !(size >= 4) {
+            return Err(DecodeError::SizeAssertionError {
+                obj: "Command",
+                size,
+                assertion: "size >= 4",
+            });
+        }
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Command",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let args_count = buf.get_u8() as usize;
+        if buf.remaining() < args_count * 1usize {
+            return Err(DecodeError::LengthError {
+                obj: "Command",
+                wanted: args_count * 1usize,
+                got: buf.remaining(),
+            });
+        }
+        let args = (0..args_count)
+            .map(|_| Ok::<_, DecodeError>(buf.get_u8()))
+            .collect::<Result<Vec<_>, DecodeError>>()?;
+        if buf.is_empty() {
+            Ok(Self { args })
+        } else {
+            Err(DecodeError::TrailingBytesError)
+        }
+    }
+    pub fn encode_partial(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.args.len() as u8);
+        for elem in &self.args {
+            buf.put_u8(*elem);
+        }
+        Ok(())
+    }
+    pub fn args(&self) -> &Vec<u8> {
+        &self.args
+    }
+    pub fn opcode(&self) -> u8 {
+        1
+    }
+}
+impl Default for Command {
+    fn default() -> Command {
+        Command { args: vec![] }
+    }
+}
+impl Packet for Command {
+    fn encoded_len(&self) -> usize {
+        3 + self.args.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        let size = self.encoded_len();
+        if !(size >= 4) {
+            return Err(EncodeError::SizeAssertion {
+                packet: "Command",
+                size,
+                assertion: "size >= 4",
+            });
+        }
+        if !(size <= 16) {
+            return Err(EncodeError::SizeAssertion {
+                packet: "Command",
+                size,
+                assertion: "size <= 16",
+            });
+        }
+        #[allow(unused_comparisons)]
+        if 1 + self.args.len() > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Frame",
+                field: "_payload_",
+                size: 1 + self.args.len(),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8((1 + self.args.len()) as u8);
+        buf.put_u8(self.opcode());
+        self.encode_partial(buf)?;
+        Ok(())
+    }
+    fn decode(buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let (parent, trailing_bytes) = Frame::decode(buf)?;
+        let packet = Self::decode_partial(&parent)?;
+        Ok((packet, trailing_bytes))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    pub a: u16,
+}
+impl Header {
+    pub fn a(&self) -> u16 {
+        self.a
+    }
+}
+impl Default for Header {
+    fn default() -> Header {
+        Header { a: 0 }
+    }
+}
+impl Packet for Header {
+    fn encoded_len(&self) -> usize {
+        2
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u16_le(self.a());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Header",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u16_le();
+        Ok((Self { a }, buf))
+    }
+}
@@ -0,0 +1,179 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: Vec<u8>,
+    pub b: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+impl Foo {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn a(&self) -> &Vec<u8> {
+        &self.a
+    }
+    pub fn b(&self) -> &Vec<u8> {
+        &self.b
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: vec![],
+            b: vec![],
+            payload: vec![],
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        4 + self.a.len() + self.b.len() + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        #[allow(unused_comparisons)]
+        if (self.a.len() * 8) > 0xffff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Foo",
+                field: "a",
+                size: (self.a.len() * 8),
+                maximum_size: 0xffff,
+            });
+        }
+        buf.put_u16(((self.a.len() * 8)) as u16);
+        if self.b.len() % 4 != 0 {
+            return Err(EncodeError::UnalignedSize {
+                packet: "Foo",
+                field: "b",
+                size: self.b.len(),
+                unit: 4,
+            });
+        }
+        #[allow(unused_comparisons)]
+        if (self.b.len() / 4) > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Foo",
+                field: "b",
+                size: (self.b.len() / 4),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8(((self.b.len() / 4)) as u8);
+        for elem in &self.a {
+            buf.put_u8(*elem);
+        }
+        for elem in &self.b {
+            buf.put_u8(*elem);
+        }
+        if self.payload.len() % 2 != 0 {
+            return Err(EncodeError::UnalignedSize {
+                packet: "Foo",
+                field: "_payload_",
+                size: self.payload.len(),
+                unit: 2,
+            });
+        }
+        #[allow(unused_comparisons)]
+        if (self.payload.len() / 2) > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Foo",
+                field: "_payload_",
+                size: (self.payload.len() / 2),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8(((self.payload.len() / 2)) as u8);
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let a_size = buf.get_u16() as usize;
+        if a_size % 8 != 0 {
+            return Err(DecodeError::SizeUnitError {
+                obj: "Foo",
+                field: "a",
+                size: a_size,
+            });
+        }
+        let a_size = a_size / 8;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let b_size = (buf.get_u8() as usize) * 4;
+        if buf.remaining() < a_size {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: a_size,
+                got: buf.remaining(),
+            });
+        }
+        let mut a = Vec::with_capacity(a_size);
+        for _ in 0..a_size {
+            a.push(Ok::<_, DecodeError>(buf.get_u8())?);
+        }
+        if buf.remaining() < b_size {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: b_size,
+                got: buf.remaining(),
+            });
+        }
+        let mut b = Vec::with_capacity(b_size);
+        for _ in 0..b_size {
+            b.push(Ok::<_, DecodeError>(buf.get_u8())?);
+        }
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let payload_size = (buf.get_u8() as usize) * 2;
+        if buf.remaining() < payload_size {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: payload_size,
+                got: buf.remaining(),
+            });
+        }
+        let payload = buf[..payload_size].to_vec();
+        buf.advance(payload_size);
+        Ok((Self { payload, a, b }, buf))
+    }
+}
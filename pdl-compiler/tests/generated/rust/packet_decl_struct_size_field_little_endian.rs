@@ -0,0 +1,144 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub a: u8,
+    pub payload: Vec<u8>,
+}
+impl Bar {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn a(&self) -> u8 {
+        self.a
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar { a: 0, payload: vec![] }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        1 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.a());
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u8();
+        let payload = buf.to_vec();
+        buf.advance(payload.len());
+        let payload = Vec::from(payload);
+        Ok((Self { payload, a }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub b: Bar,
+    pub c: u8,
+}
+impl Foo {
+    pub fn b(&self) -> &Bar {
+        &self.b
+    }
+    pub fn c(&self) -> u8 {
+        self.c
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo { b: Default::default(), c: 0 }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        2 + self.b.encoded_len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        if self.b.encoded_len() % 2 != 0 {
+            return Err(EncodeError::UnalignedSize {
+                packet: "Foo",
+                field: "b",
+                size: self.b.encoded_len(),
+                unit: 2,
+            });
+        }
+        #[allow(unused_comparisons)]
+        if (self.b.encoded_len() / 2) > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Foo",
+                field: "b",
+                size: (self.b.encoded_len() / 2),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8(((self.b.encoded_len() / 2)) as u8);
+        self.b.encode(buf)?;
+        buf.put_u8(self.c());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let b_size = (buf.get_u8() as usize) * 2;
+        if buf.remaining() < b_size {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: b_size,
+                got: buf.remaining(),
+            });
+        }
+        let (head, tail) = buf.split_at(b_size);
+        buf = tail;
+        let b = Bar::decode_full(head)?;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let c = buf.get_u8();
+        Ok((Self { b, c }, buf))
+    }
+}
@@ -0,0 +1,255 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: Vec<u8>,
+    pub c: u8,
+}
+impl Foo {
+    pub fn a(&self) -> &Vec<u8> {
+        &self.a
+    }
+    pub fn c(&self) -> u8 {
+        self.c
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo { a: vec![], c: 0 }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        3 + self.a.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        for elem in &self.a {
+            buf.put_u8(*elem);
+        }
+        #[allow(unused_comparisons)]
+        if (self.a.len() * 8) > 0xffff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Foo",
+                field: "a",
+                size: (self.a.len() * 8),
+                maximum_size: 0xffff,
+            });
+        }
+        buf.put_u16(((self.a.len() * 8)) as u16);
+        buf.put_u8(self.c());
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 3 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 3,
+                got: buf.remaining(),
+            });
+        }
+        let a_size = buf.len() - 3;
+        if buf.remaining() < a_size {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: a_size,
+                got: buf.remaining(),
+            });
+        }
+        let mut a = Vec::with_capacity(a_size);
+        for _ in 0..a_size {
+            a.push(Ok::<_, DecodeError>(buf.get_u8())?);
+        }
+        if buf.remaining() < 2 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 2,
+                got: buf.remaining(),
+            });
+        }
+        let trailing_a_size = buf.get_u16() as usize;
+        if trailing_a_size % 8 != 0 {
+            return Err(DecodeError::SizeUnitError {
+                obj: "Foo",
+                field: "a",
+                size: trailing_a_size,
+            });
+        }
+        let trailing_a_size = trailing_a_size / 8;
+        if trailing_a_size != a_size {
+            return Err(DecodeError::TrailingSizeError {
+                obj: "Foo",
+                field: "a",
+                expected: a_size,
+                actual: trailing_a_size,
+            });
+        }
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let c = buf.get_u8();
+        Ok((Self { a, c }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    pub b: Vec<u16>,
+}
+impl Bar {
+    pub fn b(&self) -> &Vec<u16> {
+        &self.b
+    }
+}
+impl Default for Bar {
+    fn default() -> Bar {
+        Bar { b: vec![] }
+    }
+}
+impl Packet for Bar {
+    fn encoded_len(&self) -> usize {
+        1 + (self.b.len() * 2)
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        for elem in &self.b {
+            buf.put_u16(*elem);
+        }
+        buf.put_u8(self.b.len() as u8);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let b_size = buf.len() - 1;
+        if buf.remaining() < b_size {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: b_size,
+                got: buf.remaining(),
+            });
+        }
+        if b_size % 2 != 0 {
+            return Err(DecodeError::ArraySizeError {
+                array: b_size,
+                element: 2,
+            });
+        }
+        let b_count = b_size / 2;
+        let mut b = Vec::with_capacity(b_count);
+        for _ in 0..b_count {
+            b.push(Ok::<_, DecodeError>(buf.get_u16())?);
+        }
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Bar",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let trailing_b_count = buf.get_u8() as usize;
+        if trailing_b_count != b.len() {
+            return Err(DecodeError::TrailingSizeError {
+                obj: "Bar",
+                field: "b",
+                expected: b.len(),
+                actual: trailing_b_count,
+            });
+        }
+        Ok((Self { b }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Baz {
+    pub payload: Vec<u8>,
+}
+impl Baz {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+impl Default for Baz {
+    fn default() -> Baz {
+        Baz { payload: vec![] }
+    }
+}
+impl Packet for Baz {
+    fn encoded_len(&self) -> usize {
+        1 + self.payload.len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_slice(&self.payload);
+        #[allow(unused_comparisons)]
+        if (self.payload.len() + 1) > 0xff {
+            return Err(EncodeError::SizeOverflow {
+                packet: "Baz",
+                field: "_payload_",
+                size: (self.payload.len() + 1),
+                maximum_size: 0xff,
+            });
+        }
+        buf.put_u8(((self.payload.len() + 1)) as u8);
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Baz",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let payload = buf[..buf.len() - 1].to_vec();
+        buf.advance(payload.len());
+        let payload_size = payload.len() + 1;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Baz",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let trailing_payload_size = buf.get_u8() as usize;
+        if trailing_payload_size != payload_size {
+            return Err(DecodeError::TrailingSizeError {
+                obj: "Baz",
+                field: "_payload_",
+                expected: payload_size,
+                actual: trailing_payload_size,
+            });
+        }
+        Ok((Self { payload }, buf))
+    }
+}
@@ -0,0 +1,205 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Default, Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "u128", into = "u128"))]
+pub struct Uuid(u128);
+impl From<&Uuid> for u128 {
+    fn from(value: &Uuid) -> u128 {
+        value.0
+    }
+}
+impl From<Uuid> for u128 {
+    fn from(value: Uuid) -> u128 {
+        value.0
+    }
+}
+impl Packet for Uuid {
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.len() < 16 {
+            return Err(DecodeError::LengthError {
+                obj: "Uuid",
+                wanted: 16,
+                got: buf.len(),
+            });
+        }
+        Ok((buf.get_u128_le().into(), buf))
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u128_le(u128::from(self));
+        Ok(())
+    }
+    fn encoded_len(&self) -> usize {
+        16
+    }
+}
+impl From<u128> for Uuid {
+    fn from(value: u128) -> Self {
+        Uuid(value)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: u128,
+    pub b: u128,
+    pub d: u128,
+    pub e: u8,
+    pub u: Uuid,
+    pub f: Option<u128>,
+    pub g: Vec<u128>,
+}
+impl Foo {
+    pub fn a(&self) -> u128 {
+        self.a
+    }
+    pub fn b(&self) -> u128 {
+        self.b
+    }
+    pub fn d(&self) -> u128 {
+        self.d
+    }
+    pub fn e(&self) -> u8 {
+        self.e
+    }
+    pub fn u(&self) -> Uuid {
+        self.u
+    }
+    pub fn f(&self) -> Option<u128> {
+        self.f
+    }
+    pub fn g(&self) -> &Vec<u128> {
+        &self.g
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: 0,
+            b: 0,
+            d: 0,
+            e: 0,
+            u: Default::default(),
+            f: None,
+            g: vec![],
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        54 + if self.f.is_some() { 16usize } else { 0 } + (self.g.len() * 16)
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u128_le(self.a());
+        if self.b() > 0xff_ffff_ffff_ffff_ffff_u128 {
+            return Err(EncodeError::InvalidWideScalarValue {
+                packet: "Foo",
+                field: "b",
+                value: self.b() as u128,
+                maximum_value: 0xff_ffff_ffff_ffff_ffff_u128 as u128,
+            });
+        }
+        buf.put_slice(&(self.b()).to_le_bytes()[..9]);
+        if self.d() > 0xffff_ffff_ffff_ffff_ffff_ffff_u128 {
+            return Err(EncodeError::InvalidWideScalarValue {
+                packet: "Foo",
+                field: "d",
+                value: self.d() as u128,
+                maximum_value: 0xffff_ffff_ffff_ffff_ffff_ffff_u128 as u128,
+            });
+        }
+        if self.e() > 0xf {
+            return Err(EncodeError::InvalidScalarValue {
+                packet: "Foo",
+                field: "e",
+                value: self.e() as u64,
+                maximum_value: 0xf as u64,
+            });
+        }
+        let value = (if self.f.is_some() { 1 } else { 0 } as u128) | (self.d() << 4)
+            | ((self.e() as u128) << 100);
+        buf.put_slice(&(value).to_le_bytes()[..13]);
+        buf.put_u128_le(u128::from(self.u));
+        if let Some(f) = &self.f {
+            buf.put_u128_le(*f);
+        }
+        for elem in &self.g {
+            buf.put_u128_le(*elem);
+        }
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        if buf.remaining() < 16 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 16,
+                got: buf.remaining(),
+            });
+        }
+        let a = buf.get_u128_le();
+        if buf.remaining() < 9 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 9,
+                got: buf.remaining(),
+            });
+        }
+        let b = {
+            let mut bytes = [0; 16];
+            buf.copy_to_slice(&mut bytes[..9]);
+            u128::from_le_bytes(bytes)
+        };
+        if buf.remaining() < 13 {
+            return Err(DecodeError::LengthError {
+                obj: "Foo",
+                wanted: 13,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = {
+            let mut bytes = [0; 16];
+            buf.copy_to_slice(&mut bytes[..13]);
+            u128::from_le_bytes(bytes)
+        };
+        let c = (chunk & 0x1) as u8;
+        let d = ((chunk >> 4) & 0xffff_ffff_ffff_ffff_ffff_ffff_u128);
+        let e = ((chunk >> 100) & 0xf) as u8;
+        let u = buf.get_u128_le().into();
+        let f = (c == 1).then(|| buf.get_u128_le());
+        if buf.remaining() % 16 != 0 {
+            return Err(DecodeError::ArraySizeError {
+                array: buf.remaining(),
+                element: 16,
+            });
+        }
+        let g_count = buf.remaining() / 16;
+        let mut g = Vec::with_capacity(g_count);
+        for _ in 0..g_count {
+            g.push(Ok::<_, DecodeError>(buf.get_u128_le())?);
+        }
+        Ok((Self { a, b, d, e, u, f, g }, buf))
+    }
+}
@@ -0,0 +1,160 @@
+#![rustfmt::skip]
+/// @generated rust packets from test.
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::{TryFrom, TryInto};
+use std::cell::Cell;
+use std::fmt;
+use std::result::Result;
+use pdl_runtime::{DecodeError, EncodeError, Packet};
+/// Private prevents users from creating arbitrary scalar values
+/// in situations where the value needs to be validated.
+/// Users can freely deref the value, but only the backend
+/// may create it.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Private<T>(T);
+impl<T> std::ops::Deref for Private<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Private<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(&self.0, f)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tlv {
+    pub tag: u8,
+    pub children: Vec<Tlv>,
+}
+impl Tlv {
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+    pub fn children(&self) -> &Vec<Tlv> {
+        &self.children
+    }
+}
+impl Default for Tlv {
+    fn default() -> Tlv {
+        Tlv { tag: 0, children: vec![] }
+    }
+}
+impl Packet for Tlv {
+    fn encoded_len(&self) -> usize {
+        2 + self.children.iter().map(Packet::encoded_len).sum::<usize>()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(self.tag());
+        buf.put_u8(self.children.len() as u8);
+        for elem in &self.children {
+            elem.encode(buf)?;
+        }
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let _depth_guard = pdl_runtime::DecodeDepthGuard::enter("Tlv")?;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Tlv",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let tag = buf.get_u8();
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Tlv",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let children_count = buf.get_u8() as usize;
+        let children = (0..children_count)
+            .map(|_| Tlv::decode_mut(&mut buf))
+            .collect::<Result<Vec<_>, DecodeError>>()?;
+        Ok((Self { tag, children }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    pub next: Option<Box<Node>>,
+}
+impl Node {
+    pub fn next(&self) -> &Option<Box<Node>> {
+        &self.next
+    }
+}
+impl Default for Node {
+    fn default() -> Node {
+        Node { next: None }
+    }
+}
+impl Packet for Node {
+    fn encoded_len(&self) -> usize {
+        1 + self.next.as_deref().map(Node::encoded_len).unwrap_or(0)
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        buf.put_u8(if self.next.is_some() { 1 } else { 0 });
+        if let Some(next) = &self.next {
+            next.encode(buf)?;
+        }
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let _depth_guard = pdl_runtime::DecodeDepthGuard::enter("Node")?;
+        if buf.remaining() < 1 {
+            return Err(DecodeError::LengthError {
+                obj: "Node",
+                wanted: 1,
+                got: buf.remaining(),
+            });
+        }
+        let chunk = buf.get_u8();
+        let c = (chunk & 0x1);
+        let next = (c == 1)
+            .then(|| Node::decode_mut(&mut buf).map(Box::new))
+            .transpose()?;
+        Ok((Self { next }, buf))
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Foo {
+    pub a: Tlv,
+    pub b: Node,
+}
+impl Foo {
+    pub fn a(&self) -> &Tlv {
+        &self.a
+    }
+    pub fn b(&self) -> &Node {
+        &self.b
+    }
+}
+impl Default for Foo {
+    fn default() -> Foo {
+        Foo {
+            a: Default::default(),
+            b: Default::default(),
+        }
+    }
+}
+impl Packet for Foo {
+    fn encoded_len(&self) -> usize {
+        self.a.encoded_len() + self.b.encoded_len()
+    }
+    fn encode(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        self.a.encode(buf)?;
+        self.b.encode(buf)?;
+        Ok(())
+    }
+    fn decode(mut buf: &[u8]) -> Result<(Self, &[u8]), DecodeError> {
+        let (a, mut buf) = Tlv::decode(buf)?;
+        let (b, mut buf) = Node::decode(buf)?;
+        Ok((Self { a, b }, buf))
+    }
+}
@@ -58,7 +58,8 @@ def match_object(self, left, right):
 def create_object(typ, value):
     """Build an object of the selected type using the input value."""
     if dataclasses.is_dataclass(typ):
-        field_types = dict([(f.name, f.type) for f in dataclasses.fields(typ)])
+        # Resolve the quoted references to recursive declarations.
+        field_types = typing.get_type_hints(typ)
         values = dict()
         for (f, v) in value.items():
             field_type = field_types[f]
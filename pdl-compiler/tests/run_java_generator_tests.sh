@@ -40,7 +40,9 @@ EXCLUDE_DECLS="--exclude-declaration SizedCustomField \
 --exclude-declaration Struct_Optional_Enum_Field_ \
 --exclude-declaration Struct_Optional_Enum_Field \
 --exclude-declaration Struct_Optional_Struct_Field_ \
---exclude-declaration Struct_Optional_Struct_Field"
+--exclude-declaration Struct_Optional_Struct_Field \
+--exclude-declaration Struct_Recursive_Array_Field_ \
+--exclude-declaration Struct_Recursive_Array_Field"
 
 mkdir -p out/
 OUT_DIR="$(pwd)/out"
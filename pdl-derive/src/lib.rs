@@ -19,7 +19,37 @@ use std::env;
 use std::path::Path;
 use syn::parse_macro_input;
 
-fn pdl_proc_macro(path: syn::LitStr, input: syn::ItemMod) -> TokenStream {
+/// Arguments of the `pdl` and `pdl_inline` attributes: the grammar path
+/// or source, optionally followed by the selected build profiles,
+/// e.g. `#[pdl("grammar.pdl", profiles = ["vendor_x", "v5_3"])]`.
+struct PdlAttr {
+    grammar: syn::LitStr,
+    profiles: Vec<String>,
+}
+
+impl syn::parse::Parse for PdlAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let grammar = input.parse()?;
+        let mut profiles = vec![];
+        if input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            if ident != "profiles" {
+                return Err(syn::Error::new(ident.span(), "error: expected `profiles`"));
+            }
+            input.parse::<syn::Token![=]>()?;
+            let content;
+            syn::bracketed!(content in input);
+            let list =
+                content.parse_terminated(|input| input.parse::<syn::LitStr>(), syn::Token![,])?;
+            profiles = list.iter().map(|profile| profile.value()).collect();
+            input.parse::<Option<syn::Token![,]>>()?;
+        }
+        Ok(PdlAttr { grammar, profiles })
+    }
+}
+
+fn pdl_proc_macro(attr: PdlAttr, input: syn::ItemMod) -> TokenStream {
+    let PdlAttr { grammar: path, profiles } = attr;
     // Locate the source grammar file.
     let root = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
     let relative_path = Path::new(&root).join(path.value());
@@ -30,7 +60,11 @@ fn pdl_proc_macro(path: syn::LitStr, input: syn::ItemMod) -> TokenStream {
     // Load and parse the grammar.
     let mut sources = pdl_compiler::ast::SourceDatabase::new();
     let relative_path = relative_path.into_os_string().into_string().unwrap();
-    let file = match pdl_compiler::parser::parse_file(&mut sources, &relative_path) {
+    let file = match pdl_compiler::parser::parse_file_with_profiles(
+        &mut sources,
+        &relative_path,
+        &profiles,
+    ) {
         Ok(file) => file,
         Err(err) => {
             let mut buffer = termcolor::Buffer::no_color();
@@ -74,10 +108,17 @@ fn pdl_proc_macro(path: syn::LitStr, input: syn::ItemMod) -> TokenStream {
     }
 }
 
-fn pdl_inline_proc_macro(code: syn::LitStr, input: syn::ItemMod) -> TokenStream {
+fn pdl_inline_proc_macro(attr: PdlAttr, input: syn::ItemMod) -> TokenStream {
+    let PdlAttr { grammar: code, profiles } = attr;
+
     // Load and parse the grammar.
     let mut sources = pdl_compiler::ast::SourceDatabase::new();
-    let file = match pdl_compiler::parser::parse_inline(&mut sources, "stdin", code.value()) {
+    let file = match pdl_compiler::parser::parse_inline_with_profiles(
+        &mut sources,
+        "stdin",
+        code.value(),
+        &profiles,
+    ) {
         Ok(file) => file,
         Err(err) => {
             let mut buffer = termcolor::Buffer::no_color();
@@ -122,7 +163,7 @@ pub fn pdl(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let attr = parse_macro_input!(attr as syn::LitStr);
+    let attr = parse_macro_input!(attr as PdlAttr);
     let input = parse_macro_input!(input as syn::ItemMod);
     pdl_proc_macro(attr, input).into()
 }
@@ -134,13 +175,14 @@ pub fn pdl_inline(
     attr: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let attr = parse_macro_input!(attr as syn::LitStr);
+    let attr = parse_macro_input!(attr as PdlAttr);
     let input = parse_macro_input!(input as syn::ItemMod);
     pdl_inline_proc_macro(attr, input).into()
 }
 
 #[cfg(test)]
 mod test {
+    use super::PdlAttr;
     use super::pdl_inline_proc_macro;
     use super::pdl_proc_macro;
     use proc_macro2::TokenStream;
@@ -170,8 +212,8 @@ mod test {
         }
     }
 
-    fn make_attr(input: TokenStream) -> syn::LitStr {
-        syn::parse2::<syn::LitStr>(input).unwrap()
+    fn make_attr(input: TokenStream) -> PdlAttr {
+        syn::parse2::<PdlAttr>(input).unwrap()
     }
 
     fn make_input(input: TokenStream) -> syn::ItemMod {
@@ -272,4 +314,28 @@ mod test {
             Some("error[E")
         ));
     }
+
+    #[test]
+    fn test_derive_profiles_inline() {
+        let grammar = quote! {
+            r#"
+                little_endian_packets
+                #[profile(vendor_x)]
+                packet A {
+                    x: Unknown,
+                }
+            "#
+        };
+        assert!(!is_compile_error(
+            pdl_inline_proc_macro(make_attr(grammar.clone()), make_input(quote! { mod Test {} })),
+            None
+        ));
+        assert!(is_compile_error(
+            pdl_inline_proc_macro(
+                make_attr(quote! { #grammar, profiles = ["v5_3", "vendor_x"] }),
+                make_input(quote! { mod Test {} }),
+            ),
+            Some("error[E")
+        ));
+    }
 }
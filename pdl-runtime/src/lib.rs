@@ -15,6 +15,7 @@
 //! Helper definitions used used by the generated Rust backend.
 
 use bytes::{BufMut, Bytes, BytesMut};
+use std::cell::Cell;
 
 /// Type of parsing errors.
 #[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
@@ -43,6 +44,24 @@ pub enum DecodeError {
     TrailingBytesError,
     #[error("packet has trailing bytes inside {obj}.{field} array")]
     TrailingBytesInArray { obj: &'static str, field: &'static str },
+    #[error("when parsing {obj}.{field} size, {size} bits is not an integral number of octets")]
+    SizeUnitError { obj: &'static str, field: &'static str, size: usize },
+    #[error(
+        "when parsing {obj}.{field}, the trailing size or count {actual} does not match the decoded value {expected}"
+    )]
+    TrailingSizeError { obj: &'static str, field: &'static str, expected: usize, actual: usize },
+    #[error("when parsing {obj}.{field}, the count {count} exceeds the bound of {bound}")]
+    BoundError { obj: &'static str, field: &'static str, count: usize, bound: usize },
+    #[error("when parsing {obj}.{field}, the size {size} exceeds the bound of {bound}")]
+    SizeBoundError { obj: &'static str, field: &'static str, size: usize, bound: usize },
+    #[error("when parsing {obj}, the nesting depth exceeds the limit of {limit}")]
+    DepthLimitError { obj: &'static str, limit: usize },
+    #[error(
+        "when parsing {obj}, the size ({size} octets) does not satisfy the assertion {assertion}"
+    )]
+    SizeAssertionError { obj: &'static str, size: usize, assertion: &'static str },
+    #[error("when parsing {packet}, the invariant {invariant} is violated")]
+    InvariantViolation { packet: &'static str, invariant: &'static str },
 }
 
 /// Type of serialization errors.
@@ -60,6 +79,15 @@ pub enum EncodeError {
         "the value of {packet}::{field} ({value}) is outside the range of valid values 0..{maximum_value}"
     )]
     InvalidScalarValue { packet: &'static str, field: &'static str, value: u64, maximum_value: u64 },
+    #[error(
+        "the value of {packet}::{field} ({value}) is outside the range of valid values 0..{maximum_value}"
+    )]
+    InvalidWideScalarValue {
+        packet: &'static str,
+        field: &'static str,
+        value: u128,
+        maximum_value: u128,
+    },
     #[error(
         "{packet}.{field}[{element_index}] size is {size}, but {expected_size} was expected (size of {packet}.{field}[0])"
     )]
@@ -72,6 +100,24 @@ pub enum EncodeError {
     },
     #[error("{packet}.{field} value cannot be uniquely determined")]
     InconsistentConditionValue { packet: &'static str, field: &'static str },
+    #[error("{packet}.{field} presence is inconsistent with the preceding extension fields")]
+    InconsistentExtensionField { packet: &'static str, field: &'static str },
+    #[error(
+        "the size of {packet}::{field} ({size} octets) is not a multiple of the size unit ({unit} octets)"
+    )]
+    UnalignedSize { packet: &'static str, field: &'static str, size: usize, unit: usize },
+    #[error("the size of {packet} ({size} octets) does not satisfy the assertion {assertion}")]
+    SizeAssertion { packet: &'static str, size: usize, assertion: &'static str },
+    #[error("the invariant {invariant} of {packet} is violated")]
+    InvariantViolation { packet: &'static str, invariant: &'static str },
+}
+
+/// Error returned when parsing an enum tag from its name.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("{value:?} is not a valid {type_} tag name")]
+pub struct ParseEnumError {
+    pub type_: &'static str,
+    pub value: String,
 }
 
 /// Trait implemented for all toplevel packet declarations.
@@ -117,3 +163,60 @@ pub trait Packet: Sized {
         Ok(buf.freeze())
     }
 }
+
+/// Trait implemented for the packet declarations paired with a response
+/// packet by a response declaration.
+pub trait Command: Packet {
+    /// Type of the packet sent in response to the command.
+    type Response: Packet;
+
+    /// Try parsing the response to the command from the input slice.
+    /// Returns an error if unparsed bytes remain at the end of the input slice.
+    fn parse_response(buf: &[u8]) -> Result<Self::Response, DecodeError> {
+        Self::Response::decode_full(buf)
+    }
+}
+
+/// Default maximum nesting depth of recursive declarations.
+pub const DEFAULT_MAX_DECODE_DEPTH: usize = 64;
+
+thread_local! {
+    static DECODE_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static MAX_DECODE_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_DECODE_DEPTH) };
+}
+
+/// Set the maximum nesting depth of recursive declarations accepted
+/// by the decoders running on the current thread.
+pub fn set_max_decode_depth(depth: usize) {
+    MAX_DECODE_DEPTH.set(depth)
+}
+
+/// Return the maximum nesting depth of recursive declarations accepted
+/// by the decoders running on the current thread.
+pub fn max_decode_depth() -> usize {
+    MAX_DECODE_DEPTH.get()
+}
+
+/// Guard held by the decoders of recursive declarations for the duration
+/// of the decoding. The nesting depth is decremented when the guard is dropped.
+pub struct DecodeDepthGuard(());
+
+impl DecodeDepthGuard {
+    /// Increment the nesting depth, returns an error if the depth
+    /// exceeds the configured limit.
+    pub fn enter(obj: &'static str) -> Result<Self, DecodeError> {
+        let limit = max_decode_depth();
+        let depth = DECODE_DEPTH.get();
+        if depth >= limit {
+            return Err(DecodeError::DepthLimitError { obj, limit });
+        }
+        DECODE_DEPTH.set(depth + 1);
+        Ok(DecodeDepthGuard(()))
+    }
+}
+
+impl Drop for DecodeDepthGuard {
+    fn drop(&mut self) {
+        DECODE_DEPTH.set(DECODE_DEPTH.get() - 1)
+    }
+}
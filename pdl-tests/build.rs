@@ -0,0 +1,36 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generate the Rust backend and the unit tests declared in
+//! `tests/declared.pdl`. The output is included by `tests/declared.rs`.
+
+use pdl_compiler::{analyzer, ast, backends, parser};
+use std::path::PathBuf;
+
+fn main() {
+    let input = "tests/declared.pdl";
+    println!("cargo:rerun-if-changed={input}");
+
+    let mut sources = ast::SourceDatabase::new();
+    let file = parser::parse_file(&mut sources, input)
+        .unwrap_or_else(|err| panic!("could not parse {input}: {err:?}"));
+    let file =
+        analyzer::analyze(&file).unwrap_or_else(|err| panic!("could not analyze {input}: {err:?}"));
+    let backend = backends::rust::generate(&sources, &file, &[]);
+    let tests = backends::rust::test::generate_declared_tests(&file)
+        .unwrap_or_else(|err| panic!("could not generate the tests of {input}: {err}"));
+
+    let output = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("declared.rs");
+    std::fs::write(output, format!("{backend}\n{tests}")).unwrap();
+}
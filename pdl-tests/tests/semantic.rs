@@ -118,3 +118,1021 @@ mod payload_size_field {
         ));
     }
 }
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+enum Version : 8 {
+    V1 = 1,
+    V2 = 2,
+}
+
+packet Header {
+    version : Version = V2,
+    ttl : 8 = 64,
+    flags : 8,
+}
+"#
+)]
+#[cfg(test)]
+mod default_values {
+    #[test]
+    fn test_default() {
+        // Fields declare their default values in the PDL source,
+        // other fields default to zero.
+        let value = Header::default();
+        assert_eq!(value, Header { version: Version::V2, ttl: 64, flags: 0 });
+        assert_eq!(value.encode_to_vec(), Ok(vec![2, 64, 0]));
+    }
+}
+
+#[pdl_inline(
+    r#"
+big_endian_packets msb_first
+packet Ipv4Header {
+    version: 4,
+    ihl: 4,
+    dscp: 6,
+    ecn: 2,
+}
+
+struct Lsb lsb_first {
+    a: 4,
+    b: 4,
+}
+"#
+)]
+#[cfg(test)]
+mod bit_order {
+    #[test]
+    fn test_msb_first() {
+        // The first field occupies the most significant bits.
+        let value = Ipv4Header { version: 4, ihl: 5, dscp: 0x2e, ecn: 1 };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x45, 0xb9]);
+        assert_eq!(Ipv4Header::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_lsb_first_override() {
+        // The declaration overrides the file bit order.
+        let value = Lsb { a: 4, b: 5 };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x54]);
+        assert_eq!(Lsb::decode_full(&bytes), Ok(value));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+struct Network big_endian {
+    port: 16,
+    magic: 16 little_endian,
+}
+
+packet Mixed {
+    length: 16,
+    id: 32 big_endian,
+    network: Network,
+}
+"#
+)]
+#[cfg(test)]
+mod endianness_override {
+    #[test]
+    fn test_encode_decode() {
+        let value = Mixed {
+            length: 0x0102,
+            id: 0x03040506,
+            network: Network { port: 0x0708, magic: 0x090a },
+        };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x02, 0x01, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x0a, 0x09]);
+        assert_eq!(Mixed::decode_full(&bytes), Ok(value));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Key {
+    _size_(key, bits): 16,
+    _size_(data, words32): 8,
+    key: 8[],
+    data: 8[],
+}
+"#
+)]
+#[cfg(test)]
+mod size_units {
+    #[test]
+    fn test_encode_decode() {
+        let value = Key { key: vec![1, 2], data: vec![3, 4, 5, 6] };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![16, 0, 1, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(Key::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_err_unaligned_size() {
+        // Attempting to encode an array whose size is not a multiple
+        // of the size unit must fail.
+        assert!(matches!(
+            Key { key: vec![], data: vec![1, 2] }.encode_to_vec(),
+            Err(EncodeError::UnalignedSize { .. })
+        ));
+        // Decoding a bit size that is not a whole number of octets must fail.
+        assert!(matches!(
+            Key::decode_full(&[12, 0, 0, 1, 2]),
+            Err(DecodeError::SizeUnitError { .. })
+        ));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Trailer {
+    data: 8[],
+    _size_(data): 8,
+    crc: 8,
+}
+
+packet Items {
+    items: 16[],
+    _count_(items): 8,
+}
+"#
+)]
+#[cfg(test)]
+mod trailing_size_fields {
+    #[test]
+    fn test_encode_decode() {
+        let value = Trailer { data: vec![1, 2, 3], crc: 4 };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![1, 2, 3, 3, 4]);
+        assert_eq!(Trailer::decode_full(&bytes), Ok(value));
+
+        let value = Items { items: vec![0x0102, 0x0304] };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x02, 0x01, 0x04, 0x03, 2]);
+        assert_eq!(Items::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_err_inconsistent_size() {
+        // Decoding a trailing size or count that does not match
+        // the decoded field must fail.
+        assert!(matches!(
+            Trailer::decode_full(&[1, 2, 3, 2, 4]),
+            Err(DecodeError::TrailingSizeError { expected: 3, actual: 2, .. })
+        ));
+        assert!(matches!(
+            Items::decode_full(&[0x02, 0x01, 2]),
+            Err(DecodeError::TrailingSizeError { expected: 1, actual: 2, .. })
+        ));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+flags Permissions : 12 {
+    READ = 0x1,
+    WRITE = 0x2,
+    EXECUTE = 0x4,
+}
+
+flags Features : 8 {
+    ENCRYPTION = 0x1,
+    COMPRESSION = 0x2,
+}
+
+packet File {
+    permissions: Permissions,
+    kind: 4,
+    features: Features[],
+}
+"#
+)]
+#[cfg(test)]
+mod flags_declarations {
+    #[test]
+    fn test_flag_set() {
+        let mut permissions = Permissions::READ | Permissions::EXECUTE;
+        assert!(permissions.contains(Permissions::READ));
+        assert!(!permissions.contains(Permissions::READ | Permissions::WRITE));
+        permissions.insert(Permissions::WRITE);
+        permissions.remove(Permissions::READ);
+        assert_eq!(
+            permissions.iter().collect::<Vec<_>>(),
+            vec![Permissions::WRITE, Permissions::EXECUTE]
+        );
+        assert_eq!(permissions.bits(), 0x6);
+        assert_eq!(!permissions, Permissions::try_from(0xff9).unwrap());
+        assert_eq!(format!("{:?}", permissions), "Permissions(WRITE | EXECUTE)");
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let value = File {
+            permissions: Permissions::READ | Permissions::WRITE,
+            kind: 0x1,
+            features: vec![Features::empty(), Features::all()],
+        };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x03, 0x10, 0x00, 0x03]);
+        assert_eq!(File::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_unknown_bits() {
+        // Bits that do not match a declared flag are preserved.
+        let value = File::decode_full(&[0x13, 0x18, 0x81]).unwrap();
+        assert!(value.permissions.contains(Permissions::READ | Permissions::WRITE));
+        assert_eq!(value.permissions.unknown_bits(), 0x810);
+        assert_eq!(value.kind, 0x1);
+        assert_eq!(value.features[0].unknown_bits(), 0x80);
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x13, 0x18, 0x81]));
+        // Values that do not fit the flags width are rejected.
+        assert_eq!(Permissions::try_from(0x1000), Err(0x1000));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+enum OpCode : 16 {
+    RESET = 0x0c03,
+    READ_VERSION = 0x1001,
+}
+
+enum VendorOpCode : OpCode {
+    VENDOR_CONFIG = 0xfc01,
+}
+
+packet Command {
+    op_code: OpCode,
+}
+
+packet VendorCommand {
+    op_code: VendorOpCode,
+}
+"#
+)]
+#[cfg(test)]
+mod enum_extensions {
+    #[test]
+    fn test_conversions() {
+        assert_eq!(VendorOpCode::from(OpCode::Reset), VendorOpCode::Reset);
+        assert_eq!(OpCode::try_from(VendorOpCode::ReadVersion), Ok(OpCode::ReadVersion));
+        assert_eq!(OpCode::try_from(VendorOpCode::VendorConfig), Err(VendorOpCode::VendorConfig));
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        // The base enum tags are inherited by the enum extension.
+        let value = VendorCommand { op_code: OpCode::Reset.into() };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, Command { op_code: OpCode::Reset }.encode_to_vec().unwrap());
+        assert_eq!(VendorCommand::decode_full(&bytes), Ok(value));
+        assert_eq!(
+            VendorCommand::decode_full(&[0x01, 0xfc]),
+            Ok(VendorCommand { op_code: VendorOpCode::VendorConfig })
+        );
+        assert!(Command::decode_full(&[0x01, 0xfc]).is_err());
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+struct Tlv {
+    tag: 8,
+    _count_(children): 8,
+    children: Tlv[],
+}
+
+struct Node {
+    has_next: 1,
+    _reserved_: 7,
+    value: 8,
+    next: Node if has_next = 1,
+}
+"#
+)]
+#[cfg(test)]
+mod recursive_declarations {
+    #[test]
+    fn test_encode_decode() {
+        let value = Tlv {
+            tag: 1,
+            children: vec![Tlv { tag: 2, children: vec![] }, Tlv { tag: 3, children: vec![] }],
+        };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x1, 0x2, 0x2, 0x0, 0x3, 0x0]);
+        assert_eq!(Tlv::decode_full(&bytes), Ok(value));
+
+        let value = Node { value: 1, next: Some(Box::new(Node { value: 2, next: None })) };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x1, 0x1, 0x0, 0x2]);
+        assert_eq!(Node::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_decode_depth_limit() {
+        let bytes = [[0x1, 0x0]; 8].concat();
+        pdl_runtime::set_max_decode_depth(4);
+        assert_eq!(
+            Node::decode_full(&bytes),
+            Err(DecodeError::DepthLimitError { obj: "Node", limit: 4 })
+        );
+        pdl_runtime::set_max_decode_depth(8);
+        assert!(Node::decode_full(&bytes).is_err());
+        assert!(Node::decode_full(&[&bytes[..14], &[0x0, 0x0]].concat()).is_ok());
+        pdl_runtime::set_max_decode_depth(pdl_runtime::DEFAULT_MAX_DECODE_DEPTH);
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+struct Version {
+    major: 8,
+    minor: 8,
+}
+
+struct Extensible {
+    major: 8,
+    _payload_,
+}
+
+packet Header {
+    _size_(version): 8,
+    version: Version,
+    _size_(extensible): 8,
+    extensible: Extensible,
+    flags: 8,
+}
+"#
+)]
+#[cfg(test)]
+mod struct_size_fields {
+    #[test]
+    fn test_encode_decode() {
+        let value = Header {
+            version: Version { major: 1, minor: 2 },
+            extensible: Extensible { major: 3, payload: vec![0x4, 0x5] },
+            flags: 6,
+        };
+        let bytes = value.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x2, 0x1, 0x2, 0x3, 0x3, 0x4, 0x5, 0x6]);
+        assert_eq!(Header::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        // Trailing bytes are rejected unless the struct declares a payload.
+        assert_eq!(
+            Header::decode_full(&[0x3, 0x1, 0x2, 0x0, 0x1, 0x3, 0x6]),
+            Err(DecodeError::TrailingBytesError)
+        );
+        assert_eq!(
+            Header::decode_full(&[0x2, 0x1, 0x2, 0x1, 0x3, 0x6]),
+            Ok(Header {
+                version: Version { major: 1, minor: 2 },
+                extensible: Extensible { major: 3, payload: vec![] },
+                flags: 6,
+            })
+        );
+        // The size field must fit the remaining bytes.
+        assert!(matches!(
+            Header::decode_full(&[0x8, 0x1, 0x2]),
+            Err(DecodeError::LengthError { .. })
+        ));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Report {
+    interval: 12 scale 1.25 unit "ms",
+    has_power: 1,
+    _reserved_: 3,
+    power: 8 scale 0.5 unit "dBm" if has_power = 1,
+}
+"#
+)]
+#[cfg(test)]
+mod scaled_scalars {
+    #[test]
+    fn test_scaled_accessors() {
+        let mut report = Report { interval: 8, power: None };
+        assert_eq!(report.interval_scaled(), 10.0);
+        assert_eq!(report.power_scaled(), None);
+
+        report.set_interval_scaled(12.6);
+        report.set_power_scaled(Some(-0.0));
+        assert_eq!(report.interval, 10);
+        assert_eq!(report.power, Some(0));
+        assert_eq!(report.power_scaled(), Some(0.0));
+
+        // Out of range values are saturated to the field width.
+        report.set_interval_scaled(1e9);
+        assert_eq!(report.interval, 0xfff);
+        report.set_interval_scaled(-5.0);
+        assert_eq!(report.interval, 0);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let mut report = Report::default();
+        report.set_interval_scaled(100.0);
+        report.set_power_scaled(Some(21.5));
+        let bytes = report.encode_to_vec().unwrap();
+        assert_eq!(bytes, vec![0x50, 0x10, 0x2b]);
+        let report = Report::decode_full(&bytes).unwrap();
+        assert_eq!(report.interval_scaled(), 100.0);
+        assert_eq!(report.power_scaled(), Some(21.5));
+    }
+}
+
+#[pdl_inline(
+    r#"
+big_endian_packets
+
+custom_field Uuid : 128 "uuid"
+
+packet Wide {
+    key: 128,
+    addr: 72 = 0xa1a2a3a4a5a6a7a8a9,
+    low: 4,
+    mid: 96,
+    high: 4,
+    uuid: Uuid,
+    keys: 128[],
+}
+"#
+)]
+#[cfg(test)]
+mod wide_scalars {
+    #[test]
+    fn test_encode_decode() {
+        let value = Wide {
+            key: 0x0102030405060708090a0b0c0d0e0f10,
+            addr: 0xa1a2a3a4a5a6a7a8a9,
+            low: 0x1,
+            mid: 0xb1b2b3b4b5b6b7b8b9babbbc,
+            high: 0x2,
+            uuid: Uuid::from(u128::MAX),
+            keys: vec![0x42],
+        };
+        let bytes = value.encode_to_vec().unwrap();
+        let mut expected = vec![];
+        expected.extend_from_slice(&0x0102030405060708090a0b0c0d0e0f10u128.to_be_bytes());
+        expected.extend_from_slice(&[0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7, 0xa8, 0xa9]);
+        expected.extend_from_slice(&[
+            0x2b, 0x1b, 0x2b, 0x3b, 0x4b, 0x5b, 0x6b, 0x7b, 0x8b, 0x9b, 0xab, 0xbb, 0xc1,
+        ]);
+        expected.extend_from_slice(&[0xff; 16]);
+        expected.extend_from_slice(&0x42u128.to_be_bytes());
+        assert_eq!(bytes, expected);
+        assert_eq!(Wide::decode_full(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(Wide::default().addr, 0xa1a2a3a4a5a6a7a8a9);
+    }
+
+    #[test]
+    fn test_encode_out_of_range() {
+        let value = Wide { addr: 1 << 72, ..Default::default() };
+        assert_eq!(
+            value.encode_to_vec(),
+            Err(EncodeError::InvalidWideScalarValue {
+                packet: "Wide",
+                field: "addr",
+                value: 1 << 72,
+                maximum_value: (1 << 72) - 1,
+            })
+        );
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+enum Kind : 8 {
+    A = 1,
+    B = 2,
+}
+
+struct Version {
+    major: 8,
+}
+
+packet Hello {
+    id: 8,
+    _size_(_payload_): 8,
+    _payload_,
+    _extension_ {
+        flags: 16,
+        kind: Kind,
+    },
+    _extension_ {
+        version: Version,
+    },
+}
+"#
+)]
+#[cfg(test)]
+mod extension_fields {
+    #[test]
+    fn test_decode_short_form() {
+        let value = Hello::decode_full(&[0x01, 0x01, 0xaa]).unwrap();
+        assert_eq!(value.flags(), None);
+        assert_eq!(value.kind(), None);
+        assert_eq!(value.version(), &None);
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x01, 0x01, 0xaa]));
+    }
+
+    #[test]
+    fn test_decode_long_form() {
+        let bytes = [0x01, 0x01, 0xaa, 0x34, 0x12, 0x02, 0x05];
+        let value = Hello::decode_full(&bytes).unwrap();
+        assert_eq!(value.flags(), Some(0x1234));
+        assert_eq!(value.kind(), Some(Kind::B));
+        assert_eq!(value.version(), &Some(Version { major: 5 }));
+        assert_eq!(value.encode_to_vec(), Ok(bytes.to_vec()));
+
+        let value = Hello::decode_full(&bytes[..6]).unwrap();
+        assert_eq!(value.flags(), Some(0x1234));
+        assert_eq!(value.version(), &None);
+    }
+
+    #[test]
+    fn test_decode_truncated_extension() {
+        assert!(matches!(
+            Hello::decode_full(&[0x01, 0x01, 0xaa, 0x34, 0x12]),
+            Err(DecodeError::LengthError { obj: "Hello", wanted: 1, got: 0 })
+        ));
+        assert!(matches!(
+            Hello::decode_full(&[0x01, 0x01, 0xaa, 0x34]),
+            Err(DecodeError::LengthError { obj: "Hello", wanted: 2, got: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_encode_inconsistent_extension() {
+        let value = Hello { flags: Some(1), ..Default::default() };
+        assert_eq!(
+            value.encode_to_vec(),
+            Err(EncodeError::InconsistentExtensionField { packet: "Hello", field: "kind" })
+        );
+
+        let value = Hello { version: Some(Version { major: 1 }), ..Default::default() };
+        assert_eq!(
+            value.encode_to_vec(),
+            Err(EncodeError::InconsistentExtensionField { packet: "Hello", field: "version" })
+        );
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+struct Record {
+    id: 8,
+    _extension_ {
+        flags: 8,
+    },
+}
+
+packet Tail {
+    count: 8,
+    record: Record,
+}
+
+packet Sized {
+    _size_(record): 8,
+    record: Record,
+    count: 8,
+}
+
+packet Records {
+    _elementsize_(records): 8,
+    _count_(records): 8,
+    records: Record[],
+    trailer: 8,
+}
+"#
+)]
+#[cfg(test)]
+mod extension_structs {
+    #[test]
+    fn test_trailing_struct() {
+        let value = Tail { count: 2, record: Record { id: 1, flags: None } };
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x02, 0x01]));
+        assert_eq!(Tail::decode_full(&[0x02, 0x01]), Ok(value));
+
+        let value = Tail { count: 2, record: Record { id: 1, flags: Some(3) } };
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x02, 0x01, 0x03]));
+        assert_eq!(Tail::decode_full(&[0x02, 0x01, 0x03]), Ok(value));
+    }
+
+    #[test]
+    fn test_sized_struct() {
+        let value = Sized { record: Record { id: 1, flags: None }, count: 2 };
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x01, 0x01, 0x02]));
+        assert_eq!(Sized::decode_full(&[0x01, 0x01, 0x02]), Ok(value));
+
+        let value = Sized { record: Record { id: 1, flags: Some(3) }, count: 2 };
+        assert_eq!(value.encode_to_vec(), Ok(vec![0x02, 0x01, 0x03, 0x02]));
+        assert_eq!(Sized::decode_full(&[0x02, 0x01, 0x03, 0x02]), Ok(value));
+    }
+
+    #[test]
+    fn test_delimited_struct_array() {
+        let value = Records {
+            records: vec![Record { id: 1, flags: Some(2) }, Record { id: 3, flags: Some(4) }],
+            trailer: 5,
+        };
+        let bytes = vec![0x02, 0x02, 0x01, 0x02, 0x03, 0x04, 0x05];
+        assert_eq!(value.encode_to_vec(), Ok(bytes.clone()));
+        assert_eq!(Records::decode_full(&bytes), Ok(value));
+
+        let value = Records {
+            records: vec![Record { id: 1, flags: None }, Record { id: 3, flags: None }],
+            trailer: 5,
+        };
+        let bytes = vec![0x01, 0x02, 0x01, 0x03, 0x05];
+        assert_eq!(value.encode_to_vec(), Ok(bytes.clone()));
+        assert_eq!(Records::decode_full(&bytes), Ok(value));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Frame {
+    _count_(data) : 8,
+    data : 8[..4],
+    trailer : 16[..2],
+}
+
+packet Envelope {
+    _size_(_payload_) : 8,
+    _payload_ : [..3],
+}
+"#
+)]
+#[cfg(test)]
+mod bounded_arrays {
+    #[test]
+    fn test_bound_constants() {
+        assert_eq!(Frame::DATA_MAX_COUNT, 4);
+        assert_eq!(Frame::TRAILER_MAX_COUNT, 2);
+        assert_eq!(Envelope::PAYLOAD_MAX_SIZE, 3);
+    }
+
+    #[test]
+    fn test_decode_within_bounds() {
+        let bytes = [0x04, 1, 2, 3, 4, 0x01, 0x00, 0x02, 0x00];
+        let value = Frame::decode_full(&bytes).unwrap();
+        assert_eq!(value.data(), &vec![1, 2, 3, 4]);
+        assert_eq!(value.trailer(), &vec![1, 2]);
+        assert_eq!(value.encode_to_vec(), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn test_decode_exceeding_bounds() {
+        assert_eq!(
+            Frame::decode_full(&[0x05, 1, 2, 3, 4, 5]),
+            Err(DecodeError::BoundError { obj: "Frame", field: "data", count: 5, bound: 4 })
+        );
+        assert_eq!(
+            Frame::decode_full(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+            Err(DecodeError::BoundError { obj: "Frame", field: "trailer", count: 3, bound: 2 })
+        );
+        assert_eq!(
+            Envelope::decode_full(&[0x04, 1, 2, 3, 4]),
+            Err(DecodeError::SizeBoundError {
+                obj: "Envelope",
+                field: "_payload_",
+                size: 4,
+                bound: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_exceeding_bounds() {
+        let value = Frame { data: vec![0; 5], trailer: vec![] };
+        assert_eq!(
+            value.encode_to_vec(),
+            Err(EncodeError::CountOverflow {
+                packet: "Frame",
+                field: "data",
+                count: 5,
+                maximum_count: 4
+            })
+        );
+        let value = Envelope { payload: vec![0; 4] };
+        assert_eq!(
+            value.encode_to_vec(),
+            Err(EncodeError::SizeOverflow {
+                packet: "Envelope",
+                field: "_payload_",
+                size: 4,
+                maximum_size: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_exceeding_bounds_writes_nothing() {
+        let value = Frame { data: vec![0; 5], trailer: vec![] };
+        let mut buf = Vec::new();
+        assert!(value.encode(&mut buf).is_err());
+        assert!(buf.is_empty());
+        let value = Envelope { payload: vec![0; 4] };
+        let mut buf = Vec::new();
+        assert!(value.encode(&mut buf).is_err());
+        assert!(buf.is_empty());
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet CompletedPackets {
+    _count_(handles) : 8,
+    handles : { handle : 12, _reserved_ : 4, count : 16 }[],
+    info : { version : 8 },
+}
+"#
+)]
+#[cfg(test)]
+mod inline_structs {
+    #[test]
+    fn test_decode_inline_structs() {
+        let bytes = [0x02, 0x01, 0x00, 0x05, 0x00, 0x02, 0x00, 0x07, 0x00, 0x03];
+        let value = CompletedPackets::decode_full(&bytes).unwrap();
+        assert_eq!(
+            value.handles(),
+            &vec![
+                CompletedPacketsHandles { handle: 1, count: 5 },
+                CompletedPacketsHandles { handle: 2, count: 7 },
+            ]
+        );
+        assert_eq!(value.info(), &CompletedPacketsInfo { version: 3 });
+        assert_eq!(value.encode_to_vec(), Ok(bytes.to_vec()));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet BasicFrame {
+    _size_(_payload_) : 16,
+    channel_id : 16,
+    _payload_,
+}
+
+packet Att {
+    opcode : 8,
+    _payload_,
+}
+
+packet Smp {
+    code : 8,
+    _payload_,
+}
+
+bind BasicFrame.payload as Att if channel_id = 4
+bind BasicFrame.payload as Smp if channel_id = 6
+"#
+)]
+#[cfg(test)]
+mod payload_bindings {
+    #[test]
+    fn test_decode_payload_as() {
+        let frame = BasicFrame::decode_full(&[0x02, 0x00, 0x04, 0x00, 0x0a, 0x01]).unwrap();
+        let att = frame.decode_payload_as_att().unwrap();
+        assert_eq!(att.opcode(), 0x0a);
+        assert_eq!(att.payload(), &[0x01]);
+        let smp = frame.decode_payload_as_smp().unwrap();
+        assert_eq!(smp.code(), 0x0a);
+    }
+
+    #[test]
+    fn test_decode_payload() {
+        let frame = BasicFrame::decode_full(&[0x01, 0x00, 0x04, 0x00, 0x0a]).unwrap();
+        assert_eq!(
+            frame.decode_payload(),
+            Ok(BasicFrameBinding::Att(Att { opcode: 0x0a, payload: vec![] }))
+        );
+        let frame = BasicFrame::decode_full(&[0x01, 0x00, 0x06, 0x00, 0x0b]).unwrap();
+        assert_eq!(
+            frame.decode_payload(),
+            Ok(BasicFrameBinding::Smp(Smp { code: 0x0b, payload: vec![] }))
+        );
+        let frame = BasicFrame::decode_full(&[0x01, 0x00, 0x05, 0x00, 0x0b]).unwrap();
+        assert_eq!(frame.decode_payload(), Ok(BasicFrameBinding::None));
+        let frame = BasicFrame::decode_full(&[0x00, 0x00, 0x04, 0x00]).unwrap();
+        assert!(matches!(frame.decode_payload(), Err(DecodeError::LengthError { .. })));
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet ReadBdAddr {
+    opcode : 16,
+}
+
+packet ReadBdAddrComplete {
+    status : 8,
+    bd_addr : 48,
+}
+
+response ReadBdAddr -> ReadBdAddrComplete
+"#
+)]
+#[cfg(test)]
+mod responses {
+    use pdl_runtime::Command;
+
+    #[test]
+    fn test_parse_response() {
+        let response = ReadBdAddr::parse_response(&[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(response, Ok(ReadBdAddrComplete { status: 0, bd_addr: 0x060504030201 }));
+        assert_eq!(
+            ReadBdAddr::parse_response(&[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]),
+            Err(DecodeError::TrailingBytesError)
+        );
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Frame : size <= 4 {
+    _size_(_payload_) : 8,
+    opcode : 8,
+    _payload_,
+}
+
+packet Command : Frame (opcode = 1) : size >= 4 {
+    _count_(args) : 8,
+    args : 8[],
+}
+"#
+)]
+#[cfg(test)]
+mod size_assertions {
+    #[test]
+    fn test_decode_size_assertion() {
+        assert!(Frame::decode_full(&[0x02, 0x00, 0x01, 0x02]).is_ok());
+        assert_eq!(
+            Frame::decode_full(&[0x03, 0x00, 0x01, 0x02, 0x03]),
+            Err(DecodeError::SizeAssertionError { obj: "Frame", size: 5, assertion: "size <= 4" })
+        );
+        assert_eq!(
+            Command::decode_full(&[0x01, 0x01, 0x00]),
+            Err(DecodeError::SizeAssertionError {
+                obj: "Command",
+                size: 3,
+                assertion: "size >= 4"
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_size_assertion() {
+        assert_eq!(Command { args: vec![0x01] }.encode_to_vec(), Ok(vec![0x02, 0x01, 0x01, 0x01]));
+        assert_eq!(
+            Command { args: vec![] }.encode_to_vec(),
+            Err(EncodeError::SizeAssertion { packet: "Command", size: 3, assertion: "size >= 4" })
+        );
+        assert_eq!(
+            Command { args: vec![0x01, 0x02] }.encode_to_vec(),
+            Err(EncodeError::SizeAssertion { packet: "Command", size: 5, assertion: "size <= 4" })
+        );
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+packet Frame {
+    length : 8,
+    opcode : 8,
+    _payload_,
+    assert length >= 2
+}
+
+packet Command : Frame (opcode = 1) {
+    _count_(args) : 8,
+    args : 8[],
+    assert len(args) + 3 == length
+}
+"#
+)]
+#[cfg(test)]
+mod invariants {
+    #[test]
+    fn test_decode_invariant() {
+        assert!(Command::decode_full(&[0x04, 0x01, 0x01, 0x07]).is_ok());
+        assert_eq!(
+            Frame::decode_full(&[0x01, 0x00]),
+            Err(DecodeError::InvariantViolation { packet: "Frame", invariant: "length >= 2" })
+        );
+        assert_eq!(
+            Command::decode_full(&[0x05, 0x01, 0x01, 0x07]),
+            Err(DecodeError::InvariantViolation {
+                packet: "Command",
+                invariant: "len(args) + 3 == length"
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_invariant() {
+        assert_eq!(
+            Command { length: 4, args: vec![0x07] }.encode_to_vec(),
+            Ok(vec![0x04, 0x01, 0x01, 0x07])
+        );
+        assert_eq!(
+            Command { length: 5, args: vec![0x07] }.encode_to_vec(),
+            Err(EncodeError::InvariantViolation {
+                packet: "Command",
+                invariant: "len(args) + 3 == length"
+            })
+        );
+        assert_eq!(
+            Frame { length: 1, opcode: 0, payload: vec![] }.encode_to_vec(),
+            Err(EncodeError::InvariantViolation { packet: "Frame", invariant: "length >= 2" })
+        );
+    }
+}
+
+#[pdl_inline(
+    r#"
+little_endian_packets
+
+enum OpCode : 16 {
+    LE_SET_ADVERTISING_PARAMETERS | LE_SET_ADV_PARAMS = 0x2006 "LE Set Advertising Parameters",
+    RESET = 0x0c03,
+    VENDOR = 0xfc00..0xfcff,
+}
+"#
+)]
+#[cfg(test)]
+mod enum_display_names {
+    #[test]
+    fn test_display() {
+        assert_eq!(OpCode::LeSetAdvertisingParameters.name(), "LE Set Advertising Parameters");
+        assert_eq!(OpCode::Reset.to_string(), "RESET");
+        assert_eq!(OpCode::try_from(0xfc01).unwrap().to_string(), "VENDOR");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "LE Set Advertising Parameters".parse::<OpCode>(),
+            Ok(OpCode::LeSetAdvertisingParameters)
+        );
+        assert_eq!("LE_SET_ADV_PARAMS".parse::<OpCode>(), Ok(OpCode::LeSetAdvertisingParameters));
+        assert_eq!("RESET".parse::<OpCode>(), Ok(OpCode::Reset));
+        assert_eq!(
+            "VENDOR".parse::<OpCode>(),
+            Err(pdl_runtime::ParseEnumError { type_: "OpCode", value: "VENDOR".to_owned() })
+        );
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(OpCode::LeSetAdvParams, OpCode::LeSetAdvertisingParameters);
+    }
+}